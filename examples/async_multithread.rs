@@ -2,8 +2,6 @@
 #[cfg(feature = "async")]
 use tdms_rs::*;
 #[cfg(feature = "async")]
-use tokio;
-#[cfg(feature = "async")]
 use std::sync::Arc;
 
 #[cfg(feature = "async")]
@@ -35,6 +35,12 @@ from .tdms import (
     TdmsReader,
     DataType,
     defragment,
+    to_csv,
+    to_parquet,
+    column_name,
+    validate,
+    repair,
+    check_conformance,
     __version__,
     AsyncTdmsWriter,         # <-- ADDED
     AsyncRotatingTdmsWriter  # <-- ADDED
@@ -46,6 +52,12 @@ __all__ = [
     'TdmsReader',
     'DataType',
     'defragment',
+    'to_csv',
+    'to_parquet',
+    'column_name',
+    'validate',
+    'repair',
+    'check_conformance',
     '__version__',
     'AsyncTdmsWriter',         # <-- ADDED
     'AsyncRotatingTdmsWriter', # <-- ADDED
@@ -10,6 +10,11 @@ from .tdms_python import (
     AsyncRotatingTdmsWriter,
     TdmsReader as _TdmsReader,
     defragment as _defragment,
+    to_csv as _to_csv,
+    to_arrow_stream as _to_arrow_stream,
+    validate as _validate,
+    repair as _repair,
+    check_conformance as _check_conformance,
     __version__
 )
 
@@ -96,7 +101,8 @@ class TdmsWriter:
         
         Args:
             name: Property name
-            value: Property value (int, float, str, or bool)
+            value: Property value (int, float, str, bool, or a NumPy scalar,
+                which keeps its width, e.g. np.uint16)
         """
         self._writer.set_file_property(name, value)
         
@@ -107,7 +113,8 @@ class TdmsWriter:
         Args:
             group: Group name
             name: Property name
-            value: Property value (int, float, str, or bool)
+            value: Property value (int, float, str, bool, or a NumPy scalar,
+                which keeps its width, e.g. np.uint16)
         """
         self._writer.set_group_property(group, name, value)
         
@@ -120,7 +127,8 @@ class TdmsWriter:
             group: Group name
             channel: Channel name
             name: Property name
-            value: Property value (int, float, str, or bool)
+            value: Property value (int, float, str, bool, or a NumPy scalar,
+                which keeps its width, e.g. np.uint16)
         """
         self._writer.set_channel_property(group, channel, name, value)
         
@@ -220,7 +228,7 @@ class RotatingTdmsWriter:
         ...         writer.write_data("Data", "Signal", data)
     """
     
-    def __init__(self, path: str, max_size_bytes: int):
+    def __init__(self, path: str, max_size_bytes: int, naming: Optional[str] = None):
         """
         Create a new rotating TDMS file writer.
         
@@ -229,8 +237,13 @@ class RotatingTdmsWriter:
             max_size_bytes: Maximum size of a single file before rotating.
                             Rotated files will be named "output.1.tdms",
                             "output.2.tdms", etc.
+            naming: Optional file name template such as
+                    "{stem}_{timestamp:%Y%m%d_%H%M%S}_{index}.tdms", used
+                    instead of the numeric suffix. Files are placed next
+                    to `path`. Unlike numeric suffixes, a template never
+                    overwrites an existing file; that raises instead.
         """
-        self._writer = _RotatingTdmsWriter(path, max_size_bytes)
+        self._writer = _RotatingTdmsWriter(path, max_size_bytes, naming)
         
     def set_file_property(self, name: str, value: Union[int, float, str, bool]) -> None:
         """
@@ -238,7 +251,8 @@ class RotatingTdmsWriter:
         
         Args:
             name: Property name
-            value: Property value (int, float, str, or bool)
+            value: Property value (int, float, str, bool, or a NumPy scalar,
+                which keeps its width, e.g. np.uint16)
         """
         self._writer.set_file_property(name, value)
         
@@ -249,7 +263,8 @@ class RotatingTdmsWriter:
         Args:
             group: Group name
             name: Property name
-            value: Property value (int, float, str, or bool)
+            value: Property value (int, float, str, bool, or a NumPy scalar,
+                which keeps its width, e.g. np.uint16)
         """
         self._writer.set_group_property(group, name, value)
         
@@ -262,7 +277,8 @@ class RotatingTdmsWriter:
             group: Group name
             channel: Channel name
             name: Property name
-            value: Property value (int, float, str, or bool)
+            value: Property value (int, float, str, bool, or a NumPy scalar,
+                which keeps its width, e.g. np.uint16)
         """
         self._writer.set_channel_property(group, channel, name, value)
         
@@ -371,6 +387,18 @@ class TdmsReader:
         """
         return self._reader.list_channels()
     
+    def list_group_channels(self, group: str) -> List[str]:
+        """
+        List the channel names of a group, sorted by name.
+        
+        Args:
+            group: Group name
+            
+        Returns:
+            List of channel names (without the group)
+        """
+        return self._reader.list_group_channels(group)
+    
     def list_groups(self) -> List[str]:
         """
         List all groups in the file.
@@ -380,39 +408,48 @@ class TdmsReader:
         """
         return self._reader.list_groups()
     
-    def get_file_properties(self) -> Dict[str, Any]:
+    def get_file_properties(self, numpy_scalars: bool = False) -> Dict[str, Any]:
         """
         Get all file-level properties.
         
+        Args:
+            numpy_scalars: Return numeric values as NumPy scalars of their
+                TDMS type (np.uint16, np.float32, ...) instead of Python
+                ints and floats, so they are written back with the same type
+        
         Returns:
             Dictionary mapping property names to values
         """
-        return self._reader.get_file_properties()
+        return self._reader.get_file_properties(numpy_scalars)
     
-    def get_group_properties(self, group: str) -> Optional[Dict[str, Any]]:
+    def get_group_properties(self, group: str, numpy_scalars: bool = False) -> Optional[Dict[str, Any]]:
         """
         Get all properties for a group.
         
         Args:
             group: Group name
+            numpy_scalars: Return numeric values as NumPy scalars (see
+                get_file_properties)
             
         Returns:
             Dictionary mapping property names to values, or None if group doesn't exist
         """
-        return self._reader.get_group_properties(group)
+        return self._reader.get_group_properties(group, numpy_scalars)
     
-    def get_channel_properties(self, group: str, channel: str) -> Optional[Dict[str, Any]]:
+    def get_channel_properties(self, group: str, channel: str, numpy_scalars: bool = False) -> Optional[Dict[str, Any]]:
         """
         Get all properties for a channel.
         
         Args:
             group: Group name
             channel: Channel name
+            numpy_scalars: Return numeric values as NumPy scalars (see
+                get_file_properties)
             
         Returns:
             Dictionary mapping property names to values, or None if channel doesn't exist
         """
-        return self._reader.get_channel_properties(group, channel)
+        return self._reader.get_channel_properties(group, channel, numpy_scalars)
     
     def read_data(self, group: str, channel: str, dtype: Optional[np.dtype] = None) -> np.ndarray:
         """
@@ -443,6 +480,60 @@ class TdmsReader:
         
         return data
     
+    def read_xy(self, group: str, time_channel: str, value_channel: str,
+                structured: bool = False, truncate: bool = False):
+        """
+        Read a timestamp channel and a value channel together.
+        
+        Args:
+            group: Group containing both channels
+            time_channel: Timestamp channel, returned as datetime64[ns]
+            value_channel: Channel holding the values
+            structured: Return one structured array with 'time' and 'value'
+                fields instead of a tuple of arrays
+            truncate: Cut both channels to the shorter length instead of
+                raising ValueError when their lengths differ
+            
+        Returns:
+            Tuple of (times, values) arrays, or a structured array if
+            `structured` is set.
+        
+        Examples:
+            >>> times, volts = reader.read_xy("Data", "Time", "Voltage")
+            >>> xy = reader.read_xy("Data", "Time", "Voltage", structured=True)
+            >>> xy["time"][0], xy["value"][0]
+        """
+        times, values = self._reader.read_xy(group, time_channel, value_channel, truncate)
+        if not structured:
+            return times, values
+
+        xy = np.empty(len(times), dtype=[("time", times.dtype), ("value", values.dtype)])
+        xy["time"] = times
+        xy["value"] = values
+        return xy
+
+    def read_range(self, group: str, channel: str, start: int, length: int) -> np.ndarray:
+        """
+        Read a slice of a channel without loading the rest of it.
+        
+        Only the segments holding the requested values are read, so this
+        is cheap even for very large channels. The slice is cut short at
+        the end of the channel.
+        
+        Args:
+            group: Group name
+            channel: Channel name
+            start: Index of the first value to read
+            length: Number of values to read
+            
+        Returns:
+            NumPy array of the values, typed as by read_data.
+        
+        Examples:
+            >>> window = reader.read_range("Data", "Voltage", 1_000_000, 10_000)
+        """
+        return self._reader.read_range(group, channel, start, length)
+
     def read_strings(self, group: str, channel: str) -> List[str]:
         """
         Read string data from a channel.
@@ -525,4 +616,143 @@ def defragment(source_path: str, dest_path: str) -> None:
     Examples:
         >>> defragment("fragmented.tdms", "optimized.tdms")
     """
-    _defragment(source_path, dest_path)
\ No newline at end of file
+    _defragment(source_path, dest_path)
+
+
+def to_csv(path: str, out_dir: str, name_property: Optional[str] = None,
+           include_units: bool = False) -> List[str]:
+    """
+    Export a TDMS file to CSV, one file per group.
+    
+    Each group is written to `<out_dir>/<group>.csv` with one column per
+    channel. Timestamps are written as ISO 8601 UTC text and properties are
+    not exported.
+    
+    Args:
+        path: TDMS file to export
+        out_dir: Directory for the CSV files (created if missing)
+        name_property: Property to name columns after (e.g. "NI_ChannelName"),
+            falling back to the channel name where it is missing or empty
+        include_units: Append the `unit_string` property as " [unit]"
+        
+    Returns:
+        Paths of the CSV files written.
+        
+    Examples:
+        >>> to_csv("run.tdms", "run_csv")
+        ['run_csv/Data.csv']
+    """
+    return _to_csv(path, out_dir, name_property, include_units)
+
+
+def check_conformance(path: str) -> Dict[str, Any]:
+    """
+    Check the structure of a TDMS file, and its index file if present,
+    against the TDMS 2.0 rules.
+    
+    Args:
+        path: TDMS file to check
+        
+    Returns:
+        A dict with `conformant`, `segments_checked`, `index_checked` and
+        `issues`, a list of dicts with `rule`, `segment` (None for
+        file-level issues), `offset` and `message`.
+        
+    Examples:
+        >>> report = check_conformance("run.tdms")
+        >>> assert report["conformant"], report["issues"]
+    """
+    return _check_conformance(path)
+
+
+def validate(path: str) -> Dict[str, Any]:
+    """
+    Report whether a TDMS file is healthy enough to ingest.
+    
+    A file is `valid` if it opens without the reader working around damage,
+    its last segment is complete and it passes `check_conformance`.
+    
+    Args:
+        path: TDMS file to validate
+        
+    Returns:
+        A dict with `valid`, `readable`, `error` (why it couldn't be opened,
+        or None), `segment_count`, `channel_count`, `incomplete_segment` (the
+        offset of a truncated last segment, or None), `warnings` (dicts with
+        `segment`, `offset` and `message`) and `conformance`.
+        
+    Examples:
+        >>> if not validate("run.tdms")["valid"]:
+        ...     raise RuntimeError("refusing to ingest run.tdms")
+    """
+    return _validate(path)
+
+
+def repair(source_path: str, dest_path: str) -> Dict[str, Any]:
+    """
+    Salvage a damaged TDMS file by rewriting everything readable in it,
+    including the complete chunks of a truncated last segment, to a new file.
+    
+    Args:
+        source_path: Damaged TDMS file, left untouched
+        dest_path: Where to write the repaired file
+        
+    Returns:
+        A dict with the `validate` results of the `source` and the `result`.
+        
+    Examples:
+        >>> report = repair("crashed.tdms", "recovered.tdms")
+        >>> report["source"]["incomplete_segment"], report["result"]["valid"]
+    """
+    return _repair(source_path, dest_path)
+
+
+def column_name(channel: str, properties: Optional[Dict[str, Any]],
+                name_property: Optional[str] = None, include_units: bool = False) -> str:
+    """
+    Name of an exported channel's column, the same way `to_csv` names them.
+    
+    Args:
+        channel: Channel name, used unless `name_property` gives a name
+        properties: The channel's properties
+        name_property: Property to take the name from (e.g. "NI_ChannelName")
+        include_units: Append the `unit_string` property as " [unit]"
+    """
+    properties = properties or {}
+    name = properties.get(name_property) if name_property else None
+    if not isinstance(name, str) or not name:
+        name = channel
+    unit = properties.get("unit_string")
+    if include_units and isinstance(unit, str) and unit:
+        name = f"{name} [{unit}]"
+    return name
+
+
+def to_parquet(path: str, out_path: str, name_property: Optional[str] = None,
+               include_units: bool = False) -> None:
+    """
+    Export a TDMS file to a single Parquet file.
+    
+    The columns come from the library's Arrow exporter: every channel becomes
+    a column named `<group>/<channel>`, or `<group>/<name>` with the name
+    chosen as in `column_name`. Integers are widened to 64 bits, floats to
+    double and timestamps become nanosecond UTC timestamps. Channels shorter
+    than the longest one are padded with nulls. Requires `pyarrow`.
+    
+    Args:
+        path: TDMS file to export
+        out_path: Parquet file to write
+        name_property: Property to name columns after (e.g. "NI_ChannelName")
+        include_units: Append the `unit_string` property as " [unit]"
+        
+    Examples:
+        >>> to_parquet("run.tdms", "run.parquet")
+    """
+    try:
+        import pyarrow as pa
+        import pyarrow.parquet as pq
+    except ImportError as e:
+        raise ImportError("to_parquet requires pyarrow (pip install pyarrow)") from e
+
+    stream = _to_arrow_stream(path, name_property, include_units)
+    pq.write_table(pa.ipc.open_stream(stream).read_all(), out_path)
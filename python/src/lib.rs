@@ -3,34 +3,32 @@
 
 use pyo3::prelude::*;
 use pyo3::exceptions::{PyValueError, PyTypeError};
-use pyo3::types::{PyDict, PyAny, PyDateTime, PyModule};
+use pyo3::types::{PyBytes, PyDict, PyAny, PyDateTime, PyModule};
 use numpy::{PyArray1, PyArrayMethods, IntoPyArray};
+use std::time::Duration;
 
 // Re-export the main library
 use tdms_rs as tdms;
 
-// TDMS epoch (1904-01-01) is 2082844800 seconds before the UNIX epoch (1970-01-01)
-const TDMS_EPOCH_OFFSET_SECONDS: i64 = 2082844800;
-const NANOS_PER_SECOND: i64 = 1_000_000_000;
-
 fn tdms_error_to_pyerr(err: tdms::TdmsError) -> PyErr {
     PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(err.to_string())
 }
 
 /// Helper function to convert Python float timestamp to TDMS Timestamp
 fn unix_to_tdms_timestamp(unix_seconds: i64, nanos_subsec: u32) -> tdms::Timestamp {
-    let nanos_subsec_u64 = nanos_subsec as u64;
-    let fractions = (nanos_subsec_u64 as u128 * (1u128 << 64) / 1_000_000_000) as u64;
-    let tdms_seconds = unix_seconds + TDMS_EPOCH_OFFSET_SECONDS;
-    tdms::Timestamp { seconds: tdms_seconds, fractions }
+    let unix_epoch = tdms::Timestamp::from_unix_nanos(0);
+    let whole_seconds = Duration::from_secs(unix_seconds.unsigned_abs());
+    let seconds = if unix_seconds >= 0 {
+        unix_epoch.add_duration(whole_seconds)
+    } else {
+        unix_epoch.sub_duration(whole_seconds)
+    };
+    seconds.add_duration(Duration::from_nanos(nanos_subsec as u64))
 }
 
 /// Helper function to convert nanoseconds (from numpy) to TDMS Timestamp
 fn nanos_to_tdms_timestamp(nanos_since_1970: i64) -> tdms::Timestamp {
-    let unix_seconds = nanos_since_1970.div_euclid(NANOS_PER_SECOND);
-    // Use u32 for nanos_subsec as it's always < 1,000,000,000
-    let nanos_subsec = nanos_since_1970.rem_euclid(NANOS_PER_SECOND) as u32;
-    unix_to_tdms_timestamp(unix_seconds, nanos_subsec)
+    tdms::Timestamp::from_unix_nanos(nanos_since_1970)
 }
 
 
@@ -105,6 +103,28 @@ fn py_to_property_value(_py: Python, value: &Bound<'_, PyAny>) -> PyResult<tdms:
         return Ok(tdms::PropertyValue::Timestamp(nanos_to_tdms_timestamp(nanos_since_1970)));
     }
     
+    // numpy scalars keep their width, so properties read with
+    // `numpy_scalars=True` are written back with their original type
+    if value.get_type().module()?.to_cow()? == "numpy" {
+        let property = match type_name.to_cow()?.as_ref() {
+            "int8" => Some(tdms::PropertyValue::I8(value.extract()?)),
+            "int16" => Some(tdms::PropertyValue::I16(value.extract()?)),
+            "int32" => Some(tdms::PropertyValue::I32(value.extract()?)),
+            "int64" => Some(tdms::PropertyValue::I64(value.extract()?)),
+            "uint8" => Some(tdms::PropertyValue::U8(value.extract()?)),
+            "uint16" => Some(tdms::PropertyValue::U16(value.extract()?)),
+            "uint32" => Some(tdms::PropertyValue::U32(value.extract()?)),
+            "uint64" => Some(tdms::PropertyValue::U64(value.extract()?)),
+            "float32" => Some(tdms::PropertyValue::Float(value.extract()?)),
+            "float64" => Some(tdms::PropertyValue::Double(value.extract()?)),
+            "bool" | "bool_" => Some(tdms::PropertyValue::Boolean(value.extract()?)),
+            _ => None,
+        };
+        if let Some(property) = property {
+            return Ok(property);
+        }
+    }
+    
     // --- Keep existing checks ---
     if let Ok(v) = value.extract::<i32>() {
         Ok(tdms::PropertyValue::I32(v))
@@ -124,6 +144,45 @@ fn py_to_property_value(_py: Python, value: &Bound<'_, PyAny>) -> PyResult<tdms:
     }
 }
 
+/// Convert PropertyValue to a numpy scalar of the matching width
+/// (`np.uint16`, `np.float32`, ...), falling back to `property_value_to_py`
+/// for strings and timestamps
+fn property_value_to_numpy(py: Python, value: &tdms::PropertyValue) -> PyResult<Py<PyAny>> {
+    let np_type = match value {
+        tdms::PropertyValue::I8(_) => "int8",
+        tdms::PropertyValue::I16(_) => "int16",
+        tdms::PropertyValue::I32(_) => "int32",
+        tdms::PropertyValue::I64(_) => "int64",
+        tdms::PropertyValue::U8(_) => "uint8",
+        tdms::PropertyValue::U16(_) => "uint16",
+        tdms::PropertyValue::U32(_) => "uint32",
+        tdms::PropertyValue::U64(_) => "uint64",
+        tdms::PropertyValue::Float(_) => "float32",
+        tdms::PropertyValue::Double(_) => "float64",
+        tdms::PropertyValue::Boolean(_) => "bool_",
+        tdms::PropertyValue::String(_) | tdms::PropertyValue::Timestamp(_) => {
+            return property_value_to_py(py, value);
+        }
+    };
+    let np = PyModule::import(py, "numpy")?;
+    let scalar = np.getattr(np_type)?.call1((property_value_to_py(py, value)?,))?;
+    Ok(scalar.unbind())
+}
+
+/// Convert a property map to a Python dict
+fn properties_to_py(py: Python, props: &std::collections::HashMap<String, tdms::Property>, numpy_scalars: bool) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    for (name, prop) in props.iter() {
+        let value = if numpy_scalars {
+            property_value_to_numpy(py, &prop.value)?
+        } else {
+            property_value_to_py(py, &prop.value)?
+        };
+        dict.set_item(name, value)?;
+    }
+    Ok(dict.into())
+}
+
 /// Convert PropertyValue to Python object
 fn property_value_to_py(py: Python, value: &tdms::PropertyValue) -> PyResult<Py<PyAny>> {
     Ok(match value {
@@ -141,9 +200,7 @@ fn property_value_to_py(py: Python, value: &tdms::PropertyValue) -> PyResult<Py<
         tdms::PropertyValue::String(v) => v.as_str().into_pyobject(py)?.into_any().unbind(),
         tdms::PropertyValue::Timestamp(ts) => {
             // Convert to numpy.datetime64[ns] for full precision
-            let unix_seconds = ts.seconds - TDMS_EPOCH_OFFSET_SECONDS;
-            let nanos_subsec = ((ts.fractions as u128 * 1_000_000_000) / (1u128 << 64)) as i64;
-            let nanos_since_1970 = (unix_seconds * NANOS_PER_SECOND) + nanos_subsec;
+            let nanos_since_1970 = ts.to_unix_nanos();
 
             let np = PyModule::import(py, "numpy")?;
             let scalar_array = np.call_method1("array", (nanos_since_1970,))?;
@@ -162,6 +219,14 @@ pub struct PyTdmsWriter {
     writer: Option<tdms::TdmsWriter>,
 }
 
+/// Size-limited rotating writer; `naming` is a file name template (see
+/// `RotationNaming::Template`), numeric suffixes without one
+fn rotating_writer_builder(path: &str, max_size_bytes: u64, naming: Option<String>) -> tdms::RotatingWriterBuilder {
+    tdms::RotatingTdmsWriter::builder(path)
+        .max_size(max_size_bytes)
+        .naming(naming.map_or(tdms::RotationNaming::Suffix, tdms::RotationNaming::Template))
+}
+
 /// A TDMS writer that rotates to a new file when the current file
 /// exceeds a specified size.
 #[pyclass(name = "RotatingTdmsWriter")]
@@ -172,8 +237,11 @@ pub struct PyRotatingTdmsWriter {
 #[pymethods]
 impl PyRotatingTdmsWriter {
     #[new]
-    fn new(path: &str, max_size_bytes: u64) -> PyResult<Self> {
-        let writer = tdms::RotatingTdmsWriter::new(path, max_size_bytes).map_err(tdms_error_to_pyerr)?;
+    #[pyo3(signature = (path, max_size_bytes, naming=None))]
+    fn new(path: &str, max_size_bytes: u64, naming: Option<String>) -> PyResult<Self> {
+        let writer = rotating_writer_builder(path, max_size_bytes, naming)
+            .build()
+            .map_err(tdms_error_to_pyerr)?;
         Ok(PyRotatingTdmsWriter {
             writer: Some(writer),
         })
@@ -522,11 +590,13 @@ pub struct PyAsyncRotatingTdmsWriter {
 #[pymethods]
 impl PyAsyncRotatingTdmsWriter {
     #[new]
-    fn new(path: &str, max_size_bytes: u64) -> PyResult<Self> {
+    #[pyo3(signature = (path, max_size_bytes, naming=None))]
+    fn new(path: &str, max_size_bytes: u64, naming: Option<String>) -> PyResult<Self> {
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()?;
-        let writer = runtime.block_on(tdms::AsyncRotatingTdmsWriter::new(path, max_size_bytes))
+        let builder = rotating_writer_builder(path, max_size_bytes, naming);
+        let writer = runtime.block_on(tdms::AsyncRotatingTdmsWriter::from_builder(builder))
             .map_err(tdms_error_to_pyerr)?;
         Ok(PyAsyncRotatingTdmsWriter {
             writer: Some(writer),
@@ -547,6 +617,25 @@ impl PyAsyncRotatingTdmsWriter {
         writer.set_file_property(name, prop_value).map_err(tdms_error_to_pyerr)
     }
 
+    fn set_group_property(&self, py: Python, group: &str, name: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let writer = self.writer.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Writer is closed"))?;
+        let prop_value = py_to_property_value(py, value)?;
+        writer.set_group_property(group, name, prop_value).map_err(tdms_error_to_pyerr)
+    }
+
+    fn set_channel_property(&self, py: Python, group: &str, channel: &str, name: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let writer = self.writer.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Writer is closed"))?;
+        let runtime = self.runtime.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Runtime is closed"))?;
+        let prop_value = py_to_property_value(py, value)?;
+        runtime.block_on(writer.set_channel_property(group, channel, name, prop_value)).map_err(tdms_error_to_pyerr)
+    }
+
+    fn create_group(&self, group: &str) -> PyResult<()> {
+        let writer = self.writer.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Writer is closed"))?;
+        let runtime = self.runtime.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Runtime is closed"))?;
+        runtime.block_on(writer.create_group(group)).map_err(tdms_error_to_pyerr)
+    }
+
     #[pyo3(name = "write_data")]
     fn write_data_any<'py>(
         &self,
@@ -669,6 +758,25 @@ impl PyAsyncTdmsWriter {
         writer.set_file_property(name, prop_value).map_err(tdms_error_to_pyerr)
     }
 
+    fn set_group_property(&self, py: Python, group: &str, name: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let writer = self.writer.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Writer is closed"))?;
+        let prop_value = py_to_property_value(py, value)?;
+        writer.set_group_property(group, name, prop_value).map_err(tdms_error_to_pyerr)
+    }
+
+    fn set_channel_property(&self, py: Python, group: &str, channel: &str, name: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let writer = self.writer.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Writer is closed"))?;
+        let runtime = self.runtime.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Runtime is closed"))?;
+        let prop_value = py_to_property_value(py, value)?;
+        runtime.block_on(writer.set_channel_property(group, channel, name, prop_value)).map_err(tdms_error_to_pyerr)
+    }
+
+    fn create_group(&self, group: &str) -> PyResult<()> {
+        let writer = self.writer.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Writer is closed"))?;
+        let runtime = self.runtime.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Runtime is closed"))?;
+        runtime.block_on(writer.create_group(group)).map_err(tdms_error_to_pyerr)
+    }
+
     #[pyo3(name = "write_data")]
     fn write_data_any<'py>(
         &self,
@@ -877,6 +985,13 @@ impl PyTdmsReader {
         Ok(reader.list_channels())
     }
 
+    /// List the channel names of a group, sorted by name
+    fn list_group_channels(&self, group: &str) -> PyResult<Vec<String>> {
+        let reader = self.reader.as_ref()
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Reader is closed"))?;
+        Ok(reader.list_group_channels(group))
+    }
+
     /// List all groups in the file
     fn list_groups(&self) -> PyResult<Vec<String>> {
         let reader = self.reader.as_ref()
@@ -885,45 +1000,34 @@ impl PyTdmsReader {
     }
 
     /// Get file properties
-    fn get_file_properties(&self, py: Python) -> PyResult<Py<PyAny>> {
+    ///
+    /// With `numpy_scalars`, numeric values are returned as numpy scalars of
+    /// their TDMS width (e.g. `np.uint16`) instead of Python ints and floats.
+    #[pyo3(signature = (numpy_scalars=false))]
+    fn get_file_properties(&self, py: Python, numpy_scalars: bool) -> PyResult<Py<PyAny>> {
         let reader = self.reader.as_ref()
             .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Reader is closed"))?;
-        let props = reader.get_file_properties();
-        let dict = PyDict::new(py);
-        for (name, prop) in props.iter() {
-            dict.set_item(name, property_value_to_py(py, &prop.value)?)?;
-        }
-        Ok(dict.into())
+        properties_to_py(py, reader.get_file_properties(), numpy_scalars)
     }
 
     /// Get group properties
-    fn get_group_properties(&self, py: Python, group: &str) -> PyResult<Option<Py<PyAny>>> {
+    #[pyo3(signature = (group, numpy_scalars=false))]
+    fn get_group_properties(&self, py: Python, group: &str, numpy_scalars: bool) -> PyResult<Option<Py<PyAny>>> {
         let reader = self.reader.as_ref()
             .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Reader is closed"))?;
-        if let Some(props) = reader.get_group_properties(group) {
-            let dict = PyDict::new(py);
-            for (name, prop) in props.iter() {
-                dict.set_item(name, property_value_to_py(py, &prop.value)?)?;
-            }
-            Ok(Some(dict.into()))
-        } else {
-            Ok(None)
-        }
+        reader.get_group_properties(group)
+            .map(|props| properties_to_py(py, props, numpy_scalars))
+            .transpose()
     }
 
     /// Get channel properties
-    fn get_channel_properties(&self, py: Python, group: &str, channel: &str) -> PyResult<Option<Py<PyAny>>> {
+    #[pyo3(signature = (group, channel, numpy_scalars=false))]
+    fn get_channel_properties(&self, py: Python, group: &str, channel: &str, numpy_scalars: bool) -> PyResult<Option<Py<PyAny>>> {
         let reader = self.reader.as_ref()
             .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Reader is closed"))?;
-        if let Some(props) = reader.get_channel_properties(group, channel) {
-            let dict = PyDict::new(py);
-            for (name, prop) in props.iter() {
-                dict.set_item(name, property_value_to_py(py, &prop.value)?)?;
-            }
-            Ok(Some(dict.into()))
-        } else {
-            Ok(None)
-        }
+        reader.get_channel_properties(group, channel)
+            .map(|props| properties_to_py(py, props, numpy_scalars))
+            .transpose()
     }
 
     fn get_channel_data_type(&self, group: &str, channel: &str) -> PyResult<u32> {
@@ -986,11 +1090,7 @@ impl PyTdmsReader {
         
         let data: Vec<tdms::Timestamp> = reader.read_channel_data(group, channel).map_err(tdms_error_to_pyerr)?;
 
-        let nanos: Vec<i64> = data.iter().map(|&ts| {
-            let unix_seconds = ts.seconds - TDMS_EPOCH_OFFSET_SECONDS;
-            let nanos_subsec = ((ts.fractions as u128 * 1_000_000_000) / (1u128 << 64)) as i64;
-            (unix_seconds * NANOS_PER_SECOND) + nanos_subsec
-        }).collect();
+        let nanos: Vec<i64> = data.iter().map(|ts| ts.to_unix_nanos()).collect();
 
         let nanos_array = nanos.into_pyarray(py);
 
@@ -1070,11 +1170,7 @@ impl PyTdmsReader {
             }
             tdms::DataType::TimeStamp => {
                 let data: Vec<tdms::Timestamp> = reader_mut.read_channel_data(group, channel).map_err(tdms_error_to_pyerr)?;
-                let nanos: Vec<i64> = data.iter().map(|&ts| {
-                    let unix_seconds = ts.seconds - TDMS_EPOCH_OFFSET_SECONDS;
-                    let nanos_subsec = ((ts.fractions as u128 * 1_000_000_000) / (1u128 << 64)) as i64;
-                    (unix_seconds * NANOS_PER_SECOND) + nanos_subsec
-                }).collect();
+                let nanos: Vec<i64> = data.iter().map(|ts| ts.to_unix_nanos()).collect();
                 let nanos_array = nanos.into_pyarray(py);
                 let np = PyModule::import(py, "numpy")?;
                 let datetime_dtype = np.call_method1("dtype", ("datetime64[ns]",))?;
@@ -1094,6 +1190,44 @@ impl PyTdmsReader {
         }
     }
 
+    /// Read a timestamp channel and a value channel of the same group as a
+    /// `(datetime64[ns] array, value array)` pair.
+    ///
+    /// Raises ValueError if the channels have different lengths, unless
+    /// `truncate` is set, in which case both are cut to the shorter length.
+    #[pyo3(signature = (group, time_channel, value_channel, truncate=false))]
+    fn read_xy<'py>(&mut self, py: Python<'py>, group: &str, time_channel: &str, value_channel: &str, truncate: bool) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let reader = self.reader.as_ref()
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Reader is closed"))?;
+        let time_path = tdms::ObjectPath::Channel { group: group.into(), channel: time_channel.into() }.to_string();
+        let time_type = reader.get_channel(&time_path)
+            .ok_or_else(|| PyValueError::new_err(format!("Channel not found: {}", time_path)))?
+            .data_type();
+        if time_type != tdms::DataType::TimeStamp {
+            return Err(PyTypeError::new_err(format!(
+                "Channel '{}/{}' is not a timestamp channel", group, time_channel
+            )));
+        }
+
+        let times = self.read_data_datetime64(py, group, time_channel)?;
+        let values = self.read_data_auto(py, group, value_channel)?;
+
+        let (time_len, value_len) = (times.len()?, values.len()?);
+        if time_len == value_len {
+            return Ok((times, values));
+        }
+        if !truncate {
+            return Err(PyValueError::new_err(format!(
+                "Channel '{}/{}' has {} values but '{}/{}' has {}",
+                group, time_channel, time_len, group, value_channel, value_len
+            )));
+        }
+
+        let len = time_len.min(value_len);
+        let slice = pyo3::types::PySlice::new(py, 0, len as isize, 1);
+        Ok((times.get_item(&slice)?, values.get_item(&slice)?))
+    }
+
     /// Read string data from a channel
     fn read_strings(&mut self, group: &str, channel: &str) -> PyResult<Vec<String>> {
         let reader = self.reader.as_mut()
@@ -1102,6 +1236,54 @@ impl PyTdmsReader {
         Ok(data)
     }
 
+    /// Read `length` values of a channel starting at value `start`, with the
+    /// same type detection as `read_data`
+    fn read_range<'py>(&mut self, py: Python<'py>, group: &str, channel: &str, start: u64, length: usize) -> PyResult<Bound<'py, PyAny>> {
+        let reader = self.reader.as_mut()
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Reader is closed"))?;
+        let path = tdms::ObjectPath::Channel { group: group.into(), channel: channel.into() }.to_string();
+        let data_type = reader.get_channel(&path)
+            .ok_or_else(|| PyValueError::new_err(format!("Channel not found: {}", path)))?
+            .data_type();
+
+        macro_rules! numeric_range {
+            ($t:ty) => {{
+                let data: Vec<$t> = reader.read_channel_range(group, channel, start, length).map_err(tdms_error_to_pyerr)?;
+                Ok(data.into_pyarray(py).into_any())
+            }};
+        }
+
+        match data_type {
+            tdms::DataType::DoubleFloat => numeric_range!(f64),
+            tdms::DataType::SingleFloat => numeric_range!(f32),
+            tdms::DataType::I64 => numeric_range!(i64),
+            tdms::DataType::I32 => numeric_range!(i32),
+            tdms::DataType::I16 => numeric_range!(i16),
+            tdms::DataType::I8 => numeric_range!(i8),
+            tdms::DataType::U64 => numeric_range!(u64),
+            tdms::DataType::U32 => numeric_range!(u32),
+            tdms::DataType::U16 => numeric_range!(u16),
+            tdms::DataType::U8 => numeric_range!(u8),
+            tdms::DataType::Boolean => numeric_range!(bool),
+            tdms::DataType::TimeStamp => {
+                let data: Vec<tdms::Timestamp> = reader.read_channel_range(group, channel, start, length).map_err(tdms_error_to_pyerr)?;
+                let nanos: Vec<i64> = data.iter().map(|ts| ts.to_unix_nanos()).collect();
+                let np = PyModule::import(py, "numpy")?;
+                let datetime_dtype = np.call_method1("dtype", ("datetime64[ns]",))?;
+                nanos.into_pyarray(py).call_method1("astype", (datetime_dtype,))
+            }
+            tdms::DataType::String => {
+                let data = reader.read_channel_range_strings(group, channel, start, length).map_err(tdms_error_to_pyerr)?;
+                let np = PyModule::import(py, "numpy")?;
+                np.call_method1("array", (data, "object"))
+            }
+            _ => Err(PyTypeError::new_err(format!(
+                "Unsupported data type {:?} for channel '{}/{}'",
+                data_type, group, channel
+            ))),
+        }
+    }
+
     /// Iterate over data chunks from a channel
     fn iter_data(slf: Py<PyTdmsReader>, py: Python<'_>, group: &str, channel: &str, chunk_size: usize) -> PyResult<PyTdmsIter> {
         let reader = slf.borrow(py);
@@ -1185,6 +1367,114 @@ fn defragment(source_path: &str, dest_path: &str) -> PyResult<()> {
     Ok(())
 }
 
+/// Export each group of a TDMS file to `<out_dir>/<group>.csv`, returning the paths written
+#[pyfunction]
+#[pyo3(signature = (path, out_dir, name_property=None, include_units=false))]
+fn to_csv(path: &str, out_dir: &str, name_property: Option<&str>, include_units: bool) -> PyResult<Vec<String>> {
+    let mut naming = tdms::export::ColumnNaming::new().include_units(include_units);
+    if let Some(name) = name_property {
+        naming = naming.name_property(name);
+    }
+    let written = tdms::export::export_csv_with(path, out_dir, &naming).map_err(tdms_error_to_pyerr)?;
+    Ok(written.into_iter().map(|p| p.to_string_lossy().into_owned()).collect())
+}
+
+/// Export every channel of a TDMS file as Arrow IPC stream bytes, for
+/// `to_parquet` to hand to pyarrow
+#[pyfunction]
+#[pyo3(signature = (path, name_property=None, include_units=false))]
+fn to_arrow_stream<'py>(py: Python<'py>, path: &str, name_property: Option<&str>, include_units: bool) -> PyResult<Bound<'py, PyBytes>> {
+    let mut naming = tdms::export::ColumnNaming::new().include_units(include_units);
+    if let Some(name) = name_property {
+        naming = naming.name_property(name);
+    }
+    let mut reader = tdms::TdmsReader::open(path).map_err(tdms_error_to_pyerr)?;
+    let mut stream = Vec::new();
+    tdms::export::write_arrow_stream_with(&mut reader, &[], &naming, &mut stream).map_err(tdms_error_to_pyerr)?;
+    Ok(PyBytes::new(py, &stream))
+}
+
+/// Convert a conformance report to a dict with `conformant`, `segments_checked`,
+/// `index_checked` and a list of `issues`
+fn conformance_report_to_py(py: Python, report: &tdms::conformance::ConformanceReport) -> PyResult<Py<PyAny>> {
+    let issues = report.issues.iter()
+        .map(|issue| {
+            let dict = PyDict::new(py);
+            dict.set_item("rule", issue.rule.to_string())?;
+            dict.set_item("segment", issue.segment)?;
+            dict.set_item("offset", issue.offset)?;
+            dict.set_item("message", &issue.message)?;
+            Ok(dict.into_any().unbind())
+        })
+        .collect::<PyResult<Vec<Py<PyAny>>>>()?;
+    let dict = PyDict::new(py);
+    dict.set_item("conformant", report.is_conformant())?;
+    dict.set_item("segments_checked", report.segments_checked)?;
+    dict.set_item("index_checked", report.index_checked)?;
+    dict.set_item("issues", issues)?;
+    Ok(dict.into())
+}
+
+/// Check the structure of a TDMS file against the TDMS 2.0 rules
+#[pyfunction]
+fn check_conformance(py: Python, path: &str) -> PyResult<Py<PyAny>> {
+    let report = tdms::conformance::check_conformance(path).map_err(tdms_error_to_pyerr)?;
+    conformance_report_to_py(py, &report)
+}
+
+/// Report whether a TDMS file is healthy: what the reader had to work around
+/// to open it, whether the last segment is incomplete, and its conformance
+#[pyfunction]
+fn validate(py: Python, path: &str) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    let healthy = match tdms::TdmsReader::open(path) {
+        Ok(reader) => {
+            let warnings = reader.warnings().iter()
+                .map(|warning| {
+                    let entry = PyDict::new(py);
+                    entry.set_item("segment", warning.segment)?;
+                    entry.set_item("offset", warning.offset)?;
+                    entry.set_item("message", &warning.message)?;
+                    Ok(entry.into_any().unbind())
+                })
+                .collect::<PyResult<Vec<Py<PyAny>>>>()?;
+            dict.set_item("readable", true)?;
+            dict.set_item("error", py.None())?;
+            dict.set_item("segment_count", reader.segment_count())?;
+            dict.set_item("channel_count", reader.channel_count())?;
+            dict.set_item("incomplete_segment", reader.incomplete_segment())?;
+            dict.set_item("warnings", warnings)?;
+            reader.incomplete_segment().is_none() && reader.warnings().is_empty()
+        }
+        Err(e) => {
+            dict.set_item("readable", false)?;
+            dict.set_item("error", e.to_string())?;
+            dict.set_item("segment_count", 0)?;
+            dict.set_item("channel_count", 0)?;
+            dict.set_item("incomplete_segment", py.None())?;
+            dict.set_item("warnings", Vec::<Py<PyAny>>::new())?;
+            false
+        }
+    };
+    let report = tdms::conformance::check_conformance(path).map_err(tdms_error_to_pyerr)?;
+    dict.set_item("valid", healthy && report.is_conformant())?;
+    dict.set_item("conformance", conformance_report_to_py(py, &report)?)?;
+    Ok(dict.into())
+}
+
+/// Salvage a damaged TDMS file by rewriting everything readable in it to
+/// `dest_path`, returning the `validate` results of both files
+#[pyfunction]
+fn repair(py: Python, source_path: &str, dest_path: &str) -> PyResult<Py<PyAny>> {
+    let before = validate(py, source_path)?;
+    tdms::defragment(source_path, dest_path).map_err(tdms_error_to_pyerr)?;
+    let after = validate(py, dest_path)?;
+    let dict = PyDict::new(py);
+    dict.set_item("source", before)?;
+    dict.set_item("result", after)?;
+    Ok(dict.into())
+}
+
 /// Python module for TDMS file I/O
 #[pymodule]
 fn tdms_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -1197,6 +1487,11 @@ fn tdms_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyTdmsIter>()?;       // <-- Added
     m.add_class::<PyTdmsStringIter>()?; // <-- Added
     m.add_function(wrap_pyfunction!(defragment, m)?)?;
+    m.add_function(wrap_pyfunction!(to_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(to_arrow_stream, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    m.add_function(wrap_pyfunction!(repair, m)?)?;
+    m.add_function(wrap_pyfunction!(check_conformance, m)?)?;
     
     // Add version info
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
@@ -120,6 +120,26 @@ def test_channel_properties(temp_tdms_file):
         assert props["max"] == 10.0
 
 
+def test_numpy_scalar_properties(temp_tdms_file):
+    """Test that numpy scalar properties keep their TDMS type"""
+    with tdms.TdmsWriter(temp_tdms_file) as writer:
+        writer.create_channel("Group1", "Voltage", tdms.DataType.F64)
+        writer.set_channel_property("Group1", "Voltage", "gain", np.float32(0.5))
+        writer.set_channel_property("Group1", "Voltage", "range", np.uint16(500))
+        writer.set_channel_property("Group1", "Voltage", "offset", np.int8(-3))
+        writer.write_data("Group1", "Voltage", np.array([1.0]))
+
+    with tdms.TdmsReader(temp_tdms_file) as reader:
+        props = reader.get_channel_properties("Group1", "Voltage")
+        assert type(props["range"]) is int
+        assert props["range"] == 500
+
+        props = reader.get_channel_properties("Group1", "Voltage", numpy_scalars=True)
+        assert props["gain"].dtype == np.float32
+        assert props["range"].dtype == np.uint16
+        assert props["offset"].dtype == np.int8
+        assert props["offset"] == -3
+
 def test_list_channels(temp_tdms_file):
     """Test listing channels"""
     # Write
@@ -194,6 +214,25 @@ def test_multiple_segments(temp_tdms_file):
             assert np.all(segment == i)
 
 
+def test_read_range(temp_tdms_file):
+    """Test reading a slice of a channel spanning segments"""
+    with tdms.TdmsWriter(temp_tdms_file) as writer:
+        writer.create_channel("Data", "Values", tdms.DataType.I32)
+        writer.create_channel("Data", "Labels", tdms.DataType.STRING)
+        for i in range(5):
+            writer.write_data("Data", "Values", np.arange(i * 100, (i + 1) * 100, dtype=np.int32))
+            writer.flush()
+        writer.write_strings("Data", "Labels", ["a", "b", "c"])
+
+    with tdms.TdmsReader(temp_tdms_file) as reader:
+        window = reader.read_range("Data", "Values", 150, 100)
+        assert window.dtype == np.int32
+        np.testing.assert_array_equal(window, np.arange(150, 250, dtype=np.int32))
+        assert len(reader.read_range("Data", "Values", 480, 100)) == 20
+        assert len(reader.read_range("Data", "Values", 600, 10)) == 0
+        assert list(reader.read_range("Data", "Labels", 1, 5)) == ["b", "c"]
+
+
 def test_context_manager(temp_tdms_file):
     """Test that context managers work correctly"""
     # Write using context manager
@@ -276,5 +315,117 @@ def test_defragment(temp_tdms_file):
             pass
 
 
+def test_to_csv(temp_tdms_file, tmp_path):
+    """Test exporting a file to one CSV per group"""
+    with tdms.TdmsWriter(temp_tdms_file) as writer:
+        writer.create_channel("Data", "Voltage", tdms.DataType.F64)
+        writer.create_channel("Data", "Count", tdms.DataType.I32)
+        writer.write_data("Data", "Voltage", np.array([1.5, 2.5]))
+        writer.write_data("Data", "Count", np.array([1, 2], dtype=np.int32))
+
+    written = tdms.to_csv(temp_tdms_file, str(tmp_path))
+    assert written == [str(tmp_path / "Data.csv")]
+    assert (tmp_path / "Data.csv").read_text() == "Count,Voltage\n1,1.5\n2,2.5\n"
+
+
+def test_to_parquet(temp_tdms_file, tmp_path):
+    """Test exporting a file to Parquet with ragged channels padded"""
+    pq = pytest.importorskip("pyarrow.parquet")
+
+    with tdms.TdmsWriter(temp_tdms_file) as writer:
+        writer.create_channel("Data", "Long", tdms.DataType.F64)
+        writer.create_channel("Data", "Short", tdms.DataType.I32)
+        writer.write_data("Data", "Long", np.array([1.0, 2.0, 3.0]))
+        writer.write_data("Data", "Short", np.array([7], dtype=np.int32))
+
+    out_path = str(tmp_path / "out.parquet")
+    tdms.to_parquet(temp_tdms_file, out_path)
+
+    table = pq.read_table(out_path)
+    assert table.column_names == ["Data/Long", "Data/Short"]
+    assert table.column("Data/Long").to_pylist() == [1.0, 2.0, 3.0]
+    assert table.column("Data/Short").to_pylist() == [7, None, None]
+
+
+def test_validate_and_repair(temp_tdms_file, tmp_path):
+    """Test health reports of an intact and a truncated file"""
+    with tdms.TdmsWriter(temp_tdms_file) as writer:
+        writer.create_channel("Data", "Values", tdms.DataType.I32)
+        for i in range(2):
+            writer.set_channel_property("Data", "Values", "iteration", i)
+            writer.write_data("Data", "Values", np.arange(i * 100, (i + 1) * 100, dtype=np.int32))
+            writer.flush()
+
+    report = tdms.validate(temp_tdms_file)
+    assert report["valid"]
+    assert report["segment_count"] == 2
+    assert report["incomplete_segment"] is None
+    assert tdms.check_conformance(temp_tdms_file) == report["conformance"]
+
+    # Cut the last segment short, as a crash mid-write would
+    truncated = str(tmp_path / "truncated.tdms")
+    with open(temp_tdms_file, "rb") as f:
+        content = f.read()
+    with open(truncated, "wb") as f:
+        f.write(content[:-40])
+
+    report = tdms.validate(truncated)
+    assert report["readable"]
+    assert not report["valid"]
+    assert report["incomplete_segment"] is not None
+    assert not report["conformance"]["conformant"]
+    assert report["conformance"]["issues"][0]["segment"] == 1
+
+    repaired = str(tmp_path / "repaired.tdms")
+    result = tdms.repair(truncated, repaired)
+    assert not result["source"]["valid"]
+    assert result["result"]["valid"]
+    with tdms.TdmsReader(repaired) as reader:
+        np.testing.assert_array_equal(reader.read_data("Data", "Values", dtype=np.int32)[:100],
+                                      np.arange(100, dtype=np.int32))
+
+
+def test_export_column_naming(temp_tdms_file, tmp_path):
+    """Test naming exported columns after properties, with units"""
+    with tdms.TdmsWriter(temp_tdms_file) as writer:
+        writer.create_channel("Data", "P1", tdms.DataType.F64)
+        writer.set_channel_property("Data", "P1", "NI_ChannelName", "Inlet")
+        writer.set_channel_property("Data", "P1", "unit_string", "bar")
+        writer.create_channel("Data", "T1", tdms.DataType.F64)
+        writer.write_data("Data", "P1", np.array([1.5]))
+        writer.write_data("Data", "T1", np.array([20.0]))
+
+    tdms.to_csv(temp_tdms_file, str(tmp_path), name_property="NI_ChannelName", include_units=True)
+    assert (tmp_path / "Data.csv").read_text().splitlines()[0] == "Inlet [bar],T1"
+
+    assert tdms.column_name("P1", {"NI_ChannelName": "Inlet"}, "NI_ChannelName") == "Inlet"
+    assert tdms.column_name("P1", {"unit_string": "bar"}, include_units=True) == "P1 [bar]"
+    assert tdms.column_name("P1", None, "NI_ChannelName", True) == "P1"
+
+
+def test_rotating_writer_naming(tmp_path):
+    """Test naming rotated files from a template"""
+    base = str(tmp_path / "capture.tdms")
+    with tdms.RotatingTdmsWriter(base, 1024, naming="{stem}_{index:3}.tdms") as writer:
+        writer.create_channel("Data", "Values", tdms.DataType.F64)
+        for i in range(3):
+            writer.write_data("Data", "Values", np.arange(i * 200, (i + 1) * 200, dtype=np.float64))
+            writer.flush()
+
+    files = sorted(p.name for p in tmp_path.glob("*.tdms"))
+    assert files[:2] == ["capture_000.tdms", "capture_001.tdms"]
+    values = []
+    for name in files:
+        with tdms.TdmsReader(str(tmp_path / name)) as reader:
+            values.append(reader.read_data("Data", "Values", dtype=np.float64))
+    np.testing.assert_array_equal(np.concatenate(values), np.arange(600, dtype=np.float64))
+
+    # Templates don't overwrite files that are already there
+    with pytest.raises(RuntimeError):
+        tdms.RotatingTdmsWriter(base, 1024, naming="{stem}_{index:3}.tdms")
+    with pytest.raises(RuntimeError):
+        tdms.RotatingTdmsWriter(base, 1024, naming="{stem}.tdms")
+
+
 if __name__ == "__main__":
-    pytest.main([__file__, "-v"])
\ No newline at end of file
+    pytest.main([__file__, "-v"])
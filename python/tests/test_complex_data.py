@@ -33,3 +33,35 @@ def test_write_and_read_timestamps(temp_tdms_file):
         assert len(read_timestamps) == 10
         assert all(isinstance(ts, np.datetime64) for ts in read_timestamps)
         np.testing.assert_array_equal(read_timestamps, timestamps)
+
+
+def test_read_xy(temp_tdms_file):
+    """Test reading a timestamp channel and a value channel together"""
+    start_time = np.datetime64('2024-01-01T12:00:00', 'ns')
+    timestamps = start_time + np.arange(5, dtype='timedelta64[ms]')
+    values = np.linspace(0.0, 1.0, 5)
+
+    with tdms.TdmsWriter(temp_tdms_file) as writer:
+        writer.create_channel("Data", "Time", tdms.DataType.TIMESTAMP)
+        writer.create_channel("Data", "Voltage", tdms.DataType.F64)
+        writer.create_channel("Data", "Short", tdms.DataType.F64)
+        writer.write_data("Data", "Time", timestamps)
+        writer.write_data("Data", "Voltage", values)
+        writer.write_data("Data", "Short", values[:3])
+
+    with tdms.TdmsReader(temp_tdms_file) as reader:
+        times, volts = reader.read_xy("Data", "Time", "Voltage")
+        np.testing.assert_array_equal(times, timestamps)
+        np.testing.assert_array_equal(volts, values)
+
+        xy = reader.read_xy("Data", "Time", "Voltage", structured=True)
+        assert xy.dtype.names == ("time", "value")
+        np.testing.assert_array_equal(xy["time"], timestamps)
+
+        with pytest.raises(ValueError):
+            reader.read_xy("Data", "Time", "Short")
+        times, short = reader.read_xy("Data", "Time", "Short", truncate=True)
+        assert len(times) == len(short) == 3
+
+        with pytest.raises(TypeError):
+            reader.read_xy("Data", "Voltage", "Time")
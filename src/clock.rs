@@ -0,0 +1,92 @@
+// src/clock.rs
+//! Time sources for writers.
+//!
+//! Anything the library stamps with the current time goes through a [`Clock`].
+//! The default is [`SystemClock`]; tests and simulations can substitute a
+//! [`ManualClock`], and production systems can plug in a PTP/GPS-disciplined
+//! source by implementing the trait.
+
+use crate::types::Timestamp;
+use parking_lot::Mutex;
+use std::time::Duration;
+
+/// A source of the current time
+pub trait Clock: Send + Sync {
+    /// The current time as a TDMS timestamp
+    fn now(&self) -> Timestamp;
+}
+
+/// The operating system wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::now()
+    }
+}
+
+/// A clock that only moves when told to
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use tdms_rs::clock::{Clock, ManualClock};
+/// use tdms_rs::Timestamp;
+///
+/// let clock = Arc::new(ManualClock::new(Timestamp { seconds: 3_700_000_000, fractions: 0 }));
+/// clock.advance(Duration::from_millis(500));
+/// assert_eq!(clock.now().fractions, 1u64 << 63);
+/// ```
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Mutex<Timestamp>,
+}
+
+impl ManualClock {
+    pub fn new(start: Timestamp) -> Self {
+        ManualClock { now: Mutex::new(start) }
+    }
+
+    /// Jump to an absolute time
+    pub fn set(&self, time: Timestamp) {
+        *self.now.lock() = time;
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock();
+        let fractions = (by.subsec_nanos() as u128 * (1u128 << 64) / 1_000_000_000) as u64;
+        let (fractions, carry) = now.fractions.overflowing_add(fractions);
+        now.fractions = fractions;
+        now.seconds += by.as_secs() as i64 + carry as i64;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Timestamp {
+        *self.now.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_advance_carries() {
+        let clock = ManualClock::new(Timestamp { seconds: 10, fractions: 3u64 << 62 });
+        clock.advance(Duration::from_millis(500));
+        let now = clock.now();
+        assert_eq!(now.seconds, 11);
+        assert_eq!(now.fractions, 1u64 << 62);
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(clock.now().seconds, 16);
+
+        clock.set(Timestamp::default());
+        assert_eq!(clock.now(), Timestamp::default());
+    }
+}
@@ -0,0 +1,113 @@
+// src/codec/delta_varint.rs
+//! Delta + zigzag + LEB128 varint encoding for integer channels.
+//!
+//! Each value is stored as the difference from its predecessor, zigzag-mapped so
+//! small negative steps stay small, then written as a variable-length integer.
+//! A counter or status word that rarely changes costs about one byte per value.
+
+use crate::error::{TdmsError, Result};
+
+pub(super) fn encode(raw: &[u8], elem_size: usize, signed: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len() / elem_size + 16);
+    let mut prev = 0u64;
+
+    for value in raw.chunks_exact(elem_size) {
+        let value = widen(value, signed);
+        let delta = value.wrapping_sub(prev) as i64;
+        write_varint(&mut out, ((delta << 1) ^ (delta >> 63)) as u64);
+        prev = value;
+    }
+    out
+}
+
+pub(super) fn decode(payload: &[u8], elem_size: usize, value_count: usize) -> Result<Vec<u8>> {
+    // Every value takes at least one byte, which bounds the allocation by
+    // the payload rather than by the count the file declares
+    if value_count > payload.len() {
+        return Err(corrupt());
+    }
+    let mut out = Vec::with_capacity(value_count.checked_mul(elem_size).ok_or_else(corrupt)?);
+    let mut prev = 0u64;
+    let mut pos = 0;
+
+    for _ in 0..value_count {
+        let zigzag = read_varint(payload, &mut pos)?;
+        let delta = ((zigzag >> 1) as i64) ^ -((zigzag & 1) as i64);
+        prev = prev.wrapping_add(delta as u64);
+        out.extend_from_slice(&prev.to_le_bytes()[..elem_size]);
+    }
+
+    if pos != payload.len() {
+        return Err(corrupt());
+    }
+    Ok(out)
+}
+
+/// Sign- or zero-extend a little-endian integer to 64 bits
+fn widen(bytes: &[u8], signed: bool) -> u64 {
+    let mut buf = [0u8; 8];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    if signed && bytes.last().is_some_and(|b| b & 0x80 != 0) {
+        buf[bytes.len()..].fill(0xFF);
+    }
+    u64::from_le_bytes(buf)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(payload: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *payload.get(*pos).ok_or_else(corrupt)?;
+        *pos += 1;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(corrupt())
+}
+
+fn corrupt() -> TdmsError {
+    TdmsError::CorruptData("delta-varint payload".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_roundtrip() {
+        let values: Vec<i16> = vec![0, -1, 5, i16::MIN, i16::MAX, -300, -300, 7];
+        let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let encoded = encode(&raw, 2, true);
+        assert_eq!(decode(&encoded, 2, values.len()).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_unsigned_extremes_and_size() {
+        let values: Vec<u64> = vec![u64::MAX, 0, 1, u64::MAX / 2, u64::MAX];
+        let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert_eq!(decode(&encode(&raw, 8, false), 8, values.len()).unwrap(), raw);
+
+        // A slowly-changing status word: one byte per value
+        let status: Vec<u8> = (0..1000u32).flat_map(|i| (0x8000_0000u32 | (i / 250)).to_le_bytes()).collect();
+        let encoded = encode(&status, 4, false);
+        assert!(encoded.len() < 1010, "{}", encoded.len());
+        assert_eq!(decode(&encoded, 4, 1000).unwrap(), status);
+    }
+
+    #[test]
+    fn test_corrupt_payload() {
+        assert!(decode(&[0x80], 4, 1).is_err());
+        assert!(decode(&[0, 0], 4, 1).is_err());
+        assert!(matches!(decode(&[0], 4, usize::MAX / 2), Err(TdmsError::CorruptData(_))));
+        assert!(matches!(decode(&[0], 4, 1 << 40), Err(TdmsError::CorruptData(_))));
+    }
+}
@@ -0,0 +1,55 @@
+// src/codec/zstd.rs
+//! Byte-plane shuffle followed by zstd compression.
+//!
+//! The shuffle puts the n-th byte of every value next to each other, as in
+//! [`shuffle_rle`](super::shuffle_rle), which lets zstd find the redundancy in
+//! floating-point sensor data that it misses in interleaved values.
+
+use super::shuffle_rle::{shuffle, unshuffle};
+use crate::error::{TdmsError, Result};
+use std::io::Read;
+
+/// Compression level; zstd's default balances speed and ratio for archives
+const LEVEL: i32 = 3;
+
+pub(super) fn encode(raw: &[u8], elem_size: usize) -> Result<Vec<u8>> {
+    Ok(::zstd::bulk::compress(&shuffle(raw, elem_size), LEVEL)?)
+}
+
+pub(super) fn decode(payload: &[u8], elem_size: usize, value_count: usize) -> Result<Vec<u8>> {
+    let expected = value_count.checked_mul(elem_size).ok_or_else(corrupt)?;
+    // The value count comes from the file, so let the output grow with the
+    // decompressed data instead of allocating it up front
+    let mut shuffled = Vec::new();
+    ::zstd::stream::read::Decoder::new(payload)?
+        .take(expected as u64 + 1)
+        .read_to_end(&mut shuffled)
+        .map_err(|_| corrupt())?;
+    if shuffled.len() != expected {
+        return Err(corrupt());
+    }
+    Ok(unshuffle(&shuffled, elem_size))
+}
+
+fn corrupt() -> TdmsError {
+    TdmsError::CorruptData("zstd payload".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let values: Vec<f32> = (0..4096).map(|i| (i / 16) as f32 * 0.25).collect();
+        let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let encoded = encode(&raw, 4).unwrap();
+        assert!(encoded.len() < raw.len() / 2);
+        assert_eq!(decode(&encoded, 4, values.len()).unwrap(), raw);
+
+        // Value counts that disagree with the payload are rejected
+        assert!(decode(&encoded, 4, values.len() - 1).is_err());
+        assert!(decode(&encoded, 4, values.len() + 1).is_err());
+        assert!(decode(&encoded[..encoded.len() / 2], 4, values.len()).is_err());
+    }
+}
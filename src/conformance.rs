@@ -0,0 +1,479 @@
+// src/conformance.rs
+//! Structural validation of TDMS files.
+//!
+//! [`check_conformance`] walks a file segment by segment without going through
+//! [`TdmsReader`](crate::TdmsReader), which tolerates many deviations, and reports
+//! every place the file breaks the TDMS 2.0 rules this crate's writer upholds:
+//! lead-in arithmetic, table of contents flags, metadata layout and, if present,
+//! equivalence of the `.tdms_index` file. It is cheap enough to run as a
+//! self-test after writing.
+
+use crate::error::Result;
+use crate::metadata::ObjectPath;
+use crate::reader::daqmx;
+use crate::segment::{SegmentHeader, find_index_file};
+use crate::types::{DataType, TocFlags};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Which part of the format a [`ConformanceIssue`] concerns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConformanceRule {
+    /// Tag, version and offsets of a segment lead-in
+    LeadIn,
+    /// Table of contents flags agree with the segment contents
+    TableOfContents,
+    /// Object list, raw data indexes and properties
+    Metadata,
+    /// Raw data size agrees with the raw data indexes
+    RawData,
+    /// The `.tdms_index` file mirrors the data file
+    IndexFile,
+}
+
+impl fmt::Display for ConformanceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConformanceRule::LeadIn => "lead-in",
+            ConformanceRule::TableOfContents => "table of contents",
+            ConformanceRule::Metadata => "metadata",
+            ConformanceRule::RawData => "raw data",
+            ConformanceRule::IndexFile => "index file",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single violation found by [`check_conformance`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceIssue {
+    pub rule: ConformanceRule,
+    /// Segment the issue was found in, or `None` for file-level issues
+    pub segment: Option<usize>,
+    /// Byte offset in the data file (or index file, for index issues) of the segment
+    pub offset: u64,
+    pub message: String,
+}
+
+impl fmt::Display for ConformanceIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.segment {
+            Some(segment) => write!(f, "[{}] segment {} at {}: {}", self.rule, segment, self.offset, self.message),
+            None => write!(f, "[{}] at {}: {}", self.rule, self.offset, self.message),
+        }
+    }
+}
+
+/// Result of [`check_conformance`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConformanceReport {
+    /// Number of data file segments that were examined
+    pub segments_checked: usize,
+    /// Whether a `.tdms_index` file was found and compared
+    pub index_checked: bool,
+    pub issues: Vec<ConformanceIssue>,
+}
+
+impl ConformanceReport {
+    /// Whether no issues were found
+    pub fn is_conformant(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Issues concerning one rule
+    pub fn issues_for(&self, rule: ConformanceRule) -> impl Iterator<Item = &ConformanceIssue> {
+        self.issues.iter().filter(move |issue| issue.rule == rule)
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_conformant() {
+            return write!(f, "{} segment(s) conformant", self.segments_checked);
+        }
+        write!(f, "{} issue(s) in {} segment(s)", self.issues.len(), self.segments_checked)?;
+        for issue in &self.issues {
+            write!(f, "\n  {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check a TDMS file (and its `.tdms_index`, if one exists next to it) against
+/// the structural rules of the TDMS 2.0 format.
+///
+/// Only I/O failures are returned as errors; everything wrong with the file's
+/// contents is reported in the [`ConformanceReport`].
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::conformance::check_conformance;
+///
+/// let report = check_conformance("output.tdms").unwrap();
+/// assert!(report.is_conformant(), "{}", report);
+/// ```
+pub fn check_conformance(path: impl AsRef<Path>) -> Result<ConformanceReport> {
+    let path = path.as_ref();
+    let mut report = ConformanceReport::default();
+
+    let data_segments = check_data_file(path, &mut report)?;
+    report.segments_checked = data_segments.len();
+
+    if let Some(index_path) = find_index_file(path) {
+        report.index_checked = true;
+        check_index_file(&index_path, &data_segments, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+/// Lead-in and metadata bytes of a segment, kept for comparison with the index file
+struct ScannedSegment {
+    /// Lead-in without the tag
+    lead_in: [u8; SegmentHeader::LEAD_IN_SIZE - 4],
+    metadata: Vec<u8>,
+}
+
+/// Per-channel raw data state carried between segments
+#[derive(Default)]
+struct ObjectState {
+    /// Bytes per chunk for the channel's last raw data index
+    chunk_size: Option<u64>,
+}
+
+fn check_data_file(path: &Path, report: &mut ConformanceReport) -> Result<Vec<ScannedSegment>> {
+    let mut file = BufReader::new(File::open(path)?);
+    let file_size = file.seek(SeekFrom::End(0))?;
+
+    let mut segments = Vec::new();
+    let mut objects: HashMap<ObjectPath, ObjectState> = HashMap::new();
+    let mut active: Vec<ObjectPath> = Vec::new();
+    let mut offset = 0u64;
+
+    while offset < file_size {
+        let index = segments.len();
+        let mut issue = |rule, message: String| report.issues.push(ConformanceIssue {
+            rule, segment: Some(index), offset, message,
+        });
+
+        if file_size - offset < SegmentHeader::LEAD_IN_SIZE as u64 {
+            issue(ConformanceRule::LeadIn, format!("{} trailing bytes are too short for a lead-in", file_size - offset));
+            break;
+        }
+
+        file.seek(SeekFrom::Start(offset))?;
+        let mut lead_in = [0u8; SegmentHeader::LEAD_IN_SIZE];
+        file.read_exact(&mut lead_in)?;
+
+        if &lead_in[0..4] != SegmentHeader::TDMS_TAG {
+            issue(ConformanceRule::LeadIn, format!("tag is {:?}, expected \"TDSm\"", String::from_utf8_lossy(&lead_in[0..4])));
+            break;
+        }
+
+        // The ToC is always little-endian; the rest of the lead-in follows it
+        let toc = TocFlags::new(LittleEndian::read_u32(&lead_in[4..8]));
+        let big_endian = toc.is_big_endian();
+        let (version, next_segment_offset, raw_data_offset) = if big_endian {
+            (BigEndian::read_u32(&lead_in[8..12]), BigEndian::read_u64(&lead_in[12..20]), BigEndian::read_u64(&lead_in[20..28]))
+        } else {
+            (LittleEndian::read_u32(&lead_in[8..12]), LittleEndian::read_u64(&lead_in[12..20]), LittleEndian::read_u64(&lead_in[20..28]))
+        };
+
+        if version != 4712 && version != SegmentHeader::VERSION {
+            issue(ConformanceRule::LeadIn, format!("version is {}, expected 4712 or 4713", version));
+        }
+
+        if next_segment_offset == SegmentHeader::INCOMPLETE_MARKER {
+            issue(ConformanceRule::LeadIn, "segment was never completed (next segment offset is 0xFFFFFFFFFFFFFFFF)".into());
+            break;
+        }
+        if raw_data_offset > next_segment_offset {
+            issue(ConformanceRule::LeadIn, format!(
+                "raw data offset {} exceeds next segment offset {}", raw_data_offset, next_segment_offset));
+            break;
+        }
+        let segment_end = offset
+            .checked_add(SegmentHeader::LEAD_IN_SIZE as u64)
+            .and_then(|start| start.checked_add(next_segment_offset));
+        let Some(segment_end) = segment_end.filter(|end| *end <= file_size) else {
+            issue(ConformanceRule::LeadIn, format!(
+                "segment length {} runs past the end of the file ({} bytes)", next_segment_offset, file_size));
+            break;
+        };
+
+        let raw_size = next_segment_offset - raw_data_offset;
+
+        if index == 0 && !toc.has_new_obj_list() {
+            issue(ConformanceRule::TableOfContents, "first segment does not set kTocNewObjList".into());
+        }
+        if toc.has_metadata() != (raw_data_offset > 0) {
+            issue(ConformanceRule::TableOfContents, format!(
+                "kTocMetaData is {} but the metadata is {} bytes", flag(toc.has_metadata()), raw_data_offset));
+        }
+        if toc.has_raw_data() != (raw_size > 0) {
+            issue(ConformanceRule::TableOfContents, format!(
+                "kTocRawData is {} but the raw data is {} bytes", flag(toc.has_raw_data()), raw_size));
+        }
+
+        let mut metadata = vec![0u8; raw_data_offset as usize];
+        file.read_exact(&mut metadata)?;
+
+        if toc.has_new_obj_list() {
+            active.clear();
+        }
+        let mut has_daqmx = toc.has_daqmx_data();
+        if toc.has_metadata() {
+            let mut cursor = MetaCursor { data: &metadata, pos: 0, big_endian };
+            match check_metadata(&mut cursor, &mut objects, &mut active, &mut has_daqmx) {
+                Ok(()) if cursor.pos != metadata.len() => issue(ConformanceRule::Metadata, format!(
+                    "metadata parses to {} bytes but the lead-in declares {}", cursor.pos, metadata.len())),
+                Ok(()) => {}
+                Err(message) => issue(ConformanceRule::Metadata, message),
+            }
+        }
+
+        if toc.has_raw_data() && !has_daqmx {
+            let chunk_size: u64 = active.iter()
+                .filter_map(|path| objects.get(path).and_then(|state| state.chunk_size))
+                .sum();
+            if chunk_size == 0 {
+                issue(ConformanceRule::RawData, format!("{} bytes of raw data but no channel has a raw data index", raw_size));
+            } else if raw_size % chunk_size != 0 {
+                issue(ConformanceRule::RawData, format!(
+                    "raw data size {} is not a multiple of the chunk size {}", raw_size, chunk_size));
+            }
+        }
+
+        let mut tail = [0u8; SegmentHeader::LEAD_IN_SIZE - 4];
+        tail.copy_from_slice(&lead_in[4..]);
+        segments.push(ScannedSegment { lead_in: tail, metadata });
+        offset = segment_end;
+    }
+
+    Ok(segments)
+}
+
+fn flag(set: bool) -> &'static str {
+    if set { "set" } else { "clear" }
+}
+
+/// Walk one segment's metadata block, updating the object list
+fn check_metadata(
+    cursor: &mut MetaCursor<'_>,
+    objects: &mut HashMap<ObjectPath, ObjectState>,
+    active: &mut Vec<ObjectPath>,
+    has_daqmx: &mut bool,
+) -> std::result::Result<(), String> {
+    let object_count = cursor.u32()?;
+    for _ in 0..object_count {
+        let path_string = cursor.string()?;
+        let path = ObjectPath::from_string(&path_string)
+            .map_err(|_| format!("invalid object path {:?}", path_string))?;
+        let is_channel = matches!(path, ObjectPath::Channel { .. });
+
+        let index_length = cursor.u32()?;
+        let chunk_size = match index_length {
+            0xFFFF_FFFF => Some(None),
+            0 => {
+                let previous = objects.get(&path).and_then(|state| state.chunk_size);
+                if previous.is_none() {
+                    return Err(format!("{} reuses a raw data index it never had", path));
+                }
+                Some(previous)
+            }
+            header if daqmx::is_unknown_header(header) => {
+                return Err(format!("{} uses unsupported DAQmx raw data index {:#010x}", path, header));
+            }
+            daqmx::FORMAT_CHANGING_SCALER | daqmx::DIGITAL_LINE_SCALER => {
+                skip_daqmx_index(cursor, index_length)?;
+                *has_daqmx = true;
+                Some(None)
+            }
+            _ => {
+                let start = cursor.pos;
+                let data_type_raw = cursor.u32()?;
+                let data_type = DataType::from_u32(data_type_raw)
+                    .ok_or_else(|| format!("{} has unknown data type {:#x}", path, data_type_raw))?;
+                let dimension = cursor.u32()?;
+                if dimension != 1 {
+                    return Err(format!("{} has array dimension {}, expected 1", path, dimension));
+                }
+                let count = cursor.u64()?;
+                let size = match data_type {
+                    DataType::String => cursor.u64()?,
+                    _ => count * data_type.fixed_size().unwrap_or(0) as u64,
+                };
+                let expected = if data_type == DataType::String { 28 } else { 20 };
+                if index_length != expected {
+                    return Err(format!(
+                        "{} raw data index length is {}, expected {} (the length includes its own 4 bytes)",
+                        path, index_length, expected));
+                }
+                debug_assert_eq!(cursor.pos - start + 4, expected as usize);
+                Some(Some(size))
+            }
+        };
+
+        if !is_channel && index_length != 0xFFFF_FFFF {
+            return Err(format!("{} is not a channel but has a raw data index", path));
+        }
+
+        let property_count = cursor.u32()?;
+        for _ in 0..property_count {
+            skip_property(cursor, &path)?;
+        }
+
+        if let Some(chunk_size) = chunk_size {
+            objects.entry(path.clone()).or_default().chunk_size = chunk_size;
+        }
+        if is_channel && !active.contains(&path) {
+            active.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn skip_daqmx_index(cursor: &mut MetaCursor<'_>, header: u32) -> std::result::Result<(), String> {
+    cursor.skip(16)?; // data type, dimension, value count
+    let scaler_count = cursor.u32()?;
+    let scaler_size = if header == daqmx::DIGITAL_LINE_SCALER { 17 } else { 20 };
+    cursor.skip(scaler_count as usize * scaler_size)?;
+    let width_count = cursor.u32()?;
+    cursor.skip(width_count as usize * 4)
+}
+
+fn skip_property(cursor: &mut MetaCursor<'_>, path: &ObjectPath) -> std::result::Result<(), String> {
+    let name = cursor.string()?;
+    let data_type_raw = cursor.u32()?;
+    let data_type = DataType::from_u32(data_type_raw)
+        .ok_or_else(|| format!("property '{}' of {} has unknown data type {:#x}", name, path, data_type_raw))?;
+    match data_type {
+        DataType::String => {
+            cursor.string()?;
+        }
+        DataType::DAQmxRawData | DataType::Void => {
+            return Err(format!("property '{}' of {} has data type {:?}", name, path, data_type));
+        }
+        _ => cursor.skip(data_type.fixed_size().unwrap_or(0))?,
+    }
+    Ok(())
+}
+
+fn check_index_file(index_path: &Path, data_segments: &[ScannedSegment], report: &mut ConformanceReport) -> Result<()> {
+    let mut file = BufReader::new(File::open(index_path)?);
+    let file_size = file.seek(SeekFrom::End(0))?;
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut offset = 0u64;
+    let mut index = 0usize;
+    while offset < file_size {
+        let mut issue = |message: String| report.issues.push(ConformanceIssue {
+            rule: ConformanceRule::IndexFile, segment: Some(index), offset, message,
+        });
+
+        let Some(data_segment) = data_segments.get(index) else {
+            issue("index file has more segments than the data file".into());
+            return Ok(());
+        };
+        if file_size - offset < SegmentHeader::LEAD_IN_SIZE as u64 {
+            issue(format!("{} trailing bytes are too short for a lead-in", file_size - offset));
+            return Ok(());
+        }
+
+        let mut lead_in = [0u8; SegmentHeader::LEAD_IN_SIZE];
+        file.read_exact(&mut lead_in)?;
+        if &lead_in[0..4] != SegmentHeader::INDEX_TAG {
+            issue(format!("tag is {:?}, expected \"TDSh\"", String::from_utf8_lossy(&lead_in[0..4])));
+            return Ok(());
+        }
+        if lead_in[4..] != data_segment.lead_in {
+            issue("lead-in differs from the data file".into());
+        }
+
+        let metadata_len = data_segment.metadata.len() as u64;
+        if file_size - offset - (SegmentHeader::LEAD_IN_SIZE as u64) < metadata_len {
+            issue("metadata is truncated".into());
+            return Ok(());
+        }
+        let mut metadata = vec![0u8; metadata_len as usize];
+        file.read_exact(&mut metadata)?;
+        if metadata != data_segment.metadata {
+            issue("metadata differs from the data file".into());
+        }
+
+        offset += SegmentHeader::LEAD_IN_SIZE as u64 + metadata_len;
+        index += 1;
+    }
+
+    if index < data_segments.len() {
+        report.issues.push(ConformanceIssue {
+            rule: ConformanceRule::IndexFile,
+            segment: None,
+            offset,
+            message: format!("index file has {} segments, data file has {}", index, data_segments.len()),
+        });
+    }
+    Ok(())
+}
+
+/// Bounds-checked reader over a metadata block
+struct MetaCursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl MetaCursor<'_> {
+    fn take(&mut self, len: usize) -> std::result::Result<&[u8], String> {
+        let end = self.pos.checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| format!("metadata ends inside a field at byte {}", self.pos))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> std::result::Result<(), String> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u32(&mut self) -> std::result::Result<u32, String> {
+        let big_endian = self.big_endian;
+        let bytes = self.take(4)?;
+        Ok(if big_endian { BigEndian::read_u32(bytes) } else { LittleEndian::read_u32(bytes) })
+    }
+
+    fn u64(&mut self) -> std::result::Result<u64, String> {
+        let big_endian = self.big_endian;
+        let bytes = self.take(8)?;
+        Ok(if big_endian { BigEndian::read_u64(bytes) } else { LittleEndian::read_u64(bytes) })
+    }
+
+    fn string(&mut self) -> std::result::Result<String, String> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| "string is not valid UTF-8".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_cursor_bounds() {
+        let data = [3u8, 0, 0, 0, b'a', b'b'];
+        let mut cursor = MetaCursor { data: &data, pos: 0, big_endian: false };
+        assert!(cursor.string().is_err());
+
+        let data = [0u8, 0, 0, 2, b'h', b'i'];
+        let mut cursor = MetaCursor { data: &data, pos: 0, big_endian: true };
+        assert_eq!(cursor.string().unwrap(), "hi");
+        assert!(cursor.u32().is_err());
+    }
+}
@@ -0,0 +1,527 @@
+// src/defrag.rs
+//! Rewriting a file into a compact copy.
+//!
+//! [`defragment`] reads every object of a file and writes it back as a single
+//! segment, with each channel's data contiguous. [`defragment_with`] runs the
+//! same copy with [`DefragOptions`]: channels and groups can be selected or
+//! dropped, DAQmx channels baked into plain scaled channels, properties
+//! edited on the way, huge channels copied in bounded chunks, progress
+//! reported and the result verified against the source.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use tdms_rs::defrag::{defragment_with, DefragOptions};
+//! use tdms_rs::{ObjectPath, PropertyValue};
+//!
+//! let options = DefragOptions::new()
+//!     .exclude_group("Diagnostics")
+//!     .set_property(ObjectPath::Root, "cleaned", PropertyValue::Boolean(true))
+//!     .remove_property(ObjectPath::Root, "operator")
+//!     .chunk_values(Some(1_000_000))
+//!     .verify(true)
+//!     .on_progress(|progress| println!("{}/{} {}", progress.channels_done, progress.channels_total, progress.channel));
+//! let report = defragment_with("raw.tdms", "clean.tdms", &options).unwrap();
+//! println!("copied {} values", report.values_copied);
+//! ```
+
+use crate::codec;
+use crate::diff::{self, DiffOptions, Difference};
+use crate::error::{TdmsError, Result};
+use crate::metadata::ObjectPath;
+use crate::properties;
+use crate::reader::{ChannelReader, TdmsReader};
+use crate::types::{Complex, DataType, F80, Property, PropertyValue, TdmsValue, Timestamp};
+use crate::writer::TdmsWriter;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+type FileReader = TdmsReader<BufReader<File>>;
+
+/// Where a [`defragment_with`] run is, reported after every chunk of values
+/// and once more when a channel is complete
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefragProgress {
+    /// The channel being copied
+    pub channel: ObjectPath,
+    /// Values of the channel copied so far, and in total
+    pub values_copied: u64,
+    pub values_total: u64,
+    /// Channels finished so far, counting this one once it is complete, and
+    /// channels to copy in total
+    pub channels_done: usize,
+    pub channels_total: usize,
+}
+
+/// Summary of a [`defragment_with`] run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DefragReport {
+    /// Channels written to the new file, in the order they were copied
+    pub channels: Vec<ObjectPath>,
+    /// Channels left out of the new file because their data can't be copied
+    /// as it is, i.e. DAQmx raw data without
+    /// [`DefragOptions::bake_daqmx_scaling`]
+    pub skipped: Vec<ObjectPath>,
+    pub values_copied: u64,
+}
+
+type ProgressCallback = Arc<dyn Fn(&DefragProgress) + Send + Sync>;
+
+/// How [`defragment_with`] rewrites a file
+#[derive(Clone, Default)]
+pub struct DefragOptions {
+    included_groups: HashSet<String>,
+    included_channels: HashSet<ObjectPath>,
+    excluded_groups: HashSet<String>,
+    excluded_channels: HashSet<ObjectPath>,
+    bake_daqmx_scaling: bool,
+    // `None` removes the property
+    property_edits: HashMap<ObjectPath, Vec<(String, Option<PropertyValue>)>>,
+    chunk_values: Option<usize>,
+    verify: bool,
+    progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for DefragOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DefragOptions")
+            .field("included_groups", &self.included_groups)
+            .field("included_channels", &self.included_channels)
+            .field("excluded_groups", &self.excluded_groups)
+            .field("excluded_channels", &self.excluded_channels)
+            .field("bake_daqmx_scaling", &self.bake_daqmx_scaling)
+            .field("property_edits", &self.property_edits)
+            .field("chunk_values", &self.chunk_values)
+            .field("verify", &self.verify)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl DefragOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy only the included channels and groups. Once anything is included,
+    /// channels that aren't, and aren't in an included group, are dropped.
+    /// Exclusions still apply on top.
+    pub fn include_channel(mut self, group: impl Into<String>, channel: impl Into<String>) -> Self {
+        self.included_channels.insert(ObjectPath::Channel { group: group.into(), channel: channel.into() });
+        self
+    }
+
+    /// Copy a group with all of its channels (see [`include_channel`](Self::include_channel))
+    pub fn include_group(mut self, group: impl Into<String>) -> Self {
+        self.included_groups.insert(group.into());
+        self
+    }
+
+    /// Drop a channel with its properties and data
+    pub fn exclude_channel(mut self, group: impl Into<String>, channel: impl Into<String>) -> Self {
+        self.excluded_channels.insert(ObjectPath::Channel { group: group.into(), channel: channel.into() });
+        self
+    }
+
+    /// Drop a group with its properties and all of its channels
+    pub fn exclude_group(mut self, group: impl Into<String>) -> Self {
+        self.excluded_groups.insert(group.into());
+        self
+    }
+
+    /// Write DAQmx raw data channels as standard `DoubleFloat` channels with
+    /// their `NI_Scale[n]_*` scaling applied, for readers without DAQmx
+    /// support.
+    ///
+    /// The scaling properties are kept for reference and `NI_Scaling_Status`
+    /// is set to `"scaled"`, so scaling-aware readers don't apply them again.
+    /// A scale type this crate can't evaluate fails the defragment with
+    /// [`TdmsError::Unsupported`].
+    pub fn bake_daqmx_scaling(mut self, bake: bool) -> Self {
+        self.bake_daqmx_scaling = bake;
+        self
+    }
+
+    /// Set a property of the file (`ObjectPath::Root`), a group or a channel
+    /// in the copy, replacing the source's value if it has one
+    pub fn set_property(mut self, path: ObjectPath, name: impl Into<String>, value: PropertyValue) -> Self {
+        self.property_edits.entry(path).or_default().push((name.into(), Some(value)));
+        self
+    }
+
+    /// Leave a property of the file, a group or a channel out of the copy
+    pub fn remove_property(mut self, path: ObjectPath, name: impl Into<String>) -> Self {
+        self.property_edits.entry(path).or_default().push((name.into(), None));
+        self
+    }
+
+    /// Copy at most this many values of a channel at a time, committing each
+    /// chunk before reading the next, or `None` (the default) to copy every
+    /// channel in one piece into a single segment.
+    ///
+    /// Bounds memory use for channels too big to load at once. Full chunks of
+    /// a channel are appended to one segment, but each channel and each
+    /// channel's last chunk start a new one. DAQmx channels being baked are
+    /// always copied in one piece.
+    pub fn chunk_values(mut self, values: Option<usize>) -> Self {
+        self.chunk_values = values.filter(|&values| values > 0);
+        self
+    }
+
+    /// After writing, read the copy back and compare every copied channel's
+    /// values with the source, failing with [`TdmsError::VerificationFailed`]
+    /// on any difference. Baked DAQmx channels are not compared.
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Call `progress` as channels are copied
+    pub fn on_progress(mut self, progress: impl Fn(&DefragProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    fn includes_group(&self, group: &str) -> bool {
+        !self.excluded_groups.contains(group)
+            && (!self.selects()
+                || self.included_groups.contains(group)
+                || self.included_channels.iter().any(|path| path.group() == Some(group)))
+    }
+
+    fn includes_channel(&self, path: &ObjectPath) -> bool {
+        let group = path.group().unwrap_or_default();
+        !self.excluded_groups.contains(group)
+            && !self.excluded_channels.contains(path)
+            && (!self.selects() || self.included_groups.contains(group) || self.included_channels.contains(path))
+    }
+
+    fn selects(&self) -> bool {
+        !self.included_groups.is_empty() || !self.included_channels.is_empty()
+    }
+
+    /// An object's properties with the edits for it applied
+    fn edited_properties<'a>(&self, path: &ObjectPath, source: impl IntoIterator<Item = &'a Property>) -> Vec<Property> {
+        let mut properties: Vec<Property> = source.into_iter().cloned().collect();
+        for (name, value) in self.property_edits.get(path).into_iter().flatten() {
+            properties.retain(|property| &property.name != name);
+            if let Some(value) = value {
+                properties.push(Property::new(name.clone(), value.clone()));
+            }
+        }
+        properties
+    }
+}
+
+/// Defragments a TDMS file by reading it and writing a new, optimized file.
+///
+/// This function reads all metadata and raw data from the `source_path`
+/// and writes it into a new TDMS file at `dest_path`. The new file will
+/// contain only one segment, with all metadata consolidated and all
+/// channel data stored in contiguous blocks.
+///
+/// This is useful for optimizing files for read speed or enabling
+/// zero-copy memory mapping, as fragmented channels will be made contiguous.
+/// Encoded channels (see [`crate::codec`]) are copied with their encoding.
+///
+/// # Arguments
+///
+/// * `source_path` - The path to the fragmented TDMS file to read.
+/// * `dest_path` - The path where the new, defragmented TDMS file will be created.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::defragment;
+///
+/// fn main() -> tdms_rs::Result<()> {
+///     defragment("my_fragmented_file.tdms", "my_new_file.tdms")?;
+///     Ok(())
+/// }
+/// ```
+pub fn defragment(source_path: impl AsRef<Path>, dest_path: impl AsRef<Path>) -> Result<()> {
+    defragment_with(source_path, dest_path, &DefragOptions::default())?;
+    Ok(())
+}
+
+/// Defragment a file like [`defragment`], as configured by `options`.
+///
+/// Excluding or including names that aren't in the file is not an error.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::{defragment_with, DefragOptions};
+///
+/// let options = DefragOptions::new()
+///     .exclude_group("Diagnostics")
+///     .exclude_channel("Rack", "Scratch");
+/// defragment_with("raw.tdms", "clean.tdms", &options).unwrap();
+/// ```
+pub fn defragment_with(source_path: impl AsRef<Path>, dest_path: impl AsRef<Path>,
+                       options: &DefragOptions) -> Result<DefragReport> {
+    let mut reader = TdmsReader::open(source_path.as_ref())?;
+    let mut writer = TdmsWriter::create(dest_path.as_ref())?;
+    let mut report = DefragReport::default();
+
+    let file_properties = options.edited_properties(&ObjectPath::Root, reader.get_file_properties().values());
+    writer.set_file_properties(file_properties);
+
+    for group in reader.list_groups() {
+        if !options.includes_group(&group) {
+            continue;
+        }
+        writer.create_group(group.clone())?;
+        let path = ObjectPath::Group(group.clone());
+        let properties = options.edited_properties(&path, reader.get_group_properties(&group).into_iter().flat_map(|p| p.values()));
+        for property in properties {
+            writer.set_group_property(group.clone(), property.name, property.value);
+        }
+    }
+
+    let channels: Vec<(ObjectPath, ChannelReader)> = reader.list_channels().into_iter()
+        .filter_map(|key| Some((ObjectPath::from_string(&key).ok()?, reader.get_channel(&key)?)))
+        .filter(|(path, _)| options.includes_channel(path))
+        .collect();
+    let mut baked = HashSet::new();
+    for (done, (path, channel_reader)) in channels.iter().enumerate() {
+        let ObjectPath::Channel { group, channel } = path else { continue };
+        let mut progress = DefragProgress {
+            channel: path.clone(),
+            values_copied: 0,
+            values_total: channel_reader.total_values(),
+            channels_done: done,
+            channels_total: channels.len(),
+        };
+
+        if options.bake_daqmx_scaling && channel_reader.is_daqmx() {
+            let values = read_daqmx_scaled(&mut reader, channel_reader, group, channel)?;
+            writer.create_channel(group.clone(), channel.clone(), DataType::DoubleFloat)?;
+            let mut properties: Vec<Property> = channel_reader.get_properties().values().cloned().collect();
+            if properties::Scaling::from_properties(channel_reader.get_properties()).is_some() {
+                properties.push(Property::new(properties::scaling::NI_SCALING_STATUS,
+                                              PropertyValue::String("scaled".into())));
+            }
+            writer.set_channel_properties(group, channel, options.edited_properties(path, &properties))?;
+            writer.write_channel_data(group, channel, &values)?;
+            baked.insert(path.clone());
+            progress.values_copied = values.len() as u64;
+        } else if channel_reader.data_type() == DataType::DAQmxRawData {
+            report.skipped.push(path.clone());
+            continue;
+        } else {
+            // Encoded channels keep their encoding, which sets its own markers
+            let encoding = reader.channel_encoding(group, channel)?;
+            let data_type = match encoding {
+                Some((encoding, data_type)) => {
+                    writer.create_encoded_channel(group.clone(), channel.clone(), data_type, encoding)?;
+                    data_type
+                }
+                None => {
+                    writer.create_channel(group.clone(), channel.clone(), channel_reader.data_type())?;
+                    channel_reader.data_type()
+                }
+            };
+            let source_properties = channel_reader.get_properties().values()
+                .filter(|property| property.name != codec::ENCODING_PROPERTY && property.name != codec::ORIGINAL_TYPE_PROPERTY);
+            let properties = options.edited_properties(path, source_properties);
+            writer.set_channel_properties(group, channel, properties)?;
+            let mut copy = ChannelCopy { reader: &mut reader, writer: &mut writer, options, progress: &mut progress };
+            match data_type {
+                DataType::String => copy.strings()?,
+                DataType::I8 => copy.values::<i8>()?,
+                DataType::I16 => copy.values::<i16>()?,
+                DataType::I32 => copy.values::<i32>()?,
+                DataType::I64 => copy.values::<i64>()?,
+                DataType::U8 => copy.values::<u8>()?,
+                DataType::U16 => copy.values::<u16>()?,
+                DataType::U32 => copy.values::<u32>()?,
+                DataType::U64 => copy.values::<u64>()?,
+                DataType::SingleFloat => copy.values::<f32>()?,
+                DataType::DoubleFloat => copy.values::<f64>()?,
+                DataType::ExtendedFloat => copy.values::<F80>()?,
+                DataType::Boolean => copy.values::<bool>()?,
+                DataType::TimeStamp => copy.values::<Timestamp>()?,
+                DataType::ComplexSingleFloat => copy.values::<Complex<f32>>()?,
+                DataType::ComplexDoubleFloat => copy.values::<Complex<f64>>()?,
+                // Void channels have no values, raw DAQmx channels are skipped above
+                DataType::Void | DataType::DAQmxRawData => {}
+            }
+        }
+
+        report.values_copied += progress.values_copied;
+        report.channels.push(path.clone());
+        progress.channels_done = done + 1;
+        if let Some(callback) = &options.progress {
+            callback(&progress);
+        }
+    }
+
+    // Without chunking this writes all buffered data as a single, contiguous segment
+    writer.close()?;
+
+    if options.verify {
+        verify(source_path.as_ref(), dest_path.as_ref(), &report.channels, &baked)?;
+    }
+    Ok(report)
+}
+
+/// Copies one channel from the source to the new file
+struct ChannelCopy<'a> {
+    reader: &'a mut FileReader,
+    writer: &'a mut TdmsWriter,
+    options: &'a DefragOptions,
+    progress: &'a mut DefragProgress,
+}
+
+impl ChannelCopy<'_> {
+    fn values<T: TdmsValue>(&mut self) -> Result<()> {
+        let (group, channel) = self.names();
+        let Some(chunk) = self.options.chunk_values else {
+            // Read the whole channel (this concatenates all fragments) and write it in one go
+            let data: Vec<T> = self.reader.read_channel_data(&group, &channel)?;
+            self.writer.write_channel_data(&group, &channel, &data)?;
+            self.progress.values_copied = data.len() as u64;
+            return Ok(());
+        };
+        loop {
+            let data: Vec<T> = self.reader.read_channel_range(&group, &channel, self.progress.values_copied, chunk)?;
+            if data.is_empty() {
+                return Ok(());
+            }
+            self.writer.write_channel_data(&group, &channel, &data)?;
+            self.committed(data.len())?;
+        }
+    }
+
+    fn strings(&mut self) -> Result<()> {
+        let (group, channel) = self.names();
+        let Some(chunk) = self.options.chunk_values else {
+            let data = self.reader.read_channel_strings(&group, &channel)?;
+            self.writer.write_channel_strings(&group, &channel, &data)?;
+            self.progress.values_copied = data.len() as u64;
+            return Ok(());
+        };
+        loop {
+            let data = self.reader.read_channel_range_strings(&group, &channel, self.progress.values_copied, chunk)?;
+            if data.is_empty() {
+                return Ok(());
+            }
+            self.writer.write_channel_strings(&group, &channel, &data)?;
+            self.committed(data.len())?;
+        }
+    }
+
+    fn names(&self) -> (String, String) {
+        let path = &self.progress.channel;
+        (path.group().unwrap_or_default().to_string(), path.channel().unwrap_or_default().to_string())
+    }
+
+    /// Commit a chunk and report it
+    fn committed(&mut self, values: usize) -> Result<()> {
+        self.writer.force_flush()?;
+        self.progress.values_copied += values as u64;
+        if let Some(callback) = &self.options.progress {
+            callback(self.progress);
+        }
+        Ok(())
+    }
+}
+
+/// Compare the copied channels' values in the new file with the source
+fn verify(source: &Path, dest: &Path, channels: &[ObjectPath], baked: &HashSet<ObjectPath>) -> Result<()> {
+    let options = DiffOptions { ignore_properties: true, ..Default::default() };
+    let copied: HashSet<&ObjectPath> = channels.iter().filter(|path| !baked.contains(path)).collect();
+    let differences: Vec<Difference> = diff::diff_files_with(source, dest, &options)?.differences.into_iter()
+        .filter(|difference| match difference {
+            // Channels left out on purpose, or baked into a different type
+            Difference::OnlyInLeft(path) | Difference::OnlyInRight(path)
+            | Difference::DataType { path, .. } | Difference::Length { path, .. }
+            | Difference::Values { path, .. } | Difference::Property { path, .. } => copied.contains(path),
+        })
+        .collect();
+    match differences.first() {
+        None => Ok(()),
+        Some(first) => Err(TdmsError::VerificationFailed(
+            format!("{} (and {} more differences)", first, differences.len() - 1))),
+    }
+}
+
+/// A DAQmx channel's values as `f64`, with its scaling applied unless the
+/// file says the data is already scaled
+fn read_daqmx_scaled(reader: &mut FileReader, channel_reader: &ChannelReader,
+                     group: &str, channel: &str) -> Result<Vec<f64>> {
+    let (_, data_type) = channel_reader.daqmx_info()
+        .ok_or_else(|| TdmsError::Unsupported(format!("/'{}'/'{}' is not a DAQmx channel", group, channel)))?
+        .primary_value_layout()?;
+    let raw: Vec<f64> = match data_type {
+        DataType::I8 => reader.read_channel_data::<i8>(group, channel)?.into_iter().map(f64::from).collect(),
+        DataType::I16 => reader.read_channel_data::<i16>(group, channel)?.into_iter().map(f64::from).collect(),
+        DataType::I32 => reader.read_channel_data::<i32>(group, channel)?.into_iter().map(f64::from).collect(),
+        DataType::I64 => reader.read_channel_data::<i64>(group, channel)?.into_iter().map(|v| v as f64).collect(),
+        DataType::U8 => reader.read_channel_data::<u8>(group, channel)?.into_iter().map(f64::from).collect(),
+        DataType::U16 => reader.read_channel_data::<u16>(group, channel)?.into_iter().map(f64::from).collect(),
+        DataType::U32 => reader.read_channel_data::<u32>(group, channel)?.into_iter().map(f64::from).collect(),
+        DataType::U64 => reader.read_channel_data::<u64>(group, channel)?.into_iter().map(|v| v as f64).collect(),
+        DataType::SingleFloat => reader.read_channel_data::<f32>(group, channel)?.into_iter().map(f64::from).collect(),
+        DataType::DoubleFloat => reader.read_channel_data::<f64>(group, channel)?,
+        other => return Err(TdmsError::Unsupported(format!("Baking DAQmx {:?} values", other))),
+    };
+
+    let scaling = match properties::Scaling::from_properties(channel_reader.get_properties()) {
+        Some(scaling) if !scaling.already_scaled => scaling,
+        _ => return Ok(raw),
+    };
+    raw.into_iter()
+        .map(|value| scaling.apply(value).ok_or_else(|| TdmsError::Unsupported(
+            format!("Applying the scaling of /'{}'/'{}' ({})", group, channel, scaling.describe()))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_selection() {
+        let path = |group: &str, channel: &str| ObjectPath::Channel { group: group.into(), channel: channel.into() };
+        let all = DefragOptions::new().exclude_channel("Rack", "Scratch");
+        assert!(all.includes_group("Rack") && all.includes_group("Other"));
+        assert!(all.includes_channel(&path("Rack", "ai0")));
+        assert!(!all.includes_channel(&path("Rack", "Scratch")));
+
+        let selected = DefragOptions::new()
+            .include_group("Rack")
+            .include_channel("Other", "ai0")
+            .exclude_channel("Rack", "Scratch");
+        assert!(selected.includes_group("Rack") && selected.includes_group("Other"));
+        assert!(!selected.includes_group("Spare"));
+        assert!(selected.includes_channel(&path("Rack", "ai1")));
+        assert!(!selected.includes_channel(&path("Rack", "Scratch")));
+        assert!(selected.includes_channel(&path("Other", "ai0")));
+        assert!(!selected.includes_channel(&path("Other", "ai1")));
+    }
+
+    #[test]
+    fn test_property_edits() {
+        let options = DefragOptions::new()
+            .set_property(ObjectPath::Root, "title", PropertyValue::String("new".into()))
+            .remove_property(ObjectPath::Root, "operator");
+        let source = [
+            Property::new("title", PropertyValue::String("old".into())),
+            Property::new("operator", PropertyValue::String("ana".into())),
+            Property::new("rate", PropertyValue::Double(10.0)),
+        ];
+        let mut edited = options.edited_properties(&ObjectPath::Root, &source);
+        edited.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(edited, [
+            Property::new("rate", PropertyValue::Double(10.0)),
+            Property::new("title", PropertyValue::String("new".into())),
+        ]);
+        assert_eq!(options.edited_properties(&ObjectPath::Group("Rack".into()), &source).len(), 3);
+    }
+}
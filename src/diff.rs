@@ -0,0 +1,435 @@
+// src/diff.rs
+//! Comparing two TDMS files object by object.
+//!
+//! [`diff_files`] reports channels present in only one file, channels whose
+//! data type or length differ, channels whose values differ and properties
+//! that differ. Values are compared as read back, so an encoded channel equals
+//! an ordinary one holding the same values.
+//!
+//! Re-processing data often changes the last bits of floating point results
+//! without changing what they mean. [`diff_files_with`] takes a [`Tolerance`]
+//! for float channels, overridable per channel, and a [`NanPolicy`].
+
+use crate::error::Result;
+use crate::metadata::ObjectPath;
+use crate::reader::TdmsReader;
+use crate::types::{Complex, DataType, F80, Property, PropertyValue, Timestamp};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+type FileReader = TdmsReader<BufReader<File>>;
+
+/// How far apart two float values may be and still count as equal.
+///
+/// Values are equal if they are within *any* of the three bounds. The default
+/// has all bounds at zero, so only identical values are equal.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tolerance {
+    /// Largest allowed `|a - b|`
+    pub absolute: f64,
+    /// Largest allowed `|a - b|` as a fraction of `max(|a|, |b|)`
+    pub relative: f64,
+    /// Largest allowed distance in units in the last place, counted in the
+    /// channel's own precision
+    pub ulps: u64,
+}
+
+impl Tolerance {
+    /// Only identical values are equal
+    pub const EXACT: Tolerance = Tolerance { absolute: 0.0, relative: 0.0, ulps: 0 };
+
+    pub fn absolute(absolute: f64) -> Self {
+        Tolerance { absolute, ..Self::EXACT }
+    }
+
+    pub fn relative(relative: f64) -> Self {
+        Tolerance { relative, ..Self::EXACT }
+    }
+
+    pub fn ulps(ulps: u64) -> Self {
+        Tolerance { ulps, ..Self::EXACT }
+    }
+}
+
+/// How NaN values compare
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// A NaN equals any other NaN, whatever its payload
+    #[default]
+    Equal,
+    /// A NaN equals nothing, as with `==`
+    NotEqual,
+}
+
+/// Options for [`diff_files_with`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffOptions {
+    /// Tolerance for float channels without their own entry in `channel_tolerances`
+    pub tolerance: Tolerance,
+    /// Tolerances for individual float channels
+    pub channel_tolerances: HashMap<ObjectPath, Tolerance>,
+    pub nan: NanPolicy,
+    /// Skip comparing file, group and channel properties
+    pub ignore_properties: bool,
+}
+
+impl DiffOptions {
+    /// Set the tolerance of one channel
+    pub fn channel_tolerance(mut self, group: &str, channel: &str, tolerance: Tolerance) -> Self {
+        let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
+        self.channel_tolerances.insert(path, tolerance);
+        self
+    }
+
+    fn tolerance_for(&self, path: &ObjectPath) -> Tolerance {
+        self.channel_tolerances.get(path).copied().unwrap_or(self.tolerance)
+    }
+}
+
+/// One difference found by [`diff_files`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// The channel is only in the left file
+    OnlyInLeft(ObjectPath),
+    /// The channel is only in the right file
+    OnlyInRight(ObjectPath),
+    DataType { path: ObjectPath, left: DataType, right: DataType },
+    /// The channels hold different numbers of values; the common part is still compared
+    Length { path: ObjectPath, left: u64, right: u64 },
+    Values {
+        path: ObjectPath,
+        /// Index of the first value that differs
+        first_index: u64,
+        /// Number of values that differ
+        count: u64,
+        /// Largest absolute difference, for numeric channels
+        max_difference: Option<f64>,
+    },
+    /// A property differs or is missing on one side
+    Property { path: ObjectPath, name: String, left: Option<PropertyValue>, right: Option<PropertyValue> },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::OnlyInLeft(path) => write!(f, "{}: only in left file", path),
+            Difference::OnlyInRight(path) => write!(f, "{}: only in right file", path),
+            Difference::DataType { path, left, right } => write!(f, "{}: data type {:?} vs {:?}", path, left, right),
+            Difference::Length { path, left, right } => write!(f, "{}: {} vs {} values", path, left, right),
+            Difference::Values { path, first_index, count, max_difference } => {
+                write!(f, "{}: {} values differ, first at index {}", path, count, first_index)?;
+                if let Some(max) = max_difference {
+                    write!(f, " (max difference {})", max)?;
+                }
+                Ok(())
+            }
+            Difference::Property { path, name, left, right } => {
+                write!(f, "{}: property {} is {:?} vs {:?}", path, name, left, right)
+            }
+        }
+    }
+}
+
+/// Result of [`diff_files`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileDiff {
+    /// Channels present in both files
+    pub channels_compared: usize,
+    pub differences: Vec<Difference>,
+}
+
+impl FileDiff {
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Compare two files exactly.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::diff::diff_files;
+///
+/// let diff = diff_files("expected.tdms", "actual.tdms").unwrap();
+/// for difference in &diff.differences {
+///     println!("{}", difference);
+/// }
+/// ```
+pub fn diff_files(left: impl AsRef<Path>, right: impl AsRef<Path>) -> Result<FileDiff> {
+    diff_files_with(left, right, &DiffOptions::default())
+}
+
+/// [`diff_files`] with tolerances for float channels.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::diff::{diff_files_with, DiffOptions, Tolerance};
+///
+/// let options = DiffOptions { tolerance: Tolerance::ulps(4), ..Default::default() }
+///     .channel_tolerance("Results", "Mean", Tolerance::relative(1e-9));
+/// let diff = diff_files_with("expected.tdms", "reprocessed.tdms", &options).unwrap();
+/// assert!(diff.is_identical(), "{:#?}", diff.differences);
+/// ```
+pub fn diff_files_with(left: impl AsRef<Path>, right: impl AsRef<Path>, options: &DiffOptions) -> Result<FileDiff> {
+    let mut left = TdmsReader::open(left)?;
+    let mut right = TdmsReader::open(right)?;
+    let mut diff = FileDiff::default();
+
+    if !options.ignore_properties {
+        diff_properties(&mut diff, ObjectPath::Root,
+            Some(left.get_file_properties()), Some(right.get_file_properties()));
+        let groups: BTreeSet<String> = left.list_groups().into_iter().chain(right.list_groups()).collect();
+        for group in groups {
+            diff_properties(&mut diff, ObjectPath::Group(group.clone()),
+                left.get_group_properties(&group), right.get_group_properties(&group));
+        }
+    }
+
+    let left_channels = channel_paths(&left);
+    let right_channels = channel_paths(&right);
+    for path in left_channels.union(&right_channels) {
+        if !right_channels.contains(path) {
+            diff.differences.push(Difference::OnlyInLeft(path.clone()));
+            continue;
+        }
+        if !left_channels.contains(path) {
+            diff.differences.push(Difference::OnlyInRight(path.clone()));
+            continue;
+        }
+        diff.channels_compared += 1;
+        let (group, channel) = (path.group().unwrap(), path.channel().unwrap());
+        if !options.ignore_properties {
+            diff_properties(&mut diff, path.clone(),
+                left.get_channel_properties(group, channel), right.get_channel_properties(group, channel));
+        }
+        diff_channel(&mut diff, &mut left, &mut right, path, options)?;
+    }
+    Ok(diff)
+}
+
+fn channel_paths(reader: &FileReader) -> BTreeSet<ObjectPath> {
+    reader.list_channels().iter()
+        .filter_map(|key| ObjectPath::from_string(key).ok())
+        .filter(|path| matches!(path, ObjectPath::Channel { .. }))
+        .collect()
+}
+
+fn diff_properties(diff: &mut FileDiff, path: ObjectPath,
+                   left: Option<&HashMap<String, Property>>, right: Option<&HashMap<String, Property>>) {
+    let empty = HashMap::new();
+    let (left, right) = (left.unwrap_or(&empty), right.unwrap_or(&empty));
+    let names: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    for name in names {
+        let (l, r) = (left.get(name).map(|p| &p.value), right.get(name).map(|p| &p.value));
+        if l != r {
+            diff.differences.push(Difference::Property {
+                path: path.clone(),
+                name: name.clone(),
+                left: l.cloned(),
+                right: r.cloned(),
+            });
+        }
+    }
+}
+
+fn diff_channel(diff: &mut FileDiff, left: &mut FileReader, right: &mut FileReader,
+                path: &ObjectPath, options: &DiffOptions) -> Result<()> {
+    let (group, channel) = (path.group().unwrap(), path.channel().unwrap());
+    let (Some(left_type), Some(right_type)) =
+        (left.value_data_type(group, channel)?, right.value_data_type(group, channel)?) else {
+        return Ok(());
+    };
+    if left_type != right_type {
+        diff.differences.push(Difference::DataType { path: path.clone(), left: left_type, right: right_type });
+        return Ok(());
+    }
+
+    let tolerance = options.tolerance_for(path);
+    macro_rules! compare {
+        ($t:ty, $equal:expr, $distance:expr) => {{
+            let l = left.read_channel_data::<$t>(group, channel)?;
+            let r = right.read_channel_data::<$t>(group, channel)?;
+            compare_values(diff, path, &l, &r, $equal, $distance)
+        }};
+    }
+    fn exact<T: PartialEq>(a: &T, b: &T) -> bool {
+        a == b
+    }
+    fn numeric<T: Copy + Into<f64>>(a: &T, b: &T) -> Option<f64> {
+        Some(((*a).into() - (*b).into()).abs())
+    }
+    fn none<T>(_: &T, _: &T) -> Option<f64> {
+        None
+    }
+
+    match left_type {
+        DataType::String => {
+            let l = left.read_channel_strings(group, channel)?;
+            let r = right.read_channel_strings(group, channel)?;
+            compare_values(diff, path, &l, &r, exact, none)
+        }
+        DataType::I8 => compare!(i8, exact, numeric),
+        DataType::I16 => compare!(i16, exact, numeric),
+        DataType::I32 => compare!(i32, exact, numeric),
+        DataType::I64 => compare!(i64, exact, |a: &i64, b: &i64| Some((*a as f64 - *b as f64).abs())),
+        DataType::U8 => compare!(u8, exact, numeric),
+        DataType::U16 => compare!(u16, exact, numeric),
+        DataType::U32 => compare!(u32, exact, numeric),
+        DataType::U64 => compare!(u64, exact, |a: &u64, b: &u64| Some((*a as f64 - *b as f64).abs())),
+        DataType::Boolean => compare!(bool, exact, none),
+        DataType::TimeStamp => compare!(Timestamp, exact, |a: &Timestamp, b: &Timestamp| Some(a.diff(b).abs())),
+        DataType::SingleFloat => compare!(f32,
+            |a: &f32, b: &f32| floats_equal(*a, *b, tolerance, options.nan), float_distance),
+        DataType::DoubleFloat => compare!(f64,
+            |a: &f64, b: &f64| floats_equal(*a, *b, tolerance, options.nan), float_distance),
+        DataType::ExtendedFloat => compare!(F80,
+            |a: &F80, b: &F80| floats_equal(a.to_f64(), b.to_f64(), tolerance, options.nan),
+            |a: &F80, b: &F80| float_distance(&a.to_f64(), &b.to_f64())),
+        DataType::ComplexSingleFloat => compare!(Complex<f32>,
+            |a: &Complex<f32>, b: &Complex<f32>| complex_equal(a, b, tolerance, options.nan), complex_distance),
+        DataType::ComplexDoubleFloat => compare!(Complex<f64>,
+            |a: &Complex<f64>, b: &Complex<f64>| complex_equal(a, b, tolerance, options.nan), complex_distance),
+        // Types without a typed read path (e.g. DAQmx raw data) aren't compared
+        _ => {}
+    }
+    Ok(())
+}
+
+fn compare_values<T>(diff: &mut FileDiff, path: &ObjectPath, left: &[T], right: &[T],
+                     equal: impl Fn(&T, &T) -> bool, distance: impl Fn(&T, &T) -> Option<f64>) {
+    if left.len() != right.len() {
+        diff.differences.push(Difference::Length {
+            path: path.clone(),
+            left: left.len() as u64,
+            right: right.len() as u64,
+        });
+    }
+
+    let mut first_index = None;
+    let mut count = 0;
+    let mut max_difference: Option<f64> = None;
+    for (index, (l, r)) in left.iter().zip(right).enumerate() {
+        if equal(l, r) {
+            continue;
+        }
+        first_index.get_or_insert(index as u64);
+        count += 1;
+        if let Some(d) = distance(l, r) {
+            max_difference = Some(max_difference.map_or(d, |max| max.max(d)));
+        }
+    }
+    if let Some(first_index) = first_index {
+        diff.differences.push(Difference::Values { path: path.clone(), first_index, count, max_difference });
+    }
+}
+
+/// The float types a [`Tolerance`] applies to
+trait Float: Copy + Into<f64> {
+    fn is_nan(self) -> bool;
+    /// Distance in units in the last place, or `None` if either is NaN
+    fn ulp_distance(self, other: Self) -> Option<u64>;
+}
+
+impl Float for f32 {
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+
+    fn ulp_distance(self, other: Self) -> Option<u64> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        // Map the bit patterns onto a line where adjacent floats are adjacent integers
+        let ordered = |x: f32| {
+            let bits = x.to_bits() as i32;
+            if bits < 0 { i32::MIN.wrapping_sub(bits) as i64 } else { bits as i64 }
+        };
+        Some(ordered(self).abs_diff(ordered(other)))
+    }
+}
+
+impl Float for f64 {
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+
+    fn ulp_distance(self, other: Self) -> Option<u64> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        let ordered = |x: f64| {
+            let bits = x.to_bits() as i64;
+            if bits < 0 { i64::MIN.wrapping_sub(bits) as i128 } else { bits as i128 }
+        };
+        Some(ordered(self).abs_diff(ordered(other)).min(u64::MAX as u128) as u64)
+    }
+}
+
+fn floats_equal<T: Float>(a: T, b: T, tolerance: Tolerance, nan: NanPolicy) -> bool {
+    if a.is_nan() || b.is_nan() {
+        return nan == NanPolicy::Equal && a.is_nan() && b.is_nan();
+    }
+    let (x, y) = (a.into(), b.into());
+    if x == y {
+        return true;
+    }
+    // An infinity only equals itself, however loose the tolerance
+    if x.is_infinite() || y.is_infinite() {
+        return false;
+    }
+    let difference = (x - y).abs();
+    difference <= tolerance.absolute
+        || difference <= tolerance.relative * x.abs().max(y.abs())
+        || a.ulp_distance(b).is_some_and(|ulps| ulps <= tolerance.ulps)
+}
+
+fn float_distance<T: Float>(a: &T, b: &T) -> Option<f64> {
+    let difference = ((*a).into() - (*b).into()).abs();
+    (!difference.is_nan()).then_some(difference)
+}
+
+/// Both parts equal within the tolerance
+fn complex_equal<T: Float>(a: &Complex<T>, b: &Complex<T>, tolerance: Tolerance, nan: NanPolicy) -> bool {
+    floats_equal(a.re, b.re, tolerance, nan) && floats_equal(a.im, b.im, tolerance, nan)
+}
+
+/// Modulus of the difference
+fn complex_distance<T: Float>(a: &Complex<T>, b: &Complex<T>) -> Option<f64> {
+    let difference = (a.re.into() - b.re.into()).hypot(a.im.into() - b.im.into());
+    (!difference.is_nan()).then_some(difference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulp_distance() {
+        assert_eq!(1.0f64.ulp_distance(1.0), Some(0));
+        assert_eq!(1.0f64.ulp_distance(f64::from_bits(1.0f64.to_bits() + 3)), Some(3));
+        assert_eq!(0.0f64.ulp_distance(-0.0), Some(0));
+        // The smallest subnormals either side of zero are two steps apart
+        assert_eq!(f32::from_bits(1).ulp_distance(-f32::from_bits(1)), Some(2));
+        assert_eq!(f32::NAN.ulp_distance(1.0), None);
+    }
+
+    #[test]
+    fn test_floats_equal() {
+        let next = f64::from_bits(1.0f64.to_bits() + 1);
+        assert!(!floats_equal(1.0, next, Tolerance::EXACT, NanPolicy::Equal));
+        assert!(floats_equal(1.0, next, Tolerance::ulps(1), NanPolicy::Equal));
+        assert!(floats_equal(100.0, 100.5, Tolerance::absolute(0.5), NanPolicy::Equal));
+        assert!(!floats_equal(100.0, 100.5, Tolerance::relative(1e-3), NanPolicy::Equal));
+        assert!(floats_equal(100.0, 100.05, Tolerance::relative(1e-3), NanPolicy::Equal));
+        assert!(floats_equal(f64::INFINITY, f64::INFINITY, Tolerance::EXACT, NanPolicy::Equal));
+        assert!(!floats_equal(f64::INFINITY, f64::MAX, Tolerance::relative(1.0), NanPolicy::Equal));
+        assert!(floats_equal(f32::NAN, -f32::NAN, Tolerance::EXACT, NanPolicy::Equal));
+        assert!(!floats_equal(f32::NAN, f32::NAN, Tolerance::EXACT, NanPolicy::NotEqual));
+        assert!(!floats_equal(f32::NAN, 1.0, Tolerance::absolute(f64::INFINITY), NanPolicy::Equal));
+    }
+}
@@ -1,5 +1,6 @@
 // src/error.rs
 use std::io;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -16,6 +17,9 @@ pub enum TdmsError {
     #[error("Invalid object path: {0}")]
     InvalidPath(String),
     
+    #[error("Invalid name {name:?}: {reason}")]
+    InvalidName { name: String, reason: String },
+    
     #[error("Channel not found: {0}")]
     ChannelNotFound(String),
     
@@ -30,12 +34,30 @@ pub enum TdmsError {
     
     #[error("Unsupported feature: {0}")]
     Unsupported(String),
+
+    #[error("Corrupt data: {0}")]
+    CorruptData(String),
     
     #[error("Writer closed")]
     WriterClosed,
     
     #[error("Buffer overflow: tried to write {attempted} bytes to buffer of size {capacity}")]
     BufferOverflow { attempted: usize, capacity: usize },
+
+    #[error("Schema violation: {0}")]
+    SchemaViolation(String),
+
+    #[error("Invalid schema document: {0}")]
+    InvalidSchema(String),
+
+    #[error("Byte quota exceeded: {used} of {limit} bytes used, {requested} more requested")]
+    QuotaExceeded { limit: u64, used: u64, requested: u64 },
+
+    #[error("Writer stalled: {command} has been running for {stalled_for:?} with {queued} commands queued")]
+    WriterStalled { command: &'static str, stalled_for: Duration, queued: usize },
+
+    #[error("Verification failed: {0}")]
+    VerificationFailed(String),
 }
 
 pub type Result<T> = std::result::Result<T, TdmsError>;
\ No newline at end of file
@@ -0,0 +1,320 @@
+// src/export.rs
+//! Exporting TDMS files to other formats.
+//!
+//! [`export_csv`] writes one CSV file per group, one column per channel. It is
+//! meant for handing data to tools that don't read TDMS, not for round-tripping:
+//! properties are not exported and timestamps are written as ISO 8601 UTC text.
+//!
+//! [`write_arrow_stream`] writes channels as an Arrow IPC stream to any
+//! [`Write`], such as stdout, for piping into Arrow-aware tools.
+//!
+//! Columns are named after their channels unless a [`ColumnNaming`] says to
+//! take names from a property such as `NI_ChannelName` or to add units.
+
+use crate::error::Result;
+use crate::metadata::ObjectPath;
+use crate::properties::wellknown;
+use crate::reader::TdmsReader;
+use crate::types::{DataType, Property, Timestamp};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+mod arrow;
+
+pub use arrow::{write_arrow_stream, write_arrow_stream_with};
+
+/// How exported columns are named
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::export::{export_csv_with, ColumnNaming};
+/// use tdms_rs::properties::wellknown;
+///
+/// // Headers like "Inlet pressure [bar]"
+/// let naming = ColumnNaming::new().name_property(wellknown::NI_CHANNEL_NAME).include_units(true);
+/// export_csv_with("run.tdms", "run_csv", &naming).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnNaming {
+    name_property: Option<String>,
+    include_units: bool,
+}
+
+impl ColumnNaming {
+    /// Channel names, without units
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take column names from this string property, falling back to the
+    /// channel name where it is missing or empty
+    pub fn name_property(mut self, name: impl Into<String>) -> Self {
+        self.name_property = Some(name.into());
+        self
+    }
+
+    /// Append the `unit_string` property as ` [unit]` where it is set
+    pub fn include_units(mut self, include: bool) -> Self {
+        self.include_units = include;
+        self
+    }
+
+    /// Name of the column for `channel`, whose properties are `properties`
+    pub fn column_name(&self, channel: &str, properties: Option<&HashMap<String, Property>>) -> String {
+        let name = self.name_property.as_deref()
+            .and_then(|name| properties?.get(name)?.value.as_str())
+            .filter(|name| !name.is_empty())
+            .unwrap_or(channel);
+        match properties.and_then(wellknown::unit_string).filter(|unit| self.include_units && !unit.is_empty()) {
+            Some(unit) => format!("{} [{}]", name, unit),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// Make repeated names unique by appending `_2`, `_3`, ... to later ones
+fn unique_names(names: Vec<String>) -> Vec<String> {
+    unique_names_by(names, |name| name.to_string())
+}
+
+/// [`unique_names`] where two names repeat if their `key`s are equal
+fn unique_names_by(names: Vec<String>, key: impl Fn(&str) -> String) -> Vec<String> {
+    let mut seen: HashSet<String> = HashSet::with_capacity(names.len());
+    names.into_iter()
+        .map(|name| {
+            let mut unique = name.clone();
+            let mut n = 1;
+            while seen.contains(&key(&unique)) {
+                n += 1;
+                unique = format!("{}_{}", name, n);
+            }
+            seen.insert(key(&unique));
+            unique
+        })
+        .collect()
+}
+
+/// Export every group of a TDMS file to `<out_dir>/<group>.csv`.
+///
+/// Columns are the group's channels sorted by name, with the channel name as
+/// header (see [`export_csv_with`] for other names). Channels shorter than the longest one in the group leave their
+/// trailing cells empty. Channels whose type has no text form (DAQmx raw data,
+/// complex values) are left out. Characters that aren't allowed in file names
+/// are replaced with `_` in the CSV file names; groups whose file names would
+/// then collide, ignoring case, get `_2`, `_3`, ... appended so no file is
+/// overwritten.
+///
+/// Returns the paths of the files written, in group name order.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::export::export_csv;
+///
+/// for path in export_csv("run.tdms", "run_csv").unwrap() {
+///     println!("wrote {}", path.display());
+/// }
+/// ```
+pub fn export_csv(source: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    export_csv_with(source, out_dir, &ColumnNaming::default())
+}
+
+/// [`export_csv`] with headers named by `naming`. Headers that would repeat
+/// within a file get `_2`, `_3`, ... appended.
+pub fn export_csv_with(source: impl AsRef<Path>, out_dir: impl AsRef<Path>, naming: &ColumnNaming) -> Result<Vec<PathBuf>> {
+    let out_dir = out_dir.as_ref();
+    fs::create_dir_all(out_dir)?;
+
+    let mut reader = TdmsReader::open(source)?;
+    let mut groups = reader.list_groups();
+    groups.sort();
+
+    // Case-insensitive filesystems would merge names differing only in case
+    let stems = unique_names_by(groups.iter().map(|group| file_name(group)).collect(), str::to_lowercase);
+
+    let mut written = Vec::with_capacity(groups.len());
+    for (group, stem) in groups.into_iter().zip(stems) {
+        let mut headers = Vec::new();
+        let mut columns = Vec::new();
+        for channel in reader.list_group_channels(&group) {
+            if let Some(column) = read_column(&mut reader, &group, &channel)? {
+                headers.push(naming.column_name(&channel, reader.get_channel_properties(&group, &channel)));
+                columns.push(column);
+            }
+        }
+        let headers = unique_names(headers);
+
+        let path = out_dir.join(format!("{}.csv", stem));
+        write_csv(&path, &headers, &columns)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// A channel's values in a form that can be written as text
+enum Column {
+    Signed(Vec<i64>),
+    Unsigned(Vec<u64>),
+    Float(Vec<f64>),
+    Boolean(Vec<bool>),
+    Time(Vec<Timestamp>),
+    Text(Vec<String>),
+}
+
+impl Column {
+    fn len(&self) -> usize {
+        match self {
+            Column::Signed(v) => v.len(),
+            Column::Unsigned(v) => v.len(),
+            Column::Float(v) => v.len(),
+            Column::Boolean(v) => v.len(),
+            Column::Time(v) => v.len(),
+            Column::Text(v) => v.len(),
+        }
+    }
+
+    /// Write the value at `row` as a CSV cell; nothing if the column is shorter
+    fn write_cell<W: Write>(&self, out: &mut W, row: usize) -> std::io::Result<()> {
+        match self {
+            Column::Signed(v) => v.get(row).map_or(Ok(()), |x| write!(out, "{}", x)),
+            Column::Unsigned(v) => v.get(row).map_or(Ok(()), |x| write!(out, "{}", x)),
+            Column::Float(v) => v.get(row).map_or(Ok(()), |x| write!(out, "{}", x)),
+            Column::Boolean(v) => v.get(row).map_or(Ok(()), |x| write!(out, "{}", x)),
+            Column::Time(v) => v.get(row).map_or(Ok(()), |x| write!(out, "{}", format_timestamp(*x))),
+            Column::Text(v) => v.get(row).map_or(Ok(()), |x| write_text(out, x)),
+        }
+    }
+}
+
+fn read_column<R: Read + Seek>(reader: &mut TdmsReader<R>, group: &str, channel: &str) -> Result<Option<Column>> {
+    let data_type = match reader.channel_encoding(group, channel)? {
+        Some((_, data_type)) => data_type,
+        None => match reader.get_channel(&ObjectPath::Channel { group: group.into(), channel: channel.into() }.to_string()) {
+            Some(channel_reader) => channel_reader.data_type(),
+            None => return Ok(None),
+        },
+    };
+
+    macro_rules! widen {
+        ($variant:ident, $t:ty, $wide:ty) => {
+            Column::$variant(reader.read_channel_data::<$t>(group, channel)?.into_iter().map(<$wide>::from).collect())
+        };
+    }
+
+    let column = match data_type {
+        DataType::I8 => widen!(Signed, i8, i64),
+        DataType::I16 => widen!(Signed, i16, i64),
+        DataType::I32 => widen!(Signed, i32, i64),
+        DataType::I64 => Column::Signed(reader.read_channel_data(group, channel)?),
+        DataType::U8 => widen!(Unsigned, u8, u64),
+        DataType::U16 => widen!(Unsigned, u16, u64),
+        DataType::U32 => widen!(Unsigned, u32, u64),
+        DataType::U64 => Column::Unsigned(reader.read_channel_data(group, channel)?),
+        DataType::SingleFloat => widen!(Float, f32, f64),
+        DataType::DoubleFloat => Column::Float(reader.read_channel_data(group, channel)?),
+        DataType::Boolean => Column::Boolean(reader.read_channel_data(group, channel)?),
+        DataType::TimeStamp => Column::Time(reader.read_channel_data(group, channel)?),
+        DataType::String => Column::Text(reader.read_channel_strings(group, channel)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(column))
+}
+
+fn write_csv(path: &Path, headers: &[String], columns: &[Column]) -> Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+
+    for (i, header) in headers.iter().enumerate() {
+        if i > 0 {
+            out.write_all(b",")?;
+        }
+        write_text(&mut out, header)?;
+    }
+    out.write_all(b"\n")?;
+
+    let rows = columns.iter().map(Column::len).max().unwrap_or(0);
+    for row in 0..rows {
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                out.write_all(b",")?;
+            }
+            column.write_cell(&mut out, row)?;
+        }
+        out.write_all(b"\n")?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Write a text cell, quoting it if it contains a delimiter, quote or line break
+fn write_text<W: Write>(out: &mut W, text: &str) -> std::io::Result<()> {
+    if text.contains([',', '"', '\n', '\r']) {
+        write!(out, "\"{}\"", text.replace('"', "\"\""))
+    } else {
+        out.write_all(text.as_bytes())
+    }
+}
+
+/// Format a timestamp as ISO 8601 UTC with nanosecond precision
+fn format_timestamp(ts: Timestamp) -> String {
+    let t = ts.to_civil();
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:09}Z",
+        t.year, t.month, t.day, t.hour, t.minute, t.second, t.nanosecond
+    )
+}
+
+/// Replace characters that aren't allowed in file names on common platforms
+fn file_name(group: &str) -> String {
+    let name: String = group.chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    if name.is_empty() { "_".to_string() } else { name }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_naming() {
+        let mut properties = HashMap::new();
+        for (name, value) in [("NI_ChannelName", "Inlet"), ("unit_string", "bar"), ("alias", "")] {
+            properties.insert(name.to_string(), Property::new(name, crate::PropertyValue::String(value.into())));
+        }
+
+        assert_eq!(ColumnNaming::new().column_name("P1", Some(&properties)), "P1");
+        let naming = ColumnNaming::new().name_property("NI_ChannelName").include_units(true);
+        assert_eq!(naming.column_name("P1", Some(&properties)), "Inlet [bar]");
+        assert_eq!(naming.column_name("P1", None), "P1");
+        assert_eq!(ColumnNaming::new().name_property("alias").column_name("P1", Some(&properties)), "P1");
+
+        let names = unique_names(vec!["a".into(), "a".into(), "a_2".into(), "b".into()]);
+        assert_eq!(names, ["a", "a_2", "a_2_2", "b"]);
+        let stems = unique_names_by(vec![file_name("A/B"), file_name("A:B"), file_name("a_b")], str::to_lowercase);
+        assert_eq!(stems, ["A_B", "A_B_2", "a_b_3"]);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(Timestamp::from_unix_nanos(0)), "1970-01-01T00:00:00.000000000Z");
+        assert_eq!(
+            format_timestamp(Timestamp::from_unix_nanos(1_709_210_096_500_000_000)),
+            "2024-02-29T12:34:56.500000000Z"
+        );
+        assert_eq!(format_timestamp(Timestamp::from_unix_nanos(-1)), "1969-12-31T23:59:59.999999999Z");
+    }
+
+    #[test]
+    fn test_write_text_quoting() {
+        let mut out = Vec::new();
+        write_text(&mut out, "plain").unwrap();
+        out.push(b'|');
+        write_text(&mut out, "a,\"b\"").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "plain|\"a,\"\"b\"\"\"");
+    }
+}
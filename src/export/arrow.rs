@@ -0,0 +1,424 @@
+// src/export/arrow.rs
+//! Arrow IPC stream output.
+//!
+//! The stream format is a schema message followed by record batch messages,
+//! each a flatbuffer describing the batch plus a body holding the column
+//! buffers. The few flatbuffer tables involved are encoded by hand, front to
+//! back, rather than pulling in an Arrow implementation.
+
+use super::{read_column, unique_names, Column, ColumnNaming};
+use crate::error::{Result, TdmsError};
+use crate::reader::TdmsReader;
+use std::io::{Read, Seek, Write};
+
+/// Rows per record batch
+const BATCH_ROWS: usize = 64 * 1024;
+
+const CONTINUATION: u32 = 0xFFFF_FFFF;
+const METADATA_V5: i16 = 4;
+
+// MessageHeader union
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+
+// Type union
+const TYPE_INT: u8 = 2;
+const TYPE_FLOATING_POINT: u8 = 3;
+const TYPE_UTF8: u8 = 5;
+const TYPE_BOOL: u8 = 6;
+const TYPE_TIMESTAMP: u8 = 10;
+
+const PRECISION_DOUBLE: i16 = 2;
+const TIME_UNIT_NANOSECOND: i16 = 3;
+
+/// Write channels of a file as an Arrow IPC stream
+///
+/// Each `(group, channel)` becomes a nullable column named `group/channel`
+/// (see [`write_arrow_stream_with`] for other names).
+/// Integers are widened to 64 bits and floats to `f64`, timestamps become
+/// nanosecond UTC timestamps and strings UTF-8. Channels shorter than the
+/// longest one are padded with nulls. With no channels given, every channel
+/// with a representable type is written, sorted by group and name.
+///
+/// Fails with [`TdmsError::Unsupported`] for a selected channel whose type
+/// has no Arrow equivalent here (DAQmx raw data, complex values).
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::TdmsReader;
+/// use tdms_rs::export::write_arrow_stream;
+///
+/// // tdms-to-arrow run.tdms | other-tool
+/// let mut reader = TdmsReader::open("run.tdms").unwrap();
+/// write_arrow_stream(&mut reader, &[("Rack", "Pressure")], std::io::stdout().lock()).unwrap();
+/// ```
+pub fn write_arrow_stream<R: Read + Seek, W: Write>(
+    reader: &mut TdmsReader<R>,
+    channels: &[(&str, &str)],
+    out: W,
+) -> Result<()> {
+    write_arrow_stream_with(reader, channels, &ColumnNaming::default(), out)
+}
+
+/// [`write_arrow_stream`] with columns named `group/<name>`, the name chosen
+/// by `naming`. Names that would repeat get `_2`, `_3`, ... appended.
+pub fn write_arrow_stream_with<R: Read + Seek, W: Write>(
+    reader: &mut TdmsReader<R>,
+    channels: &[(&str, &str)],
+    naming: &ColumnNaming,
+    mut out: W,
+) -> Result<()> {
+    let mut names = Vec::new();
+    let mut columns = Vec::new();
+    if channels.is_empty() {
+        let mut groups = reader.list_groups();
+        groups.sort();
+        for group in groups {
+            for channel in reader.list_group_channels(&group) {
+                if let Some(column) = read_column(reader, &group, &channel)? {
+                    let name = naming.column_name(&channel, reader.get_channel_properties(&group, &channel));
+                    names.push(format!("{}/{}", group, name));
+                    columns.push(column);
+                }
+            }
+        }
+    } else {
+        for &(group, channel) in channels {
+            if !reader.list_group_channels(group).iter().any(|c| c == channel) {
+                return Err(TdmsError::ChannelNotFound(format!("{}/{}", group, channel)));
+            }
+            let column = read_column(reader, group, channel)?.ok_or_else(|| TdmsError::Unsupported(
+                format!("Arrow export of {}/{}", group, channel)))?;
+            let name = naming.column_name(channel, reader.get_channel_properties(group, channel));
+            names.push(format!("{}/{}", group, name));
+            columns.push(column);
+        }
+    }
+    let names = unique_names(names);
+
+    write_message(&mut out, &schema_message(&names, &columns), &[])?;
+    let rows = columns.iter().map(Column::len).max().unwrap_or(0);
+    let mut start = 0;
+    while start < rows {
+        let end = (start + BATCH_ROWS).min(rows);
+        let (metadata, body) = record_batch(&columns, start, end)?;
+        write_message(&mut out, &metadata, &body)?;
+        start = end;
+    }
+    out.write_all(&CONTINUATION.to_le_bytes())?;
+    out.write_all(&0u32.to_le_bytes())?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Frame a message: continuation marker, padded metadata length, metadata, body
+fn write_message<W: Write>(out: &mut W, metadata: &[u8], body: &[u8]) -> Result<()> {
+    let padded = metadata.len().next_multiple_of(8);
+    out.write_all(&CONTINUATION.to_le_bytes())?;
+    out.write_all(&(padded as u32).to_le_bytes())?;
+    out.write_all(metadata)?;
+    out.write_all(&[0; 8][..padded - metadata.len()])?;
+    out.write_all(body)?;
+    Ok(())
+}
+
+fn message(header_type: u8, header: Object, body_length: usize) -> Vec<u8> {
+    encode(Object::Table(vec![
+        (0, Field::I16(METADATA_V5)),
+        (1, Field::U8(header_type)),
+        (2, Field::Offset(header)),
+        (3, Field::I64(body_length as i64)),
+    ]))
+}
+
+fn schema_message(names: &[String], columns: &[Column]) -> Vec<u8> {
+    let fields = names.iter().zip(columns)
+        .map(|(name, column)| {
+            let (type_id, type_table) = match column {
+                Column::Signed(_) | Column::Unsigned(_) => (TYPE_INT, vec![
+                    (0, Field::I32(64)),
+                    (1, Field::Bool(matches!(column, Column::Signed(_)))),
+                ]),
+                Column::Float(_) => (TYPE_FLOATING_POINT, vec![(0, Field::I16(PRECISION_DOUBLE))]),
+                Column::Boolean(_) => (TYPE_BOOL, vec![]),
+                Column::Time(_) => (TYPE_TIMESTAMP, vec![
+                    (0, Field::I16(TIME_UNIT_NANOSECOND)),
+                    (1, Field::Offset(Object::String("UTC".into()))),
+                ]),
+                Column::Text(_) => (TYPE_UTF8, vec![]),
+            };
+            Object::Table(vec![
+                (0, Field::Offset(Object::String(name.clone()))),
+                (1, Field::Bool(true)),
+                (2, Field::U8(type_id)),
+                (3, Field::Offset(Object::Table(type_table))),
+                (5, Field::Offset(Object::Tables(Vec::new()))),
+            ])
+        })
+        .collect();
+    let schema = Object::Table(vec![
+        (0, Field::I16(0)), // little endian
+        (1, Field::Offset(Object::Tables(fields))),
+    ]);
+    message(HEADER_SCHEMA, schema, 0)
+}
+
+/// Metadata and body of the batch holding rows `start..end`
+fn record_batch(columns: &[Column], start: usize, end: usize) -> Result<(Vec<u8>, Vec<u8>)> {
+    let rows = end - start;
+    let mut body = Vec::new();
+    let mut nodes = Vec::with_capacity(columns.len());
+    let mut buffers = Vec::new();
+    let mut push_buffer = |body: &mut Vec<u8>, bytes: &[u8]| {
+        buffers.push([body.len() as i64, bytes.len() as i64]);
+        body.extend_from_slice(bytes);
+        body.resize(body.len().next_multiple_of(8), 0);
+    };
+
+    for column in columns {
+        // Rows this column has values for, from `first`
+        let first = start.min(column.len());
+        let present = column.len().min(end) - first;
+        let null_count = rows - present;
+        nodes.push([rows as i64, null_count as i64]);
+        let validity = if null_count == 0 { Vec::new() } else { bitmap(rows, |row| row < present) };
+        push_buffer(&mut body, &validity);
+
+        match column {
+            Column::Signed(v) => push_buffer(&mut body, &values(&v[first..first + present], rows, |x| x.to_le_bytes())),
+            Column::Unsigned(v) => push_buffer(&mut body, &values(&v[first..first + present], rows, |x| x.to_le_bytes())),
+            Column::Float(v) => push_buffer(&mut body, &values(&v[first..first + present], rows, |x| x.to_le_bytes())),
+            Column::Time(v) => push_buffer(&mut body, &values(&v[first..first + present], rows, |x| x.to_unix_nanos().to_le_bytes())),
+            Column::Boolean(v) => push_buffer(&mut body, &bitmap(rows, |row| row < present && v[first + row])),
+            Column::Text(v) => {
+                let mut offsets = Vec::with_capacity((rows + 1) * 4);
+                let mut data = Vec::new();
+                offsets.extend_from_slice(&0i32.to_le_bytes());
+                for row in 0..rows {
+                    if row < present {
+                        data.extend_from_slice(v[first + row].as_bytes());
+                    }
+                    let offset = i32::try_from(data.len()).map_err(|_| TdmsError::Unsupported(
+                        "Arrow export of more than 2 GiB of text in one batch".into()))?;
+                    offsets.extend_from_slice(&offset.to_le_bytes());
+                }
+                push_buffer(&mut body, &offsets);
+                push_buffer(&mut body, &data);
+            }
+        }
+    }
+
+    let batch = Object::Table(vec![
+        (0, Field::I64(rows as i64)),
+        (1, Field::Offset(Object::Structs(nodes))),
+        (2, Field::Offset(Object::Structs(buffers))),
+    ]);
+    Ok((message(HEADER_RECORD_BATCH, batch, body.len()), body))
+}
+
+/// Little-endian values of `present`, zero-filled to `rows` values
+fn values<T: Copy, const N: usize>(present: &[T], rows: usize, to_bytes: impl Fn(T) -> [u8; N]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(rows * N);
+    for &value in present {
+        bytes.extend_from_slice(&to_bytes(value));
+    }
+    bytes.resize(rows * N, 0);
+    bytes
+}
+
+/// LSB-first bitmap of `rows` bits
+fn bitmap(rows: usize, bit: impl Fn(usize) -> bool) -> Vec<u8> {
+    let mut bytes = vec![0u8; rows.div_ceil(8)];
+    for row in 0..rows {
+        if bit(row) {
+            bytes[row / 8] |= 1 << (row % 8);
+        }
+    }
+    bytes
+}
+
+/// A flatbuffer object
+enum Object {
+    /// Fields by slot number
+    Table(Vec<(u16, Field)>),
+    String(String),
+    Tables(Vec<Object>),
+    /// Vector of structs of two `i64`s (`FieldNode` and `Buffer`)
+    Structs(Vec<[i64; 2]>),
+}
+
+enum Field {
+    U8(u8),
+    Bool(bool),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Offset(Object),
+}
+
+impl Field {
+    fn width(&self) -> usize {
+        match self {
+            Field::U8(_) | Field::Bool(_) => 1,
+            Field::I16(_) => 2,
+            Field::I32(_) | Field::Offset(_) => 4,
+            Field::I64(_) => 8,
+        }
+    }
+}
+
+/// Serialize a flatbuffer with `root` as its root table
+///
+/// Objects are written parent first, so every offset points forward; the
+/// vtable of a table is written just before it.
+fn encode(root: Object) -> Vec<u8> {
+    let mut buf = vec![0u8; 4];
+    let root_pos = write_object(&mut buf, &root);
+    patch_offset(&mut buf, 0, root_pos);
+    buf.resize(buf.len().next_multiple_of(8), 0);
+    buf
+}
+
+fn patch_offset(buf: &mut [u8], at: usize, target: usize) {
+    buf[at..at + 4].copy_from_slice(&((target - at) as u32).to_le_bytes());
+}
+
+fn pad_to(buf: &mut Vec<u8>, align: usize) {
+    buf.resize(buf.len().next_multiple_of(align), 0);
+}
+
+/// Write an object and the objects it refers to, returning its position
+fn write_object(buf: &mut Vec<u8>, object: &Object) -> usize {
+    match object {
+        Object::String(s) => {
+            pad_to(buf, 4);
+            let pos = buf.len();
+            buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            buf.extend_from_slice(s.as_bytes());
+            buf.push(0);
+            pos
+        }
+        Object::Structs(items) => {
+            // Align the elements, which follow the length, to 8
+            while (buf.len() + 4) % 8 != 0 {
+                buf.push(0);
+            }
+            let pos = buf.len();
+            buf.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for item in items {
+                for value in item {
+                    buf.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            pos
+        }
+        Object::Tables(items) => {
+            pad_to(buf, 4);
+            let pos = buf.len();
+            buf.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            let slots = buf.len();
+            buf.resize(slots + 4 * items.len(), 0);
+            for (i, item) in items.iter().enumerate() {
+                let item_pos = write_object(buf, item);
+                patch_offset(buf, slots + 4 * i, item_pos);
+            }
+            pos
+        }
+        Object::Table(fields) => {
+            // Inline layout: the vtable offset, then each field aligned to its width
+            let slot_count = fields.iter().map(|(slot, _)| *slot as usize + 1).max().unwrap_or(0);
+            let mut field_offsets = vec![0u16; slot_count];
+            let mut size: usize = 4;
+            for (slot, field) in fields {
+                size = size.next_multiple_of(field.width());
+                field_offsets[*slot as usize] = size as u16;
+                size += field.width();
+            }
+
+            pad_to(buf, 2);
+            let vtable = buf.len();
+            buf.extend_from_slice(&(4 + 2 * slot_count as u16).to_le_bytes());
+            buf.extend_from_slice(&(size as u16).to_le_bytes());
+            for offset in &field_offsets {
+                buf.extend_from_slice(&offset.to_le_bytes());
+            }
+
+            pad_to(buf, 8);
+            let table = buf.len();
+            buf.extend_from_slice(&((table - vtable) as i32).to_le_bytes());
+            buf.resize(table + size, 0);
+            for (slot, field) in fields {
+                let at = table + field_offsets[*slot as usize] as usize;
+                match field {
+                    Field::U8(v) => buf[at] = *v,
+                    Field::Bool(v) => buf[at] = u8::from(*v),
+                    Field::I16(v) => buf[at..at + 2].copy_from_slice(&v.to_le_bytes()),
+                    Field::I32(v) => buf[at..at + 4].copy_from_slice(&v.to_le_bytes()),
+                    Field::I64(v) => buf[at..at + 8].copy_from_slice(&v.to_le_bytes()),
+                    Field::Offset(_) => {}
+                }
+            }
+            for (slot, field) in fields {
+                if let Field::Offset(child) = field {
+                    let child_pos = write_object(buf, child);
+                    patch_offset(buf, table + field_offsets[*slot as usize] as usize, child_pos);
+                }
+            }
+            table
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u32(buf: &[u8], at: usize) -> usize {
+        u32::from_le_bytes(buf[at..at + 4].try_into().unwrap()) as usize
+    }
+
+    /// Position of a table field, following the vtable
+    fn field_pos(buf: &[u8], table: usize, slot: usize) -> usize {
+        let soffset = i32::from_le_bytes(buf[table..table + 4].try_into().unwrap());
+        let vtable = (table as i64 - soffset as i64) as usize;
+        let offset = u16::from_le_bytes(buf[vtable + 4 + 2 * slot..vtable + 6 + 2 * slot].try_into().unwrap());
+        table + offset as usize
+    }
+
+    #[test]
+    fn test_encode_table() {
+        let buf = encode(Object::Table(vec![
+            (0, Field::I16(7)),
+            (2, Field::Offset(Object::String("name".into()))),
+            (3, Field::I64(-1)),
+        ]));
+        assert_eq!(buf.len() % 8, 0);
+        let root = read_u32(&buf, 0);
+        assert_eq!(root % 8, 0);
+
+        let version = field_pos(&buf, root, 0);
+        assert_eq!(i16::from_le_bytes(buf[version..version + 2].try_into().unwrap()), 7);
+        let long = field_pos(&buf, root, 3);
+        assert_eq!(long % 8, 0);
+        assert_eq!(i64::from_le_bytes(buf[long..long + 8].try_into().unwrap()), -1);
+
+        let name_field = field_pos(&buf, root, 2);
+        let name = name_field + read_u32(&buf, name_field);
+        assert_eq!(&buf[name + 4..name + 4 + read_u32(&buf, name)], b"name");
+        // Slot 1 is absent
+        assert_eq!(field_pos(&buf, root, 1), root);
+    }
+
+    #[test]
+    fn test_record_batch_pads_short_columns() {
+        let columns = [Column::Signed(vec![1, 2, 3]), Column::Text(vec!["a".into()])];
+        let (metadata, body) = record_batch(&columns, 1, 3).unwrap();
+        assert_eq!(metadata.len() % 8, 0);
+        // i64 column: no validity, two values; text column: validity, offsets, data
+        assert_eq!(&body[..16], [2i64.to_le_bytes(), 3i64.to_le_bytes()].concat());
+        assert_eq!(body[16], 0b00);
+        assert_eq!(&body[24..36], [0i32.to_le_bytes(); 3].concat());
+        assert_eq!(body.len(), 40);
+    }
+}
@@ -85,6 +85,17 @@ pub mod segment;
 pub mod raw_data;
 pub mod writer;
 pub mod reader;
+pub mod clock;
+pub mod properties;
+pub mod codec;
+pub mod stitch;
+pub mod diff;
+pub mod conformance;
+pub mod export;
+pub mod schema;
+pub mod signal;
+pub mod storage;
+pub mod defrag;
 
 mod utils;
 
@@ -95,16 +106,33 @@ pub use error::{TdmsError, Result};
 pub use types::{
     DataType,
     TocFlags,
+    Endianness,
+    Complex,
+    F80,
     Timestamp,
+    TimestampConvention,
     Property,
     PropertyValue,
+    TdmsValue,
+    NumericValue,
 };
 
+pub use clock::{Clock, SystemClock, ManualClock};
+pub use storage::{TdmsStorage, StorageCursor};
+#[cfg(feature = "encryption")]
+pub use storage::{EncryptedStorage, KeyProvider};
+pub use codec::ChannelEncoding;
+pub use utils::StringEncoding;
+pub use defrag::{defragment, defragment_with, DefragOptions};
+
 // Metadata exports
 pub use metadata::{
     ObjectPath,
     RawDataIndex,
     ChannelMetadata,
+    GroupTree,
+    GroupNode,
+    ChannelId,
 };
 
 // Segment exports
@@ -112,6 +140,11 @@ pub use segment::{
     Segment,
     SegmentHeader,
     SegmentInfo,
+    SegmentTimeRange,
+    index_path_for,
+    find_index_file,
+    write_index_file,
+    metadata_crc_path_for,
 };
 
 // Raw data exports
@@ -122,12 +155,19 @@ pub use raw_data::{
 
 // Writer exports
 pub use writer::TdmsWriter;
-pub use writer::RotatingTdmsWriter;
+pub use writer::{DurabilityMode, DurabilityPolicy};
+pub use writer::FlushWindow;
+pub use writer::NamePolicy;
+pub use writer::{SegmentAction, SegmentPlan, SegmentReason, MetadataOverhead};
+pub use writer::ChannelSink;
+pub use writer::{RotatingTdmsWriter, RotatingWriterBuilder, RotationNaming};
 
 #[cfg(feature = "async")]
 pub use writer::AsyncTdmsWriter;
 #[cfg(feature = "async")]
 pub use writer::AsyncRotatingTdmsWriter;
+#[cfg(feature = "async")]
+pub use writer::WriterStall;
 
 
 // Reader exports
@@ -137,7 +177,34 @@ pub use reader::{
     StreamingReader,
     TdmsIter,        // Added
     TdmsStringIter,  // Added
+    TdmsWindowIter,
+    TdmsZipIter,
+    OwnedTdmsIter,
+    OwnedTdmsStringIter,
+    ReaderPool,
+    DecompressionCache,
+    TdmsFollower,
+    DaqmxInfo,
+    DaqmxScaler,
+    DaqmxScalerKind,
+    ParseWarning,
+    RawProperty,
+    IndexFileStatus,
+    MetadataCrcStatus,
+    ChannelPreview,
+    ChannelSummary,
+    WriterInfo,
+    AllProperties,
+    GroupProperties,
+    ReaderOptions,
+    UnknownPropertyPolicy,
+    IncompleteSegmentPolicy,
+    ConversionStats,
+    IndexStats,
+    ChannelIndexStats,
 };
+#[cfg(feature = "mmap")]
+pub use reader::MmapHints;
 
 // Prelude module for glob imports
 pub mod prelude {
@@ -148,7 +215,7 @@ pub mod prelude {
     //! ```
     
     pub use crate::error::{TdmsError, Result};
-    pub use crate::types::{DataType, PropertyValue, Timestamp};
+    pub use crate::types::{Complex, DataType, PropertyValue, TdmsValue, Timestamp};
     pub use crate::writer::TdmsWriter;
     pub use crate::reader::{TdmsReader, StreamingReader};
     
@@ -164,148 +231,8 @@ pub const TDMS_VERSION: u32 = 4713;
 pub const LIBRARY_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 
-// --- NEW DEFRAGMENT FEATURE ---
-use std::path::Path;
-
-/// Defragments a TDMS file by reading it and writing a new, optimized file.
-///
-/// This function reads all metadata and raw data from the `source_path`
-/// and writes it into a new TDMS file at `dest_path`. The new file will
-/// contain only one segment, with all metadata consolidated and all
-/// channel data stored in contiguous blocks.
-///
-/// This is useful for optimizing files for read speed or enabling
-/// zero-copy memory mapping, as fragmented channels will be made contiguous.
-///
-/// # Arguments
-///
-/// * `source_path` - The path to the fragmented TDMS file to read.
-/// * `dest_path` - The path where the new, defragmented TDMS file will be created.
-///
-/// # Example
-///
-/// ```no_run
-/// use tdms_rs::defragment;
-///
-/// fn main() -> tdms_rs::Result<()> {
-///     defragment("my_fragmented_file.tdms", "my_new_file.tdms")?;
-///     Ok(())
-/// }
-/// ```
-pub fn defragment(source_path: impl AsRef<Path>, dest_path: impl AsRef<Path>) -> Result<()> {
-    // Open the source file for reading.
-    let mut reader = TdmsReader::open(source_path)?;
-
-    // Create the new destination file for writing.
-    let mut writer = TdmsWriter::create(dest_path)?;
-
-    // 1. Copy File Properties
-    for prop in reader.get_file_properties().values() {
-        writer.set_file_property(prop.name.clone(), prop.value.clone());
-    }
-
-    // 2. Copy Group Properties
-    for group_name in reader.list_groups() {
-        if let Some(props) = reader.get_group_properties(&group_name) {
-            for prop in props.values() {
-                // TdmsWriter::set_group_property is fallible, but this should be fine
-                let _ = writer.set_group_property(group_name.clone(), prop.name.clone(), prop.value.clone());
-            }
-        }
-    }
-
-    // 3. Copy Channels (Properties and ALL Data)
-    for channel_path_str in reader.list_channels() {
-        if let Some(channel_reader) = reader.get_channel(&channel_path_str) {
-            let path = ObjectPath::from_string(&channel_path_str)?;
-            let (group, channel) = match path {
-                ObjectPath::Channel { group, channel } => (group, channel),
-                _ => continue, // Should not happen if list_channels is correct
-            };
-
-            // Create the channel in the new file
-            writer.create_channel(group.clone(), channel.clone(), channel_reader.data_type())?;
-
-            // Copy channel properties
-            for prop in channel_reader.get_properties().values() {
-                writer.set_channel_property(
-                    &group,
-                    &channel,
-                    prop.name.clone(),
-                    prop.value.clone(),
-                )?;
-            }
-
-            // Read ALL data for the channel (this concatenates all fragments)
-            // and write it to the new file in one go.
-            match channel_reader.data_type() {
-                DataType::String => {
-                    let data = reader.read_channel_strings(&group, &channel)?;
-                    writer.write_channel_strings(&group, &channel, &data)?;
-                }
-                DataType::I8 => {
-                    let data = reader.read_channel_data::<i8>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::I16 => {
-                    let data = reader.read_channel_data::<i16>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::I32 => {
-                    let data = reader.read_channel_data::<i32>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::I64 => {
-                    let data = reader.read_channel_data::<i64>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::U8 => {
-                    let data = reader.read_channel_data::<u8>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::U16 => {
-                    let data = reader.read_channel_data::<u16>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::U32 => {
-                    let data = reader.read_channel_data::<u32>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::U64 => {
-                    let data = reader.read_channel_data::<u64>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::SingleFloat => {
-                    let data = reader.read_channel_data::<f32>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::DoubleFloat => {
-                    let data = reader.read_channel_data::<f64>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::Boolean => {
-                    let data = reader.read_channel_data::<bool>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                DataType::TimeStamp => {
-                    let data = reader.read_channel_data::<Timestamp>(&group, &channel)?;
-                    writer.write_channel_data(&group, &channel, &data)?;
-                }
-                _ => {
-                    // Skip unsupported types for now
-                }
-            }
-        }
-    }
-
-    // 4. Flush the writer.
-    // This writes all buffered data into a single, contiguous segment.
-    writer.flush()?;
-
-    Ok(())
-}
-
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
@@ -323,9 +250,43 @@ mod tests {
         assert_eq!(DataType::I64.fixed_size(), Some(8));
         assert_eq!(DataType::F64.fixed_size(), Some(8));
         assert_eq!(DataType::TimeStamp.fixed_size(), Some(16));
+        assert_eq!(DataType::ExtendedFloat.fixed_size(), Some(10));
         assert_eq!(DataType::String.fixed_size(), None);
     }
 
+    #[test]
+    fn test_widens_to() {
+        assert!(DataType::I16.widens_to(DataType::I16));
+        assert!(DataType::I16.widens_to(DataType::SingleFloat));
+        assert!(DataType::U32.widens_to(DataType::I64));
+        assert!(DataType::SingleFloat.widens_to(DataType::DoubleFloat));
+        assert!(!DataType::I32.widens_to(DataType::SingleFloat));
+        assert!(!DataType::I64.widens_to(DataType::DoubleFloat));
+        assert!(!DataType::U16.widens_to(DataType::I16));
+        assert!(!DataType::I8.widens_to(DataType::U64));
+        assert!(!DataType::DoubleFloat.widens_to(DataType::I64));
+        assert!(!DataType::Boolean.widens_to(DataType::U8));
+    }
+
+    #[test]
+    fn test_f80_conversion() {
+        let one = F80([0, 0, 0, 0, 0, 0, 0, 0x80, 0xFF, 0x3F]);
+        assert_eq!(F80::from_f64(1.0), one);
+        assert_eq!(one.to_f64(), 1.0);
+        // π to 64 bits rounds to the f64 π
+        let pi = F80([0x35, 0xC2, 0x68, 0x21, 0xA2, 0xDA, 0x0F, 0xC9, 0x00, 0x40]);
+        assert_eq!(pi.to_f64(), std::f64::consts::PI);
+
+        for value in [0.0, -0.0, -2.5, 1e300, -1e-300, f64::MIN_POSITIVE, 5e-324, f64::MAX, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(F80::from_f64(value).to_f64().to_bits(), value.to_bits(), "{}", value);
+        }
+        assert!(F80::from_f64(f64::NAN).to_f64().is_nan());
+
+        // Beyond the range of f64
+        assert_eq!(F80([0, 0, 0, 0, 0, 0, 0, 0x80, 0xFE, 0x7F]).to_f64(), f64::INFINITY);
+        assert_eq!(F80([0, 0, 0, 0, 0, 0, 0, 0x80, 0x01, 0x80]).to_f64(), -0.0);
+    }
+
     #[test]
     fn test_toc_flags() {
         let mut toc = TocFlags::empty();
@@ -349,6 +310,31 @@ mod tests {
         assert!(ts.seconds > 0);
     }
 
+    #[test]
+    fn test_timestamp_arithmetic() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        for nanos in [0i64, 1, 999_999_999, 1_700_000_000_123_456_789, -1, -86_400_000_000_001] {
+            assert_eq!(Timestamp::from_unix_nanos(nanos).to_unix_nanos(), nanos);
+        }
+        let epoch = Timestamp::from_unix_nanos(0);
+        assert_eq!(epoch, Timestamp { seconds: 2_082_844_800, fractions: 0 });
+        assert_eq!(Timestamp::from_unix_nanos(-1).seconds, 2_082_844_799);
+
+        let later = epoch.add_duration(Duration::from_millis(1500));
+        assert_eq!(later, Timestamp { seconds: 2_082_844_801, fractions: 1 << 63 });
+        assert_eq!(later.diff(&epoch), 1.5);
+        assert_eq!(epoch.diff(&later), -1.5);
+        assert_eq!(later.sub_duration(Duration::from_millis(1500)), epoch);
+        assert!(epoch < later && later > Timestamp::default());
+
+        // Pre-1970 timestamps convert to SystemTime and back
+        let before = UNIX_EPOCH - Duration::new(86_400, 250_000_000);
+        assert_eq!(Timestamp::from_system_time(before).to_system_time(), before);
+        let after = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        assert_eq!(Timestamp::from_system_time(after).to_system_time(), after);
+    }
+
     #[test]
     fn test_object_path_formatting() {
         let root = ObjectPath::Root;
@@ -374,6 +360,17 @@ mod tests {
             ObjectPath::Group(name) => assert_eq!(name, "MyGroup"),
             _ => panic!("Expected Group"),
         }
+        
+        // Slashes and doubled quotes inside names
+        let channel = ObjectPath::Channel {
+            group: "Vehicle/Engine".to_string(),
+            channel: "Driver's RPM".to_string(),
+        };
+        assert_eq!(ObjectPath::from_string(&channel.to_string()).unwrap(), channel);
+        
+        assert!(ObjectPath::from_string("/'Unterminated").is_err());
+        assert!(ObjectPath::from_string("/'a'/'b'/'c'").is_err());
+        assert!(ObjectPath::from_string("NoSlash").is_err());
     }
 
     #[test]
@@ -435,9 +432,7 @@ pub mod test_helpers {
         std::fs::remove_file(path).ok();
         
         // Also remove index file
-        let mut index_path = path.to_path_buf();
-        index_path.set_extension("tdms_index");
-        std::fs::remove_file(index_path).ok();
+        std::fs::remove_file(crate::index_path_for(path)).ok();
     }
     
     /// Generate test data
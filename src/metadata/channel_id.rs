@@ -0,0 +1,21 @@
+// src/metadata/channel_id.rs
+
+/// A channel resolved once by name, for writing or reading it repeatedly
+/// without building an [`ObjectPath`](crate::ObjectPath) and hashing its
+/// names each time.
+/// 
+/// Get one from [`TdmsWriter::channel_id`](crate::TdmsWriter::channel_id) or
+/// [`TdmsReader::channel_id`](crate::TdmsReader::channel_id). An id is only
+/// meaningful to the writer or reader that handed it out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChannelId(usize);
+
+impl ChannelId {
+    pub(crate) fn new(index: usize) -> Self {
+        ChannelId(index)
+    }
+    
+    pub(crate) fn index(self) -> usize {
+        self.0
+    }
+}
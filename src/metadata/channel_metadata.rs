@@ -1,6 +1,7 @@
 // src/metadata/channel_metadata.rs
 use crate::types::{Property, PropertyValue, DataType};
 use crate::metadata::{ObjectPath, RawDataIndex};
+use crate::properties::wellknown;
 use std::collections::HashMap;
 
 /// Channel metadata tracking for TDMS channels
@@ -272,9 +273,9 @@ impl ChannelMetadata {
         increment: f64,
         samples: u64,
     ) {
-        self.set_property("wf_start_time", PropertyValue::Timestamp(start_time));
-        self.set_property("wf_increment", PropertyValue::Double(increment));
-        self.set_property("wf_samples", PropertyValue::U64(samples));
+        self.set_property(wellknown::WF_START_TIME, PropertyValue::Timestamp(start_time));
+        self.set_property(wellknown::WF_INCREMENT, PropertyValue::Double(increment));
+        self.set_property(wellknown::WF_SAMPLES, PropertyValue::U64(samples));
     }
     
     /// Set the unit string for this channel (convenience method)
@@ -283,12 +284,12 @@ impl ChannelMetadata {
     /// 
     /// * `unit` - The unit string (e.g., "V", "A", "Hz")
     pub fn set_unit(&mut self, unit: impl Into<String>) {
-        self.set_property("unit_string", PropertyValue::String(unit.into()));
+        self.set_property(wellknown::UNIT_STRING, PropertyValue::String(unit.into()));
     }
     
     /// Get the unit string if set
     pub fn get_unit(&self) -> Option<&str> {
-        self.get_property("unit_string").and_then(|p| {
+        self.get_property(wellknown::UNIT_STRING).and_then(|p| {
             match &p.value {
                 PropertyValue::String(s) => Some(s.as_str()),
                 _ => None,
@@ -0,0 +1,210 @@
+// src/metadata/hierarchy.rs
+//! Virtual group hierarchies.
+//!
+//! TDMS only has two levels below the root (groups and channels). By convention
+//! this crate treats a `/` inside a group name as a hierarchy separator, so the
+//! group `"Vehicle/Powertrain/Engine"` is the `Engine` node below
+//! `Vehicle/Powertrain`. The file itself still contains a single flat group with
+//! that name, so other TDMS readers see it unchanged. Empty levels are kept, so
+//! `"A//B"` and `"A/B"` stay distinct groups and every node's path is exactly
+//! the group name it stands for.
+
+use std::collections::BTreeMap;
+
+/// Separator between levels of a virtual group path
+pub const HIERARCHY_SEPARATOR: char = '/';
+
+/// Build a group name from hierarchy levels, e.g. `["Vehicle", "Engine"]` -> `"Vehicle/Engine"`
+pub fn join_group_path<S: AsRef<str>>(levels: &[S]) -> String {
+    let levels: Vec<&str> = levels.iter().map(|l| l.as_ref()).collect();
+    levels.join(&HIERARCHY_SEPARATOR.to_string())
+}
+
+/// Split a group name into its hierarchy levels, the inverse of
+/// [`join_group_path`]. Empty levels, e.g. from a leading `/`, are kept; only
+/// the empty name has no levels.
+pub fn split_group_path(group: &str) -> Vec<&str> {
+    if group.is_empty() {
+        return Vec::new();
+    }
+    group.split(HIERARCHY_SEPARATOR).collect()
+}
+
+/// A node in a virtual group tree
+#[derive(Debug, Clone, Default)]
+pub struct GroupNode {
+    name: String,
+    path: String,
+    is_group: bool,
+    channels: Vec<String>,
+    children: BTreeMap<String, GroupNode>,
+}
+
+impl GroupNode {
+    /// Name of this level (last path component)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Full virtual path of this node, usable as a group name
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Whether a real TDMS group exists with this node's path.
+    ///
+    /// Intermediate levels that only exist because of deeper groups return `false`.
+    pub fn is_group(&self) -> bool {
+        self.is_group
+    }
+
+    /// Channel names in the group at this node (not including descendants)
+    pub fn channels(&self) -> &[String] {
+        &self.channels
+    }
+
+    /// Child nodes, ordered by name
+    pub fn children(&self) -> impl Iterator<Item = &GroupNode> {
+        self.children.values()
+    }
+
+    /// Look up a direct child by name
+    pub fn child(&self, name: &str) -> Option<&GroupNode> {
+        self.children.get(name)
+    }
+
+    /// Paths of all real groups at or below this node
+    pub fn descendant_groups(&self) -> Vec<&str> {
+        let mut groups = Vec::new();
+        self.collect_groups(&mut groups);
+        groups
+    }
+
+    fn collect_groups<'a>(&'a self, out: &mut Vec<&'a str>) {
+        if self.is_group {
+            out.push(&self.path);
+        }
+        for child in self.children.values() {
+            child.collect_groups(out);
+        }
+    }
+}
+
+/// Tree view over the flat group list of a file
+///
+/// # Example
+///
+/// ```
+/// use tdms_rs::metadata::GroupTree;
+///
+/// let tree = GroupTree::from_groups([
+///     ("Vehicle/Powertrain/Engine", vec!["RPM".to_string()]),
+///     ("Vehicle/Chassis", vec![]),
+/// ]);
+/// let engine = tree.find("Vehicle/Powertrain/Engine").unwrap();
+/// assert_eq!(engine.channels(), ["RPM"]);
+/// assert!(!tree.find("Vehicle").unwrap().is_group());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GroupTree {
+    root: GroupNode,
+}
+
+impl GroupTree {
+    /// Build a tree from `(group name, channel names)` pairs
+    pub fn from_groups<G, I>(groups: I) -> Self
+    where
+        G: AsRef<str>,
+        I: IntoIterator<Item = (G, Vec<String>)>,
+    {
+        let mut root = GroupNode::default();
+
+        for (group, mut channels) in groups {
+            let levels = split_group_path(group.as_ref());
+            let mut node = &mut root;
+            for (depth, level) in levels.iter().enumerate() {
+                node = node.children.entry(level.to_string()).or_insert_with(|| GroupNode {
+                    name: level.to_string(),
+                    path: join_group_path(&levels[..=depth]),
+                    ..Default::default()
+                });
+            }
+            node.is_group = true;
+            node.channels.append(&mut channels);
+            node.channels.sort();
+        }
+
+        GroupTree { root }
+    }
+
+    /// Top-level nodes, ordered by name
+    pub fn roots(&self) -> impl Iterator<Item = &GroupNode> {
+        self.root.children()
+    }
+
+    /// Find the node at a virtual path such as `"Vehicle/Powertrain"`
+    pub fn find(&self, path: &str) -> Option<&GroupNode> {
+        let mut node = &self.root;
+        for level in split_group_path(path) {
+            node = node.children.get(level)?;
+        }
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_and_split() {
+        let group = join_group_path(&["Vehicle", "Powertrain", "Engine"]);
+        assert_eq!(group, "Vehicle/Powertrain/Engine");
+        assert_eq!(split_group_path(&group), vec!["Vehicle", "Powertrain", "Engine"]);
+        assert_eq!(split_group_path("/Flat/"), vec!["", "Flat", ""]);
+        assert_eq!(join_group_path(&split_group_path("/Flat/")), "/Flat/");
+        assert!(split_group_path("").is_empty());
+    }
+
+    #[test]
+    fn test_group_tree_keeps_empty_levels() {
+        let tree = GroupTree::from_groups([
+            ("A//B", vec!["x".to_string()]),
+            ("A/B", vec!["y".to_string()]),
+            ("/Flat/", vec!["z".to_string()]),
+        ]);
+
+        assert_eq!(tree.find("A//B").unwrap().channels(), ["x"]);
+        assert_eq!(tree.find("A/B").unwrap().channels(), ["y"]);
+        assert_eq!(tree.find("A").unwrap().descendant_groups(), vec!["A//B", "A/B"]);
+
+        let flat = tree.find("/Flat/").unwrap();
+        assert_eq!(flat.path(), "/Flat/");
+        assert_eq!(flat.channels(), ["z"]);
+        assert!(!tree.find("/Flat").unwrap().is_group());
+    }
+
+    #[test]
+    fn test_group_tree() {
+        let tree = GroupTree::from_groups([
+            ("Vehicle/Powertrain/Engine", vec!["RPM".to_string(), "Torque".to_string()]),
+            ("Vehicle/Powertrain", vec!["Gear".to_string()]),
+            ("Vehicle/Chassis", vec![]),
+            ("Ambient", vec!["Temp".to_string()]),
+        ]);
+
+        let roots: Vec<&str> = tree.roots().map(|n| n.name()).collect();
+        assert_eq!(roots, vec!["Ambient", "Vehicle"]);
+
+        let vehicle = tree.find("Vehicle").unwrap();
+        assert!(!vehicle.is_group());
+        assert_eq!(vehicle.descendant_groups(), vec!["Vehicle/Chassis", "Vehicle/Powertrain", "Vehicle/Powertrain/Engine"]);
+
+        let powertrain = vehicle.child("Powertrain").unwrap();
+        assert!(powertrain.is_group());
+        assert_eq!(powertrain.channels(), ["Gear"]);
+        assert_eq!(powertrain.child("Engine").unwrap().path(), "Vehicle/Powertrain/Engine");
+
+        assert!(tree.find("Vehicle/Missing").is_none());
+    }
+}
@@ -2,7 +2,11 @@
 mod object_path;
 mod raw_data_index;
 mod channel_metadata;
+mod hierarchy;
+mod channel_id;
 
 pub use object_path::ObjectPath;
 pub use raw_data_index::RawDataIndex;
-pub use channel_metadata::ChannelMetadata;
\ No newline at end of file
+pub use channel_metadata::ChannelMetadata;
+pub use channel_id::ChannelId;
+pub use hierarchy::{GroupTree, GroupNode, HIERARCHY_SEPARATOR, join_group_path, split_group_path};
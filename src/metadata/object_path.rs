@@ -3,7 +3,7 @@ use crate::error::{TdmsError, Result};
 use std::fmt;
 
 /// Represents an object path in the TDMS hierarchy
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ObjectPath {
     Root,
     Group(String),
@@ -30,29 +30,43 @@ impl ObjectPath {
             return Ok(ObjectPath::Root);
         }
 
-        let s = s.strip_prefix('/').ok_or_else(|| TdmsError::InvalidPath(s.to_string()))?;
-        let parts: Vec<&str> = s.split('/').collect();
+        let parts = Self::split_components(s).ok_or_else(|| TdmsError::InvalidPath(s.to_string()))?;
 
         match parts.as_slice() {
-            [group] => {
-                let group = group.strip_prefix('\'').and_then(|s| s.strip_suffix('\''))
-                    .ok_or_else(|| TdmsError::InvalidPath(s.to_string()))?
-                    .replace("''", "'");
-                Ok(ObjectPath::Group(group))
-            },
-            [group, channel] => {
-                let group = group.strip_prefix('\'').and_then(|s| s.strip_suffix('\''))
-                    .ok_or_else(|| TdmsError::InvalidPath(s.to_string()))?
-                    .replace("''", "'");
-                let channel = channel.strip_prefix('\'').and_then(|s| s.strip_suffix('\''))
-                    .ok_or_else(|| TdmsError::InvalidPath(s.to_string()))?
-                    .replace("''", "'");
-                Ok(ObjectPath::Channel { group: group.to_string(), channel })
-            },
+            [group] => Ok(ObjectPath::Group(group.clone())),
+            [group, channel] => Ok(ObjectPath::Channel { group: group.clone(), channel: channel.clone() }),
             _ => Err(TdmsError::InvalidPath(s.to_string())),
         }
     }
 
+    /// Split `/'a'/'b'` into its unescaped components.
+    ///
+    /// Names are quoted, so a `/` inside quotes is part of the name, not a separator.
+    fn split_components(s: &str) -> Option<Vec<String>> {
+        let mut parts = Vec::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '/' || chars.next() != Some('\'') {
+                return None;
+            }
+            let mut name = String::new();
+            loop {
+                match chars.next()? {
+                    '\'' if chars.peek() == Some(&'\'') => {
+                        chars.next();
+                        name.push('\'');
+                    }
+                    '\'' => break,
+                    c => name.push(c),
+                }
+            }
+            parts.push(name);
+        }
+
+        Some(parts)
+    }
+
     pub fn group(&self) -> Option<&str> {
         match self {
             ObjectPath::Channel { group, .. } => Some(group),
@@ -0,0 +1,29 @@
+// src/properties/alignment.rs
+//! Alignment of channels that join a recording after it started.
+//!
+//! A channel created mid-run has no values for the samples written before it
+//! existed. [`TdmsWriter::create_late_channel`](crate::TdmsWriter::create_late_channel)
+//! records how many samples it missed in `tdms_rs_start_index`, and
+//! [`TdmsReader::read_channel_data_padded`](crate::TdmsReader::read_channel_data_padded)
+//! uses it to put the missing samples back, e.g. as NaN, so the channel lines up
+//! with the ones that were there from the start. Other TDMS readers see the
+//! channel's values unpadded.
+
+use crate::types::{Property, PropertyValue};
+use std::collections::HashMap;
+
+/// Index, in samples of the run, of a channel's first value
+pub const START_INDEX: &str = "tdms_rs_start_index";
+
+/// The start index recorded in a channel's properties; 0 if there is none
+pub fn start_index(properties: &HashMap<String, Property>) -> u64 {
+    properties.get(START_INDEX)
+        .and_then(|p| p.value.as_i64())
+        .and_then(|v| u64::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// The property recording a channel's start index
+pub fn start_index_property(start_index: u64) -> Property {
+    Property::new(START_INDEX, PropertyValue::U64(start_index))
+}
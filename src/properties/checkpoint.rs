@@ -0,0 +1,36 @@
+// src/properties/checkpoint.rs
+//! Heartbeat checkpoints of how much data a file holds.
+//!
+//! With [`TdmsWriter::set_heartbeat`](crate::TdmsWriter::set_heartbeat) the
+//! writer periodically stamps the file with `tdms_rs_last_checkpoint_time`
+//! and each channel with `tdms_rs_checkpoint_samples`, the number of its
+//! values in the file up to and including the segment carrying the
+//! checkpoint. After a crash, [`TdmsReader::checkpoint`](crate::TdmsReader::checkpoint)
+//! tells how far the file is known to be complete and when it last was,
+//! without reading any raw data.
+
+use crate::metadata::ObjectPath;
+use crate::types::{Property, PropertyValue, Timestamp};
+
+/// File property: writer clock time of the last checkpoint
+pub const LAST_CHECKPOINT_TIME: &str = "tdms_rs_last_checkpoint_time";
+/// Channel property: values in the file as of the last checkpoint
+pub const CHECKPOINT_SAMPLES: &str = "tdms_rs_checkpoint_samples";
+
+/// The last checkpoint recorded in a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub time: Timestamp,
+    /// Values each channel had in the file at the checkpoint, sorted by path
+    pub samples: Vec<(ObjectPath, u64)>,
+}
+
+/// The file property recording a checkpoint's time
+pub fn checkpoint_time_property(time: Timestamp) -> Property {
+    Property::new(LAST_CHECKPOINT_TIME, PropertyValue::Timestamp(time))
+}
+
+/// The channel property recording its values at a checkpoint
+pub fn checkpoint_samples_property(samples: u64) -> Property {
+    Property::new(CHECKPOINT_SAMPLES, PropertyValue::U64(samples))
+}
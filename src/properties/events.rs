@@ -0,0 +1,28 @@
+// src/properties/events.rs
+//! Event channels: timestamped text messages annotating a run.
+//!
+//! An event channel is a pair of channels in one group: a `String` channel
+//! holding the messages and a `TimeStamp` channel, by default named
+//! `"<name> Time"`, holding when each happened. The message channel names its
+//! time channel in the `tdms_rs_event_time_channel` property.
+//! [`TdmsWriter::write_event`](crate::TdmsWriter::write_event) buffers both
+//! halves of an event together, so they always land in the same segment, and
+//! [`TdmsReader::read_events`](crate::TdmsReader::read_events) pairs them back
+//! up.
+
+use crate::types::Timestamp;
+
+/// Message channel property: name of the channel holding the event times
+pub const EVENT_TIME_CHANNEL: &str = "tdms_rs_event_time_channel";
+
+/// Name of the time channel created for the event channel `name`
+pub fn time_channel_name(name: &str) -> String {
+    format!("{} Time", name)
+}
+
+/// One event of an event channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub time: Timestamp,
+    pub message: String,
+}
@@ -0,0 +1,66 @@
+// src/properties/experiment.rs
+//! Experiment timestamps written by SignalExpress and FlexLogger.
+//!
+//! These live on the root object: `NI_ExpStartTimeStamp` marks when the
+//! experiment (or logging session) began and `NI_ExpTimeStamp` when the file
+//! was last updated.
+
+use crate::types::{Property, PropertyValue, Timestamp};
+use std::collections::HashMap;
+
+/// Start of the experiment / logging session
+pub const NI_EXP_START_TIME_STAMP: &str = "NI_ExpStartTimeStamp";
+/// Time the experiment data was last written
+pub const NI_EXP_TIME_STAMP: &str = "NI_ExpTimeStamp";
+
+/// The experiment timestamps found on an object
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExperimentTimestamps {
+    pub start: Option<Timestamp>,
+    pub time: Option<Timestamp>,
+}
+
+impl ExperimentTimestamps {
+    /// Read the timestamps from a property map, ignoring values that aren't timestamps
+    pub fn from_properties(properties: &HashMap<String, Property>) -> Self {
+        let ts = |name: &str| properties.get(name).and_then(|p| p.value.as_timestamp());
+        ExperimentTimestamps {
+            start: ts(NI_EXP_START_TIME_STAMP),
+            time: ts(NI_EXP_TIME_STAMP),
+        }
+    }
+
+    /// The properties to write for these timestamps (unset ones are omitted)
+    pub fn to_properties(&self) -> Vec<Property> {
+        let mut properties = Vec::new();
+        if let Some(start) = self.start {
+            properties.push(Property::new(NI_EXP_START_TIME_STAMP, PropertyValue::Timestamp(start)));
+        }
+        if let Some(time) = self.time {
+            properties.push(Property::new(NI_EXP_TIME_STAMP, PropertyValue::Timestamp(time)));
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_experiment_timestamps_roundtrip() {
+        let stamps = ExperimentTimestamps {
+            start: Some(Timestamp { seconds: 3_800_000_000, fractions: 0 }),
+            time: None,
+        };
+        let properties: HashMap<String, Property> = stamps.to_properties().into_iter()
+            .map(|p| (p.name.clone(), p))
+            .collect();
+        assert_eq!(properties.len(), 1);
+        assert_eq!(ExperimentTimestamps::from_properties(&properties), stamps);
+
+        let mut wrong = HashMap::new();
+        wrong.insert(NI_EXP_TIME_STAMP.to_string(), Property::new(NI_EXP_TIME_STAMP, PropertyValue::String("now".into())));
+        assert_eq!(ExperimentTimestamps::from_properties(&wrong), ExperimentTimestamps::default());
+    }
+}
@@ -0,0 +1,176 @@
+// src/properties/flexlogger.rs
+//! The property layout produced by FlexLogger.
+//!
+//! FlexLogger logs test properties on the root object and describes each
+//! channel with a unit, waveform timing and an NI scaling chain. These structs
+//! collect that layout so analysis code can work with typed fields, and can
+//! write the same layout back out.
+
+use crate::properties::experiment::ExperimentTimestamps;
+use crate::properties::scaling::{Scaling, NI_NUMBER_OF_SCALES, NI_SCALING_STATUS};
+use crate::properties::waveform::WaveformTiming;
+use crate::types::{Property, PropertyValue};
+use std::collections::HashMap;
+
+pub use crate::properties::wellknown::{NAME, DESCRIPTION, UNIT_STRING, NI_CHANNEL_NAME};
+use crate::properties::wellknown::{LIBRARY, LIBRARY_VERSION};
+
+fn is_scale_property(name: &str) -> bool {
+    name.starts_with("NI_Scale[") || name == NI_NUMBER_OF_SCALES || name == NI_SCALING_STATUS
+}
+
+fn string(properties: &HashMap<String, Property>, name: &str) -> Option<String> {
+    properties.get(name).and_then(|p| p.value.as_str()).map(str::to_string)
+}
+
+/// File-level FlexLogger metadata
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlexLoggerFile {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub experiment: ExperimentTimestamps,
+    /// User-defined test properties (operator, DUT serial number, ...)
+    pub test_properties: HashMap<String, PropertyValue>,
+}
+
+impl FlexLoggerFile {
+    pub fn from_properties(properties: &HashMap<String, Property>) -> Self {
+        let test_properties = properties.iter()
+            .filter(|(name, _)| !name.starts_with("NI_") && ![NAME, DESCRIPTION, LIBRARY, LIBRARY_VERSION].contains(&name.as_str()))
+            .map(|(name, p)| (name.clone(), p.value.clone()))
+            .collect();
+
+        FlexLoggerFile {
+            name: string(properties, NAME),
+            description: string(properties, DESCRIPTION),
+            experiment: ExperimentTimestamps::from_properties(properties),
+            test_properties,
+        }
+    }
+
+    pub fn to_properties(&self) -> Vec<Property> {
+        let mut properties = Vec::new();
+        if let Some(name) = &self.name {
+            properties.push(Property::new(NAME, PropertyValue::String(name.clone())));
+        }
+        if let Some(description) = &self.description {
+            properties.push(Property::new(DESCRIPTION, PropertyValue::String(description.clone())));
+        }
+        properties.extend(self.experiment.to_properties());
+        properties.extend(self.test_properties.iter().map(|(name, value)| Property::new(name.clone(), value.clone())));
+        properties
+    }
+}
+
+/// Channel-level FlexLogger metadata
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlexLoggerChannel {
+    pub display_name: Option<String>,
+    pub unit: Option<String>,
+    pub description: Option<String>,
+    pub timing: Option<WaveformTiming>,
+    pub scaling: Option<Scaling>,
+    /// Remaining properties, such as sensor metadata
+    pub other: HashMap<String, PropertyValue>,
+}
+
+impl FlexLoggerChannel {
+    pub fn from_properties(properties: &HashMap<String, Property>) -> Self {
+        let other = properties.iter()
+            .filter(|(name, _)| {
+                !name.starts_with("wf_")
+                    && !is_scale_property(name)
+                    && ![NI_CHANNEL_NAME, UNIT_STRING, DESCRIPTION].contains(&name.as_str())
+            })
+            .map(|(name, p)| (name.clone(), p.value.clone()))
+            .collect();
+
+        FlexLoggerChannel {
+            display_name: string(properties, NI_CHANNEL_NAME),
+            unit: string(properties, UNIT_STRING),
+            description: string(properties, DESCRIPTION),
+            timing: WaveformTiming::from_properties(properties),
+            scaling: Scaling::from_properties(properties),
+            other,
+        }
+    }
+
+    pub fn to_properties(&self) -> Vec<Property> {
+        let mut properties = Vec::new();
+        if let Some(name) = &self.display_name {
+            properties.push(Property::new(NI_CHANNEL_NAME, PropertyValue::String(name.clone())));
+        }
+        if let Some(unit) = &self.unit {
+            properties.push(Property::new(UNIT_STRING, PropertyValue::String(unit.clone())));
+        }
+        if let Some(description) = &self.description {
+            properties.push(Property::new(DESCRIPTION, PropertyValue::String(description.clone())));
+        }
+        if let Some(timing) = &self.timing {
+            properties.extend(timing.to_properties());
+        }
+        if let Some(scaling) = &self.scaling {
+            properties.extend(scaling.to_properties());
+        }
+        properties.extend(self.other.iter().map(|(name, value)| Property::new(name.clone(), value.clone())));
+        properties
+    }
+
+    /// Sample rate in Hz, from the waveform increment
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.timing.as_ref().and_then(WaveformTiming::sample_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::properties::scaling::Scale;
+    use crate::types::Timestamp;
+
+    fn to_map(properties: Vec<Property>) -> HashMap<String, Property> {
+        properties.into_iter().map(|p| (p.name.clone(), p)).collect()
+    }
+
+    #[test]
+    fn test_channel_profile_roundtrip() {
+        let mut other = HashMap::new();
+        other.insert("Sensor Serial".to_string(), PropertyValue::String("SN-123".into()));
+        let channel = FlexLoggerChannel {
+            display_name: Some("Accel X".into()),
+            unit: Some("g".into()),
+            description: None,
+            timing: Some(WaveformTiming {
+                start_time: Some(Timestamp { seconds: 3_800_000_000, fractions: 0 }),
+                increment: Some(0.001),
+                samples: None,
+                start_offset: None,
+            }),
+            scaling: Some(Scaling {
+                scales: vec![Scale::Linear { slope: 0.01, intercept: 0.0, input_source: -1 }],
+                already_scaled: true,
+            }),
+            other,
+        };
+        let parsed = FlexLoggerChannel::from_properties(&to_map(channel.to_properties()));
+        assert_eq!(parsed, channel);
+        assert_eq!(parsed.sample_rate(), Some(1000.0));
+    }
+
+    #[test]
+    fn test_file_profile_separates_test_properties() {
+        let mut map = HashMap::new();
+        for (name, value) in [
+            (NAME, PropertyValue::String("Run 7".into())),
+            ("Operator", PropertyValue::String("J. Doe".into())),
+            ("NI_ExpStartTimeStamp", PropertyValue::Timestamp(Timestamp::default())),
+        ] {
+            map.insert(name.to_string(), Property::new(name, value));
+        }
+        let file = FlexLoggerFile::from_properties(&map);
+        assert_eq!(file.name.as_deref(), Some("Run 7"));
+        assert_eq!(file.experiment.start, Some(Timestamp::default()));
+        assert_eq!(file.test_properties.len(), 1);
+        assert!(file.test_properties.contains_key("Operator"));
+    }
+}
@@ -0,0 +1,128 @@
+// src/properties/labview.rs
+//! Properties written by LabVIEW's TDMS functions.
+//!
+//! When LabVIEW writes a 2D array or a cluster of channels it stores each column
+//! as its own channel and records where it came from with `NI_ArrayColumn`,
+//! alongside bookkeeping such as `NI_ChannelLength` and `NI_DataType`.
+
+use crate::types::Property;
+use std::collections::HashMap;
+
+/// Column index of the channel within the array it was written from
+pub const NI_ARRAY_COLUMN: &str = "NI_ArrayColumn";
+/// Number of values LabVIEW recorded for the channel
+pub const NI_CHANNEL_LENGTH: &str = "NI_ChannelLength";
+/// Original channel (or cluster element) name
+pub const NI_CHANNEL_NAME: &str = "NI_ChannelName";
+/// LabVIEW type code of the source data
+pub const NI_DATA_TYPE: &str = "NI_DataType";
+/// Free-form description of the channel unit
+pub const NI_UNIT_DESCRIPTION: &str = "NI_UnitDescription";
+
+/// The LabVIEW bookkeeping properties of a single channel
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LabVIEWChannelProperties {
+    pub array_column: Option<u32>,
+    pub channel_length: Option<u64>,
+    pub channel_name: Option<String>,
+    pub data_type_code: Option<i64>,
+    pub unit_description: Option<String>,
+}
+
+impl LabVIEWChannelProperties {
+    /// Extract the LabVIEW properties from a channel's property map.
+    ///
+    /// Integer properties are accepted in any integer width, since different
+    /// LabVIEW versions store them differently. Values of the wrong kind are ignored.
+    pub fn from_properties(properties: &HashMap<String, Property>) -> Self {
+        let int = |name: &str| properties.get(name).and_then(|p| p.value.as_i64());
+        let string = |name: &str| properties.get(name)
+            .and_then(|p| p.value.as_str())
+            .map(str::to_string);
+
+        LabVIEWChannelProperties {
+            array_column: int(NI_ARRAY_COLUMN).and_then(|v| u32::try_from(v).ok()),
+            channel_length: int(NI_CHANNEL_LENGTH).and_then(|v| u64::try_from(v).ok()),
+            channel_name: string(NI_CHANNEL_NAME),
+            data_type_code: int(NI_DATA_TYPE),
+            unit_description: string(NI_UNIT_DESCRIPTION),
+        }
+    }
+
+    /// Whether any LabVIEW property was present
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A channel that was one column of a LabVIEW array
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayColumn {
+    pub channel: String,
+    pub column: u32,
+    pub length: Option<u64>,
+}
+
+/// Reassemble the column order of a group written from a LabVIEW array.
+///
+/// Channels without `NI_ArrayColumn` are skipped; the rest are ordered by column.
+pub fn array_columns<'a, I>(channels: I) -> Vec<ArrayColumn>
+where
+    I: IntoIterator<Item = (&'a str, &'a HashMap<String, Property>)>,
+{
+    let mut columns: Vec<ArrayColumn> = channels.into_iter()
+        .filter_map(|(channel, properties)| {
+            let props = LabVIEWChannelProperties::from_properties(properties);
+            props.array_column.map(|column| ArrayColumn {
+                channel: channel.to_string(),
+                column,
+                length: props.channel_length,
+            })
+        })
+        .collect();
+    columns.sort_by(|a, b| a.column.cmp(&b.column).then_with(|| a.channel.cmp(&b.channel)));
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PropertyValue;
+
+    fn props(list: &[(&str, PropertyValue)]) -> HashMap<String, Property> {
+        list.iter()
+            .map(|(name, value)| (name.to_string(), Property::new(*name, value.clone())))
+            .collect()
+    }
+
+    #[test]
+    fn test_channel_properties_any_int_width() {
+        let p = props(&[
+            (NI_ARRAY_COLUMN, PropertyValue::I32(2)),
+            (NI_CHANNEL_LENGTH, PropertyValue::U64(1000)),
+            (NI_CHANNEL_NAME, PropertyValue::String("Temp".into())),
+            (NI_DATA_TYPE, PropertyValue::I16(10)),
+        ]);
+        let lv = LabVIEWChannelProperties::from_properties(&p);
+        assert_eq!(lv.array_column, Some(2));
+        assert_eq!(lv.channel_length, Some(1000));
+        assert_eq!(lv.channel_name.as_deref(), Some("Temp"));
+        assert_eq!(lv.data_type_code, Some(10));
+        assert!(lv.unit_description.is_none());
+
+        let wrong = props(&[(NI_ARRAY_COLUMN, PropertyValue::String("x".into()))]);
+        assert!(LabVIEWChannelProperties::from_properties(&wrong).is_empty());
+    }
+
+    #[test]
+    fn test_array_columns_ordering() {
+        let a = props(&[(NI_ARRAY_COLUMN, PropertyValue::I32(1))]);
+        let b = props(&[(NI_ARRAY_COLUMN, PropertyValue::U32(0)), (NI_CHANNEL_LENGTH, PropertyValue::U64(5))]);
+        let c = props(&[]);
+        let columns = array_columns([("a", &a), ("b", &b), ("c", &c)]);
+        assert_eq!(columns, vec![
+            ArrayColumn { channel: "b".into(), column: 0, length: Some(5) },
+            ArrayColumn { channel: "a".into(), column: 1, length: None },
+        ]);
+    }
+}
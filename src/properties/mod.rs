@@ -0,0 +1,26 @@
+// src/properties/mod.rs
+//! Typed views over conventional property sets.
+//!
+//! NI software encodes a lot of structure as plain properties with agreed-upon
+//! names. The submodules here interpret those conventions so callers don't have
+//! to look up and convert raw [`PropertyValue`](crate::types::PropertyValue)s.
+
+pub mod labview;
+pub mod experiment;
+pub mod scaling;
+pub mod waveform;
+pub mod flexlogger;
+pub mod alignment;
+pub mod sampling;
+pub mod checkpoint;
+pub mod events;
+pub mod wellknown;
+
+pub use labview::{LabVIEWChannelProperties, ArrayColumn};
+pub use experiment::ExperimentTimestamps;
+pub use scaling::{Scale, Scaling};
+pub use waveform::WaveformTiming;
+pub use flexlogger::{FlexLoggerFile, FlexLoggerChannel};
+pub use sampling::SampleAccounting;
+pub use checkpoint::Checkpoint;
+pub use events::Event;
@@ -0,0 +1,106 @@
+// src/properties/sampling.rs
+//! Expected sample rates and dropped-sample accounting.
+//!
+//! An overloaded acquisition system silently writes fewer samples than its
+//! configured rate implies. [`TdmsWriter::set_expected_sample_rate`](crate::TdmsWriter::set_expected_sample_rate)
+//! records the rate a channel should run at in `tdms_rs_expected_sample_rate`,
+//! and each [`TdmsWriter::record_sample_accounting`](crate::TdmsWriter::record_sample_accounting)
+//! stores how many samples were expected by then and how many were written.
+//! The latest record is read back with
+//! [`TdmsReader::sample_accounting`](crate::TdmsReader::sample_accounting);
+//! [`TdmsReader::property_history`](crate::TdmsReader::property_history) has
+//! every earlier one, so drops can be placed in time.
+
+use crate::types::{Property, PropertyValue, Timestamp};
+use std::collections::HashMap;
+
+/// Samples per second the channel is expected to run at
+pub const EXPECTED_SAMPLE_RATE: &str = "tdms_rs_expected_sample_rate";
+/// Samples expected between the rate being set and the last record
+pub const EXPECTED_SAMPLES: &str = "tdms_rs_expected_samples";
+/// Samples written between the rate being set and the last record
+pub const ACTUAL_SAMPLES: &str = "tdms_rs_actual_samples";
+/// Time of the last record
+pub const ACCOUNTED_AT: &str = "tdms_rs_accounted_at";
+
+/// Expected and written sample counts of a channel at one point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleAccounting {
+    /// Samples per second the channel should run at
+    pub expected_rate: f64,
+    pub expected: u64,
+    pub actual: u64,
+    pub at: Option<Timestamp>,
+}
+
+impl SampleAccounting {
+    /// Samples missing compared to the expected rate
+    pub fn dropped(&self) -> u64 {
+        self.expected.saturating_sub(self.actual)
+    }
+
+    /// Fraction of the expected samples that are missing, 0 when none were expected
+    pub fn drop_ratio(&self) -> f64 {
+        if self.expected == 0 {
+            0.0
+        } else {
+            self.dropped() as f64 / self.expected as f64
+        }
+    }
+
+    /// Read the accounting from a channel's properties. `None` without an
+    /// expected rate; counts not recorded yet are 0.
+    pub fn from_properties(properties: &HashMap<String, Property>) -> Option<Self> {
+        let count = |name: &str| properties.get(name)
+            .and_then(|p| p.value.as_i64())
+            .and_then(|v| u64::try_from(v).ok())
+            .unwrap_or(0);
+        Some(SampleAccounting {
+            expected_rate: properties.get(EXPECTED_SAMPLE_RATE)?.value.as_f64()?,
+            expected: count(EXPECTED_SAMPLES),
+            actual: count(ACTUAL_SAMPLES),
+            at: properties.get(ACCOUNTED_AT).and_then(|p| p.value.as_timestamp()),
+        })
+    }
+
+    /// The properties recording the counts (the rate is written separately)
+    pub fn to_properties(&self) -> Vec<Property> {
+        let mut properties = vec![
+            Property::new(EXPECTED_SAMPLES, PropertyValue::U64(self.expected)),
+            Property::new(ACTUAL_SAMPLES, PropertyValue::U64(self.actual)),
+        ];
+        if let Some(at) = self.at {
+            properties.push(Property::new(ACCOUNTED_AT, PropertyValue::Timestamp(at)));
+        }
+        properties
+    }
+}
+
+/// The property recording a channel's expected sample rate
+pub fn expected_sample_rate_property(rate: f64) -> Property {
+    Property::new(EXPECTED_SAMPLE_RATE, PropertyValue::Double(rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_accounting_roundtrip() {
+        let accounting = SampleAccounting { expected_rate: 10.0, expected: 40, actual: 30, at: None };
+        let mut properties: HashMap<String, Property> = accounting.to_properties().into_iter()
+            .map(|p| (p.name.clone(), p))
+            .collect();
+        assert_eq!(SampleAccounting::from_properties(&properties), None);
+
+        let rate = expected_sample_rate_property(10.0);
+        properties.insert(rate.name.clone(), rate);
+        assert_eq!(SampleAccounting::from_properties(&properties), Some(accounting));
+        assert_eq!(accounting.dropped(), 10);
+        assert_eq!(accounting.drop_ratio(), 0.25);
+
+        // More samples than expected isn't a negative drop
+        let ahead = SampleAccounting { actual: 50, ..accounting };
+        assert_eq!(ahead.dropped(), 0);
+    }
+}
@@ -0,0 +1,260 @@
+// src/properties/scaling.rs
+//! NI scaling properties (`NI_Scale[n]_*`).
+//!
+//! DAQmx, SignalExpress and FlexLogger describe how to turn stored values into
+//! engineering units with a numbered list of scales. Scale `n` reads its input
+//! from `NI_Scale[n]_<Type>_Input_Source`, where `-1` is the raw channel data.
+
+use crate::properties::wellknown;
+use crate::types::{Property, PropertyValue};
+use std::collections::HashMap;
+
+/// Number of scales defined for the channel
+pub const NI_NUMBER_OF_SCALES: &str = "NI_Number_Of_Scales";
+/// `"scaled"` if the stored data already has scaling applied, `"unscaled"` otherwise
+pub const NI_SCALING_STATUS: &str = "NI_Scaling_Status";
+
+/// Name of a property belonging to scale `index`, e.g. `scale_property(1, "Linear_Slope")`
+pub fn scale_property(index: usize, suffix: &str) -> String {
+    format!("NI_Scale[{}]_{}", index, suffix)
+}
+
+/// One past the highest `n` of the properties named `<prefix>n]...`.
+///
+/// Counts stored in a file can be anything, so they are capped by the
+/// properties actually there.
+fn indexes_present(properties: &HashMap<String, Property>, prefix: &str) -> usize {
+    properties.keys()
+        .filter_map(|name| name.strip_prefix(prefix)?.split_once(']')?.0.parse::<usize>().ok())
+        .map(|index| index.saturating_add(1))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Some writers store the raw-data source `-1` as an unsigned 32-bit value
+fn normalize_source(source: i64) -> i64 {
+    if source == u32::MAX as i64 { -1 } else { source }
+}
+
+/// A single scale in a channel's scaling chain
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scale {
+    Linear { slope: f64, intercept: f64, input_source: i64 },
+    Polynomial { coefficients: Vec<f64>, input_source: i64 },
+    /// A scale type this crate doesn't interpret, empty if the scale has no
+    /// type property
+    Other { scale_type: String },
+}
+
+impl Scale {
+    /// Apply the scale to a value, or `None` for uninterpreted scale types
+    pub fn apply(&self, value: f64) -> Option<f64> {
+        match self {
+            Scale::Linear { slope, intercept, .. } => Some(value * slope + intercept),
+            Scale::Polynomial { coefficients, .. } => {
+                Some(coefficients.iter().rev().fold(0.0, |acc, c| acc * value + c))
+            }
+            Scale::Other { .. } => None,
+        }
+    }
+
+    /// Short human-readable description, e.g. `"Linear (y = 2x + 0.5)"`
+    pub fn describe(&self) -> String {
+        match self {
+            Scale::Linear { slope, intercept, .. } => format!("Linear (y = {}x + {})", slope, intercept),
+            Scale::Polynomial { coefficients, .. } => format!("Polynomial ({} coefficients)", coefficients.len()),
+            Scale::Other { scale_type } => scale_type.clone(),
+        }
+    }
+
+    fn read(properties: &HashMap<String, Property>, index: usize) -> Scale {
+        let get = |name: String| properties.get(&name).map(|p| &p.value);
+        let scale_type = get(wellknown::ni_scale_type(index)).and_then(PropertyValue::as_str).unwrap_or_default().to_string();
+
+        match scale_type.as_str() {
+            "Linear" => Scale::Linear {
+                slope: get(wellknown::ni_scale_linear_slope(index)).and_then(PropertyValue::as_f64).unwrap_or(1.0),
+                intercept: get(wellknown::ni_scale_linear_y_intercept(index)).and_then(PropertyValue::as_f64).unwrap_or(0.0),
+                input_source: get(wellknown::ni_scale_linear_input_source(index)).and_then(PropertyValue::as_i64).map(normalize_source).unwrap_or(-1),
+            },
+            "Polynomial" => {
+                let size = get(wellknown::ni_scale_polynomial_coefficients_size(index)).and_then(PropertyValue::as_i64).unwrap_or(0);
+                let present = indexes_present(properties, &scale_property(index, "Polynomial_Coefficients["));
+                let coefficients = (0..size.clamp(0, present as i64) as usize)
+                    .map(|i| get(wellknown::ni_scale_polynomial_coefficient(index, i)).and_then(PropertyValue::as_f64).unwrap_or(0.0))
+                    .collect();
+                Scale::Polynomial {
+                    coefficients,
+                    input_source: get(wellknown::ni_scale_polynomial_input_source(index)).and_then(PropertyValue::as_i64).map(normalize_source).unwrap_or(-1),
+                }
+            }
+            _ => Scale::Other { scale_type },
+        }
+    }
+
+    fn write(&self, index: usize, out: &mut Vec<Property>) {
+        let mut push = |name: String, value: PropertyValue| {
+            out.push(Property::new(name, value));
+        };
+        match self {
+            Scale::Linear { slope, intercept, input_source } => {
+                push(wellknown::ni_scale_type(index), PropertyValue::String("Linear".into()));
+                push(wellknown::ni_scale_linear_slope(index), PropertyValue::Double(*slope));
+                push(wellknown::ni_scale_linear_y_intercept(index), PropertyValue::Double(*intercept));
+                push(wellknown::ni_scale_linear_input_source(index), PropertyValue::I32(*input_source as i32));
+            }
+            Scale::Polynomial { coefficients, input_source } => {
+                push(wellknown::ni_scale_type(index), PropertyValue::String("Polynomial".into()));
+                push(wellknown::ni_scale_polynomial_coefficients_size(index), PropertyValue::U32(coefficients.len() as u32));
+                for (i, c) in coefficients.iter().enumerate() {
+                    push(wellknown::ni_scale_polynomial_coefficient(index, i), PropertyValue::Double(*c));
+                }
+                push(wellknown::ni_scale_polynomial_input_source(index), PropertyValue::I32(*input_source as i32));
+            }
+            Scale::Other { scale_type } => {
+                push(wellknown::ni_scale_type(index), PropertyValue::String(scale_type.clone()));
+            }
+        }
+    }
+}
+
+/// A channel's full scaling description
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scaling {
+    /// Scales in index order (`NI_Scale[0]` first)
+    pub scales: Vec<Scale>,
+    /// `true` if `NI_Scaling_Status` says the data is already scaled
+    pub already_scaled: bool,
+}
+
+impl Scaling {
+    /// Read the scaling properties of a channel; `None` if it has no scales
+    pub fn from_properties(properties: &HashMap<String, Property>) -> Option<Self> {
+        let count = properties.get(NI_NUMBER_OF_SCALES)?.value.as_i64()?;
+        let present = indexes_present(properties, "NI_Scale[");
+        // Scales without a type keep their place, so input sources still
+        // refer to the right scale
+        let scales: Vec<Scale> = (0..count.clamp(0, present as i64) as usize)
+            .map(|i| Scale::read(properties, i))
+            .collect();
+        if scales.is_empty() {
+            return None;
+        }
+        let already_scaled = properties.get(NI_SCALING_STATUS)
+            .and_then(|p| p.value.as_str())
+            .is_some_and(|s| s.eq_ignore_ascii_case("scaled"));
+        Some(Scaling { scales, already_scaled })
+    }
+
+    /// The properties describing this scaling
+    pub fn to_properties(&self) -> Vec<Property> {
+        let mut properties = vec![
+            Property::new(NI_NUMBER_OF_SCALES, PropertyValue::U32(self.scales.len() as u32)),
+            Property::new(NI_SCALING_STATUS, PropertyValue::String(
+                if self.already_scaled { "scaled" } else { "unscaled" }.into())),
+        ];
+        for (i, scale) in self.scales.iter().enumerate() {
+            scale.write(i, &mut properties);
+        }
+        properties
+    }
+
+    /// Apply the scale chain to a raw value.
+    ///
+    /// The last scale is evaluated, following `Input_Source` references back to
+    /// the raw value (`-1`). Returns `None` if the chain contains a scale type
+    /// that isn't interpreted or refers to a missing scale.
+    pub fn apply(&self, raw: f64) -> Option<f64> {
+        self.apply_index(self.scales.len().checked_sub(1)?, raw, 0)
+    }
+
+    fn apply_index(&self, index: usize, raw: f64, depth: usize) -> Option<f64> {
+        if depth > self.scales.len() {
+            return None; // cyclic references
+        }
+        let scale = self.scales.get(index)?;
+        let source = match scale {
+            Scale::Linear { input_source, .. } | Scale::Polynomial { input_source, .. } => *input_source,
+            Scale::Other { .. } => return None,
+        };
+        let input = if source < 0 { raw } else { self.apply_index(source as usize, raw, depth + 1)? };
+        scale.apply(input)
+    }
+
+    /// Human-readable summary of the chain
+    pub fn describe(&self) -> String {
+        self.scales.iter().map(Scale::describe).collect::<Vec<_>>().join(" -> ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_map(properties: Vec<Property>) -> HashMap<String, Property> {
+        properties.into_iter().map(|p| (p.name.clone(), p)).collect()
+    }
+
+    #[test]
+    fn test_scaling_roundtrip_and_apply() {
+        let scaling = Scaling {
+            scales: vec![
+                Scale::Linear { slope: 2.0, intercept: 1.0, input_source: -1 },
+                Scale::Polynomial { coefficients: vec![0.0, 0.0, 1.0], input_source: 0 },
+            ],
+            already_scaled: false,
+        };
+        let map = to_map(scaling.to_properties());
+        assert!(map.contains_key("NI_Scale[1]_Polynomial_Coefficients[2]"));
+        let parsed = Scaling::from_properties(&map).unwrap();
+        assert_eq!(parsed, scaling);
+
+        // (2 * 3 + 1)^2
+        assert_eq!(parsed.apply(3.0), Some(49.0));
+        assert_eq!(parsed.describe(), "Linear (y = 2x + 1) -> Polynomial (3 coefficients)");
+    }
+
+    #[test]
+    fn test_unknown_scale_type() {
+        let scaling = Scaling {
+            scales: vec![Scale::Other { scale_type: "Thermocouple".into() }],
+            already_scaled: true,
+        };
+        let parsed = Scaling::from_properties(&to_map(scaling.to_properties())).unwrap();
+        assert!(parsed.already_scaled);
+        assert_eq!(parsed.apply(1.0), None);
+        assert!(Scaling::from_properties(&HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_counts_bounded_by_properties() {
+        let scaling = Scaling {
+            scales: vec![Scale::Polynomial { coefficients: vec![1.0, 2.0], input_source: -1 }],
+            already_scaled: false,
+        };
+        let mut map = to_map(scaling.to_properties());
+        map.insert(NI_NUMBER_OF_SCALES.into(), Property::new(NI_NUMBER_OF_SCALES, PropertyValue::I64(i64::MAX)));
+        let size = wellknown::ni_scale_polynomial_coefficients_size(0);
+        map.insert(size.clone(), Property::new(size, PropertyValue::U32(u32::MAX)));
+        assert_eq!(Scaling::from_properties(&map).unwrap(), scaling);
+    }
+
+    #[test]
+    fn test_untyped_scale_keeps_positions() {
+        let scaling = Scaling {
+            scales: vec![
+                Scale::Linear { slope: 2.0, intercept: 0.0, input_source: -1 },
+                Scale::Linear { slope: 1.0, intercept: 1.0, input_source: -1 },
+                Scale::Linear { slope: 10.0, intercept: 0.0, input_source: 1 },
+            ],
+            already_scaled: false,
+        };
+        let mut map = to_map(scaling.to_properties());
+        map.remove(&wellknown::ni_scale_type(0));
+        let parsed = Scaling::from_properties(&map).unwrap();
+        assert_eq!(parsed.scales.len(), 3);
+        assert_eq!(parsed.scales[0], Scale::Other { scale_type: String::new() });
+        // Scale 2 still reads from scale 1: (3 + 1) * 10
+        assert_eq!(parsed.apply(3.0), Some(40.0));
+    }
+}
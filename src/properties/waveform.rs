@@ -0,0 +1,62 @@
+// src/properties/waveform.rs
+//! Waveform timing properties (`wf_start_time`, `wf_increment`, ...).
+
+use crate::types::{Property, PropertyValue, Timestamp};
+use std::collections::HashMap;
+
+/// Time of the first sample
+pub const WF_START_TIME: &str = "wf_start_time";
+/// Seconds between samples
+pub const WF_INCREMENT: &str = "wf_increment";
+/// Number of samples per acquisition block
+pub const WF_SAMPLES: &str = "wf_samples";
+/// Offset of the first sample relative to `wf_start_time`
+pub const WF_START_OFFSET: &str = "wf_start_offset";
+/// Unit of the x axis, normally `"s"`
+pub const WF_XUNIT_STRING: &str = "wf_xunit_string";
+
+/// Timing of an evenly sampled channel
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WaveformTiming {
+    pub start_time: Option<Timestamp>,
+    pub increment: Option<f64>,
+    pub samples: Option<u64>,
+    pub start_offset: Option<f64>,
+}
+
+impl WaveformTiming {
+    /// Read the waveform properties of a channel; `None` if it has no `wf_increment`
+    pub fn from_properties(properties: &HashMap<String, Property>) -> Option<Self> {
+        let value = |name: &str| properties.get(name).map(|p| &p.value);
+        let increment = value(WF_INCREMENT).and_then(PropertyValue::as_f64)?;
+        Some(WaveformTiming {
+            start_time: value(WF_START_TIME).and_then(PropertyValue::as_timestamp),
+            increment: Some(increment),
+            samples: value(WF_SAMPLES).and_then(PropertyValue::as_i64).and_then(|v| u64::try_from(v).ok()),
+            start_offset: value(WF_START_OFFSET).and_then(PropertyValue::as_f64),
+        })
+    }
+
+    /// Sample rate in Hz, if the increment is known and non-zero
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.increment.filter(|inc| *inc > 0.0).map(|inc| 1.0 / inc)
+    }
+
+    /// The properties describing this timing (unset ones are omitted)
+    pub fn to_properties(&self) -> Vec<Property> {
+        let mut properties = Vec::new();
+        if let Some(start) = self.start_time {
+            properties.push(Property::new(WF_START_TIME, PropertyValue::Timestamp(start)));
+        }
+        if let Some(increment) = self.increment {
+            properties.push(Property::new(WF_INCREMENT, PropertyValue::Double(increment)));
+        }
+        if let Some(samples) = self.samples {
+            properties.push(Property::new(WF_SAMPLES, PropertyValue::U64(samples)));
+        }
+        if let Some(offset) = self.start_offset {
+            properties.push(Property::new(WF_START_OFFSET, PropertyValue::Double(offset)));
+        }
+        properties
+    }
+}
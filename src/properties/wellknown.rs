@@ -0,0 +1,147 @@
+// src/properties/wellknown.rs
+//! Names of well-known properties in one place.
+//!
+//! The constants from the other `properties` modules are re-exported here next
+//! to the generic ones (`name`, `description`, `unit_string`, ...), together
+//! with formatters for the numbered `NI_Scale[n]_*` names and typed accessors
+//! for the most common properties. Using them instead of string literals turns
+//! a misspelled property name into a compile error.
+//!
+//! # Example
+//!
+//! ```
+//! use tdms_rs::properties::wellknown;
+//! use tdms_rs::{Property, PropertyValue};
+//! use std::collections::HashMap;
+//!
+//! let mut properties = HashMap::new();
+//! properties.insert(wellknown::UNIT_STRING.to_string(),
+//!     Property::new(wellknown::UNIT_STRING, PropertyValue::String("V".into())));
+//! assert_eq!(wellknown::unit_string(&properties), Some("V"));
+//! assert_eq!(wellknown::ni_scale_linear_slope(0), "NI_Scale[0]_Linear_Slope");
+//! ```
+
+use crate::properties::scaling::scale_property;
+use crate::types::{Property, PropertyValue, Timestamp};
+use std::collections::HashMap;
+
+pub use crate::properties::alignment::START_INDEX;
+pub use crate::properties::experiment::{NI_EXP_START_TIME_STAMP, NI_EXP_TIME_STAMP};
+pub use crate::properties::labview::{NI_ARRAY_COLUMN, NI_CHANNEL_LENGTH, NI_CHANNEL_NAME, NI_DATA_TYPE, NI_UNIT_DESCRIPTION};
+pub use crate::properties::sampling::{ACCOUNTED_AT, ACTUAL_SAMPLES, EXPECTED_SAMPLES, EXPECTED_SAMPLE_RATE};
+pub use crate::properties::scaling::{NI_NUMBER_OF_SCALES, NI_SCALING_STATUS};
+pub use crate::properties::waveform::{WF_INCREMENT, WF_SAMPLES, WF_START_OFFSET, WF_START_TIME, WF_XUNIT_STRING};
+
+/// Object name
+pub const NAME: &str = "name";
+/// Object description
+pub const DESCRIPTION: &str = "description";
+/// File title
+pub const TITLE: &str = "title";
+/// File author
+pub const AUTHOR: &str = "author";
+/// Time the file was created
+pub const DATETIME: &str = "datetime";
+/// Engineering unit of a channel
+pub const UNIT_STRING: &str = "unit_string";
+/// Name of the library that wrote the file
+pub const LIBRARY: &str = "library";
+/// Version of the library that wrote the file
+pub const LIBRARY_VERSION: &str = "library_version";
+
+/// `NI_Scale[n]_Scale_Type`
+pub fn ni_scale_type(scale: usize) -> String {
+    scale_property(scale, "Scale_Type")
+}
+
+/// `NI_Scale[n]_Linear_Slope`
+pub fn ni_scale_linear_slope(scale: usize) -> String {
+    scale_property(scale, "Linear_Slope")
+}
+
+/// `NI_Scale[n]_Linear_Y_Intercept`
+pub fn ni_scale_linear_y_intercept(scale: usize) -> String {
+    scale_property(scale, "Linear_Y_Intercept")
+}
+
+/// `NI_Scale[n]_Linear_Input_Source`
+pub fn ni_scale_linear_input_source(scale: usize) -> String {
+    scale_property(scale, "Linear_Input_Source")
+}
+
+/// `NI_Scale[n]_Polynomial_Coefficients_Size`
+pub fn ni_scale_polynomial_coefficients_size(scale: usize) -> String {
+    scale_property(scale, "Polynomial_Coefficients_Size")
+}
+
+/// `NI_Scale[n]_Polynomial_Coefficients[i]`
+pub fn ni_scale_polynomial_coefficient(scale: usize, coefficient: usize) -> String {
+    scale_property(scale, &format!("Polynomial_Coefficients[{}]", coefficient))
+}
+
+/// `NI_Scale[n]_Polynomial_Input_Source`
+pub fn ni_scale_polynomial_input_source(scale: usize) -> String {
+    scale_property(scale, "Polynomial_Input_Source")
+}
+
+fn value<'a>(properties: &'a HashMap<String, Property>, name: &str) -> Option<&'a PropertyValue> {
+    properties.get(name).map(|p| &p.value)
+}
+
+/// The `name` property, if it is a string
+pub fn name(properties: &HashMap<String, Property>) -> Option<&str> {
+    value(properties, NAME).and_then(PropertyValue::as_str)
+}
+
+/// The `description` property, if it is a string
+pub fn description(properties: &HashMap<String, Property>) -> Option<&str> {
+    value(properties, DESCRIPTION).and_then(PropertyValue::as_str)
+}
+
+/// The `unit_string` property, if it is a string
+pub fn unit_string(properties: &HashMap<String, Property>) -> Option<&str> {
+    value(properties, UNIT_STRING).and_then(PropertyValue::as_str)
+}
+
+/// The `wf_start_time` property, if it is a timestamp
+pub fn wf_start_time(properties: &HashMap<String, Property>) -> Option<Timestamp> {
+    value(properties, WF_START_TIME).and_then(PropertyValue::as_timestamp)
+}
+
+/// The `wf_increment` property, in any numeric type
+pub fn wf_increment(properties: &HashMap<String, Property>) -> Option<f64> {
+    value(properties, WF_INCREMENT).and_then(PropertyValue::as_f64)
+}
+
+/// The `wf_samples` property, in any non-negative integer type
+pub fn wf_samples(properties: &HashMap<String, Property>) -> Option<u64> {
+    value(properties, WF_SAMPLES).and_then(PropertyValue::as_i64).and_then(|v| u64::try_from(v).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessors_check_types() {
+        let properties: HashMap<String, Property> = [
+            Property::new(NAME, PropertyValue::String("Pump".into())),
+            Property::new(UNIT_STRING, PropertyValue::I32(3)),
+            Property::new(WF_INCREMENT, PropertyValue::Float(0.5)),
+            Property::new(WF_SAMPLES, PropertyValue::I32(-1)),
+        ].into_iter().map(|p| (p.name.clone(), p)).collect();
+
+        assert_eq!(name(&properties), Some("Pump"));
+        assert_eq!(unit_string(&properties), None);
+        assert_eq!(wf_increment(&properties), Some(0.5));
+        assert_eq!(wf_samples(&properties), None);
+        assert_eq!(description(&properties), None);
+    }
+
+    #[test]
+    fn test_scale_names() {
+        assert_eq!(ni_scale_type(2), "NI_Scale[2]_Scale_Type");
+        assert_eq!(ni_scale_polynomial_coefficient(1, 3), "NI_Scale[1]_Polynomial_Coefficients[3]");
+        assert_eq!(ni_scale_polynomial_input_source(0), "NI_Scale[0]_Polynomial_Input_Source");
+    }
+}
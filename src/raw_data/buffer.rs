@@ -2,6 +2,7 @@
 use bytes::{BytesMut, BufMut};
 use crate::types::DataType;
 use crate::error::{TdmsError, Result};
+use crate::utils::StringEncoding;
 use std::mem;
 
 /// Efficient buffer for accumulating raw data before writing to TDMS file
@@ -149,6 +150,19 @@ impl RawDataBuffer {
         Ok(())
     }
     
+    /// Write timestamps in their on-disk form (fractions, then seconds, little-endian),
+    /// independent of how [`Timestamp`](crate::types::Timestamp) is laid out in memory
+    pub fn write_timestamp_slice(&mut self, values: &[crate::types::Timestamp]) -> Result<()> {
+        self.check_type(DataType::TimeStamp)?;
+        
+        self.buffer.reserve(values.len() * 16);
+        for value in values {
+            self.buffer.extend_from_slice(&value.to_bytes_le());
+        }
+        self.value_count += values.len() as u64;
+        Ok(())
+    }
+    
     /// Write a slice of values efficiently (zero-copy when possible)
     /// 
     /// This is the most efficient way to write multiple values of the same type.
@@ -200,7 +214,7 @@ impl RawDataBuffer {
         let bytes = unsafe {
             std::slice::from_raw_parts(
                 values.as_ptr() as *const u8,
-                values.len() * type_size,
+                std::mem::size_of_val(values),
             )
         };
         
@@ -209,6 +223,27 @@ impl RawDataBuffer {
         Ok(())
     }
     
+    /// Write booleans as one byte each, 0 for false and 1 for true
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use tdms_rs::raw_data::RawDataBuffer;
+    /// use tdms_rs::types::DataType;
+    /// 
+    /// let mut buffer = RawDataBuffer::new(DataType::Boolean);
+    /// buffer.write_bools(&[true, false, true]).unwrap();
+    /// 
+    /// assert_eq!(buffer.as_bytes(), &[1, 0, 1]);
+    /// ```
+    pub fn write_bools(&mut self, values: &[bool]) -> Result<()> {
+        self.check_type(DataType::Boolean)?;
+        
+        self.buffer.extend(values.iter().map(|&value| u8::from(value)));
+        self.value_count += values.len() as u64;
+        Ok(())
+    }
+    
     /// Write strings with proper TDMS string array format (cumulative end offsets)
     /// 
     /// TDMS stores string arrays with cumulative end offsets followed by concatenated data.
@@ -235,40 +270,72 @@ impl RawDataBuffer {
     /// assert_eq!(buffer.byte_len(), 23);
     /// ```
     pub fn write_strings(&mut self, strings: &[impl AsRef<str>]) -> Result<()> {
+        self.write_byte_strings(strings.len(), strings.iter().map(|s| s.as_ref().as_bytes()))
+    }
+    
+    /// Write an array of strings transcoded to `encoding`.
+    /// 
+    /// Fails without writing anything if a string has characters the encoding
+    /// can't represent.
+    pub fn write_encoded_strings(&mut self, strings: &[impl AsRef<str>], encoding: StringEncoding) -> Result<()> {
+        if encoding == StringEncoding::Utf8 {
+            return self.write_strings(strings);
+        }
+        let encoded = strings.iter()
+            .map(|s| encoding.encode(s.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        self.write_byte_strings(encoded.len(), encoded.iter().map(Vec::as_slice))
+    }
+    
+    fn write_byte_strings<'a>(&mut self, count: usize, strings: impl Iterator<Item = &'a [u8]> + Clone) -> Result<()> {
         self.check_type(DataType::String)?;
         
-        if strings.is_empty() {
+        if count == 0 {
             return Ok(());
         }
         
-        // Calculate cumulative offsets
-        let mut cumulative_offset = 0u32;
-        let mut offsets = Vec::with_capacity(strings.len());
+        // Calculate cumulative offsets, continuing from strings already buffered
+        let mut cumulative_offset = self.string_data_len() as u32;
+        let mut offsets = Vec::with_capacity(count);
         
-        for s in strings {
-            let bytes = s.as_ref().as_bytes();
+        for bytes in strings.clone() {
             cumulative_offset = cumulative_offset.checked_add(bytes.len() as u32)
-                .ok_or_else(|| TdmsError::BufferOverflow {
+                .ok_or(TdmsError::BufferOverflow {
                     attempted: bytes.len(),
                     capacity: u32::MAX as usize,
                 })?;
             offsets.push(cumulative_offset);
         }
         
-        // Write offsets (little-endian)
-        for offset in offsets {
-            self.buffer.put_u32_le(offset);
-        }
+        self.insert_string_offsets(&offsets);
         
         // Write concatenated string data
-        for s in strings {
-            self.buffer.extend_from_slice(s.as_ref().as_bytes());
+        for bytes in strings {
+            self.buffer.extend_from_slice(bytes);
         }
         
-        self.value_count += strings.len() as u64;
+        self.value_count += count as u64;
         Ok(())
     }
     
+    /// Bytes of string data buffered after the offsets
+    fn string_data_len(&self) -> usize {
+        self.buffer.len() - self.value_count as usize * 4
+    }
+    
+    /// Add offsets (little-endian) after those already buffered, ahead of the
+    /// string data, keeping the one offsets-then-data block TDMS expects
+    fn insert_string_offsets(&mut self, offsets: &[u32]) {
+        let offsets_end = self.value_count as usize * 4;
+        let data_len = self.string_data_len();
+        self.buffer.resize(self.buffer.len() + offsets.len() * 4, 0);
+        self.buffer.copy_within(offsets_end..offsets_end + data_len, offsets_end + offsets.len() * 4);
+        for (i, offset) in offsets.iter().enumerate() {
+            let at = offsets_end + i * 4;
+            self.buffer[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+        }
+    }
+    
     /// Write an empty string array
     /// 
     /// Writes `count` empty strings efficiently
@@ -279,10 +346,9 @@ impl RawDataBuffer {
             return Ok(());
         }
         
-        // All offsets will be 0 for empty strings
-        for _ in 0..count {
-            self.buffer.put_u32_le(0);
-        }
+        // Empty strings end where the previous string did
+        let end = self.string_data_len() as u32;
+        self.insert_string_offsets(&vec![end; count]);
         
         self.value_count += count as u64;
         Ok(())
@@ -318,6 +384,12 @@ impl RawDataBuffer {
         &self.buffer
     }
     
+    /// Append already-encoded values of this buffer's type
+    pub(crate) fn extend_raw(&mut self, bytes: &[u8], value_count: u64) {
+        self.buffer.extend_from_slice(bytes);
+        self.value_count += value_count;
+    }
+    
     /// Clear the buffer, resetting value count and removing all data
     pub fn clear(&mut self) {
         self.buffer.clear();
@@ -383,6 +455,7 @@ impl std::fmt::Debug for RawDataBuffer {
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
     use crate::types::Timestamp;
@@ -458,6 +531,17 @@ mod tests {
         assert_eq!(bytes[2], 1);
     }
 
+    #[test]
+    fn test_write_bool_slice() {
+        let mut buffer = RawDataBuffer::new(DataType::Boolean);
+        buffer.write_bools(&[false, true, true]).unwrap();
+        assert_eq!(buffer.value_count(), 3);
+        assert_eq!(buffer.as_bytes(), &[0, 1, 1]);
+
+        let mut wrong = RawDataBuffer::new(DataType::U8);
+        assert!(wrong.write_bools(&[true]).is_err());
+    }
+
     #[test]
     fn test_type_mismatch() {
         let mut buffer = RawDataBuffer::new(DataType::I32);
@@ -513,6 +597,21 @@ mod tests {
         assert_eq!(buffer.byte_len(), 16);
     }
 
+    #[test]
+    fn test_write_timestamp_slice() {
+        let values = [
+            Timestamp { seconds: 0x0102_0304_0506_0708, fractions: 0x1112_1314_1516_1718 },
+            Timestamp { seconds: -1, fractions: 0 },
+        ];
+        let mut buffer = RawDataBuffer::new(DataType::TimeStamp);
+        buffer.write_timestamp_slice(&values).unwrap();
+
+        assert_eq!(buffer.value_count(), 2);
+        assert_eq!(&buffer.as_bytes()[..16], &values[0].to_bytes_le());
+        assert_eq!(&buffer.as_bytes()[16..], &values[1].to_bytes_le());
+        assert!(RawDataBuffer::new(DataType::I64).write_timestamp_slice(&values).is_err());
+    }
+
     #[test]
     fn test_multiple_data_types() {
         // Test different numeric types
@@ -565,6 +664,15 @@ mod tests {
         buffer.write_strings(&["Test", ""]).unwrap();
         assert_eq!(buffer.value_count(), 4);
         assert!(buffer.byte_len() > first_len);
+        
+        // Still one block of offsets followed by the data
+        buffer.write_empty_strings(1).unwrap();
+        let mut expected = Vec::new();
+        for offset in [5u32, 10, 14, 14, 14] {
+            expected.extend_from_slice(&offset.to_le_bytes());
+        }
+        expected.extend_from_slice(b"HelloWorldTest");
+        assert_eq!(buffer.as_bytes(), expected);
     }
 
     #[test]
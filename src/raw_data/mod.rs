@@ -51,6 +51,7 @@ pub use reader::RawDataReader;
 // Re-export for convenience
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
     use crate::types::DataType;
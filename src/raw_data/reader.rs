@@ -1,5 +1,7 @@
 // src/raw_data/reader.rs
 use crate::error::{TdmsError, Result};
+use crate::types::TdmsValue;
+use crate::utils::StringEncoding;
 use std::io::Read;
 use byteorder::{ReadBytesExt, LittleEndian, BigEndian};
 
@@ -14,7 +16,8 @@ impl RawDataReader {
     /// 
     /// # Type Parameters
     /// 
-    /// * `T` - The type to read (must be Copy + Default)
+    /// * `T` - The type to read; the bytes are taken as-is, without checking
+    ///   them against a channel's data type
     /// * `R` - The reader type (must implement Read)
     /// 
     /// # Arguments
@@ -45,13 +48,12 @@ impl RawDataReader {
         is_big_endian: bool,
     ) -> Result<Vec<T>>
     where
-        T: Copy + Default,
+        T: TdmsValue,
     {
         if count == 0 {
             return Ok(Vec::new());
         }
 
-        let mut result = vec![T::default(); count];
         let size = std::mem::size_of::<T>();
         
         let byte_count = count * size;
@@ -60,6 +62,12 @@ impl RawDataReader {
         let mut bytes = vec![0u8; byte_count];
         reader.read_exact(&mut bytes)?;
         
+        // Booleans and timestamps are decoded value by value rather than
+        // relying on their in-memory layout
+        if let Some(values) = T::decode_values(&bytes, is_big_endian) {
+            return Ok(values);
+        }
+        
         // Swap endianness if needed (only for multi-byte types)
         if is_big_endian && size > 1 {
             for chunk in bytes.chunks_exact_mut(size) {
@@ -68,6 +76,7 @@ impl RawDataReader {
         }
         
         // Copy bytes into result array
+        let mut result = vec![T::default(); count];
         unsafe {
             std::ptr::copy_nonoverlapping(
                 bytes.as_ptr(),
@@ -117,6 +126,17 @@ impl RawDataReader {
         reader: &mut R,
         count: usize,
         is_big_endian: bool,
+    ) -> Result<Vec<String>> {
+        Self::read_strings_with(reader, count, is_big_endian, StringEncoding::Utf8)
+    }
+    
+    /// Read a string array whose strings are in `encoding`
+    /// (see [`read_strings`](Self::read_strings))
+    pub fn read_strings_with<R: Read>(
+        reader: &mut R,
+        count: usize,
+        is_big_endian: bool,
+        encoding: StringEncoding,
     ) -> Result<Vec<String>> {
         if count == 0 {
             return Ok(Vec::new());
@@ -164,8 +184,7 @@ impl RawDataReader {
             
             if length > 0 {
                 let bytes = &string_data[start..end];
-                let s = String::from_utf8(bytes.to_vec())
-                    .map_err(|_| TdmsError::InvalidUtf8)?;
+                let s = encoding.decode(bytes)?;
                 result.push(s);
             } else {
                 result.push(String::new());
@@ -280,6 +299,7 @@ impl RawDataReader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::Timestamp;
     use std::io::Cursor;
 
     #[test]
@@ -384,12 +404,29 @@ mod tests {
         let data = vec![1];
         let mut cursor = Cursor::new(&data);
         let value = RawDataReader::read_bool(&mut cursor).unwrap();
-        assert_eq!(value, true);
+        assert!(value);
         
         let data = vec![0];
         let mut cursor = Cursor::new(&data);
         let value = RawDataReader::read_bool(&mut cursor).unwrap();
-        assert_eq!(value, false);
+        assert!(!value);
+    }
+
+    #[test]
+    fn test_read_booleans() {
+        // Any non-zero byte reads as true
+        let mut cursor = Cursor::new(vec![0u8, 1, 2, 255]);
+        let values: Vec<bool> = RawDataReader::read_values(&mut cursor, 4, true).unwrap();
+        assert_eq!(values, vec![false, true, true, true]);
+    }
+
+    #[test]
+    fn test_read_timestamps() {
+        let ts = Timestamp { seconds: 3_786_825_600, fractions: 1 << 63 };
+        let values: Vec<Timestamp> = RawDataReader::read_values(&mut Cursor::new(ts.to_bytes_le()), 1, false).unwrap();
+        assert_eq!(values, vec![ts]);
+        let values: Vec<Timestamp> = RawDataReader::read_values(&mut Cursor::new(ts.to_bytes_be()), 1, true).unwrap();
+        assert_eq!(values, vec![ts]);
     }
 
     #[test]
@@ -0,0 +1,275 @@
+// src/reader/cache.rs
+use crate::error::Result;
+use crate::reader::TdmsReader;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A directory of decompressed copies of compressed TDMS archives
+///
+/// Opening a `.tdms.gz` or `.tdms.zst` archive means decompressing it to a
+/// plain file first. The cache keeps those files between sessions, keyed by
+/// the archive's path, size and modification time, so analysing the same
+/// archive again skips the decompression. A replaced archive gets a new entry.
+/// When the cache grows past its size limit, the least recently used entries
+/// are deleted.
+///
+/// This crate doesn't decompress anything itself; the caller provides the
+/// decompression, e.g. with `flate2` or `zstd`.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::reader::DecompressionCache;
+///
+/// let cache = DecompressionCache::new("/var/cache/tdms", 20 << 30).unwrap();
+/// let mut reader = cache.open("archive/run.tdms.gz", |archive, out| {
+///     // std::io::copy(&mut flate2::read::GzDecoder::new(archive), out)?;
+///     # let _ = (archive, out);
+///     Ok(())
+/// }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct DecompressionCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+/// Distinguishes temporary files of concurrent decompressions in one process
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+impl DecompressionCache {
+    /// Use `dir` (created if missing) as a cache holding at most `max_bytes`
+    /// of decompressed files.
+    ///
+    /// Only files named like the cache's entries, `<name>-<16 hex digits>.tdms`,
+    /// are counted, evicted or cleared; other files in `dir` are left alone.
+    pub fn new(dir: impl AsRef<Path>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(DecompressionCache { dir, max_bytes })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the decompressed copy of `archive`, running `decompress` with
+    /// the archive and the file to write unless a copy is already cached
+    ///
+    /// Decompression goes to a temporary file that is renamed into place
+    /// once complete, so an interrupted or failed decompression never leaves a
+    /// truncated entry behind and concurrent sessions can share the cache.
+    pub fn get_or_decompress<F>(&self, archive: impl AsRef<Path>, decompress: F) -> Result<PathBuf>
+    where
+        F: FnOnce(&mut File, &mut dyn Write) -> Result<()>,
+    {
+        let archive = archive.as_ref();
+        let entry = self.entry_path(archive)?;
+        if entry.exists() {
+            // Mark it recently used; failing to is harmless
+            if let Ok(file) = File::options().append(true).open(&entry) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+            return Ok(entry);
+        }
+
+        let temp = self.dir.join(format!(
+            ".{}-{}-{}.tmp",
+            entry.file_name().unwrap_or_default().to_string_lossy(),
+            std::process::id(),
+            NEXT_TEMP.fetch_add(1, Ordering::Relaxed),
+        ));
+        let result: Result<()> = (|| {
+            let mut source = File::open(archive)?;
+            let mut out = BufWriter::new(File::create(&temp)?);
+            decompress(&mut source, &mut out)?;
+            out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            fs::rename(&temp, &entry)?;
+            Ok(())
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        result?;
+
+        self.evict_except(Some(&entry))?;
+        Ok(entry)
+    }
+
+    /// Open the decompressed copy of `archive` (see [`Self::get_or_decompress`])
+    pub fn open<F>(&self, archive: impl AsRef<Path>, decompress: F) -> Result<TdmsReader<BufReader<File>>>
+    where
+        F: FnOnce(&mut File, &mut dyn Write) -> Result<()>,
+    {
+        TdmsReader::open(self.get_or_decompress(archive, decompress)?)
+    }
+
+    /// Delete least recently used entries until the cache fits its size
+    /// limit, returning the number of bytes freed
+    pub fn evict(&self) -> Result<u64> {
+        self.evict_except(None)
+    }
+
+    /// Delete every entry
+    pub fn clear(&self) -> Result<()> {
+        for (path, _, _) in self.entries()? {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Total size of the cached files
+    pub fn size(&self) -> Result<u64> {
+        Ok(self.entries()?.iter().map(|(_, size, _)| size).sum())
+    }
+
+    /// Evict, keeping `keep` even if it alone exceeds the limit
+    fn evict_except(&self, keep: Option<&Path>) -> Result<u64> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(_, _, used)| *used);
+
+        let mut freed = 0;
+        for (path, size, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if Some(path.as_path()) == keep {
+                continue;
+            }
+            // Another session may have evicted it already
+            match fs::remove_file(&path) {
+                Ok(()) => freed += size,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+            total -= size;
+        }
+        Ok(freed)
+    }
+
+    /// Cached files with their sizes and last use
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if !path.file_name().and_then(|name| name.to_str()).is_some_and(is_entry_name) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            entries.push((path, metadata.len(), metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)));
+        }
+        Ok(entries)
+    }
+
+    /// `<archive name without extensions>-<hash>.tdms`, the hash covering
+    /// what identifies one version of the archive.
+    ///
+    /// The hash has to name the same entry in every session, so it is FNV-1a
+    /// over a fixed encoding rather than the standard library's hasher, whose
+    /// output may change between Rust releases.
+    fn entry_path(&self, archive: &Path) -> Result<PathBuf> {
+        let metadata = fs::metadata(archive)?;
+        let modified = metadata.modified().ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_nanos());
+
+        let mut key = fs::canonicalize(archive)?.into_os_string().into_encoded_bytes();
+        key.push(0);
+        key.extend_from_slice(&metadata.len().to_le_bytes());
+        key.extend_from_slice(&modified.to_le_bytes());
+
+        let name = archive.file_name().unwrap_or_default().to_string_lossy();
+        let stem = name.split('.').next().unwrap_or_default();
+        Ok(self.dir.join(format!("{}-{:016x}.tdms", stem, fnv1a(&key))))
+    }
+}
+
+/// Whether `name` is one of [`DecompressionCache::entry_path`]'s file names
+fn is_entry_name(name: &str) -> bool {
+    name.strip_suffix(".tdms")
+        .and_then(|rest| rest.rsplit_once('-'))
+        .is_some_and(|(_, hash)| hash.len() == 16 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
+}
+
+/// 64-bit FNV-1a
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn copy(source: &mut File, out: &mut dyn Write) -> Result<()> {
+        let mut bytes = Vec::new();
+        source.read_to_end(&mut bytes)?;
+        out.write_all(&bytes)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_hits_and_evicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DecompressionCache::new(dir.path().join("cache"), 150).unwrap();
+        let archives: Vec<PathBuf> = (0..2).map(|i| {
+            let path = dir.path().join(format!("run{}.tdms.gz", i));
+            fs::write(&path, vec![i as u8; 100]).unwrap();
+            path
+        }).collect();
+
+        let first = cache.get_or_decompress(&archives[0], copy).unwrap();
+        assert_eq!(fs::read(&first).unwrap(), vec![0; 100]);
+        assert!(first.file_name().unwrap().to_string_lossy().starts_with("run0-"));
+        let again = cache.get_or_decompress(&archives[0], |_, _| panic!("cached")).unwrap();
+        assert_eq!(again, first);
+
+        // A failed decompression leaves nothing behind
+        let failed = cache.get_or_decompress(&archives[1], |_, _| Err(crate::TdmsError::Unsupported("corrupt".into())));
+        assert!(failed.is_err());
+        assert_eq!(fs::read_dir(cache.dir()).unwrap().count(), 1);
+
+        // Over the limit the least recently used entry goes, never the new one
+        let second = cache.get_or_decompress(&archives[1], copy).unwrap();
+        assert!(!first.exists());
+        assert!(second.exists());
+        assert_eq!(cache.size().unwrap(), 100);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.size().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cache_leaves_other_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let recording = dir.path().join("recording.tdms");
+        let dashed = dir.path().join("run-2024.tdms");
+        fs::write(&recording, vec![0; 1000]).unwrap();
+        fs::write(&dashed, vec![0; 1000]).unwrap();
+        let archive = dir.path().join("run.tdms.gz");
+        fs::write(&archive, vec![1; 100]).unwrap();
+
+        // A cache pointed at a data directory only manages its own entries
+        let cache = DecompressionCache::new(dir.path(), 50).unwrap();
+        let entry = cache.get_or_decompress(&archive, copy).unwrap();
+        assert!(is_entry_name(&entry.file_name().unwrap().to_string_lossy()));
+        assert_eq!(cache.size().unwrap(), 100);
+        cache.evict().unwrap();
+        cache.clear().unwrap();
+        assert!(!entry.exists());
+        assert!(recording.exists() && dashed.exists());
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        // Published FNV-1a test vectors: the key must not depend on the build
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
@@ -1,27 +1,40 @@
 // src/reader/channel_reader.rs
 use crate::error::{TdmsError, Result};
-use crate::types::{DataType, Property}; // <-- Added Property
-use crate::segment::SegmentInfo;
+use crate::types::{DataType, Property, TdmsValue}; // <-- Added Property
+use crate::segment::{SegmentHeader, SegmentInfo};
 use crate::raw_data::RawDataReader;
+use crate::reader::daqmx::DaqmxInfo;
+use crate::reader::segment_list::SegmentList;
+use crate::utils::StringEncoding;
 use std::io::{Read, Seek, SeekFrom};
 use std::collections::HashMap; // <-- Added HashMap
 
 /// Data for a channel within a specific segment
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct SegmentData {
     pub segment_index: usize,
     pub value_count: u64,
     pub byte_size: u64,
     pub byte_offset: u64, // Offset within the segment's raw data section
+    /// Distance in bytes between consecutive values when they are not contiguous
+    pub stride: Option<u64>,
 }
 
 /// Information about a channel read from a TDMS file
 #[derive(Debug, Clone)]
 pub struct ChannelInfo {
     pub data_type: DataType,
+    /// Where the channel's data is in each segment. Empty when the entries are
+    /// kept in a [`SegmentList`] instead; [`segment_entries`](Self::segment_entries)
+    /// covers both.
     pub segments: Vec<SegmentData>,
     pub total_values: u64,
     pub properties: HashMap<String, Property>, // <-- ADDED
+    /// Raw buffer layout for DAQmx channels
+    pub daqmx: Option<DaqmxInfo>,
+    /// Compact or spilled index used in place of `segments`, for readers
+    /// opened with `ReaderOptions::compact_index` or `ReaderOptions::spill_dir`
+    pub(crate) segment_list: Option<SegmentList>,
 }
 
 impl ChannelInfo {
@@ -31,12 +44,78 @@ impl ChannelInfo {
             segments: Vec::new(),
             total_values: 0,
             properties: HashMap::new(), // <-- ADDED
+            daqmx: None,
+            segment_list: None,
         }
     }
 
-    pub fn add_segment(&mut self, segment_data: SegmentData) {
+    /// Keep segment entries in a [`SegmentList`] rather than `segments`
+    pub(crate) fn with_segment_list(mut self) -> Self {
+        self.segment_list = Some(SegmentList::new());
+        self
+    }
+
+    pub fn add_segment(&mut self, segment_data: SegmentData) -> Result<()> {
+        match &mut self.segment_list {
+            Some(list) => list.push(segment_data)?,
+            None => self.segments.push(segment_data),
+        }
         self.total_values += segment_data.value_count;
-        self.segments.push(segment_data);
+        Ok(())
+    }
+
+    /// Number of segments the channel has data in
+    pub fn segment_count(&self) -> usize {
+        self.segment_list.as_ref().map_or(self.segments.len(), SegmentList::len)
+    }
+
+    /// Where the channel's data is in each segment, in file order, wherever
+    /// the entries are kept. Entries spilled to disk can fail to read back.
+    pub fn segment_entries(&self) -> impl Iterator<Item = Result<SegmentData>> + '_ {
+        self.segments.iter().copied().map(Ok).chain(self.segment_list.iter().flat_map(SegmentList::iter))
+    }
+
+    /// Entry `index` of [`segment_entries`](Self::segment_entries)
+    pub fn segment_entry(&self, index: usize) -> Result<Option<SegmentData>> {
+        match &self.segment_list {
+            Some(list) => list.get(index),
+            None => Ok(self.segments.get(index).copied()),
+        }
+    }
+
+    /// The last entry of [`segment_entries`](Self::segment_entries)
+    pub fn last_segment(&self) -> Result<Option<SegmentData>> {
+        match self.segment_count().checked_sub(1) {
+            Some(index) => self.segment_entry(index),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Bytes of a channel's raw data that needed extra work to turn into values.
+/// 
+/// Native-endian, contiguous, non-DAQmx data is copied straight out of the file;
+/// each counter below is work on top of that. High counts suggest converting
+/// the file (e.g. with [`defragment`](crate::defragment)) before repeated analysis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConversionStats {
+    /// Value bytes read in total
+    pub bytes_read: u64,
+    /// Bytes stored in the other byte order than this machine's
+    pub bytes_swapped: u64,
+    /// Bytes picked out of records holding other data in between
+    pub bytes_deinterleaved: u64,
+    /// Bytes extracted from DAQmx raw buffers
+    pub bytes_daqmx: u64,
+}
+
+impl ConversionStats {
+    /// Add another set of counters to this one
+    pub fn merge(&mut self, other: &ConversionStats) {
+        self.bytes_read += other.bytes_read;
+        self.bytes_swapped += other.bytes_swapped;
+        self.bytes_deinterleaved += other.bytes_deinterleaved;
+        self.bytes_daqmx += other.bytes_daqmx;
     }
 }
 
@@ -47,6 +126,7 @@ impl ChannelInfo {
 pub struct ChannelReader {
     channel_key: String,
     info: ChannelInfo,
+    string_encoding: StringEncoding,
 }
 
 impl ChannelReader {
@@ -57,7 +137,13 @@ impl ChannelReader {
     /// * `channel_key` - The key identifying this channel (format: "group/channel")
     /// * `info` - Channel information including data type and segment locations
     pub(crate) fn new(channel_key: String, info: ChannelInfo) -> Self {
-        ChannelReader { channel_key, info }
+        ChannelReader { channel_key, info, string_encoding: StringEncoding::default() }
+    }
+    
+    /// Decode string values with `encoding` instead of UTF-8
+    pub(crate) fn with_string_encoding(mut self, encoding: StringEncoding) -> Self {
+        self.string_encoding = encoding;
+        self
     }
 
     /// Get the data type of this channel
@@ -72,7 +158,7 @@ impl ChannelReader {
 
     /// Get the number of segments containing data for this channel
     pub fn segment_count(&self) -> usize {
-        self.info.segments.len()
+        self.info.segment_count()
     }
 
     /// Get the channel key (group/channel format)
@@ -84,6 +170,141 @@ impl ChannelReader {
     pub fn get_properties(&self) -> &HashMap<String, Property> {
         &self.info.properties
     }
+    
+    /// Whether this channel stores DAQmx raw data
+    pub fn is_daqmx(&self) -> bool {
+        self.info.daqmx.is_some()
+    }
+    
+    /// Raw buffer layout of a DAQmx channel
+    /// 
+    /// Values read from a DAQmx channel are the raw (unscaled) values of its
+    /// first scaler, in that scaler's data type.
+    pub fn daqmx_info(&self) -> Option<&DaqmxInfo> {
+        self.info.daqmx.as_ref()
+    }
+    
+    /// Conversion work needed to read `count` values starting at `start_index`.
+    /// 
+    /// Segment entries spilled to disk that can't be read back end the count
+    /// early; the read itself reports the error.
+    pub fn conversion_stats(&self, segments: &[SegmentInfo], start_index: u64, count: u64) -> ConversionStats {
+        let value_size = match &self.info.daqmx {
+            Some(daqmx) => daqmx.primary_value_layout().ok().and_then(|(_, t)| t.fixed_size()),
+            None => self.info.data_type.fixed_size(),
+        };
+        let end_index = start_index.saturating_add(count).min(self.info.total_values);
+        let mut stats = ConversionStats::default();
+        
+        let mut segment_start = 0u64;
+        for segment_data in self.info.segment_entries().map_while(Result::ok) {
+            let segment_end = segment_start + segment_data.value_count;
+            let overlap = segment_end.min(end_index).saturating_sub(segment_start.max(start_index));
+            segment_start = segment_end;
+            if overlap == 0 {
+                continue;
+            }
+            
+            let is_big_endian = segments[segment_data.segment_index].is_big_endian;
+            let swapped = is_big_endian != cfg!(target_endian = "big");
+            let (bytes, swapped_bytes) = match value_size {
+                Some(size) => (overlap * size as u64, if swapped && size > 1 { overlap * size as u64 } else { 0 }),
+                // Strings: the share of the segment's bytes, of which only the offsets are swapped
+                None => (segment_data.byte_size * overlap / segment_data.value_count, if swapped { overlap * 4 } else { 0 }),
+            };
+            
+            stats.bytes_read += bytes;
+            stats.bytes_swapped += swapped_bytes;
+            if segment_data.stride.is_some_and(|stride| Some(stride as usize) != value_size) {
+                stats.bytes_deinterleaved += bytes;
+            }
+            if self.info.daqmx.is_some() {
+                stats.bytes_daqmx += bytes;
+            }
+        }
+        
+        stats
+    }
+    
+    /// File byte ranges, as `(offset, length)`, holding `count` values starting
+    /// at `start_index`, in file order with touching ranges merged.
+    /// 
+    /// Variable-size values (strings) cover their whole chunk. This is the read
+    /// plan used for [`MmapHints`](crate::reader::MmapHints) prefetching. As
+    /// with [`conversion_stats`](Self::conversion_stats), unreadable spilled
+    /// entries end the plan early.
+    pub fn byte_ranges(&self, segments: &[SegmentInfo], start_index: u64, count: u64) -> Vec<(u64, u64)> {
+        let value_size = match &self.info.daqmx {
+            Some(daqmx) => daqmx.primary_value_layout().ok().and_then(|(_, t)| t.fixed_size()),
+            None => self.info.data_type.fixed_size(),
+        };
+        let end_index = start_index.saturating_add(count).min(self.info.total_values);
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        
+        let mut segment_start = 0u64;
+        for segment_data in self.info.segment_entries().map_while(Result::ok) {
+            let segment_end = segment_start + segment_data.value_count;
+            let first = segment_start.max(start_index);
+            let overlap = segment_end.min(end_index).saturating_sub(first);
+            let skipped = first.saturating_sub(segment_start);
+            segment_start = segment_end;
+            if overlap == 0 {
+                continue;
+            }
+            
+            let segment_info = &segments[segment_data.segment_index];
+            let data_offset = segment_info.offset
+                + SegmentHeader::LEAD_IN_SIZE as u64
+                + segment_info.metadata_size
+                + segment_data.byte_offset;
+            let (offset, length) = match value_size {
+                Some(size) => {
+                    let stride = segment_data.stride.unwrap_or(size as u64);
+                    (data_offset + skipped * stride, (overlap - 1) * stride + size as u64)
+                }
+                None => (data_offset, segment_data.byte_size),
+            };
+            
+            match ranges.last_mut() {
+                Some((last_offset, last_length)) if *last_offset + *last_length == offset => *last_length += length,
+                _ => ranges.push((offset, length)),
+            }
+        }
+        
+        ranges
+    }
+    
+    /// Make sure `T` matches the channel's data type, or for DAQmx channels the
+    /// raw type of the first scaler
+    pub(crate) fn check_value_type<T: TdmsValue>(&self) -> Result<()> {
+        let data_type = match &self.info.daqmx {
+            Some(daqmx) => daqmx.primary_value_layout()?.1,
+            None => self.info.data_type,
+        };
+        if data_type != T::DATA_TYPE {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", data_type),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Make sure values of `T` are the size of the channel's stored values,
+    /// which is all an unchecked read needs to stay in step with the data
+    fn check_value_size<T: TdmsValue>(&self) -> Result<()> {
+        let data_type = match &self.info.daqmx {
+            Some(daqmx) => daqmx.primary_value_layout()?.1,
+            None => self.info.data_type,
+        };
+        if data_type.fixed_size() != T::DATA_TYPE.fixed_size() {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?} or another type of its size", data_type),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
+        Ok(())
+    }
 
     /// Read all data from the channel
     /// 
@@ -115,7 +336,25 @@ impl ChannelReader {
     /// // This is a low-level function; typically you would use TdmsReader::read_channel_data
     /// let data: Vec<i32> = reader.read_channel_data("Group1", "Channel1").unwrap();
     /// ```
-    pub fn read_all_data<T: Copy + Default, R: Read + Seek>(
+    pub fn read_all_data<T: TdmsValue, R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        segments: &[SegmentInfo],
+    ) -> Result<Vec<T>> {
+        if self.info.total_values > usize::MAX as u64 {
+            return Err(TdmsError::Unsupported(
+                "Channel has more values than can fit in memory".to_string(),
+            ));
+        }
+
+        self.check_value_type::<T>()?;
+        self.read_all_values(reader, segments)
+    }
+
+    /// [`read_all_data`](Self::read_all_data) without the type check: the
+    /// stored bytes are reinterpreted as `T`, which only has to be the same
+    /// size as the channel's values, e.g. an `I64` channel read as `u64`
+    pub fn read_all_data_unchecked<T: TdmsValue, R: Read + Seek>(
         &self,
         reader: &mut R,
         segments: &[SegmentInfo],
@@ -126,10 +365,20 @@ impl ChannelReader {
             ));
         }
 
+        self.check_value_size::<T>()?;
+        self.read_all_values(reader, segments)
+    }
+
+    fn read_all_values<T: TdmsValue, R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        segments: &[SegmentInfo],
+    ) -> Result<Vec<T>> {
         let total_values = self.info.total_values as usize;
         let mut result = Vec::with_capacity(total_values);
 
-        for segment_data in &self.info.segments {
+        for segment_data in self.info.segment_entries() {
+            let segment_data = segment_data?;
             let segment_info = &segments[segment_data.segment_index];
             
             // Calculate absolute position in file
@@ -141,9 +390,10 @@ impl ChannelReader {
             reader.seek(SeekFrom::Start(data_offset))?;
 
             // Read values from this segment
-            let values = RawDataReader::read_values::<T, _>(
+            let values = read_segment_values::<T, _>(
                 reader,
                 segment_data.value_count as usize,
+                segment_data.stride,
                 segment_info.is_big_endian,
             )?;
 
@@ -181,7 +431,7 @@ impl ChannelReader {
     /// // In a real application, you might build a higher-level abstraction.
     /// // let values: Vec<f64> = channel.read_chunk(&mut reader.file, &reader.segments, 0, 100).unwrap();
     /// ```
-    pub fn read_chunk<T: Copy + Default, R: Read + Seek>(
+    pub fn read_chunk<T: TdmsValue, R: Read + Seek>(
         &self,
         reader: &mut R,
         segments: &[SegmentInfo],
@@ -191,6 +441,7 @@ impl ChannelReader {
         if start_index >= self.info.total_values {
             return Ok(Vec::new());
         }
+        self.check_value_type::<T>()?;
 
         let end_index = (start_index + count as u64).min(self.info.total_values);
         let actual_count = (end_index - start_index) as usize;
@@ -199,7 +450,8 @@ impl ChannelReader {
         let mut current_index = 0u64;
         let mut remaining_to_read = actual_count;
 
-        for segment_data in &self.info.segments {
+        for segment_data in self.info.segment_entries() {
+            let segment_data = segment_data?;
             let segment_start = current_index;
             let segment_end = current_index + segment_data.value_count;
 
@@ -214,30 +466,27 @@ impl ChannelReader {
             }
 
             // Calculate what to read from this segment
-            let read_start_in_segment = if start_index > segment_start {
-                start_index - segment_start
-            } else {
-                0
-            };
+            let read_start_in_segment = start_index.saturating_sub(segment_start);
 
             let values_available_in_segment = segment_data.value_count - read_start_in_segment;
             let values_to_read = (remaining_to_read as u64).min(values_available_in_segment) as usize;
 
             // Seek to position in segment
             let segment_info = &segments[segment_data.segment_index];
-            let type_size = std::mem::size_of::<T>() as u64;
+            let value_stride = segment_data.stride.unwrap_or(std::mem::size_of::<T>() as u64);
             let data_offset = segment_info.offset
                 + 28
                 + segment_info.metadata_size // <-- FIX: Use the correct field name
                 + segment_data.byte_offset
-                + (read_start_in_segment * type_size);
+                + (read_start_in_segment * value_stride);
 
             reader.seek(SeekFrom::Start(data_offset))?;
 
             // Read values
-            let values = RawDataReader::read_values::<T, _>(
+            let values = read_segment_values::<T, _>(
                 reader,
                 values_to_read,
+                segment_data.stride,
                 segment_info.is_big_endian,
             )?;
 
@@ -284,7 +533,8 @@ impl ChannelReader {
         let total_values = self.info.total_values as usize;
         let mut result = Vec::with_capacity(total_values);
 
-        for segment_data in &self.info.segments {
+        for segment_data in self.info.segment_entries() {
+            let segment_data = segment_data?;
             let segment_info = &segments[segment_data.segment_index];
             
             let data_offset = segment_info.offset
@@ -294,10 +544,11 @@ impl ChannelReader {
             
             reader.seek(SeekFrom::Start(data_offset))?;
 
-            let strings = RawDataReader::read_strings(
+            let strings = RawDataReader::read_strings_with(
                 reader,
                 segment_data.value_count as usize,
                 segment_info.is_big_endian,
+                self.string_encoding,
             )?;
 
             result.extend(strings);
@@ -343,7 +594,8 @@ impl ChannelReader {
         let mut current_index = 0u64;
         let mut remaining_to_read = actual_count;
 
-        for segment_data in &self.info.segments {
+        for segment_data in self.info.segment_entries() {
+            let segment_data = segment_data?;
             let segment_start = current_index;
             let segment_end = current_index + segment_data.value_count;
 
@@ -357,11 +609,7 @@ impl ChannelReader {
             }
 
             // Calculate what to read from *this* segment
-            let read_start_in_segment = if start_index > segment_start {
-                start_index - segment_start // <-- FIX: Removed parentheses
-            } else {
-                0
-            } as u64;
+            let read_start_in_segment = start_index.saturating_sub(segment_start);
 
             let values_available_in_segment = segment_data.value_count - read_start_in_segment;
             let values_to_read = (remaining_to_read as u64).min(values_available_in_segment) as usize;
@@ -390,7 +638,11 @@ impl ChannelReader {
             reader.seek(SeekFrom::Start(offset_block_start + (read_start_in_segment + values_to_read as u64 - 1) * 4))?;
             let byte_end_offset = RawDataReader::read_u32(reader, is_big_endian)? as u64;
 
-            let bytes_to_read = (byte_end_offset - byte_start_offset) as usize;
+            let bytes_to_read = byte_end_offset.checked_sub(byte_start_offset)
+                .ok_or_else(|| TdmsError::InvalidTag {
+                    expected: "valid string offsets".to_string(),
+                    found: "corrupt offsets".to_string(),
+                })? as usize;
 
             // 3. Read the relevant offsets for parsing this chunk
             reader.seek(SeekFrom::Start(offset_block_start + read_start_in_segment * 4))?;
@@ -410,7 +662,8 @@ impl ChannelReader {
                 let mut local_start = 0;
                 for &cumulative_end in &offsets_in_chunk {
                     // Make offset relative to our small buffer
-                    let local_end = (cumulative_end as u64 - byte_start_offset) as usize;
+                    let local_end = (cumulative_end as u64).checked_sub(byte_start_offset)
+                        .map_or(usize::MAX, |end| end as usize);
                     if local_end < local_start || local_end > data_buf.len() {
                         return Err(TdmsError::InvalidTag {
                             expected: "valid string offsets".to_string(),
@@ -418,8 +671,7 @@ impl ChannelReader {
                         });
                     }
                     
-                    let s = String::from_utf8(data_buf[local_start..local_end].to_vec())
-                        .map_err(|_| TdmsError::InvalidUtf8)?;
+                    let s = self.string_encoding.decode(&data_buf[local_start..local_end])?;
                     result.push(s);
                     local_start = local_end;
                 }
@@ -469,7 +721,7 @@ impl ChannelReader {
     /// //     println!("Read chunk of size {}", chunk.len());
     /// // }
     /// ```
-    pub fn iter_chunks<T: Copy + Default>(&self, chunk_size: usize) -> ChunkIterator<T> {
+    pub fn iter_chunks<T: TdmsValue>(&self, chunk_size: usize) -> ChunkIterator<T> {
         ChunkIterator::new(self.clone(), chunk_size)
     }
 
@@ -481,11 +733,19 @@ impl ChannelReader {
     /// 
     /// # Returns
     /// 
-    /// Reference to the segment data if it exists
+    /// Reference to the segment data if it exists. Readers that keep a compact
+    /// or spilled index hold no entries to refer to and return `None`; use
+    /// [`segment`](Self::segment) with those.
     pub fn get_segment_data(&self, segment_index: usize) -> Option<&SegmentData> {
         self.info.segments.get(segment_index)
     }
 
+    /// A copy of the segment data if it exists, however the reader keeps its
+    /// segment index
+    pub fn segment(&self, segment_index: usize) -> Result<Option<SegmentData>> {
+        self.info.segment_entry(segment_index)
+    }
+
     /// Check if the channel is empty (has no data)
     pub fn is_empty(&self) -> bool {
         self.info.total_values == 0
@@ -498,6 +758,7 @@ impl Clone for ChannelReader {
         ChannelReader {
             channel_key: self.channel_key.clone(),
             info: self.info.clone(),
+            string_encoding: self.string_encoding,
         }
     }
 }
@@ -506,14 +767,14 @@ impl Clone for ChannelReader {
 /// 
 /// This allows memory-efficient processing of large channels by reading
 /// and processing one chunk at a time.
-pub struct ChunkIterator<T: Copy + Default> {
+pub struct ChunkIterator<T: TdmsValue> {
     channel: ChannelReader,
     chunk_size: usize,
     current_position: u64,
     _phantom: std::marker::PhantomData<T>,
 }
 
-impl<T: Copy + Default> ChunkIterator<T> {
+impl<T: TdmsValue> ChunkIterator<T> {
     fn new(channel: ChannelReader, chunk_size: usize) -> Self {
         ChunkIterator {
             channel,
@@ -575,9 +836,38 @@ impl<T: Copy + Default> ChunkIterator<T> {
     }
 }
 
+/// Read `count` values starting at the current position.
+///
+/// With a stride, values are `stride` bytes apart (e.g. one field of interleaved
+/// DAQmx records) and only the value bytes of each record are kept.
+fn read_segment_values<T, R: Read>(
+    reader: &mut R,
+    count: usize,
+    stride: Option<u64>,
+    is_big_endian: bool,
+) -> Result<Vec<T>>
+where
+    T: TdmsValue,
+{
+    let size = std::mem::size_of::<T>();
+    let stride = match stride {
+        Some(stride) if count > 0 && stride as usize != size => stride as usize,
+        _ => return RawDataReader::read_values::<T, _>(reader, count, is_big_endian),
+    };
+
+    let mut records = vec![0u8; (count - 1) * stride + size];
+    reader.read_exact(&mut records)?;
+    let mut packed = Vec::with_capacity(count * size);
+    for i in 0..count {
+        packed.extend_from_slice(&records[i * stride..i * stride + size]);
+    }
+    RawDataReader::read_values::<T, _>(&mut packed.as_slice(), count, is_big_endian)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::TocFlags;
 
     fn create_test_channel_info() -> ChannelInfo {
         let mut info = ChannelInfo::new(DataType::I32);
@@ -588,21 +878,24 @@ mod tests {
             value_count: 100,
             byte_size: 400,
             byte_offset: 0,
-        });
+            stride: None,
+        }).unwrap();
         
         info.add_segment(SegmentData {
             segment_index: 1,
             value_count: 200,
             byte_size: 800,
             byte_offset: 0,
-        });
+            stride: None,
+        }).unwrap();
         
         info.add_segment(SegmentData {
             segment_index: 2,
             value_count: 150,
             byte_size: 600,
             byte_offset: 0,
-        });
+            stride: None,
+        }).unwrap();
         
         info
     }
@@ -654,6 +947,34 @@ mod tests {
         assert!(iter.has_more());
     }
 
+    #[test]
+    fn test_byte_ranges() {
+        let info = create_test_channel_info();
+        let reader = ChannelReader::new("Group1/Channel1".to_string(), info);
+        let segment = |offset, metadata_size| SegmentInfo {
+            offset,
+            toc: TocFlags::new(0),
+            is_big_endian: false,
+            version: SegmentHeader::VERSION,
+            metadata_size,
+            total_raw_data_size: 0,
+        };
+        let segments = [segment(0, 100), segment(528, 0), segment(1356, 0)];
+        
+        assert_eq!(reader.byte_ranges(&segments, 0, 450), vec![(128, 400), (556, 800), (1384, 600)]);
+        assert_eq!(reader.byte_ranges(&segments, 150, 10), vec![(556 + 200, 40)]);
+        assert_eq!(reader.byte_ranges(&segments, 299, 2), vec![(556 + 796, 4), (1384, 4)]);
+        assert!(reader.byte_ranges(&segments, 450, 10).is_empty());
+        
+        // Appended chunks of one segment are contiguous
+        let mut info = ChannelInfo::new(DataType::I32);
+        for chunk in 0..2 {
+            info.add_segment(SegmentData { segment_index: 0, value_count: 100, byte_size: 400, byte_offset: chunk * 400, stride: None }).unwrap();
+        }
+        let reader = ChannelReader::new("Group1/Channel1".to_string(), info);
+        assert_eq!(reader.byte_ranges(&segments, 0, 200), vec![(128, 800)]);
+    }
+
     #[test]
     fn test_segment_data_access() {
         let info = create_test_channel_info();
@@ -0,0 +1,132 @@
+// src/reader/daqmx.rs
+use crate::error::{TdmsError, Result};
+use crate::types::DataType;
+
+/// Raw data index header of a DAQmx channel using format changing scalers
+pub(crate) const FORMAT_CHANGING_SCALER: u32 = 0x6912_0000;
+/// Raw data index header of a DAQmx channel using digital line scalers
+pub(crate) const DIGITAL_LINE_SCALER: u32 = 0x6913_0000;
+
+/// Whether a raw data index header belongs to the DAQmx family (`0x69xx0000`)
+/// but is not one of the variants this crate can parse.
+///
+/// Newer drivers may emit further variants; their layout is unknown, so the
+/// rest of the segment's metadata can't be located.
+pub(crate) fn is_unknown_header(header: u32) -> bool {
+    header & 0xFF00_FFFF == 0x6900_0000
+        && header != FORMAT_CHANGING_SCALER
+        && header != DIGITAL_LINE_SCALER
+}
+
+/// How a DAQmx scaler locates its value in the raw buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaqmxScalerKind {
+    /// Value stored as a full number at a byte offset within the raw record
+    FormatChanging { byte_offset: u32 },
+    /// Single bit of a digital port at a bit offset within the raw record
+    DigitalLine { bit_offset: u32 },
+}
+
+/// One scaler of a DAQmx channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaqmxScaler {
+    /// Type of the raw value, or `None` if the DAQmx type code is unknown
+    pub data_type: Option<DataType>,
+    pub daqmx_type_code: u32,
+    pub raw_buffer_index: u32,
+    pub kind: DaqmxScalerKind,
+    pub sample_format_bitmap: u32,
+    pub scale_id: u32,
+}
+
+/// Layout of a DAQmx channel's raw data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaqmxInfo {
+    pub scalers: Vec<DaqmxScaler>,
+    /// Width in bytes of each raw buffer; one record holds one sample of every buffer
+    pub raw_data_widths: Vec<u32>,
+}
+
+impl DaqmxInfo {
+    /// Bytes per sample across all raw buffers
+    pub fn record_width(&self) -> u64 {
+        self.raw_data_widths.iter().map(|w| *w as u64).sum()
+    }
+
+    /// Position of the first scaler's value within a record, with its type.
+    ///
+    /// Only format changing scalers can be read as plain values.
+    pub(crate) fn primary_value_layout(&self) -> Result<(u64, DataType)> {
+        let scaler = self.scalers.first()
+            .ok_or_else(|| TdmsError::Unsupported("DAQmx channel without scalers".into()))?;
+        let byte_offset = match scaler.kind {
+            DaqmxScalerKind::FormatChanging { byte_offset } => byte_offset as u64,
+            DaqmxScalerKind::DigitalLine { .. } => {
+                return Err(TdmsError::Unsupported("Reading DAQmx digital line scalers".into()));
+            }
+        };
+        let data_type = scaler.data_type
+            .ok_or(TdmsError::InvalidDataType(scaler.daqmx_type_code))?;
+        let buffer_offset: u64 = self.raw_data_widths.iter()
+            .take(scaler.raw_buffer_index as usize)
+            .map(|w| *w as u64)
+            .sum();
+        Ok((buffer_offset + byte_offset, data_type))
+    }
+}
+
+/// Map a DAQmx scaler type code to a TDMS data type
+pub(crate) fn daqmx_data_type(code: u32) -> Option<DataType> {
+    match code {
+        0 => Some(DataType::U8),
+        1 => Some(DataType::I8),
+        2 => Some(DataType::U16),
+        3 => Some(DataType::I16),
+        4 => Some(DataType::U32),
+        5 => Some(DataType::I32),
+        6 => Some(DataType::U64),
+        7 => Some(DataType::I64),
+        8 => Some(DataType::SingleFloat),
+        9 => Some(DataType::DoubleFloat),
+        0xFFFF_FFFF => Some(DataType::TimeStamp),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primary_value_layout() {
+        let info = DaqmxInfo {
+            scalers: vec![DaqmxScaler {
+                data_type: daqmx_data_type(3),
+                daqmx_type_code: 3,
+                raw_buffer_index: 1,
+                kind: DaqmxScalerKind::FormatChanging { byte_offset: 2 },
+                sample_format_bitmap: 0,
+                scale_id: 0,
+            }],
+            raw_data_widths: vec![4, 6],
+        };
+        assert_eq!(info.record_width(), 10);
+        assert_eq!(info.primary_value_layout().unwrap(), (6, DataType::I16));
+
+        let digital = DaqmxInfo {
+            scalers: vec![DaqmxScaler { kind: DaqmxScalerKind::DigitalLine { bit_offset: 3 }, ..info.scalers[0].clone() }],
+            raw_data_widths: vec![1],
+        };
+        assert!(digital.primary_value_layout().is_err());
+    }
+
+    #[test]
+    fn test_unknown_header() {
+        assert!(is_unknown_header(0x6914_0000));
+        assert!(is_unknown_header(0x6900_0000));
+        assert!(!is_unknown_header(FORMAT_CHANGING_SCALER));
+        assert!(!is_unknown_header(DIGITAL_LINE_SCALER));
+        assert!(!is_unknown_header(20));
+        assert!(!is_unknown_header(0xFFFF_FFFF));
+    }
+}
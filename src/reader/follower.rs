@@ -0,0 +1,148 @@
+// src/reader/follower.rs
+use crate::error::{Result, TdmsError};
+use crate::metadata::ObjectPath;
+use crate::reader::{StreamingReader, TdmsReader};
+use crate::types::TdmsValue;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+type FileReader = TdmsReader<BufReader<File>>;
+
+/// Reads a channel from the stream's position to its end, handing the values to a callback
+type Dispatch = Box<dyn FnMut(&mut FileReader, StreamingReader) -> Result<()>>;
+
+/// Number of values passed to a callback at once
+const CHUNK_SIZE: usize = 65536;
+
+struct Subscription {
+    path: ObjectPath,
+    /// Values already handed to the callback
+    delivered: u64,
+    dispatch: Dispatch,
+}
+
+/// Follows a TDMS file that another process is still writing and calls back
+/// with the values appended to each subscribed channel.
+///
+/// Each [`poll`](Self::poll) re-reads the file's metadata, then calls every
+/// subscriber whose channel grew since the last poll with just the new values.
+/// Callbacks get the index of the first value and a slice of at most 65536
+/// values, so a large backlog arrives in several calls. A subscription to a
+/// channel that doesn't exist yet starts delivering once it appears.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::TdmsFollower;
+/// use std::time::Duration;
+///
+/// let mut follower = TdmsFollower::new("live.tdms");
+/// follower.on_new_data::<f64>("Rack", "Pressure", |start, values| {
+///     println!("{} new values from #{}", values.len(), start);
+/// });
+/// follower.on_new_strings("Rack", "Events", |_, events| {
+///     for event in events {
+///         println!("event: {}", event);
+///     }
+/// });
+/// loop {
+///     follower.poll().unwrap();
+///     std::thread::sleep(Duration::from_millis(200));
+/// }
+/// ```
+pub struct TdmsFollower {
+    path: PathBuf,
+    subscriptions: Vec<Subscription>,
+}
+
+impl TdmsFollower {
+    /// Follow the file at `path`, which doesn't have to exist yet
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        TdmsFollower {
+            path: path.as_ref().to_path_buf(),
+            subscriptions: Vec::new(),
+        }
+    }
+
+    /// Path of the followed file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Call `callback` with the values appended to a channel.
+    ///
+    /// Values already in the file are delivered by the first poll. A `T` that
+    /// doesn't match the channel's data type makes [`poll`](Self::poll) fail
+    /// with [`TdmsError::TypeMismatch`].
+    pub fn on_new_data<T: TdmsValue>(&mut self, group: &str, channel: &str,
+                                     mut callback: impl FnMut(u64, &[T]) + 'static) -> &mut Self {
+        self.subscribe(group, channel, Box::new(move |reader, mut stream| {
+            let mut start = stream.position();
+            while let Some(values) = reader.read_streaming_data::<T>(&mut stream)? {
+                callback(start, &values);
+                start += values.len() as u64;
+            }
+            Ok(())
+        }))
+    }
+
+    /// Call `callback` with the strings appended to a string channel
+    pub fn on_new_strings(&mut self, group: &str, channel: &str,
+                          mut callback: impl FnMut(u64, &[String]) + 'static) -> &mut Self {
+        self.subscribe(group, channel, Box::new(move |reader, mut stream| {
+            let mut start = stream.position();
+            while let Some(values) = reader.read_streaming_strings(&mut stream)? {
+                callback(start, &values);
+                start += values.len() as u64;
+            }
+            Ok(())
+        }))
+    }
+
+    fn subscribe(&mut self, group: &str, channel: &str, dispatch: Dispatch) -> &mut Self {
+        self.subscriptions.push(Subscription {
+            path: ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() },
+            delivered: 0,
+            dispatch,
+        });
+        self
+    }
+
+    /// Deliver everything appended since the last poll.
+    ///
+    /// Returns the number of values handed to callbacks. A file that doesn't
+    /// exist yet counts as empty.
+    pub fn poll(&mut self) -> Result<u64> {
+        let mut reader = match TdmsReader::open(&self.path) {
+            Ok(reader) => reader,
+            Err(TdmsError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut dispatched = 0;
+        for subscription in &mut self.subscriptions {
+            let Some(channel) = reader.get_channel(&subscription.path.to_string()) else { continue };
+            let total = channel.total_values();
+            if total <= subscription.delivered {
+                continue;
+            }
+            let mut stream = StreamingReader::new(channel, CHUNK_SIZE);
+            stream.seek(subscription.delivered);
+            (subscription.dispatch)(&mut reader, stream)?;
+            dispatched += total - subscription.delivered;
+            subscription.delivered = total;
+        }
+        Ok(dispatched)
+    }
+
+    /// Poll every `interval` for as long as `keep_going` returns true
+    pub fn follow(&mut self, interval: Duration, mut keep_going: impl FnMut() -> bool) -> Result<()> {
+        while keep_going() {
+            self.poll()?;
+            std::thread::sleep(interval);
+        }
+        Ok(())
+    }
+}
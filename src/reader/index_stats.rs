@@ -0,0 +1,149 @@
+// src/reader/index_stats.rs
+//! Summaries of a file's segment layout, computed from the index alone.
+
+use crate::error::Result;
+use crate::metadata::ObjectPath;
+use crate::reader::channel_reader::ChannelInfo;
+use crate::segment::{SegmentHeader, SegmentInfo};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// How a file's bytes and each channel's values are split across segments.
+///
+/// Returned by [`TdmsReader::index_stats`](crate::TdmsReader::index_stats).
+/// Many small segments mean a lot of lead-in and metadata per value; compare
+/// [`metadata_overhead_percent`](Self::metadata_overhead_percent) before and
+/// after [`defragment`](crate::defragment) to see what rewriting would save.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexStats {
+    pub segment_count: usize,
+    /// Bytes of segment lead-ins (28 per segment)
+    pub lead_in_bytes: u64,
+    pub metadata_bytes: u64,
+    pub raw_data_bytes: u64,
+    /// One entry per channel, sorted by path
+    pub channels: Vec<ChannelIndexStats>,
+}
+
+/// How one channel's values are spread over segments
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelIndexStats {
+    pub path: ObjectPath,
+    /// Segments holding data for the channel
+    pub segment_count: usize,
+    /// Contiguous runs of values; more than the segment count when segments repeat
+    /// their raw data in several chunks
+    pub chunk_count: usize,
+    pub total_values: u64,
+    pub min_chunk_values: u64,
+    pub max_chunk_values: u64,
+    /// Number of chunks by size: `(lower bound, count)` for power-of-two buckets
+    /// `[lower, 2 * lower)`, plus `(0, count)` for empty chunks. Only non-empty
+    /// buckets are listed, smallest first.
+    pub chunk_histogram: Vec<(u64, usize)>,
+}
+
+impl IndexStats {
+    /// Total size of the segments
+    pub fn total_bytes(&self) -> u64 {
+        self.lead_in_bytes + self.metadata_bytes + self.raw_data_bytes
+    }
+
+    /// Share of the file spent on lead-ins and metadata rather than raw data, in percent
+    pub fn metadata_overhead_percent(&self) -> f64 {
+        match self.total_bytes() {
+            0 => 0.0,
+            total => (self.lead_in_bytes + self.metadata_bytes) as f64 * 100.0 / total as f64,
+        }
+    }
+
+    /// Stats for one channel
+    pub fn channel(&self, group: &str, channel: &str) -> Option<&ChannelIndexStats> {
+        self.channels.iter().find(|stats| matches!(&stats.path,
+            ObjectPath::Channel { group: g, channel: c } if g == group && c == channel))
+    }
+}
+
+impl ChannelIndexStats {
+    /// Average number of values per chunk
+    pub fn mean_chunk_values(&self) -> f64 {
+        match self.chunk_count {
+            0 => 0.0,
+            chunks => self.total_values as f64 / chunks as f64,
+        }
+    }
+}
+
+impl fmt::Display for IndexStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} segments, {} bytes ({:.1}% lead-in and metadata)",
+            self.segment_count, self.total_bytes(), self.metadata_overhead_percent())?;
+        for channel in &self.channels {
+            writeln!(f, "  {}: {} values in {} segments ({} chunks, {}..{} values, mean {:.1})",
+                channel.path, channel.total_values, channel.segment_count, channel.chunk_count,
+                channel.min_chunk_values, channel.max_chunk_values, channel.mean_chunk_values())?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn compute<'a>(
+    segments: &[SegmentInfo],
+    channels: impl IntoIterator<Item = (&'a ObjectPath, &'a ChannelInfo)>,
+) -> Result<IndexStats> {
+    let mut stats = IndexStats {
+        segment_count: segments.len(),
+        lead_in_bytes: segments.len() as u64 * SegmentHeader::LEAD_IN_SIZE as u64,
+        metadata_bytes: segments.iter().map(|s| s.metadata_size).sum(),
+        raw_data_bytes: segments.iter().map(|s| s.total_raw_data_size).sum(),
+        channels: Vec::new(),
+    };
+
+    for (path, info) in channels {
+        let mut segment_indices = HashSet::new();
+        let mut histogram = BTreeMap::new();
+        let (mut min, mut max) = (u64::MAX, 0);
+        for segment in info.segment_entries() {
+            let segment = segment?;
+            segment_indices.insert(segment.segment_index);
+            min = min.min(segment.value_count);
+            max = max.max(segment.value_count);
+            *histogram.entry(bucket(segment.value_count)).or_insert(0) += 1;
+        }
+        stats.channels.push(ChannelIndexStats {
+            path: path.clone(),
+            segment_count: segment_indices.len(),
+            chunk_count: info.segment_count(),
+            total_values: info.total_values,
+            min_chunk_values: if info.segment_count() == 0 { 0 } else { min },
+            max_chunk_values: max,
+            chunk_histogram: histogram.into_iter().collect(),
+        });
+    }
+
+    stats.channels.sort_by_cached_key(|channel| channel.path.to_string());
+    Ok(stats)
+}
+
+/// Lower bound of the power-of-two bucket holding `values`
+fn bucket(values: u64) -> u64 {
+    match values {
+        0 => 0,
+        n => 1 << (63 - n.leading_zeros()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket() {
+        assert_eq!(bucket(0), 0);
+        assert_eq!(bucket(1), 1);
+        assert_eq!(bucket(3), 2);
+        assert_eq!(bucket(4), 4);
+        assert_eq!(bucket(1000), 512);
+        assert_eq!(bucket(u64::MAX), 1 << 63);
+    }
+}
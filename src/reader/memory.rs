@@ -0,0 +1,94 @@
+// src/reader/memory.rs
+//! Heuristic memory estimates for opening files and reading channels.
+//!
+//! Both estimates are meant for picking a worker size before doing the work,
+//! so they err on the high side and are cheap: opening only reads segment
+//! lead-ins, reading only looks at the index.
+
+use crate::error::Result;
+use crate::reader::channel_reader::ChannelInfo;
+use crate::segment::{SegmentHeader, SegmentInfo};
+use crate::types::{DataType, TocFlags};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Reader state per byte of segment metadata: parsed paths, raw data
+/// indexes, property values and their history
+const METADATA_EXPANSION: u64 = 3;
+
+/// Typical metadata bytes describing one channel's data in a segment, for
+/// guessing how many index entries a segment adds
+const METADATA_PER_ENTRY: u64 = 48;
+
+/// Index bytes per channel per segment
+#[cfg(not(feature = "compact-index"))]
+const ENTRY_SIZE: u64 = std::mem::size_of::<crate::reader::channel_reader::SegmentData>() as u64;
+#[cfg(feature = "compact-index")]
+const ENTRY_SIZE: u64 = 8;
+
+/// Estimate the memory a reader holds after opening `path`
+pub(crate) fn estimate_open(path: &Path) -> Result<u64> {
+    let mut file = BufReader::new(File::open(path)?);
+    let file_size = file.get_ref().metadata()?.len();
+
+    let mut estimate = 0u64;
+    let mut entries_per_segment = 0u64;
+    let mut offset = 0u64;
+    while file_size - offset >= SegmentHeader::LEAD_IN_SIZE as u64 {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut lead_in = [0u8; SegmentHeader::LEAD_IN_SIZE];
+        file.read_exact(&mut lead_in)?;
+        if &lead_in[0..4] != SegmentHeader::TDMS_TAG {
+            break;
+        }
+        let toc = TocFlags::new(LittleEndian::read_u32(&lead_in[4..8]));
+        let (next_segment_offset, metadata_size) = if toc.is_big_endian() {
+            (BigEndian::read_u64(&lead_in[12..20]), BigEndian::read_u64(&lead_in[20..28]))
+        } else {
+            (LittleEndian::read_u64(&lead_in[12..20]), LittleEndian::read_u64(&lead_in[20..28]))
+        };
+
+        estimate += std::mem::size_of::<SegmentInfo>() as u64 + metadata_size * METADATA_EXPANSION;
+        // Segments without metadata repeat the previous segment's channels
+        if toc.has_metadata() {
+            let entries = metadata_size / METADATA_PER_ENTRY;
+            entries_per_segment = if toc.has_new_obj_list() { entries } else { entries_per_segment.max(entries) };
+        }
+        if toc.has_raw_data() {
+            estimate += entries_per_segment * ENTRY_SIZE;
+        }
+
+        match offset.checked_add(SegmentHeader::LEAD_IN_SIZE as u64 + next_segment_offset) {
+            Some(next) if next_segment_offset != SegmentHeader::INCOMPLETE_MARKER && next <= file_size => offset = next,
+            _ => break,
+        }
+    }
+    Ok(estimate)
+}
+
+/// Estimate the peak memory of reading a whole channel whose values are of
+/// `data_type` once decoded: the values plus the largest raw buffer read
+/// at once
+pub(crate) fn estimate_read(info: &ChannelInfo, data_type: DataType) -> Result<u64> {
+    let (mut raw_bytes, mut largest_chunk) = (0u64, 0u64);
+    for segment in info.segment_entries() {
+        let byte_size = segment?.byte_size;
+        raw_bytes += byte_size;
+        largest_chunk = largest_chunk.max(byte_size);
+    }
+    let values = info.total_values;
+
+    let decoded = match data_type {
+        // The string headers plus their text, which is the raw data without
+        // its offset table
+        DataType::String => values * std::mem::size_of::<String>() as u64 + raw_bytes.saturating_sub(4 * values),
+        // Read as scaled f64
+        DataType::DAQmxRawData => values * 8,
+        _ => values * data_type.fixed_size().unwrap_or(8) as u64,
+    };
+    // Encoded channels are decoded from all their bytes at once
+    let transient = if data_type != info.data_type { raw_bytes } else { largest_chunk };
+    Ok(decoded + transient)
+}
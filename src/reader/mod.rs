@@ -2,7 +2,21 @@
 mod sync_reader;
 mod channel_reader;
 mod streaming;
+mod pool;
+mod follower;
+mod index_stats;
+mod memory;
+mod segment_list;
+mod cache;
+pub(crate) mod daqmx;
 
-pub use sync_reader::TdmsReader;
-pub use channel_reader::ChannelReader;
-pub use streaming::{StreamingReader, TdmsIter, TdmsStringIter};
\ No newline at end of file
+pub use sync_reader::{TdmsReader, ParseWarning, RawProperty, IndexFileStatus, MetadataCrcStatus, ChannelPreview, ChannelSummary, ReaderOptions, WriterInfo, UnknownPropertyPolicy, IncompleteSegmentPolicy, AllProperties, GroupProperties};
+#[cfg(feature = "mmap")]
+pub use sync_reader::MmapHints;
+pub use channel_reader::{ChannelReader, ConversionStats};
+pub use streaming::{StreamingReader, TdmsIter, TdmsStringIter, TdmsWindowIter, TdmsZipIter, OwnedTdmsIter, OwnedTdmsStringIter};
+pub use pool::ReaderPool;
+pub use cache::DecompressionCache;
+pub use follower::TdmsFollower;
+pub use index_stats::{IndexStats, ChannelIndexStats};
+pub use daqmx::{DaqmxInfo, DaqmxScaler, DaqmxScalerKind};
@@ -0,0 +1,180 @@
+// src/reader/pool.rs
+use crate::error::Result;
+use crate::reader::TdmsReader;
+use parking_lot::{Condvar, Mutex};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+type FileReader = TdmsReader<BufReader<File>>;
+
+/// A bounded pool of open TDMS readers
+///
+/// Reading a large set of files (e.g. everything produced by a `RotatingTdmsWriter`)
+/// from several threads can exhaust the process file-descriptor limit. The pool caps
+/// the number of readers that hold an open file at once. Idle readers are cached,
+/// so revisiting a file does not re-parse its metadata; the least recently used one
+/// is closed when a new file needs a slot. When every slot is in use, callers block
+/// until one is returned.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::reader::ReaderPool;
+///
+/// let pool = ReaderPool::new(64);
+/// let data: Vec<f64> = pool.with_reader("run.3.tdms", |reader| {
+///     reader.read_channel_data("Group1", "Voltage")
+/// }).unwrap();
+/// ```
+pub struct ReaderPool {
+    max_open: usize,
+    state: Mutex<PoolState>,
+    returned: Condvar,
+}
+
+struct PoolState {
+    /// Idle readers, least recently used first
+    idle: Vec<(PathBuf, FileReader)>,
+    /// Readers currently lent out or being opened
+    in_use: usize,
+}
+
+impl ReaderPool {
+    /// Create a pool that keeps at most `max_open` files open (minimum 1)
+    pub fn new(max_open: usize) -> Self {
+        ReaderPool {
+            max_open: max_open.max(1),
+            state: Mutex::new(PoolState { idle: Vec::new(), in_use: 0 }),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Maximum number of simultaneously open files
+    pub fn max_open(&self) -> usize {
+        self.max_open
+    }
+
+    /// Number of files currently open, idle or in use
+    pub fn open_count(&self) -> usize {
+        let state = self.state.lock();
+        state.idle.len() + state.in_use
+    }
+
+    /// Run `f` with a reader for `path`, opening the file if it is not cached.
+    ///
+    /// Blocks while the pool is at capacity and every reader is in use.
+    pub fn with_reader<T, F>(&self, path: impl AsRef<Path>, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut FileReader) -> Result<T>,
+    {
+        let path = path.as_ref();
+        let mut lease = Lease { pool: self, path: path.to_path_buf(), reader: None };
+
+        {
+            let mut state = self.state.lock();
+            loop {
+                if let Some(pos) = state.idle.iter().position(|(p, _)| p == path) {
+                    let (_, reader) = state.idle.remove(pos);
+                    lease.reader = Some(reader);
+                    break;
+                }
+                if state.idle.len() + state.in_use < self.max_open {
+                    break;
+                }
+                if !state.idle.is_empty() {
+                    // Close the least recently used reader to free its handle
+                    state.idle.remove(0);
+                    continue;
+                }
+                self.returned.wait(&mut state);
+            }
+            state.in_use += 1;
+        }
+
+        // Open outside the lock; the slot is already reserved
+        if lease.reader.is_none() {
+            lease.reader = Some(TdmsReader::open(path)?);
+        }
+
+        f(lease.reader.as_mut().unwrap())
+    }
+
+    /// Close all idle readers
+    pub fn clear(&self) {
+        self.state.lock().idle.clear();
+    }
+}
+
+/// Returns a reader (and its slot) to the pool, even if the caller panics
+struct Lease<'a> {
+    pool: &'a ReaderPool,
+    path: PathBuf,
+    reader: Option<FileReader>,
+}
+
+impl Drop for Lease<'_> {
+    fn drop(&mut self) {
+        let mut state = self.pool.state.lock();
+        state.in_use -= 1;
+        if let Some(reader) = self.reader.take() {
+            state.idle.push((std::mem::take(&mut self.path), reader));
+        }
+        drop(state);
+        self.pool.returned.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+    use crate::writer::TdmsWriter;
+    use std::sync::Arc;
+
+    fn write_file(path: &str, value: i32) {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("G", "C", DataType::I32).unwrap();
+        writer.write_channel_data("G", "C", &[value; 4]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    #[test]
+    fn test_pool_caps_open_files() {
+        std::fs::create_dir_all("test_output").unwrap();
+        let paths: Vec<String> = (0..6).map(|i| format!("test_output/pool_{}.tdms", i)).collect();
+        for (i, path) in paths.iter().enumerate() {
+            write_file(path, i as i32);
+        }
+
+        let pool = Arc::new(ReaderPool::new(2));
+        let handles: Vec<_> = (0..4).map(|t| {
+            let pool = Arc::clone(&pool);
+            let paths = paths.clone();
+            std::thread::spawn(move || {
+                for round in 0..3 {
+                    for (i, path) in paths.iter().enumerate().skip((t + round) % 2) {
+                        let data: Vec<i32> = pool.with_reader(path, |r| r.read_channel_data("G", "C")).unwrap();
+                        assert_eq!(data, vec![i as i32; 4]);
+                        assert!(pool.open_count() <= 2);
+                    }
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(pool.open_count() <= 2);
+        pool.clear();
+        assert_eq!(pool.open_count(), 0);
+
+        assert!(pool.with_reader("test_output/pool_missing.tdms", |r| Ok(r.channel_count())).is_err());
+        assert_eq!(pool.open_count(), 0);
+
+        for path in &paths {
+            std::fs::remove_file(path).ok();
+            std::fs::remove_file(format!("{}_index", path)).ok();
+        }
+    }
+}
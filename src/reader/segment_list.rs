@@ -0,0 +1,365 @@
+// src/reader/segment_list.rs
+//! Storage for where a channel's data lives in each segment.
+//!
+//! Channels normally keep a plain `Vec<SegmentData>` in
+//! [`ChannelInfo::segments`](crate::reader::channel_reader::ChannelInfo::segments);
+//! a [`SegmentList`] replaces it for readers opened with
+//! `ReaderOptions::compact_index` or `ReaderOptions::spill_dir`.
+//!
+//! Without the `compact-index` feature a list is a plain vector of
+//! [`SegmentData`]. With it, each entry is two `u32`s instead: the segment index
+//! and an index into a per-channel table of distinct (value count, size, offset,
+//! stride) shapes. Files written as many small appends repeat the same shape in
+//! every segment, so this cuts the per-segment cost of a channel from 48 bytes
+//! to 8 for files with millions of segments.
+//!
+//! A reader opened with [`ReaderOptions::spill_dir`](crate::reader::ReaderOptions::spill_dir)
+//! also moves entries to a temporary [`SpillFile`] in blocks of
+//! [`SPILL_BLOCK`] while parsing, so only the newest partial block of each
+//! channel stays in memory.
+
+use crate::error::Result;
+use crate::reader::channel_reader::SegmentData;
+use byteorder::{ByteOrder, LittleEndian};
+use parking_lot::Mutex;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[cfg(feature = "compact-index")]
+use crate::error::TdmsError;
+#[cfg(feature = "compact-index")]
+use std::collections::HashMap;
+
+/// The segments a channel has data in, in file order
+#[derive(Debug, Clone, Default)]
+pub struct SegmentList {
+    /// Entries moved to a spill file; the in-memory entries come after them
+    spilled: Option<Spilled>,
+
+    #[cfg(not(feature = "compact-index"))]
+    entries: Vec<SegmentData>,
+
+    /// (segment index, shape index) per entry
+    #[cfg(feature = "compact-index")]
+    entries: Vec<(u32, u32)>,
+    #[cfg(feature = "compact-index")]
+    shapes: Vec<Shape>,
+    #[cfg(feature = "compact-index")]
+    shape_ids: HashMap<Shape, u32>,
+}
+
+/// Everything in a [`SegmentData`] except the segment index
+#[cfg(feature = "compact-index")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Shape {
+    value_count: u64,
+    byte_size: u64,
+    byte_offset: u64,
+    stride: Option<u64>,
+}
+
+/// Entries per block moved to a spill file
+const SPILL_BLOCK: usize = 4096;
+
+/// Bytes per spilled entry: segment index, value count, byte size, byte offset
+/// and stride as little-endian `u64`s, with `u64::MAX` for no stride
+const RECORD_SIZE: usize = 40;
+
+/// Spilled blocks of one channel
+#[derive(Debug, Clone)]
+struct Spilled {
+    file: Arc<SpillFile>,
+    /// File offset of each block
+    blocks: Vec<u64>,
+}
+
+impl Spilled {
+    fn len(&self) -> usize {
+        self.blocks.len() * SPILL_BLOCK
+    }
+
+    fn read_block(&self, offset: u64) -> Result<Vec<SegmentData>> {
+        let mut bytes = vec![0u8; SPILL_BLOCK * RECORD_SIZE];
+        self.file.read_at(offset, &mut bytes)?;
+        Ok(bytes.chunks_exact(RECORD_SIZE).map(decode_record).collect())
+    }
+
+    fn read_entry(&self, index: usize) -> Result<SegmentData> {
+        let mut bytes = [0u8; RECORD_SIZE];
+        let offset = self.blocks[index / SPILL_BLOCK] + ((index % SPILL_BLOCK) * RECORD_SIZE) as u64;
+        self.file.read_at(offset, &mut bytes)?;
+        Ok(decode_record(&bytes))
+    }
+}
+
+fn encode_record(segment_data: SegmentData, bytes: &mut Vec<u8>) {
+    let mut record = [0u8; RECORD_SIZE];
+    LittleEndian::write_u64_into(&[
+        segment_data.segment_index as u64,
+        segment_data.value_count,
+        segment_data.byte_size,
+        segment_data.byte_offset,
+        segment_data.stride.unwrap_or(u64::MAX),
+    ], &mut record);
+    bytes.extend_from_slice(&record);
+}
+
+fn decode_record(record: &[u8]) -> SegmentData {
+    let mut fields = [0u64; 5];
+    LittleEndian::read_u64_into(record, &mut fields);
+    SegmentData {
+        segment_index: fields[0] as usize,
+        value_count: fields[1],
+        byte_size: fields[2],
+        byte_offset: fields[3],
+        stride: (fields[4] != u64::MAX).then_some(fields[4]),
+    }
+}
+
+/// Anonymous temporary file shared by the segment lists of one reader.
+///
+/// The file is unlinked as soon as it's created (deleted on close on
+/// Windows), so nothing is left behind however the reader goes away.
+#[derive(Debug)]
+pub(crate) struct SpillFile {
+    file: Mutex<File>,
+}
+
+impl SpillFile {
+    /// Create a spill file in `dir`
+    pub(crate) fn create(dir: &Path) -> Result<Self> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let path = dir.join(format!(
+            "tdms-rs-spill-{}-{}.tmp",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::OpenOptionsExt;
+            const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x0400_0000;
+            options.custom_flags(FILE_FLAG_DELETE_ON_CLOSE);
+        }
+        let file = options.open(&path)?;
+        #[cfg(not(windows))]
+        std::fs::remove_file(&path)?;
+
+        Ok(SpillFile { file: Mutex::new(file) })
+    }
+
+    /// Append `bytes`, returning the offset they were written at
+    fn append(&self, bytes: &[u8]) -> Result<u64> {
+        let mut file = self.file.lock();
+        let offset = file.seek(SeekFrom::End(0))?;
+        file.write_all(bytes)?;
+        Ok(offset)
+    }
+
+    /// Read back bytes written by [`append`](Self::append)
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let mut file = self.file.lock();
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(buf)?;
+        Ok(())
+    }
+}
+
+impl SegmentList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.spilled_len() + self.entries.len()
+    }
+
+    /// The entries in order; reading a spilled block back can fail
+    pub fn iter(&self) -> impl Iterator<Item = Result<SegmentData>> + '_ {
+        let spilled = self.spilled.iter()
+            .flat_map(|spilled| spilled.blocks.iter().flat_map(move |&offset| {
+                match spilled.read_block(offset) {
+                    Ok(block) => block.into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                }
+            }));
+        spilled.chain((0..self.entries.len()).map(|i| Ok(self.entry(i))))
+    }
+
+    pub fn get(&self, index: usize) -> Result<Option<SegmentData>> {
+        match &self.spilled {
+            Some(spilled) if index < spilled.len() => spilled.read_entry(index).map(Some),
+            _ => Ok((index < self.len()).then(|| self.entry(index - self.spilled_len()))),
+        }
+    }
+
+    /// Move whole blocks of in-memory entries to `file`
+    pub(crate) fn spill(&mut self, file: &Arc<SpillFile>) -> Result<()> {
+        let count = self.entries.len() / SPILL_BLOCK * SPILL_BLOCK;
+        if count == 0 {
+            return Ok(());
+        }
+
+        let mut offsets = Vec::with_capacity(count / SPILL_BLOCK);
+        let mut bytes = Vec::with_capacity(SPILL_BLOCK * RECORD_SIZE);
+        for start in (0..count).step_by(SPILL_BLOCK) {
+            bytes.clear();
+            for i in start..start + SPILL_BLOCK {
+                encode_record(self.entry(i), &mut bytes);
+            }
+            offsets.push(file.append(&bytes)?);
+        }
+
+        self.spilled
+            .get_or_insert_with(|| Spilled { file: Arc::clone(file), blocks: Vec::new() })
+            .blocks
+            .extend(offsets);
+        self.entries.drain(..count);
+        Ok(())
+    }
+
+    fn spilled_len(&self) -> usize {
+        self.spilled.as_ref().map_or(0, Spilled::len)
+    }
+}
+
+#[cfg(not(feature = "compact-index"))]
+impl SegmentList {
+    pub fn push(&mut self, segment_data: SegmentData) -> Result<()> {
+        self.entries.push(segment_data);
+        Ok(())
+    }
+
+    fn entry(&self, index: usize) -> SegmentData {
+        self.entries[index]
+    }
+}
+
+#[cfg(feature = "compact-index")]
+impl SegmentList {
+    /// Add an entry; fails past `u32::MAX` segments, which the compact form
+    /// can't index
+    pub fn push(&mut self, segment_data: SegmentData) -> Result<()> {
+        let segment_index = u32::try_from(segment_data.segment_index).map_err(|_| TdmsError::Unsupported(
+            format!("compact segment index of a file with more than {} segments", u32::MAX)))?;
+        let shape = Shape {
+            value_count: segment_data.value_count,
+            byte_size: segment_data.byte_size,
+            byte_offset: segment_data.byte_offset,
+            stride: segment_data.stride,
+        };
+        let next_id = self.shapes.len() as u32;
+        let shape_id = *self.shape_ids.entry(shape).or_insert(next_id);
+        if shape_id == next_id {
+            self.shapes.push(shape);
+        }
+        self.entries.push((segment_index, shape_id));
+        Ok(())
+    }
+
+    fn entry(&self, index: usize) -> SegmentData {
+        let (segment_index, shape_id) = self.entries[index];
+        let shape = self.shapes[shape_id as usize];
+        SegmentData {
+            segment_index: segment_index as usize,
+            value_count: shape.value_count,
+            byte_size: shape.byte_size,
+            byte_offset: shape.byte_offset,
+            stride: shape.stride,
+        }
+    }
+
+    /// Number of distinct shapes stored
+    #[cfg(test)]
+    fn shape_count(&self) -> usize {
+        self.shapes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(segment_index: usize, value_count: u64) -> SegmentData {
+        SegmentData {
+            segment_index,
+            value_count,
+            byte_size: value_count * 8,
+            byte_offset: 0,
+            stride: None,
+        }
+    }
+
+    #[test]
+    fn test_push_and_iterate() {
+        let mut list = SegmentList::new();
+        assert_eq!(list.len(), 0);
+        assert!(list.get(0).unwrap().is_none());
+
+        for i in 0..100 {
+            list.push(segment(i, if i % 10 == 9 { 3 } else { 100 })).unwrap();
+        }
+
+        assert_eq!(list.len(), 100);
+        assert_eq!(list.get(9).unwrap().unwrap().value_count, 3);
+        assert_eq!(list.get(10).unwrap().unwrap().value_count, 100);
+        assert!(list.get(100).unwrap().is_none());
+        assert_eq!(list.get(99).unwrap().unwrap().segment_index, 99);
+        assert_eq!(list.iter().map(|s| s.unwrap().value_count).sum::<u64>(), 90 * 100 + 10 * 3);
+    }
+
+    #[cfg(feature = "compact-index")]
+    #[test]
+    fn test_shapes_are_shared() {
+        let mut list = SegmentList::new();
+        for i in 0..1000 {
+            list.push(segment(i, 100)).unwrap();
+        }
+        list.push(segment(1000, 5)).unwrap();
+
+        assert_eq!(list.len(), 1001);
+        assert_eq!(list.shape_count(), 2);
+        assert_eq!(list.get(500).unwrap().unwrap().segment_index, 500);
+        assert_eq!(list.get(1000).unwrap().unwrap().byte_size, 40);
+
+        // Segment indexes past u32::MAX don't fit the compact entries
+        assert!(list.push(segment(u32::MAX as usize + 1, 5)).is_err());
+        assert_eq!(list.len(), 1001);
+    }
+
+    #[test]
+    fn test_spill() {
+        let file = Arc::new(SpillFile::create(&std::env::temp_dir()).unwrap());
+        let mut list = SegmentList::new();
+        let total = SPILL_BLOCK * 2 + 100;
+        for i in 0..total {
+            let mut data = segment(i, (i % 7) as u64);
+            data.stride = (i % 3 == 0).then_some(24);
+            list.push(data).unwrap();
+            list.spill(&file).unwrap();
+        }
+
+        assert_eq!(list.spilled_len(), SPILL_BLOCK * 2);
+        assert_eq!(list.entries.len(), 100);
+        assert_eq!(list.len(), total);
+        let entries: Vec<SegmentData> = list.iter().collect::<Result<_>>().unwrap();
+        assert_eq!(entries.len(), total);
+        for (i, data) in entries.iter().enumerate() {
+            assert_eq!(data.segment_index, i);
+            assert_eq!(data.value_count, (i % 7) as u64);
+            assert_eq!(data.stride, (i % 3 == 0).then_some(24));
+        }
+        assert_eq!(list.get(SPILL_BLOCK + 3).unwrap().unwrap().segment_index, SPILL_BLOCK + 3);
+        assert_eq!(list.get(total - 1).unwrap().unwrap().segment_index, total - 1);
+        assert!(list.get(total).unwrap().is_none());
+
+        // Clones share the spill file
+        let clone = list.clone();
+        assert_eq!(clone.get(5).unwrap().unwrap().byte_size, list.get(5).unwrap().unwrap().byte_size);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::error::Result;
 use crate::reader::{ChannelReader, TdmsReader};
 use crate::segment::SegmentInfo;
+use crate::types::TdmsValue;
 use std::io::{Read, Seek};
 use std::marker::PhantomData;
 
@@ -45,7 +46,7 @@ impl StreamingReader {
     /// # Returns
     /// 
     /// `Some(Vec<T>)` with the next chunk, or `None` if no more data
-    pub fn next<T: Copy + Default, R: Read + Seek>(
+    pub fn next<T: TdmsValue, R: Read + Seek>(
         &mut self,
         reader: &mut R,
         segments: &[SegmentInfo],
@@ -131,6 +132,11 @@ impl StreamingReader {
         self.current_position < self.channel.total_values()
     }
     
+    /// The channel being read
+    pub fn channel(&self) -> &ChannelReader {
+        &self.channel
+    }
+    
     /// Get the chunk size
     pub fn chunk_size(&self) -> usize {
         self.chunk_size
@@ -184,15 +190,11 @@ impl<'a, T, R: Read + Seek> TdmsIter<'a, T, R> {
     }
 }
 
-impl<'a, T: Copy + Default, R: Read + Seek> Iterator for TdmsIter<'a, T, R> {
+impl<'a, T: TdmsValue, R: Read + Seek> Iterator for TdmsIter<'a, T, R> {
     type Item = Result<Vec<T>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.tracker.next(&mut self.reader.file, &self.reader.segments) {
-            Ok(Some(data)) => Some(Ok(data)),
-            Ok(None) => None,
-            Err(e) => Some(Err(e)),
-        }
+        self.reader.read_streaming_data(&mut self.tracker).transpose()
     }
 }
 
@@ -222,11 +224,184 @@ impl<'a, R: Read + Seek> Iterator for TdmsStringIter<'a, R> {
     type Item = Result<Vec<String>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.tracker.next_strings(&mut self.reader.file, &self.reader.segments) {
-            Ok(Some(data)) => Some(Ok(data)),
-            Ok(None) => None,
-            Err(e) => Some(Err(e)),
+        self.reader.read_streaming_strings(&mut self.tracker).transpose()
+    }
+}
+
+/// Iterator over fixed-size, possibly overlapping windows of a channel's data
+///
+/// Returned by [`TdmsReader::iter_channel_windows`]. Each item holds `window`
+/// consecutive values and starts `hop` values after the previous one, so a hop
+/// smaller than the window overlaps them and a larger one leaves gaps. Windows
+/// are assembled across chunk and segment boundaries; a final partial window
+/// is not returned.
+pub struct TdmsWindowIter<'a, T, R: Read + Seek> {
+    reader: &'a mut TdmsReader<R>,
+    tracker: StreamingReader,
+    window: usize,
+    hop: usize,
+    // Values read from the start of the next window on
+    pending: Vec<T>,
+}
+
+impl<'a, T, R: Read + Seek> TdmsWindowIter<'a, T, R> {
+    pub fn new(reader: &'a mut TdmsReader<R>, channel: ChannelReader, window: usize, hop: usize) -> Self {
+        Self {
+            reader,
+            tracker: StreamingReader::new(channel, window),
+            window,
+            hop,
+            pending: Vec::new(),
+        }
+    }
+    
+    /// Get current progress percentage
+    pub fn progress(&self) -> f64 {
+        self.tracker.progress_percent()
+    }
+}
+
+impl<'a, T: TdmsValue, R: Read + Seek> Iterator for TdmsWindowIter<'a, T, R> {
+    type Item = Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.len() < self.window {
+            self.tracker.set_chunk_size(self.window - self.pending.len());
+            match self.reader.read_streaming_data::<T>(&mut self.tracker) {
+                Ok(Some(chunk)) => self.pending.extend(chunk),
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
         }
+        let window = self.pending[..self.window].to_vec();
+        if self.hop < self.pending.len() {
+            self.pending.drain(..self.hop);
+        } else {
+            // Values in the gap before the next window are never read
+            let gap = (self.hop - self.pending.len()) as u64;
+            self.pending.clear();
+            self.tracker.seek(self.tracker.position() + gap);
+        }
+        Some(Ok(window))
+    }
+}
+
+/// Iterator over equally long channels in aligned chunks
+///
+/// Returned by [`TdmsReader::iter_channels_zipped`]. Each item holds one chunk
+/// per channel, in the order the channels were given, all covering the same
+/// value indexes.
+pub struct TdmsZipIter<'a, T, R: Read + Seek> {
+    reader: &'a mut TdmsReader<R>,
+    trackers: Vec<StreamingReader>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T, R: Read + Seek> TdmsZipIter<'a, T, R> {
+    pub fn new(reader: &'a mut TdmsReader<R>, channels: Vec<ChannelReader>, chunk_size: usize) -> Self {
+        Self {
+            reader,
+            trackers: channels.into_iter().map(|channel| StreamingReader::new(channel, chunk_size)).collect(),
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Get current progress percentage
+    pub fn progress(&self) -> f64 {
+        self.trackers.first().map_or(100.0, StreamingReader::progress_percent)
+    }
+}
+
+impl<'a, T: TdmsValue, R: Read + Seek> Iterator for TdmsZipIter<'a, T, R> {
+    type Item = Result<Vec<Vec<T>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.trackers.first()?.has_more() {
+            return None;
+        }
+        let mut chunks = Vec::with_capacity(self.trackers.len());
+        for tracker in &mut self.trackers {
+            match self.reader.read_streaming_data(tracker) {
+                Ok(chunk) => chunks.push(chunk.unwrap_or_default()),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(chunks))
+    }
+}
+
+/// Iterator over a channel's numeric data in chunks that owns its reader
+///
+/// Returned by [`TdmsReader::into_iter_channel_data`]. Unlike [`TdmsIter`] it
+/// doesn't borrow anything, so it can be returned from functions, stored in
+/// structs or sent to another thread. [`into_reader`](Self::into_reader) gives
+/// the reader back.
+pub struct OwnedTdmsIter<T, R: Read + Seek> {
+    reader: TdmsReader<R>,
+    tracker: StreamingReader,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, R: Read + Seek> OwnedTdmsIter<T, R> {
+    pub fn new(reader: TdmsReader<R>, channel: ChannelReader, chunk_size: usize) -> Self {
+        Self {
+            reader,
+            tracker: StreamingReader::new(channel, chunk_size),
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Get current progress percentage
+    pub fn progress(&self) -> f64 {
+        self.tracker.progress_percent()
+    }
+    
+    /// Stop iterating and take the reader back
+    pub fn into_reader(self) -> TdmsReader<R> {
+        self.reader
+    }
+}
+
+impl<T: TdmsValue, R: Read + Seek> Iterator for OwnedTdmsIter<T, R> {
+    type Item = Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_streaming_data(&mut self.tracker).transpose()
+    }
+}
+
+/// Iterator over a channel's string data in chunks that owns its reader
+///
+/// Returned by [`TdmsReader::into_iter_channel_strings`]; see [`OwnedTdmsIter`].
+pub struct OwnedTdmsStringIter<R: Read + Seek> {
+    reader: TdmsReader<R>,
+    tracker: StreamingReader,
+}
+
+impl<R: Read + Seek> OwnedTdmsStringIter<R> {
+    pub fn new(reader: TdmsReader<R>, channel: ChannelReader, chunk_size: usize) -> Self {
+        Self {
+            reader,
+            tracker: StreamingReader::new(channel, chunk_size),
+        }
+    }
+    
+    /// Get current progress percentage
+    pub fn progress(&self) -> f64 {
+        self.tracker.progress_percent()
+    }
+    
+    /// Stop iterating and take the reader back
+    pub fn into_reader(self) -> TdmsReader<R> {
+        self.reader
+    }
+}
+
+impl<R: Read + Seek> Iterator for OwnedTdmsStringIter<R> {
+    type Item = Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_streaming_strings(&mut self.tracker).transpose()
     }
 }
 
@@ -1,26 +1,271 @@
 // src/reader/sync_reader.rs
 use crate::error::{TdmsError, Result};
-use crate::types::{DataType, TocFlags, Property, PropertyValue}; 
-use crate::segment::{SegmentHeader, SegmentInfo};
-use crate::reader::channel_reader::{ChannelReader, SegmentData, ChannelInfo};
-use crate::reader::streaming::{TdmsIter, TdmsStringIter, StreamingReader}; // <-- Added StreamingReader
-use crate::metadata::ObjectPath;
+use crate::types::{DataType, F80, TocFlags, Property, PropertyValue, NumericValue, TdmsValue, Timestamp, TimestampConvention}; 
+use crate::segment::{self, SegmentHeader, SegmentInfo, SegmentTimeRange};
+use crate::reader::channel_reader::{ChannelReader, ConversionStats, SegmentData, ChannelInfo};
+use crate::reader::index_stats::{self, IndexStats, ChannelIndexStats};
+use crate::reader::memory;
+use crate::reader::segment_list::SpillFile;
+use crate::reader::daqmx::{self, DaqmxInfo, DaqmxScaler, DaqmxScalerKind};
+use crate::reader::streaming::{TdmsIter, TdmsStringIter, TdmsWindowIter, TdmsZipIter, OwnedTdmsIter, OwnedTdmsStringIter, StreamingReader}; // <-- Added StreamingReader
+use crate::metadata::{ObjectPath, ChannelId, GroupTree};
+use crate::properties::{alignment, checkpoint, events, labview, waveform, wellknown, ArrayColumn, Checkpoint, Event, ExperimentTimestamps, FlexLoggerFile, FlexLoggerChannel, SampleAccounting, Scaling, WaveformTiming};
 use crate::raw_data::RawDataReader;
+use crate::codec::{self, ChannelEncoding};
+use crate::utils::StringEncoding;
+use crate::storage::{StorageCursor, TdmsStorage};
+#[cfg(feature = "encryption")]
+use crate::storage::{EncryptedStorage, KeyProvider};
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom, BufReader};
-use std::path::Path;
-use std::collections::HashMap;
+use std::io::{Read, Write, Seek, SeekFrom, BufReader};
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
 use byteorder::{ReadBytesExt, LittleEndian, BigEndian};
 
 #[cfg(feature = "mmap")]
 use memmap2::Mmap;
+#[cfg(all(feature = "mmap", unix))]
+use memmap2::Advice;
 #[cfg(feature = "mmap")]
 use std::io::Cursor;
 
+/// Access-pattern hints passed to the kernel for memory-mapped reading
+/// (see [`TdmsReader::open_mmap_with`]).
+/// 
+/// Hints only change how pages are read from disk, never what is read. They
+/// help most on large files that are not yet in the page cache. On platforms
+/// without `madvise` they are ignored.
+#[cfg(feature = "mmap")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MmapHints {
+    /// Advise `MADV_SEQUENTIAL` for the whole file: aggressive read-ahead, and
+    /// pages behind the read position may be dropped early
+    pub sequential: bool,
+    /// Advise `MADV_WILLNEED` for the byte ranges of each channel read before
+    /// reading them, and for the chunk after the current one when streaming
+    pub will_need: bool,
+}
+
+/// Receives the `(offset, length)` byte ranges a read is about to touch
+type PrefetchHook<R> = fn(&R, &[(u64, u64)]);
+
 /// Trait alias for Read + Seek
 pub trait ReadSeek: Read + Seek {}
 impl<T: Read + Seek> ReadSeek for T {}
 
+/// A problem the reader worked around while parsing a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Index of the affected segment
+    pub segment: usize,
+    /// Byte offset of the segment in the file
+    pub offset: u64,
+    pub message: String,
+}
+
+/// A property the reader couldn't decode, kept as the bytes of its value
+/// (see [`TdmsReader::raw_properties`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawProperty {
+    pub path: ObjectPath,
+    pub name: String,
+    /// The type the file declares for the value
+    pub data_type: DataType,
+    /// The value as stored, in the segment's byte order; for strings, the text
+    /// without its length prefix
+    pub bytes: Vec<u8>,
+    pub is_big_endian: bool,
+    /// Index of the segment holding the property
+    pub segment: usize,
+}
+
+/// What to do with a property whose data type this crate can't decode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownPropertyPolicy {
+    /// Fail to open the file
+    #[default]
+    Error,
+    /// Skip the property and record a [`ParseWarning`]. The value's bytes are
+    /// kept as a [`RawProperty`], as are those of strings that aren't valid in
+    /// the file's string encoding.
+    /// 
+    /// Properties carry no length, so only types with a known size (e.g.
+    /// complex numbers) can be stepped over. An unknown type code ends the
+    /// segment's metadata: the rest of it and the segment's raw data are
+    /// skipped, as are later segments that reuse its object list.
+    Skip,
+}
+
+/// What to do with a final segment that is still being written (see
+/// [`TdmsReader::incomplete_segment`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IncompleteSegmentPolicy {
+    /// Read the whole chunks of raw data the segment has so far. Opening the
+    /// same live file twice can give different amounts of data.
+    #[default]
+    Include,
+    /// Leave the segment out, so only committed data is read
+    Ignore,
+    /// Fail to open the file with [`TdmsError::IncompleteSegment`]
+    Error,
+}
+
+/// Options for [`TdmsReader::open_with_options`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReaderOptions {
+    pub unknown_properties: UnknownPropertyPolicy,
+    /// Decode strings with this encoding instead of the one the file declares
+    /// in [`StringEncoding::PROPERTY`], e.g. for files from a Windows machine
+    /// that don't declare theirs. `None` uses the declared encoding, or UTF-8.
+    pub string_encoding: Option<StringEncoding>,
+    /// Keep channels' segment indexes in a temporary file in this directory
+    /// instead of in memory.
+    ///
+    /// Each channel normally holds an entry for every segment it has data in,
+    /// which for files with millions of segments and many channels can exceed
+    /// the available memory before the file is even open. With a spill
+    /// directory, entries move to disk in blocks as they are parsed, so only
+    /// the file's segment table and the newest block of each channel stay in
+    /// memory. Opening and reading get slower; listing channels and
+    /// streaming still work. The file is deleted by the operating system
+    /// once the reader is gone.
+    pub spill_dir: Option<PathBuf>,
+    /// Keep channels' segment indexes as a list of shared (value count, size,
+    /// offset, stride) shapes with two `u32`s per segment, rather than a full
+    /// entry per segment.
+    ///
+    /// Files written as many small appends repeat the same shape in every
+    /// segment, so this cuts the per-segment cost of a channel from 48 bytes
+    /// to 8. [`ChannelReader::get_segment_data`] has no entries to refer to
+    /// then; [`ChannelReader::segment`] works either way.
+    #[cfg(feature = "compact-index")]
+    pub compact_index: bool,
+    /// What to do with a final segment that a writer hasn't finished
+    pub allow_incomplete: IncompleteSegmentPolicy,
+}
+
+/// How a `.tdms_index` file compares with the data file a reader was opened on
+/// (see [`TdmsReader::check_index_file`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexFileStatus {
+    /// There is no index file
+    Missing,
+    /// Every lead-in and metadata block matches the data file
+    Consistent,
+    /// The index file is stale or damaged from `segment` on, e.g. because the
+    /// writer crashed between updating the data and index files
+    Mismatch { segment: usize, reason: String },
+}
+
+/// Result of [`TdmsReader::check_metadata_crc`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataCrcStatus {
+    /// There is no checksum sidecar
+    Missing,
+    /// Every segment's metadata matches its recorded checksum
+    Consistent,
+    /// The metadata of `segment` doesn't match the sidecar, or has no
+    /// checksum there
+    Mismatch { segment: usize, reason: String },
+}
+
+/// The ends of a channel, from [`TdmsReader::preview`] and
+/// [`TdmsReader::preview_strings`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelPreview<T> {
+    /// The channel's data type as read back (the original type for encoded channels)
+    pub data_type: DataType,
+    /// Number of values in the whole channel
+    pub count: u64,
+    /// Up to `n` values from the start of the channel
+    pub first: Vec<T>,
+    /// Up to `n` values from the end of the channel, not repeating any of
+    /// `first`: for channels of `2 * n` values or fewer, `first` followed by
+    /// `last` is the whole channel
+    pub last: Vec<T>,
+}
+
+impl<T> ChannelPreview<T> {
+    /// Index of the first value of `last`
+    pub fn last_start(&self) -> u64 {
+        self.count - self.last.len() as u64
+    }
+}
+
+/// What a file records about the software that wrote it (see
+/// [`TdmsReader::writer_info`])
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriterInfo {
+    /// The `library` file property
+    pub library: Option<String>,
+    /// The `library_version` file property
+    pub library_version: Option<String>,
+    /// Distinct lead-in version numbers, in the order they first appear
+    pub format_versions: Vec<u32>,
+}
+
+/// What a UI needs to present a channel, in one lookup (see
+/// [`TdmsReader::channel_info`])
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelSummary {
+    /// The channel's data type as read back (the original type for encoded channels)
+    pub data_type: DataType,
+    /// Number of values, or `None` for encoded channels, whose length is only
+    /// known once they are decoded
+    pub length: Option<u64>,
+    /// The encoding of a channel written with the encoded-channel extension
+    pub encoding: Option<ChannelEncoding>,
+    /// The `unit_string` property, or else LabVIEW's `NI_UnitDescription`
+    pub unit: Option<String>,
+    /// The `NI_Scale[n]` chain; [`Scaling::describe`] gives a summary
+    pub scaling: Option<Scaling>,
+    pub waveform: Option<WaveformTiming>,
+    /// Raw data layout of a DAQmx channel, `None` for ordinary channels
+    pub daqmx: Option<DaqmxInfo>,
+    /// How the channel's values are spread over segments
+    pub fragmentation: ChannelIndexStats,
+}
+
+/// Every property in a file, by object (see [`TdmsReader::all_properties`])
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllProperties<'a> {
+    pub file: &'a HashMap<String, Property>,
+    /// Groups by name, including groups only known from their channels
+    pub groups: BTreeMap<&'a str, GroupProperties<'a>>,
+}
+
+/// A group's properties and those of its channels
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupProperties<'a> {
+    pub properties: &'a HashMap<String, Property>,
+    /// Channel properties by channel name
+    pub channels: BTreeMap<&'a str, &'a HashMap<String, Property>>,
+}
+
+/// How far parsing a segment's metadata got
+enum MetadataOutcome {
+    Complete,
+    /// A channel used a DAQmx raw data index variant with an unknown layout;
+    /// nothing after it could be parsed
+    UnknownDaqmxIndex { path: ObjectPath, header: u32 },
+    /// A property had a type code that isn't a TDMS data type, so its size and
+    /// everything after it are unknown
+    UnknownPropertyType { path: ObjectPath, name: String, type_code: u32 },
+}
+
+/// Result of reading one property
+enum PropertyOutcome {
+    Read(Property),
+    /// The property's type can't be decoded but its bytes were skipped
+    Skipped,
+    /// The property's type code is unknown; the metadata can't be read further
+    Unparseable { name: String, type_code: u32 },
+}
+
 /// Synchronous TDMS file reader
 /// 
 /// Provides efficient reading of TDMS files with support for:
@@ -28,6 +273,12 @@ impl<T: Read + Seek> ReadSeek for T {}
 /// - Channel discovery and listing
 /// - Efficient data access through ChannelReader
 /// 
+/// # Thread safety
+/// 
+/// `TdmsReader<R>` is `Send` and `Sync` whenever `R` is, which holds for both
+/// the buffered-file and memory-mapped readers. Reads take `&mut self`, so share
+/// one reader between threads behind a mutex, or use a [`ReaderPool`](crate::reader::ReaderPool).
+/// 
 /// This reader is generic over its I/O source (`R: ReadSeek`).
 /// Use `TdmsReader::open(path)` for standard buffered file reading.
 /// Use `TdmsReader::open_mmap(path)` (with "mmap" feature) for memory-mapped reading.
@@ -61,11 +312,33 @@ pub struct TdmsReader<R: ReadSeek> {
     pub(crate) file: R,
     pub(crate) segments: Vec<SegmentInfo>,
     channels: HashMap<ObjectPath, ChannelInfo>,
+    // Channels in the order they first appeared, indexed by `ChannelId`
+    channel_ids: Vec<ObjectPath>,
     string_buffer: Vec<u8>,
     
     // Storage for file and group properties
     pub file_properties: HashMap<String, Property>,
     pub groups: HashMap<String, HashMap<String, Property>>,
+    
+    timestamp_convention: TimestampConvention,
+    // Every value written for each property, keyed by object and property name
+    property_history: HashMap<ObjectPath, HashMap<String, Vec<(usize, PropertyValue)>>>,
+    warnings: Vec<ParseWarning>,
+    raw_properties: Vec<RawProperty>,
+    // Conversion work done by reads so far, keyed by channel path string
+    conversion_stats: HashMap<String, ConversionStats>,
+    // Called with the byte ranges a read is about to touch; only set for
+    // memory-mapped readers with `MmapHints::will_need`
+    prefetch: Option<PrefetchHook<R>>,
+    options: ReaderOptions,
+    // Encoding of strings in the file, declared or from the options
+    string_encoding: StringEncoding,
+    // Where segment indexes go when `ReaderOptions::spill_dir` is set
+    spill: Option<Arc<SpillFile>>,
+    // Offset of a final segment that was still being written
+    incomplete_segment: Option<u64>,
+    // Whether the metadata came from the `.tdms_index` file
+    from_index: bool,
 }
 
 /// Constructor for standard file I/O
@@ -83,16 +356,129 @@ impl TdmsReader<BufReader<File>> {
     /// 
     /// A TdmsReader ready to read data from the file
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_options(path, ReaderOptions::default())
+    }
+
+    /// Rough number of bytes [`open`](Self::open) would keep in memory for
+    /// the file at `path`, judged from its segment lead-ins alone
+    /// 
+    /// Meant for scheduling work on suitably sized workers; expect it to be
+    /// off by a small factor, usually on the high side. Reading channel data
+    /// comes on top (see [`estimate_read_memory`](Self::estimate_read_memory)).
+    pub fn estimate_open_memory(path: impl AsRef<Path>) -> Result<u64> {
+        memory::estimate_open(path.as_ref())
+    }
+    
+    /// Open a TDMS file for reading with non-default parsing options
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::reader::{ReaderOptions, TdmsReader, UnknownPropertyPolicy};
+    /// 
+    /// let options = ReaderOptions { unknown_properties: UnknownPropertyPolicy::Skip, ..Default::default() };
+    /// let reader = TdmsReader::open_with_options("exotic.tdms", options).unwrap();
+    /// for warning in reader.warnings() {
+    ///     println!("segment {}: {}", warning.segment, warning.message);
+    /// }
+    /// ```
+    pub fn open_with_options(path: impl AsRef<Path>, options: ReaderOptions) -> Result<Self> {
         let file = File::open(path)?;
-        let mut reader = TdmsReader {
-            file: BufReader::with_capacity(65536, file),
-            segments: Vec::new(),
-            channels: HashMap::new(),
-            string_buffer: Vec::with_capacity(256),
-            file_properties: HashMap::new(),
-            groups: HashMap::new(),
+        let mut reader = TdmsReader::unparsed(BufReader::with_capacity(65536, file), options)?;
+        reader.parse_file()?;
+        Ok(reader)
+    }
+    
+    /// Open a TDMS file, parsing the metadata from its index file
+    /// (see [`find_index_file`](crate::find_index_file)) instead of walking
+    /// every segment of the data file
+    /// 
+    /// The index holds the same lead-ins and metadata without the raw data,
+    /// so for large files with many segments this reads a small fraction of
+    /// the bytes [`open`](Self::open) does. The index is trusted if its
+    /// segments add up to exactly the data file's length and the last of them
+    /// matches the data file's lead-in; otherwise, or if there is no index,
+    /// the data file is parsed as usual. Use
+    /// [`opened_from_index`](Self::opened_from_index) to tell which happened,
+    /// and [`check_index_file`](Self::check_index_file) for a full comparison.
+    pub fn open_indexed(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_indexed_with_options(path, ReaderOptions::default())
+    }
+    
+    /// [`open_indexed`](Self::open_indexed) with non-default parsing options
+    pub fn open_indexed_with_options(path: impl AsRef<Path>, options: ReaderOptions) -> Result<Self> {
+        let path = path.as_ref();
+        match Self::parse_index(path, options.clone()) {
+            Ok(Some(reader)) => Ok(reader),
+            // An unreadable index is no worse than a missing one
+            Ok(None) | Err(_) => Self::open_with_options(path, options),
+        }
+    }
+    
+    /// A reader for the data file at `path` with its metadata parsed from the
+    /// index file, or `None` if there is no index or it doesn't fit the data
+    fn parse_index(path: &Path, options: ReaderOptions) -> Result<Option<Self>> {
+        let Some(index_path) = segment::find_index_file(path) else {
+            return Ok(None);
+        };
+        let index = File::open(index_path)?;
+        let mut data = File::open(path)?;
+        let data_size = data.metadata()?.len();
+        
+        let mut reader = TdmsReader::unparsed(BufReader::with_capacity(65536, index), options)?;
+        let Some(data_offsets) = reader.discover_index_segments(data_size)? else {
+            return Ok(None);
         };
         
+        // Only the last lead-in is compared: an index that falls behind the
+        // data file is caught by the total length
+        if let (Some(last), Some(&data_offset)) = (reader.segments.last(), data_offsets.last()) {
+            let mut index_lead_in = [0u8; SegmentHeader::LEAD_IN_SIZE];
+            reader.file.seek(SeekFrom::Start(last.offset))?;
+            reader.file.read_exact(&mut index_lead_in)?;
+            let mut data_lead_in = [0u8; SegmentHeader::LEAD_IN_SIZE];
+            data.seek(SeekFrom::Start(data_offset))?;
+            data.read_exact(&mut data_lead_in)?;
+            if &data_lead_in[..4] != SegmentHeader::TDMS_TAG || data_lead_in[4..] != index_lead_in[4..] {
+                return Ok(None);
+            }
+        }
+        
+        reader.parse_metadata()?;
+        for (segment, &offset) in reader.segments.iter_mut().zip(&data_offsets) {
+            segment.offset = offset;
+        }
+        for warning in &mut reader.warnings {
+            warning.offset = data_offsets[warning.segment];
+        }
+        reader.file = BufReader::with_capacity(65536, data);
+        reader.from_index = true;
+        Ok(Some(reader))
+    }
+}
+
+/// Constructor for encrypted files (requires the "encryption" feature)
+#[cfg(feature = "encryption")]
+impl TdmsReader<BufReader<StorageCursor<EncryptedStorage<File>>>> {
+    /// Open a file written by [`TdmsWriter::create_encrypted`](crate::TdmsWriter::create_encrypted),
+    /// fetching the key its header names from `keys`
+    pub fn open_encrypted(path: impl AsRef<Path>, keys: &impl KeyProvider) -> Result<Self> {
+        Self::from_storage(EncryptedStorage::open(File::open(path)?, keys)?)
+    }
+}
+
+/// Constructor for other storage backends
+impl<S: TdmsStorage> TdmsReader<BufReader<StorageCursor<S>>> {
+    /// Open TDMS data held in any [`TdmsStorage`], e.g. an in-memory buffer
+    /// or an object store client
+    pub fn from_storage(storage: S) -> Result<Self> {
+        Self::from_storage_with_options(storage, ReaderOptions::default())
+    }
+    
+    /// [`from_storage`](Self::from_storage) with non-default parsing options
+    pub fn from_storage_with_options(storage: S, options: ReaderOptions) -> Result<Self> {
+        let file = BufReader::with_capacity(65536, StorageCursor::new(storage));
+        let mut reader = TdmsReader::unparsed(file, options)?;
         reader.parse_file()?;
         Ok(reader)
     }
@@ -114,27 +500,107 @@ impl TdmsReader<Cursor<Mmap>> {
     ///
     /// A TdmsReader ready to read data from the memory-mapped file
     pub fn open_mmap(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_mmap_with(path, MmapHints::default())
+    }
+
+    /// Open a TDMS file using memory-mapping, with access-pattern hints
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tdms_rs::reader::{MmapHints, TdmsReader};
+    ///
+    /// let hints = MmapHints { sequential: true, will_need: true };
+    /// let mut reader = TdmsReader::open_mmap_with("data.tdms", hints).unwrap();
+    /// let data: Vec<f64> = reader.read_channel_data("Group1", "Channel1").unwrap();
+    /// ```
+    pub fn open_mmap_with(path: impl AsRef<Path>, hints: MmapHints) -> Result<Self> {
         let file = File::open(path)?;
         let mmap = unsafe { Mmap::map(&file)? };
+        #[cfg(unix)]
+        if hints.sequential && !mmap.is_empty() {
+            // Advice is best effort; a refusal doesn't affect correctness
+            let _ = mmap.advise(Advice::Sequential);
+        }
         let cursor = Cursor::new(mmap); // Cursor takes ownership of Mmap
         
-        let mut reader = TdmsReader {
-            file: cursor,
-            segments: Vec::new(),
-            channels: HashMap::new(),
-            string_buffer: Vec::with_capacity(256),
-            file_properties: HashMap::new(),
-            groups: HashMap::new(),
-        };
+        let mut reader = TdmsReader::unparsed(cursor, ReaderOptions::default())?;
+        if hints.will_need {
+            reader.prefetch = Some(advise_will_need);
+        }
         
         reader.parse_file()?;
         Ok(reader)
     }
+    
+    /// Change the hints used for later reads.
+    /// 
+    /// Only [`MmapHints::will_need`] takes effect; `sequential` applies to the
+    /// whole mapping and is only issued when opening.
+    pub fn set_mmap_hints(&mut self, hints: MmapHints) {
+        self.prefetch = if hints.will_need { Some(advise_will_need) } else { None };
+    }
+}
+
+/// A string property's value, treating an empty string as missing
+fn string_property<'a>(properties: &'a HashMap<String, Property>, name: &str) -> Option<&'a str> {
+    properties.get(name).and_then(|p| p.value.as_str()).filter(|s| !s.is_empty())
+}
+
+#[cfg(feature = "mmap")]
+fn advise_will_need(file: &Cursor<Mmap>, ranges: &[(u64, u64)]) {
+    #[cfg(unix)]
+    for &(offset, length) in ranges {
+        let mmap = file.get_ref();
+        let (Ok(offset), Ok(length)) = (usize::try_from(offset), usize::try_from(length)) else { continue };
+        if length > 0 && offset.saturating_add(length) <= mmap.len() {
+            let _ = mmap.advise_range(Advice::WillNeed, offset, length);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (file, ranges);
 }
 
 /// Generic implementation for all TdmsReader variants
 impl<R: ReadSeek> TdmsReader<R> {
     
+    /// Whether channels keep their segment entries in a [`SegmentList`](super::segment_list::SegmentList)
+    fn keeps_segment_list(&self) -> bool {
+        #[cfg(feature = "compact-index")]
+        if self.options.compact_index {
+            return true;
+        }
+        self.spill.is_some()
+    }
+
+    /// A reader over `file` that hasn't looked at it yet
+    fn unparsed(file: R, options: ReaderOptions) -> Result<Self> {
+        let spill = match &options.spill_dir {
+            Some(dir) => Some(Arc::new(SpillFile::create(dir)?)),
+            None => None,
+        };
+        Ok(TdmsReader {
+            file,
+            segments: Vec::new(),
+            channels: HashMap::new(),
+            channel_ids: Vec::new(),
+            string_buffer: Vec::with_capacity(256),
+            file_properties: HashMap::new(),
+            groups: HashMap::new(),
+            timestamp_convention: TimestampConvention::default(),
+            property_history: HashMap::new(),
+            warnings: Vec::new(),
+            raw_properties: Vec::new(),
+            conversion_stats: HashMap::new(),
+            prefetch: None,
+            string_encoding: options.string_encoding.unwrap_or_default(),
+            options,
+            spill,
+            incomplete_segment: None,
+            from_index: false,
+        })
+    }
+    
     /// Parse the entire file structure
     fn parse_file(&mut self) -> Result<()> {
         // First pass: discover all segments
@@ -175,41 +641,58 @@ impl<R: ReadSeek> TdmsReader<R> {
             let toc_raw = self.file.read_u32::<LittleEndian>()?;
             let toc = TocFlags::new(toc_raw);
             
-            let _version = self.file.read_u32::<LittleEndian>()?;
+            // The rest of the lead-in follows the segment's byte order
+            let is_big_endian = toc.is_big_endian();
+            let version = self.read_u32(is_big_endian)?;
             // Per spec: "length of the remaining segment (overall length ... minus length of the lead in)"
-            let next_segment_offset = self.file.read_u64::<LittleEndian>()?;
+            let next_segment_offset = self.read_u64(is_big_endian)?;
             // Per spec: "overall length of the meta information"
-            let metadata_size = self.file.read_u64::<LittleEndian>()?;
+            let metadata_size = self.read_u64(is_big_endian)?;
             
-            let total_raw_data_size = if next_segment_offset == SegmentHeader::INCOMPLETE_MARKER {
-                // This can only happen to the last segment
-                // We must calculate its size from the file size
-                let segment_data_start = segment_offset + SegmentHeader::LEAD_IN_SIZE as u64;
-                file_size.saturating_sub(segment_data_start).saturating_sub(metadata_size)
+            // A segment still being written either carries the incomplete
+            // marker or claims more bytes than the file has so far
+            let segment_data_start = segment_offset + SegmentHeader::LEAD_IN_SIZE as u64;
+            let available = file_size - segment_data_start;
+            let incomplete = next_segment_offset == SegmentHeader::INCOMPLETE_MARKER
+                || next_segment_offset > available;
+            if incomplete {
+                self.incomplete_segment = Some(segment_offset);
+                match self.options.allow_incomplete {
+                    IncompleteSegmentPolicy::Error => return Err(TdmsError::IncompleteSegment(segment_offset)),
+                    IncompleteSegmentPolicy::Ignore => break,
+                    // Without all of its metadata there's nothing to include
+                    IncompleteSegmentPolicy::Include if metadata_size > available => break,
+                    IncompleteSegmentPolicy::Include => {}
+                }
+            }
+
+            // Only whole chunks of an incomplete segment's raw data get read
+            let total_raw_data_size = if incomplete {
+                available - metadata_size
             } else {
-                // This is the normal case
                 next_segment_offset.saturating_sub(metadata_size)
             };
             
             let segment_info = SegmentInfo {
                 offset: segment_offset,
                 toc,
-                is_big_endian: toc.is_big_endian(),
+                is_big_endian,
+                version,
                 metadata_size,
                 total_raw_data_size,
             };
             
             self.segments.push(segment_info);
             
-            // Check for incomplete segment
-            if next_segment_offset == SegmentHeader::INCOMPLETE_MARKER {
+            // An incomplete segment is always the last one
+            if incomplete {
                 break;
             }
             
             // Calculate next segment position
-            let next_pos = segment_offset + SegmentHeader::LEAD_IN_SIZE as u64 + next_segment_offset;
+            let next_pos = segment_data_start + next_segment_offset;
             
-            if next_pos > file_size || next_pos <= segment_offset {
+            if next_pos <= segment_offset {
                 break;
             }
             
@@ -219,10 +702,67 @@ impl<R: ReadSeek> TdmsReader<R> {
         Ok(())
     }
     
+    /// Discover the segments of an index file, returning where each one's
+    /// lead-in is in the data file, or `None` if the index doesn't describe
+    /// a complete data file of `data_size` bytes
+    /// 
+    /// Segment offsets are left pointing into the index, for
+    /// [`parse_metadata`](Self::parse_metadata) to read from.
+    fn discover_index_segments(&mut self, data_size: u64) -> Result<Option<Vec<u64>>> {
+        let index_size = self.file.seek(SeekFrom::End(0))?;
+        let mut index_offset = 0u64;
+        let mut data_offset = 0u64;
+        let mut data_offsets = Vec::new();
+        
+        while index_offset < index_size {
+            if index_size - index_offset < SegmentHeader::LEAD_IN_SIZE as u64 {
+                return Ok(None);
+            }
+            self.file.seek(SeekFrom::Start(index_offset))?;
+            let mut tag = [0u8; 4];
+            self.file.read_exact(&mut tag)?;
+            if &tag != SegmentHeader::INDEX_TAG {
+                return Ok(None);
+            }
+            let toc = TocFlags::new(self.file.read_u32::<LittleEndian>()?);
+            let is_big_endian = toc.is_big_endian();
+            let version = self.read_u32(is_big_endian)?;
+            let next_segment_offset = self.read_u64(is_big_endian)?;
+            let metadata_size = self.read_u64(is_big_endian)?;
+            
+            // An unfinished segment is left to the data file's own policy
+            let index_remaining = index_size - index_offset - SegmentHeader::LEAD_IN_SIZE as u64;
+            let data_remaining = data_size.saturating_sub(data_offset + SegmentHeader::LEAD_IN_SIZE as u64);
+            if next_segment_offset == SegmentHeader::INCOMPLETE_MARKER
+                || next_segment_offset > data_remaining
+                || metadata_size > index_remaining
+                || metadata_size > next_segment_offset
+            {
+                return Ok(None);
+            }
+            
+            self.segments.push(SegmentInfo {
+                offset: index_offset,
+                toc,
+                is_big_endian,
+                version,
+                metadata_size,
+                total_raw_data_size: next_segment_offset - metadata_size,
+            });
+            data_offsets.push(data_offset);
+            index_offset += SegmentHeader::LEAD_IN_SIZE as u64 + metadata_size;
+            data_offset += SegmentHeader::LEAD_IN_SIZE as u64 + next_segment_offset;
+        }
+        
+        Ok((data_offset == data_size).then_some(data_offsets))
+    }
+    
     /// Parse metadata from all segments and build channel information
     fn parse_metadata(&mut self) -> Result<()> {
         let mut active_channels: Vec<ObjectPath> = Vec::new();
         let mut new_segment_indices: HashMap<ObjectPath, (u64, u64)> = HashMap::new();
+        // Set after a segment whose raw data layout couldn't be parsed
+        let mut layout_unknown = false;
 
         let segments: Vec<SegmentInfo> = self.segments.clone();
         for (segment_idx, segment) in segments.iter().enumerate() {
@@ -234,11 +774,47 @@ impl<R: ReadSeek> TdmsReader<R> {
                 let metadata_start = segment.offset + SegmentHeader::LEAD_IN_SIZE as u64;
                 self.file.seek(SeekFrom::Start(metadata_start))?;
 
-                self.parse_segment_metadata(
+                let outcome = self.parse_segment_metadata(
                     segment,
+                    segment_idx,
                     &mut segment_channels,
                     &mut new_segment_indices,
                 )?;
+
+                let message = match outcome {
+                    MetadataOutcome::Complete => None,
+                    MetadataOutcome::UnknownDaqmxIndex { path, header } => Some(format!(
+                        "{} uses unsupported DAQmx raw data index {:#010x}; segment data skipped",
+                        path, header
+                    )),
+                    MetadataOutcome::UnknownPropertyType { path, name, type_code } => Some(format!(
+                        "property '{}' of {} has unknown data type {:#x}; rest of segment skipped",
+                        name, path, type_code
+                    )),
+                };
+                if let Some(message) = message {
+                    // The raw data layout is unknown until a segment starts a new
+                    // object list, so raw data up to then is skipped
+                    self.warnings.push(ParseWarning { segment: segment_idx, offset: segment.offset, message });
+                    active_channels.clear();
+                    layout_unknown = true;
+                    continue;
+                }
+            }
+
+            if layout_unknown {
+                if segment.toc.has_new_obj_list() {
+                    layout_unknown = false;
+                } else {
+                    if segment.toc.has_raw_data() {
+                        self.warnings.push(ParseWarning {
+                            segment: segment_idx,
+                            offset: segment.offset,
+                            message: "raw data layout inherited from a segment whose metadata couldn't be parsed; segment data skipped".into(),
+                        });
+                    }
+                    continue;
+                }
             }
 
             let channels_for_this_segment = if segment.toc.has_new_obj_list() {
@@ -273,9 +849,10 @@ impl<R: ReadSeek> TdmsReader<R> {
     fn parse_segment_metadata(
         &mut self,
         segment: &SegmentInfo,
+        segment_idx: usize,
         segment_channels: &mut Vec<ObjectPath>,
         new_segment_indices: &mut HashMap<ObjectPath, (u64, u64)>,
-    ) -> Result<()> {
+    ) -> Result<MetadataOutcome> {
         let is_big_endian = segment.is_big_endian;
         
         let object_count = self.read_u32(is_big_endian)?;
@@ -292,11 +869,19 @@ impl<R: ReadSeek> TdmsReader<R> {
                 let matches_previous = raw_index_length == 0x00000000;
                 
                 let mut parsed_index: Option<(DataType, u64, u64)> = None;
+                let mut parsed_daqmx: Option<DaqmxInfo> = None;
 
-                if has_data && !matches_previous {
+                if daqmx::is_unknown_header(raw_index_length) {
+                    return Ok(MetadataOutcome::UnknownDaqmxIndex { path, header: raw_index_length });
+                } else if raw_index_length == daqmx::FORMAT_CHANGING_SCALER || raw_index_length == daqmx::DIGITAL_LINE_SCALER {
+                    let (number_of_values, info) = self.read_daqmx_index(raw_index_length, is_big_endian)?;
+                    let total_size = number_of_values * info.record_width();
+                    parsed_index = Some((DataType::DAQmxRawData, number_of_values, total_size));
+                    parsed_daqmx = Some(info);
+                } else if has_data && !matches_previous {
                     let data_type_raw = self.read_u32(is_big_endian)?;
                     let data_type = DataType::from_u32(data_type_raw)
-                        .ok_or_else(|| TdmsError::InvalidDataType(data_type_raw))?;
+                        .ok_or(TdmsError::InvalidDataType(data_type_raw))?;
                     let _dimension = self.read_u32(is_big_endian)?;
                     let number_of_values = self.read_u64(is_big_endian)?;
                     let total_size = if data_type == DataType::String {
@@ -310,14 +895,31 @@ impl<R: ReadSeek> TdmsReader<R> {
                 let property_count = self.read_u32(is_big_endian)?;
                 let mut local_properties = HashMap::with_capacity(property_count as usize);
                 for _ in 0..property_count {
-                    let prop = self.read_property(is_big_endian)?;
+                    let prop = match self.read_property(segment, segment_idx, &path)? {
+                        PropertyOutcome::Read(prop) => prop,
+                        PropertyOutcome::Skipped => continue,
+                        PropertyOutcome::Unparseable { name, type_code } => {
+                            return Ok(MetadataOutcome::UnknownPropertyType { path, name, type_code });
+                        }
+                    };
+                    self.record_property_history(&path, segment_idx, &prop);
                     local_properties.insert(prop.name.clone(), prop);
                 }
                 
+                if !self.channels.contains_key(&path) {
+                    self.channel_ids.push(path.clone());
+                }
+                let segment_list = self.keeps_segment_list();
                 let channel_info = self.channels.entry(path.clone())
-                    .or_insert_with(|| ChannelInfo::new(DataType::Void));
+                    .or_insert_with(|| {
+                        let info = ChannelInfo::new(DataType::Void);
+                        if segment_list { info.with_segment_list() } else { info }
+                    });
                 
                 channel_info.properties.extend(local_properties);
+                if parsed_daqmx.is_some() {
+                    channel_info.daqmx = parsed_daqmx;
+                }
 
                 if let Some((data_type, number_of_values, total_size)) = parsed_index {
                     channel_info.data_type = data_type;
@@ -326,7 +928,7 @@ impl<R: ReadSeek> TdmsReader<R> {
                         segment_channels.push(path.clone());
                     }
                 } else if matches_previous {
-                    if let Some(last_segment) = channel_info.segments.last() {
+                    if let Some(last_segment) = channel_info.last_segment()? {
                         new_segment_indices.insert(
                             path.clone(),
                             (last_segment.value_count, last_segment.byte_size)
@@ -342,14 +944,24 @@ impl<R: ReadSeek> TdmsReader<R> {
 
                 let raw_index_length = self.read_u32(is_big_endian)?;
                 if raw_index_length != 0xFFFFFFFF && raw_index_length != 0x00000000 {
-                    // Skip raw data index
-                    self.file.seek(SeekFrom::Current(raw_index_length as i64))?;
+                    // Skip raw data index; its length counts the 4 bytes already read
+                    self.file.seek(SeekFrom::Current(raw_index_length.saturating_sub(4) as i64))?;
                 }
                 
                 let property_count = self.read_u32(is_big_endian)?;
                 let mut local_properties = HashMap::with_capacity(property_count as usize);
                 for _ in 0..property_count {
-                    let prop = self.read_property(is_big_endian)?;
+                    let prop = match self.read_property(segment, segment_idx, &path)? {
+                        PropertyOutcome::Read(prop) => prop,
+                        PropertyOutcome::Skipped => continue,
+                        PropertyOutcome::Unparseable { name, type_code } => {
+                            return Ok(MetadataOutcome::UnknownPropertyType { path, name, type_code });
+                        }
+                    };
+                    self.record_property_history(&path, segment_idx, &prop);
+                    if path == ObjectPath::Root && prop.name == StringEncoding::PROPERTY {
+                        self.apply_declared_encoding(segment_idx, segment.offset, &prop.value);
+                    }
                     local_properties.insert(prop.name.clone(), prop);
                 }
 
@@ -361,7 +973,31 @@ impl<R: ReadSeek> TdmsReader<R> {
             }
         }
         
-        Ok(())
+        Ok(MetadataOutcome::Complete)
+    }
+    
+    /// Decode the strings that follow with the encoding the file declares,
+    /// unless the reader options say otherwise
+    fn apply_declared_encoding(&mut self, segment_idx: usize, offset: u64, value: &PropertyValue) {
+        if self.options.string_encoding.is_some() {
+            return;
+        }
+        match value.as_str().and_then(StringEncoding::from_name) {
+            Some(encoding) => self.string_encoding = encoding,
+            None => self.warnings.push(ParseWarning {
+                segment: segment_idx,
+                offset,
+                message: format!("unknown string encoding {:?}, reading strings as UTF-8", value),
+            }),
+        }
+    }
+    
+    fn record_property_history(&mut self, path: &ObjectPath, segment_idx: usize, prop: &Property) {
+        let history = match self.property_history.get_mut(path) {
+            Some(history) => history,
+            None => self.property_history.entry(path.clone()).or_default(),
+        };
+        history.entry(prop.name.clone()).or_default().push((segment_idx, prop.value.clone()));
     }
     
     fn calculate_segment_offsets(
@@ -374,16 +1010,24 @@ impl<R: ReadSeek> TdmsReader<R> {
         
         let mut total_metadata_described_raw_size = 0u64;
         let mut has_variable_length_type = false; 
+        // DAQmx channels of a segment share one block of interleaved raw records
+        let mut daqmx_block_size: Option<u64> = None;
         
         for channel_key in channel_keys {
             if let Some(&(_value_count, byte_size)) = new_segment_indices.get(channel_key) {
-                total_metadata_described_raw_size += byte_size;
-                
                 if let Some(metadata) = self.channels.get(channel_key) {
                     if metadata.data_type == DataType::String {
                         has_variable_length_type = true;
                     }
+                    if metadata.daqmx.is_some() {
+                        if daqmx_block_size.is_none() {
+                            daqmx_block_size = Some(byte_size);
+                            total_metadata_described_raw_size += byte_size;
+                        }
+                        continue;
+                    }
                 }
+                total_metadata_described_raw_size += byte_size;
             }
         }
 
@@ -391,9 +1035,36 @@ impl<R: ReadSeek> TdmsReader<R> {
             return Ok(());
         }
 
+        // Interleaved raw data holds records of one value per channel, so each
+        // channel starts at its place in the record and steps a record at a time
+        let record_width = if segment.toc.is_interleaved() {
+            if has_variable_length_type {
+                return Err(TdmsError::Unsupported(
+                    "Interleaved raw data cannot contain strings".to_string(),
+                ));
+            }
+            let mut width = 0u64;
+            for channel_key in channel_keys {
+                if let (Some(&(value_count, _)), Some(metadata)) = (new_segment_indices.get(channel_key), self.channels.get(channel_key)) {
+                    if value_count > 0 {
+                        width += metadata.data_type.fixed_size().unwrap_or(0) as u64;
+                    }
+                }
+            }
+            Some(width)
+        } else {
+            None
+        };
+
         let mut num_chunks = 1u64;
         
-        if !has_variable_length_type && segment.total_raw_data_size > total_metadata_described_raw_size {
+        if self.incomplete_segment == Some(segment.offset) {
+            // Only the chunks written in full so far
+            num_chunks = segment.total_raw_data_size / total_metadata_described_raw_size;
+            if has_variable_length_type {
+                num_chunks = num_chunks.min(1);
+            }
+        } else if !has_variable_length_type && segment.total_raw_data_size > total_metadata_described_raw_size {
             if segment.total_raw_data_size % total_metadata_described_raw_size != 0 {
                 return Err(TdmsError::InvalidTag {
                     expected: format!("Raw data size ({}) to be a multiple of chunk size ({})", 
@@ -405,7 +1076,10 @@ impl<R: ReadSeek> TdmsReader<R> {
         }
         
         for chunk_idx in 0..num_chunks {
-            let mut current_offset = chunk_idx * total_metadata_described_raw_size;
+            let chunk_start = chunk_idx * total_metadata_described_raw_size;
+            let mut current_offset = chunk_start;
+            let mut record_offset = 0u64;
+            let mut daqmx_block_start: Option<u64> = None;
             
             for channel_key in channel_keys {
                 if let Some(channel_info) = self.channels.get_mut(channel_key) {
@@ -415,29 +1089,160 @@ impl<R: ReadSeek> TdmsReader<R> {
                             continue;
                         }
 
+                        if let Some(daqmx_info) = &channel_info.daqmx {
+                            let block_start = *daqmx_block_start.get_or_insert_with(|| {
+                                let start = current_offset;
+                                current_offset += byte_size;
+                                start
+                            });
+                            // Unreadable layouts (e.g. digital lines) still get listed;
+                            // reading them reports the problem
+                            let value_offset = daqmx_info.primary_value_layout().map_or(0, |(offset, _)| offset);
+                            let stride = daqmx_info.record_width();
+                            channel_info.add_segment(SegmentData {
+                                segment_index: segment_idx,
+                                value_count,
+                                byte_size,
+                                byte_offset: block_start + value_offset,
+                                stride: Some(stride),
+                            })?;
+                            continue;
+                        }
+
+                        if let Some(stride) = record_width {
+                            channel_info.add_segment(SegmentData {
+                                segment_index: segment_idx,
+                                value_count,
+                                byte_size,
+                                byte_offset: chunk_start + record_offset,
+                                stride: Some(stride),
+                            })?;
+                            record_offset += channel_info.data_type.fixed_size().unwrap_or(0) as u64;
+                            continue;
+                        }
+
                         channel_info.add_segment(SegmentData {
                             segment_index: segment_idx,
                             value_count,
                             byte_size,
                             byte_offset: current_offset,
-                        });
+                            stride: None,
+                        })?;
                         
                         current_offset += byte_size;
                     }
                 }
             }
         }
+
+        if let Some(spill) = &self.spill {
+            for channel_key in channel_keys {
+                if let Some(channel_info) = self.channels.get_mut(channel_key) {
+                    if let Some(list) = &mut channel_info.segment_list {
+                        list.spill(spill)?;
+                    }
+                }
+            }
+        }
         
         Ok(())
     }
+    
+    /// Read a DAQmx raw data index (after its header word)
+    fn read_daqmx_index(&mut self, header: u32, is_big_endian: bool) -> Result<(u64, DaqmxInfo)> {
+        let _data_type = self.read_u32(is_big_endian)?;
+        let _dimension = self.read_u32(is_big_endian)?;
+        let number_of_values = self.read_u64(is_big_endian)?;
+        
+        let scaler_count = self.read_u32(is_big_endian)?;
+        let mut scalers = Vec::with_capacity(scaler_count.min(1024) as usize);
+        for _ in 0..scaler_count {
+            let daqmx_type_code = self.read_u32(is_big_endian)?;
+            let raw_buffer_index = self.read_u32(is_big_endian)?;
+            let offset = self.read_u32(is_big_endian)?;
+            let (kind, sample_format_bitmap) = if header == daqmx::DIGITAL_LINE_SCALER {
+                let bitmap = self.file.read_u8()?;
+                (DaqmxScalerKind::DigitalLine { bit_offset: offset }, bitmap as u32)
+            } else {
+                let bitmap = self.read_u32(is_big_endian)?;
+                (DaqmxScalerKind::FormatChanging { byte_offset: offset }, bitmap)
+            };
+            let scale_id = self.read_u32(is_big_endian)?;
+            scalers.push(DaqmxScaler {
+                data_type: daqmx::daqmx_data_type(daqmx_type_code),
+                daqmx_type_code,
+                raw_buffer_index,
+                kind,
+                sample_format_bitmap,
+                scale_id,
+            });
+        }
+        
+        let width_count = self.read_u32(is_big_endian)?;
+        let mut raw_data_widths = Vec::with_capacity(width_count.min(1024) as usize);
+        for _ in 0..width_count {
+            raw_data_widths.push(self.read_u32(is_big_endian)?);
+        }
+        
+        Ok((number_of_values, DaqmxInfo { scalers, raw_data_widths }))
+    }
 
-    fn read_property(&mut self, is_big_endian: bool) -> Result<Property> {
+    fn read_property(&mut self, segment: &SegmentInfo, segment_idx: usize, path: &ObjectPath) -> Result<PropertyOutcome> {
+        let is_big_endian = segment.is_big_endian;
+        let skip = self.options.unknown_properties == UnknownPropertyPolicy::Skip;
         let name = self.read_length_prefixed_string(is_big_endian)?;
         let data_type_raw = self.read_u32(is_big_endian)?;
-        let data_type = DataType::from_u32(data_type_raw)
-            .ok_or_else(|| TdmsError::InvalidDataType(data_type_raw))?;
-        let value = self.read_property_value(data_type, is_big_endian)?;
-        Ok(Property { name, value })
+        let Some(data_type) = DataType::from_u32(data_type_raw) else {
+            if skip {
+                return Ok(PropertyOutcome::Unparseable { name, type_code: data_type_raw });
+            }
+            return Err(TdmsError::InvalidDataType(data_type_raw));
+        };
+        if data_type == DataType::String {
+            return match self.read_length_prefixed_string(is_big_endian) {
+                Ok(value) => Ok(PropertyOutcome::Read(Property { name, value: PropertyValue::String(value) })),
+                Err(TdmsError::InvalidUtf8) if skip => {
+                    // The undecodable text is still in the string buffer
+                    let bytes = self.string_buffer.clone();
+                    self.warnings.push(ParseWarning {
+                        segment: segment_idx,
+                        offset: segment.offset,
+                        message: format!("property '{}' of {} is not valid {}; kept as raw bytes", name, path, self.string_encoding.name()),
+                    });
+                    self.keep_raw_property(segment, segment_idx, path, name, data_type, bytes);
+                    Ok(PropertyOutcome::Skipped)
+                }
+                Err(e) => Err(e),
+            };
+        }
+        match self.read_property_value(data_type, is_big_endian) {
+            Err(TdmsError::Unsupported(_)) if skip => match data_type.fixed_size() {
+                Some(size) => {
+                    let mut bytes = vec![0u8; size];
+                    self.file.read_exact(&mut bytes)?;
+                    self.warnings.push(ParseWarning {
+                        segment: segment_idx,
+                        offset: segment.offset,
+                        message: format!("property '{}' of {} has unsupported data type {:?}; skipped", name, path, data_type),
+                    });
+                    self.keep_raw_property(segment, segment_idx, path, name, data_type, bytes);
+                    Ok(PropertyOutcome::Skipped)
+                }
+                None => Ok(PropertyOutcome::Unparseable { name, type_code: data_type_raw }),
+            },
+            result => Ok(PropertyOutcome::Read(Property { name, value: result? })),
+        }
+    }
+
+    fn keep_raw_property(&mut self, segment: &SegmentInfo, segment_idx: usize, path: &ObjectPath, name: String, data_type: DataType, bytes: Vec<u8>) {
+        self.raw_properties.push(RawProperty {
+            path: path.clone(),
+            name,
+            data_type,
+            bytes,
+            is_big_endian: segment.is_big_endian,
+            segment: segment_idx,
+        });
     }
 
     fn read_property_value(&mut self, data_type: DataType, is_big_endian: bool) -> Result<PropertyValue> {
@@ -452,6 +1257,11 @@ impl<R: ReadSeek> TdmsReader<R> {
             DataType::U64 => Ok(PropertyValue::U64(RawDataReader::read_u64(&mut self.file, is_big_endian)?)),
             DataType::SingleFloat => Ok(PropertyValue::Float(RawDataReader::read_f32(&mut self.file, is_big_endian)?)),
             DataType::DoubleFloat => Ok(PropertyValue::Double(RawDataReader::read_f64(&mut self.file, is_big_endian)?)),
+            // There's no extended precision property value; the nearest double stands in
+            DataType::ExtendedFloat => {
+                let value = RawDataReader::read_values::<F80, _>(&mut self.file, 1, is_big_endian)?[0];
+                Ok(PropertyValue::Double(value.to_f64()))
+            }
             DataType::Boolean => Ok(PropertyValue::Boolean(RawDataReader::read_bool(&mut self.file)?)),
             DataType::TimeStamp => Ok(PropertyValue::Timestamp(RawDataReader::read_timestamp(&mut self.file, is_big_endian)?)),
             DataType::String => Ok(PropertyValue::String(self.read_length_prefixed_string(is_big_endian)?)),
@@ -466,11 +1276,83 @@ impl<R: ReadSeek> TdmsReader<R> {
         self.channels.keys().map(|p| p.to_string()).collect()
     }
 
+    /// List the names of a group's channels, sorted by name
+    pub fn list_group_channels(&self, group: &str) -> Vec<String> {
+        let mut names: Vec<String> = self.channels.keys()
+            .filter_map(|path| match path {
+                ObjectPath::Channel { group: g, channel } if g == group => Some(channel.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
     /// List all group names
     pub fn list_groups(&self) -> Vec<String> {
         self.groups.keys().cloned().collect()
     }
     
+    /// Build a virtual hierarchy from group names using `/` as a level separator
+    /// 
+    /// See [`crate::metadata::GroupTree`] for the naming convention.
+    pub fn group_tree(&self) -> GroupTree {
+        let mut groups: HashMap<&str, Vec<String>> = self.groups.keys()
+            .map(|g| (g.as_str(), Vec::new()))
+            .collect();
+        for path in self.channels.keys() {
+            if let ObjectPath::Channel { group, channel } = path {
+                groups.entry(group.as_str()).or_default().push(channel.clone());
+            }
+        }
+        GroupTree::from_groups(groups)
+    }
+    
+    /// The `NI_ExpStartTimeStamp` / `NI_ExpTimeStamp` file properties
+    pub fn experiment_timestamps(&self) -> ExperimentTimestamps {
+        ExperimentTimestamps::from_properties(&self.file_properties)
+    }
+    
+    /// File metadata interpreted with the FlexLogger property layout
+    pub fn flexlogger_file(&self) -> FlexLoggerFile {
+        FlexLoggerFile::from_properties(&self.file_properties)
+    }
+    
+    /// Channel metadata interpreted with the FlexLogger property layout
+    pub fn flexlogger_channel(&self, group: &str, channel: &str) -> Option<FlexLoggerChannel> {
+        self.get_channel_properties(group, channel).map(FlexLoggerChannel::from_properties)
+    }
+    
+    /// The last expected vs. written sample counts recorded for a channel
+    /// (see [`crate::properties::sampling`]); `None` without an expected rate
+    pub fn sample_accounting(&self, group: &str, channel: &str) -> Option<SampleAccounting> {
+        self.get_channel_properties(group, channel).and_then(SampleAccounting::from_properties)
+    }
+    
+    /// The last heartbeat checkpoint the writer recorded (see
+    /// [`crate::properties::checkpoint`]); `None` if it recorded none
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        let time = self.file_properties.get(checkpoint::LAST_CHECKPOINT_TIME)?.value.as_timestamp()?;
+        let mut samples: Vec<(ObjectPath, u64)> = self.channels.iter()
+            .filter_map(|(path, info)| {
+                let count = info.properties.get(checkpoint::CHECKPOINT_SAMPLES)?.value.as_i64()?;
+                Some((path.clone(), u64::try_from(count).ok()?))
+            })
+            .collect();
+        samples.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Some(Checkpoint { time, samples })
+    }
+    
+    /// Channels of a group that LabVIEW wrote from a 2D array, in column order
+    /// 
+    /// Uses the `NI_ArrayColumn` convention; see [`crate::properties::labview`].
+    pub fn array_columns(&self, group: &str) -> Vec<ArrayColumn> {
+        labview::array_columns(self.channels.iter().filter_map(|(path, info)| match path {
+            ObjectPath::Channel { group: g, channel } if g == group => Some((channel.as_str(), &info.properties)),
+            _ => None,
+        }))
+    }
+    
     /// Get all file-level properties
     pub fn get_file_properties(&self) -> &HashMap<String, Property> {
         &self.file_properties
@@ -487,6 +1369,133 @@ impl<R: ReadSeek> TdmsReader<R> {
         self.channels.get(&path).map(|info| &info.properties)
     }
     
+    /// Title to show for the file: its `title` property, or else its `name`
+    /// property. Empty strings count as missing.
+    pub fn file_display_name(&self) -> Option<&str> {
+        string_property(&self.file_properties, wellknown::TITLE)
+            .or_else(|| string_property(&self.file_properties, wellknown::NAME))
+    }
+    
+    /// Name to show for a group: its `name` property, or else the group name.
+    /// `None` if the file has no such group.
+    pub fn group_display_name(&self, group: &str) -> Option<&str> {
+        if let Some((name, properties)) = self.groups.get_key_value(group) {
+            return Some(string_property(properties, wellknown::NAME).unwrap_or(name));
+        }
+        // Groups only known from their channels have no properties
+        self.channels.keys().find_map(|path| path.group().filter(|&g| g == group))
+    }
+    
+    /// Name to show for a channel: its `name` property, then the original
+    /// name LabVIEW records in `NI_ChannelName`, then the channel name.
+    /// `None` if the file has no such channel.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::TdmsReader;
+    /// 
+    /// let reader = TdmsReader::open("data.tdms").unwrap();
+    /// for group in reader.list_groups() {
+    ///     println!("{}", reader.group_display_name(&group).unwrap_or(&group));
+    ///     for channel in reader.list_group_channels(&group) {
+    ///         let name = reader.channel_display_name(&group, &channel).unwrap_or(&channel);
+    ///         match reader.channel_description(&group, &channel) {
+    ///             Some(description) => println!("  {}: {}", name, description),
+    ///             None => println!("  {}", name),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn channel_display_name(&self, group: &str, channel: &str) -> Option<&str> {
+        let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
+        let (path, info) = self.channels.get_key_value(&path)?;
+        string_property(&info.properties, wellknown::NAME)
+            .or_else(|| string_property(&info.properties, wellknown::NI_CHANNEL_NAME))
+            .or(path.channel())
+    }
+    
+    /// The file's `description` property, if it is a non-empty string
+    pub fn file_description(&self) -> Option<&str> {
+        string_property(&self.file_properties, wellknown::DESCRIPTION)
+    }
+    
+    /// A group's `description` property, if it is a non-empty string
+    pub fn group_description(&self, group: &str) -> Option<&str> {
+        string_property(self.groups.get(group)?, wellknown::DESCRIPTION)
+    }
+    
+    /// A channel's `description` property, if it is a non-empty string
+    pub fn channel_description(&self, group: &str, channel: &str) -> Option<&str> {
+        string_property(self.get_channel_properties(group, channel)?, wellknown::DESCRIPTION)
+    }
+    
+    /// Every value written for a channel property, as `(segment index, value)`
+    /// in file order.
+    /// 
+    /// `get_channel_properties` only reports the last value; properties such as
+    /// a status or setpoint that are rewritten during a run keep their full
+    /// history here. Values are listed each time a segment contains them, which
+    /// may repeat an unchanged value. Empty if the property was never written.
+    pub fn property_history(&self, group: &str, channel: &str, name: &str) -> &[(usize, PropertyValue)] {
+        self.history_of(&ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() }, name)
+    }
+    
+    /// Every value written for a group property (see [`Self::property_history`])
+    pub fn group_property_history(&self, group: &str, name: &str) -> &[(usize, PropertyValue)] {
+        self.history_of(&ObjectPath::Group(group.to_string()), name)
+    }
+    
+    /// Every value written for a file property (see [`Self::property_history`])
+    pub fn file_property_history(&self, name: &str) -> &[(usize, PropertyValue)] {
+        self.history_of(&ObjectPath::Root, name)
+    }
+    
+    fn history_of(&self, path: &ObjectPath, name: &str) -> &[(usize, PropertyValue)] {
+        self.property_history.get(path)
+            .and_then(|history| history.get(name))
+            .map_or(&[], |values| values.as_slice())
+    }
+    
+    /// Type, length, unit, scaling, waveform timing, DAQmx layout and
+    /// fragmentation of a channel, from the metadata alone
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::TdmsReader;
+    /// 
+    /// let reader = TdmsReader::open("data.tdms").unwrap();
+    /// let info = reader.channel_info("Group", "Voltage").unwrap();
+    /// println!("{:?}, {} values in {} segments", info.data_type,
+    ///     info.length.unwrap_or(0), info.fragmentation.segment_count);
+    /// if let Some(scaling) = &info.scaling {
+    ///     println!("scaled {} to {}", scaling.describe(), info.unit.as_deref().unwrap_or("?"));
+    /// }
+    /// ```
+    pub fn channel_info(&self, group: &str, channel: &str) -> Result<ChannelSummary> {
+        let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
+        let info = self.channels.get(&path)
+            .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
+        let encoded = self.channel_encoding(group, channel)?;
+        let properties = &info.properties;
+        let unit = wellknown::unit_string(properties)
+            .or_else(|| properties.get(labview::NI_UNIT_DESCRIPTION).and_then(|p| p.value.as_str()))
+            .map(str::to_string);
+        let fragmentation = index_stats::compute(&self.segments, [(&path, info)])?
+            .channels.pop().expect("stats for the one channel");
+        Ok(ChannelSummary {
+            data_type: encoded.map_or(info.data_type, |(_, data_type)| data_type),
+            length: encoded.is_none().then_some(info.total_values),
+            encoding: encoded.map(|(encoding, _)| encoding),
+            unit,
+            scaling: Scaling::from_properties(properties),
+            waveform: WaveformTiming::from_properties(properties),
+            daqmx: info.daqmx.clone(),
+            fragmentation,
+        })
+    }
+    
     /// Get a channel reader for a specific channel
     /// 
     /// # Arguments
@@ -499,7 +1508,413 @@ impl<R: ReadSeek> TdmsReader<R> {
     pub fn get_channel(&self, key: &str) -> Option<ChannelReader> {
         ObjectPath::from_string(key).ok()
             .and_then(|path| self.channels.get(&path))
-            .map(|info| ChannelReader::new(key.to_string(), info.clone()))
+            .map(|info| ChannelReader::new(key.to_string(), info.clone()).with_string_encoding(self.string_encoding))
+    }
+    
+    /// Id of a channel for [`read_by_id`](Self::read_by_id) and
+    /// [`read_strings_by_id`](Self::read_strings_by_id)
+    pub fn channel_id(&self, group: &str, channel: &str) -> Result<ChannelId> {
+        self.channel_ids.iter()
+            .position(|path| path.group() == Some(group) && path.channel() == Some(channel))
+            .map(ChannelId::new)
+            .ok_or_else(|| TdmsError::ChannelNotFound(
+                ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() }.to_string()))
+    }
+    
+    /// Group and channel name of a channel id
+    pub fn channel_names(&self, id: ChannelId) -> Result<(&str, &str)> {
+        self.channel_ids.get(id.index())
+            .and_then(|path| Some((path.group()?, path.channel()?)))
+            .ok_or_else(|| TdmsError::ChannelNotFound(format!("{:?}", id)))
+    }
+    
+    /// Whether a channel stores DAQmx raw data
+    pub fn is_daqmx_channel(&self, group: &str, channel: &str) -> bool {
+        let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
+        self.channels.get(&path).is_some_and(|info| info.daqmx.is_some())
+    }
+    
+    /// Whether any segment of the file carries DAQmx raw data
+    pub fn has_daqmx_data(&self) -> bool {
+        self.segments.iter().any(|s| s.has_daqmx_data())
+    }
+    
+    /// Whatever identifies the producer of the file: the `library` and
+    /// `library_version` properties this crate writes, if present, and the
+    /// format versions of the segments.
+    /// 
+    /// Useful when triaging files that one tool reads and another doesn't.
+    pub fn writer_info(&self) -> WriterInfo {
+        let property = |name| self.file_properties.get(name).and_then(|p| p.value.as_str()).map(str::to_string);
+        let mut format_versions = Vec::new();
+        for segment in &self.segments {
+            if !format_versions.contains(&segment.version) {
+                format_versions.push(segment.version);
+            }
+        }
+        WriterInfo {
+            library: property(wellknown::LIBRARY),
+            library_version: property(wellknown::LIBRARY_VERSION),
+            format_versions,
+        }
+    }
+    
+    /// The properties of every object in the file, gathered in one pass.
+    /// 
+    /// Groups and channels are sorted by name. Nothing is copied, so this is
+    /// the cheap way to build a metadata catalog of many files.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::TdmsReader;
+    /// 
+    /// let reader = TdmsReader::open("data.tdms").unwrap();
+    /// let all = reader.all_properties();
+    /// for (group, contents) in &all.groups {
+    ///     for (channel, properties) in &contents.channels {
+    ///         println!("{}/{}: {} properties", group, channel, properties.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn all_properties(&self) -> AllProperties<'_> {
+        static NO_PROPERTIES: OnceLock<HashMap<String, Property>> = OnceLock::new();
+        let no_properties = NO_PROPERTIES.get_or_init(HashMap::new);
+
+        let mut groups: BTreeMap<&str, GroupProperties> = self.groups.iter()
+            .map(|(name, properties)| (name.as_str(), GroupProperties { properties, channels: BTreeMap::new() }))
+            .collect();
+        for (path, info) in &self.channels {
+            if let ObjectPath::Channel { group, channel } = path {
+                groups.entry(group.as_str())
+                    .or_insert_with(|| GroupProperties { properties: no_properties, channels: BTreeMap::new() })
+                    .channels
+                    .insert(channel.as_str(), &info.properties);
+            }
+        }
+        AllProperties { file: &self.file_properties, groups }
+    }
+    
+    /// Information about every segment, in file order
+    pub fn segments(&self) -> &[SegmentInfo] {
+        &self.segments
+    }
+
+    /// Index of a channel's first value in the run, from the
+    /// `tdms_rs_start_index` property written by
+    /// [`TdmsWriter::create_late_channel`](crate::TdmsWriter::create_late_channel);
+    /// 0 for channels without it
+    pub fn channel_start_index(&self, group: &str, channel: &str) -> u64 {
+        self.get_channel_properties(group, channel)
+            .map_or(0, alignment::start_index)
+    }
+
+    /// Read a channel's values preceded by `fill` for every sample it missed
+    /// before joining the run (see [`Self::channel_start_index`]), so late
+    /// channels line up with the ones recorded from the start.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// # let mut reader = TdmsReader::open("run.tdms").unwrap();
+    /// let pressure: Vec<f64> = reader.read_channel_data("Rack", "Pressure").unwrap();
+    /// let temperature = reader.read_channel_data_padded("Rack", "Temperature", f64::NAN).unwrap();
+    /// assert_eq!(pressure.len(), temperature.len());
+    /// ```
+    pub fn read_channel_data_padded<T: TdmsValue>(&mut self, group: &str, channel: &str, fill: T) -> Result<Vec<T>> {
+        let start = self.channel_start_index(group, channel) as usize;
+        let data: Vec<T> = self.read_channel_data(group, channel)?;
+        let mut padded = Vec::with_capacity(start + data.len());
+        padded.resize(start, fill);
+        padded.extend(data);
+        Ok(padded)
+    }
+
+    /// Segment and chunk statistics from the file's index, without reading raw data
+    /// (see [`IndexStats`])
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// let reader = TdmsReader::open("log.tdms").unwrap();
+    /// let stats = reader.index_stats().unwrap();
+    /// println!("{:.1}% of the file is metadata", stats.metadata_overhead_percent());
+    /// print!("{}", stats);
+    /// ```
+    /// 
+    /// Fails only if a segment index spilled to disk can't be read back.
+    pub fn index_stats(&self) -> Result<IndexStats> {
+        index_stats::compute(&self.segments, &self.channels)
+    }
+
+    /// Rough peak number of bytes reading a whole channel allocates: its
+    /// decoded values plus the largest raw buffer read at once. String
+    /// channels count their `String` headers and text, DAQmx channels their
+    /// scaled `f64` values.
+    pub fn estimate_read_memory(&self, group: &str, channel: &str) -> Result<u64> {
+        let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
+        let info = self.channels.get(&path)
+            .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
+        let data_type = match self.channel_encoding(group, channel)? {
+            Some((_, logical)) => logical,
+            None => info.data_type,
+        };
+        memory::estimate_read(info, data_type)
+    }
+    
+    /// Compare an index file with the segments parsed from the data file.
+    /// 
+    /// The reader itself always parses the data file, so a stale index never
+    /// affects what is read. A [`Mismatch`](IndexFileStatus::Mismatch) means
+    /// tools that trust the index (including LabVIEW) will see a different file;
+    /// fix it with [`rewrite_index_file`](Self::rewrite_index_file).
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// let mut reader = TdmsReader::open("log.tdms").unwrap();
+    /// let index_path = index_path_for("log.tdms");
+    /// if let IndexFileStatus::Mismatch { .. } = reader.check_index_file(&index_path).unwrap() {
+    ///     reader.rewrite_index_file(&index_path).unwrap();
+    /// }
+    /// ```
+    pub fn check_index_file(&mut self, index_path: impl AsRef<Path>) -> Result<IndexFileStatus> {
+        let file = match File::open(index_path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(IndexFileStatus::Missing),
+            Err(err) => return Err(err.into()),
+        };
+        let index_size = file.metadata()?.len();
+        let mut index = BufReader::new(file);
+        
+        let mismatch = |segment, reason: &str| Ok(IndexFileStatus::Mismatch { segment, reason: reason.to_string() });
+        let mut offset = 0u64;
+        for segment in 0..self.segments.len() {
+            let expected = self.index_segment_bytes(segment)?;
+            if index_size - offset < expected.len() as u64 {
+                return mismatch(segment, "index file ends early");
+            }
+            let mut actual = vec![0u8; expected.len()];
+            index.read_exact(&mut actual)?;
+            if actual[..4] != expected[..4] {
+                return mismatch(segment, "tag is not TDSh");
+            }
+            if actual[4..SegmentHeader::LEAD_IN_SIZE] != expected[4..SegmentHeader::LEAD_IN_SIZE] {
+                return mismatch(segment, "lead-in differs from the data file");
+            }
+            if actual != expected {
+                return mismatch(segment, "metadata differs from the data file");
+            }
+            offset += expected.len() as u64;
+        }
+        if offset < index_size {
+            return mismatch(self.segments.len(), "index file has more segments than the data file");
+        }
+        Ok(IndexFileStatus::Consistent)
+    }
+    
+    /// Write an index file that mirrors the data file's lead-ins and metadata,
+    /// replacing any file at `index_path`
+    pub fn rewrite_index_file(&mut self, index_path: impl AsRef<Path>) -> Result<()> {
+        let mut index = std::io::BufWriter::new(File::create(index_path)?);
+        for segment in 0..self.segments.len() {
+            index.write_all(&self.index_segment_bytes(segment)?)?;
+        }
+        index.flush()?;
+        Ok(())
+    }
+    
+    /// A segment's lead-in and metadata as they appear in the index file
+    /// Check each segment's metadata against the checksums the writer recorded
+    /// with [`TdmsWriter::enable_metadata_crc`](crate::TdmsWriter::enable_metadata_crc)
+    /// in the sidecar at `crc_path`
+    /// 
+    /// Reports the first segment whose metadata differs from its checksum or
+    /// has none. A missing checksum for the last segment usually means the
+    /// writer stopped between committing the segment and recording it.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// let mut reader = TdmsReader::open("log.tdms").unwrap();
+    /// let status = reader.check_metadata_crc(metadata_crc_path_for("log.tdms")).unwrap();
+    /// assert_eq!(status, MetadataCrcStatus::Consistent);
+    /// ```
+    pub fn check_metadata_crc(&mut self, crc_path: impl AsRef<Path>) -> Result<MetadataCrcStatus> {
+        let records = match segment::read_metadata_crc_file(crc_path) {
+            Ok(records) => records,
+            Err(TdmsError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => return Ok(MetadataCrcStatus::Missing),
+            Err(err) => return Err(err),
+        };
+        let records: HashMap<u64, segment::MetadataCrcRecord> = records.into_iter().map(|r| (r.offset, r)).collect();
+        
+        let mismatch = |segment, reason: &str| Ok(MetadataCrcStatus::Mismatch { segment, reason: reason.to_string() });
+        for segment in 0..self.segments.len() {
+            let info = &self.segments[segment];
+            if !info.toc.has_metadata() {
+                continue;
+            }
+            let Some(record) = records.get(&info.offset) else {
+                return mismatch(segment, "no checksum recorded");
+            };
+            if record.metadata_size != info.metadata_size {
+                return mismatch(segment, "metadata size differs from the recorded one");
+            }
+            let bytes = self.index_segment_bytes(segment)?;
+            if segment::crc32(&bytes[SegmentHeader::LEAD_IN_SIZE..]) != record.crc {
+                return mismatch(segment, "metadata checksum differs");
+            }
+        }
+        Ok(MetadataCrcStatus::Consistent)
+    }
+    
+    fn index_segment_bytes(&mut self, segment: usize) -> Result<Vec<u8>> {
+        let info = &self.segments[segment];
+        let mut bytes = vec![0u8; SegmentHeader::LEAD_IN_SIZE + info.metadata_size as usize];
+        self.file.seek(SeekFrom::Start(info.offset))?;
+        self.file.read_exact(&mut bytes)?;
+        bytes[..4].copy_from_slice(SegmentHeader::INDEX_TAG);
+        Ok(bytes)
+    }
+    
+    /// Conversion work done so far by reads of one channel (see [`ConversionStats`])
+    pub fn channel_conversion_stats(&self, group: &str, channel: &str) -> ConversionStats {
+        let key = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() }.to_string();
+        self.conversion_stats.get(&key).copied().unwrap_or_default()
+    }
+    
+    /// Conversion work done so far by reads of all channels
+    pub fn conversion_stats(&self) -> ConversionStats {
+        let mut total = ConversionStats::default();
+        for stats in self.conversion_stats.values() {
+            total.merge(stats);
+        }
+        total
+    }
+    
+    /// Reset the conversion counters of all channels to zero
+    pub fn reset_conversion_stats(&mut self) {
+        self.conversion_stats.clear();
+    }
+    
+    /// Tell the prefetch hook, if any, which bytes a read is about to touch
+    pub(crate) fn advise_read(&self, channel: &ChannelReader, start_index: u64, count: u64) {
+        if let Some(prefetch) = self.prefetch {
+            prefetch(&self.file, &channel.byte_ranges(&self.segments, start_index, count));
+        }
+    }
+    
+    pub(crate) fn record_conversions(&mut self, channel: &ChannelReader, start_index: u64, count: u64) {
+        let stats = channel.conversion_stats(&self.segments, start_index, count);
+        self.conversion_stats.entry(channel.key().to_string()).or_default().merge(&stats);
+    }
+
+    /// Problems worked around while parsing, such as segments skipped because
+    /// they use a DAQmx raw data index variant this crate can't parse
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// Properties that couldn't be decoded, in file order, with the raw bytes
+    /// of their values for custom recovery
+    /// 
+    /// Only filled with [`UnknownPropertyPolicy::Skip`]; otherwise such
+    /// properties fail the open. A property whose type code is unknown has no
+    /// known length and isn't included.
+    pub fn raw_properties(&self) -> &[RawProperty] {
+        &self.raw_properties
+    }
+    
+    /// Close the reader, releasing the underlying file immediately
+    pub fn close(self) {}
+    
+    /// Consume the reader and return its I/O source
+    /// 
+    /// For [`TdmsReader::open`] this is the buffered file; call
+    /// [`BufReader::into_inner`] on it to get the [`File`] itself.
+    pub fn into_inner(self) -> R {
+        self.file
+    }
+    
+    /// Estimate the wall-clock interval covered by each segment, indexed like
+    /// [`Self::segments`].
+    /// 
+    /// Uses the waveform timing of channels with a `wf_increment`: sample `n`
+    /// after a `wf_start_time` was written is at `wf_start_time + n * wf_increment`.
+    /// Channels without `wf_start_time` count from the file's `NI_ExpStartTimeStamp`.
+    /// A segment's range spans all its timed channels; segments without any are
+    /// `None`. Use the ranges to pick segments for a time window without reading data.
+    /// Fails only if a segment index spilled to disk can't be read back.
+    pub fn segment_time_ranges(&self) -> Result<Vec<Option<SegmentTimeRange>>> {
+        let mut ranges: Vec<Option<SegmentTimeRange>> = vec![None; self.segments.len()];
+        let run_start = self.experiment_timestamps().start;
+        
+        for (path, info) in &self.channels {
+            let Some(timing) = WaveformTiming::from_properties(&info.properties) else { continue };
+            let Some(increment) = timing.increment.filter(|inc| *inc > 0.0) else { continue };
+            let offset = timing.start_offset.unwrap_or(0.0);
+            // A changed wf_start_time starts a new waveform at that segment
+            let mut starts: Vec<(usize, Timestamp)> = self.property_history.get(path)
+                .and_then(|history| history.get(waveform::WF_START_TIME))
+                .map(|values| values.iter()
+                    .filter_map(|(segment, value)| Some((*segment, value.as_timestamp()?)))
+                    .collect())
+                .unwrap_or_default();
+            starts.dedup_by_key(|(_, start)| *start);
+            
+            let mut active_start = None;
+            let mut values_since_start = 0u64;
+            for segment in info.segment_entries() {
+                let segment = segment?;
+                let active = starts.iter().rposition(|(idx, _)| *idx <= segment.segment_index);
+                if active != active_start {
+                    active_start = active;
+                    values_since_start = 0;
+                }
+                let first_value = values_since_start;
+                values_since_start += segment.value_count;
+                
+                let Some(base) = active.map(|i| starts[i].1).or(run_start) else { continue };
+                let at = |n: u64| offset_by(base, n as f64 * increment + offset);
+                let range = SegmentTimeRange { start: at(first_value), end: at(values_since_start) };
+                let slot = &mut ranges[segment.segment_index];
+                *slot = Some(match *slot {
+                    Some(existing) => SegmentTimeRange {
+                        start: existing.start.min(range.start),
+                        end: existing.end.max(range.end),
+                    },
+                    None => range,
+                });
+            }
+        }
+        
+        Ok(ranges)
+    }
+    
+    /// Indices of the segments whose estimated time range overlaps `[start, end)`
+    pub fn segments_in_time_range(&self, start: Timestamp, end: Timestamp) -> Result<Vec<usize>> {
+        Ok(self.segment_time_ranges()?.iter()
+            .enumerate()
+            .filter(|(_, range)| range.is_some_and(|r| r.overlaps(start, end)))
+            .map(|(idx, _)| idx)
+            .collect())
+    }
+    
+    /// Offset of the final segment if a writer hadn't finished it when the
+    /// file was opened, whether or not it was read (see
+    /// [`ReaderOptions::allow_incomplete`])
+    pub fn incomplete_segment(&self) -> Option<u64> {
+        self.incomplete_segment
+    }
+    
+    /// Whether the metadata was parsed from the index file by
+    /// [`open_indexed`](TdmsReader::open_indexed) rather than from the data file
+    pub fn opened_from_index(&self) -> bool {
+        self.from_index
     }
     
     /// Get the number of segments in the file
@@ -516,7 +1931,8 @@ impl<R: ReadSeek> TdmsReader<R> {
     /// 
     /// # Type Parameters
     /// 
-    /// * `T` - The type to read (must match the channel's data type)
+    /// * `T` - The type to read (must match the channel's data type; see
+    ///   [`read_channel_data_as`](Self::read_channel_data_as) for widening reads)
     /// 
     /// # Arguments
     /// 
@@ -526,7 +1942,7 @@ impl<R: ReadSeek> TdmsReader<R> {
     /// # Returns
     /// 
     /// A vector of values
-    pub fn read_channel_data<T: Copy + Default>(
+    pub fn read_channel_data<T: TdmsValue>(
         &mut self,
         group: &str,
         channel: &str,
@@ -534,10 +1950,288 @@ impl<R: ReadSeek> TdmsReader<R> {
         let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
         let key_string = path.to_string();
         let channel_reader = self.channels.get(&path)
-            .map(|info| ChannelReader::new(key_string.clone(), info.clone()))
-            .ok_or_else(|| TdmsError::ChannelNotFound(key_string))?;
+            .map(|info| ChannelReader::new(key_string.clone(), info.clone()).with_string_encoding(self.string_encoding))
+            .ok_or(TdmsError::ChannelNotFound(key_string))?;
         
-        channel_reader.read_all_data(&mut self.file, &self.segments)
+        self.advise_read(&channel_reader, 0, channel_reader.total_values());
+        let data = match self.channel_encoding(group, channel)? {
+            Some(encoding) => self.read_encoded_channel(&channel_reader, encoding)?,
+            None => channel_reader.read_all_data(&mut self.file, &self.segments)?,
+        };
+        self.record_conversions(&channel_reader, 0, channel_reader.total_values());
+        Ok(data)
+    }
+    
+    /// Read `len` values of a channel starting at value `start`
+    /// 
+    /// Only the segments holding the range are touched, so this is the way to
+    /// pull a window out of a channel too big to load. The range is cut short
+    /// at the end of the channel, and is empty if it starts past the end.
+    /// Encoded channels are decoded in full and then sliced.
+    pub fn read_channel_range<T: TdmsValue>(&mut self, group: &str, channel: &str, start: u64, len: usize) -> Result<Vec<T>> {
+        if self.channel_encoding(group, channel)?.is_some() {
+            let data: Vec<T> = self.read_channel_data(group, channel)?;
+            let start = start.min(data.len() as u64) as usize;
+            return Ok(data[start..].iter().take(len).copied().collect());
+        }
+        let channel_reader = self.channel_reader(group, channel)?;
+        channel_reader.check_value_type::<T>()?;
+        self.advise_read(&channel_reader, start, len as u64);
+        let data = channel_reader.read_chunk(&mut self.file, &self.segments, start, len)?;
+        self.record_conversions(&channel_reader, start, data.len() as u64);
+        Ok(data)
+    }
+    
+    /// [`read_channel_range`](Self::read_channel_range) for string channels
+    pub fn read_channel_range_strings(&mut self, group: &str, channel: &str, start: u64, len: usize) -> Result<Vec<String>> {
+        let channel_reader = self.channel_reader(group, channel)?;
+        self.advise_read(&channel_reader, start, len as u64);
+        channel_reader.read_string_chunk(&mut self.file, &self.segments, start, len)
+    }
+    
+    /// Read a channel's raw values as `T` without checking the channel's type
+    /// 
+    /// An escape hatch for data written with the wrong type, such as counters
+    /// stored in an `I64` channel that are really `u64`: the bytes are
+    /// reinterpreted as is. `T` still has to be the same size as the stored
+    /// values, or this fails with [`TdmsError::TypeMismatch`], and encoded
+    /// channels are decoded and checked as by
+    /// [`read_channel_data`](Self::read_channel_data).
+    pub fn read_channel_data_unchecked<T: TdmsValue>(&mut self, group: &str, channel: &str) -> Result<Vec<T>> {
+        if self.channel_encoding(group, channel)?.is_some() {
+            return self.read_channel_data(group, channel);
+        }
+        let channel_reader = self.channel_reader(group, channel)?;
+        self.advise_read(&channel_reader, 0, channel_reader.total_values());
+        let data = channel_reader.read_all_data_unchecked(&mut self.file, &self.segments)?;
+        self.record_conversions(&channel_reader, 0, channel_reader.total_values());
+        Ok(data)
+    }
+    
+    /// Read a numeric channel as `T`, widening narrower stored types
+    /// 
+    /// Where [`read_channel_data`](Self::read_channel_data) insists on the
+    /// stored type, this also accepts any type that converts to `T` without
+    /// loss (see [`DataType::widens_to`]), so an `I16` or `SingleFloat` channel
+    /// reads as `f64`. Conversions that could round or overflow, like `I64` to
+    /// `f64` or anything to a narrower type, fail with
+    /// [`TdmsError::TypeMismatch`] as they would for an exact read.
+    pub fn read_channel_data_as<T: NumericValue>(&mut self, group: &str, channel: &str) -> Result<Vec<T>> {
+        let data_type = self.value_data_type(group, channel)?
+            .ok_or_else(|| TdmsError::ChannelNotFound(ObjectPath::Channel {
+                group: group.to_string(),
+                channel: channel.to_string(),
+            }.to_string()))?;
+        if data_type == T::DATA_TYPE || !data_type.widens_to(T::DATA_TYPE) {
+            return self.read_channel_data(group, channel);
+        }
+        match data_type {
+            DataType::I8 => self.read_widened::<i8, T>(group, channel),
+            DataType::I16 => self.read_widened::<i16, T>(group, channel),
+            DataType::I32 => self.read_widened::<i32, T>(group, channel),
+            DataType::U8 => self.read_widened::<u8, T>(group, channel),
+            DataType::U16 => self.read_widened::<u16, T>(group, channel),
+            DataType::U32 => self.read_widened::<u32, T>(group, channel),
+            DataType::SingleFloat => self.read_widened::<f32, T>(group, channel),
+            _ => unreachable!("{:?} widens to nothing but itself", data_type),
+        }
+    }
+    
+    fn read_widened<S: NumericValue, T: NumericValue>(&mut self, group: &str, channel: &str) -> Result<Vec<T>> {
+        Ok(self.read_channel_data::<S>(group, channel)?
+            .into_iter()
+            .map(|value| T::from_f64(value.to_f64()))
+            .collect())
+    }
+    
+    /// The channels with their parsed metadata, in the order they first
+    /// appeared in the file
+    pub(crate) fn channels_in_order(&self) -> impl Iterator<Item = (&ObjectPath, &ChannelInfo)> {
+        self.channel_ids.iter().filter_map(|path| Some((path, self.channels.get(path)?)))
+    }
+    
+    /// Number of values in an encoded channel's frames, without decoding them
+    pub(crate) fn encoded_value_count(&mut self, group: &str, channel: &str) -> Result<u64> {
+        let channel_reader = self.channel_reader(group, channel)?;
+        let frames: Vec<u8> = channel_reader.read_all_data(&mut self.file, &self.segments)?;
+        codec::frame_value_count(&frames)
+    }
+    
+    /// Data type of a channel's values as read back: the original type for
+    /// encoded channels, or `None` if there is no such channel
+    pub(crate) fn value_data_type(&self, group: &str, channel: &str) -> Result<Option<DataType>> {
+        if let Some((_, data_type)) = self.channel_encoding(group, channel)? {
+            return Ok(Some(data_type));
+        }
+        let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
+        Ok(self.channels.get(&path).map(|info| info.data_type))
+    }
+    
+    /// The encoding and logical data type of a channel written with the
+    /// encoded-channel extension, or `None` for ordinary channels
+    /// 
+    /// Fails with [`TdmsError::Unsupported`] if the channel is marked as encoded
+    /// with a codec this version doesn't know.
+    pub fn channel_encoding(&self, group: &str, channel: &str) -> Result<Option<(ChannelEncoding, DataType)>> {
+        let Some(properties) = self.get_channel_properties(group, channel) else {
+            return Ok(None);
+        };
+        let Some(name) = properties.get(codec::ENCODING_PROPERTY).and_then(|p| p.value.as_str()) else {
+            return Ok(None);
+        };
+        let encoding = ChannelEncoding::from_name(name)
+            .ok_or_else(|| TdmsError::Unsupported(format!("Unknown channel encoding '{}'", name)))?;
+        let type_code = properties.get(codec::ORIGINAL_TYPE_PROPERTY)
+            .and_then(|p| p.value.as_i64())
+            .ok_or_else(|| TdmsError::Unsupported(format!("Encoded channel without {}", codec::ORIGINAL_TYPE_PROPERTY)))?;
+        let data_type = DataType::from_u32(type_code as u32)
+            .ok_or(TdmsError::InvalidDataType(type_code as u32))?;
+        Ok(Some((encoding, data_type)))
+    }
+    
+    fn read_encoded_channel<T: TdmsValue>(
+        &mut self,
+        channel_reader: &ChannelReader,
+        (encoding, data_type): (ChannelEncoding, DataType),
+    ) -> Result<Vec<T>> {
+        if data_type != T::DATA_TYPE {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", data_type),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
+        
+        let frames: Vec<u8> = channel_reader.read_all_data(&mut self.file, &self.segments)?;
+        let (raw, count) = codec::decode_frames(encoding, data_type, &frames)?;
+        RawDataReader::read_values(&mut raw.as_slice(), count, false)
+    }
+    
+    /// Set how this file's timestamps relate to UTC (see [`TimestampConvention`]).
+    /// 
+    /// Applies to the time conversions below; raw reads such as
+    /// `read_channel_data::<Timestamp>` and property values are left untouched.
+    pub fn set_timestamp_convention(&mut self, convention: TimestampConvention) {
+        self.timestamp_convention = convention;
+    }
+    
+    /// The timestamp convention applied to time conversions
+    pub fn timestamp_convention(&self) -> TimestampConvention {
+        self.timestamp_convention
+    }
+    
+    /// Convert a timestamp from this file to `SystemTime`, applying the
+    /// file's timestamp convention
+    pub fn to_system_time(&self, timestamp: Timestamp) -> SystemTime {
+        self.timestamp_convention.to_system_time(timestamp)
+    }
+    
+    /// Read a timestamp channel, corrected to standard UTC TDMS timestamps
+    pub fn read_channel_utc_timestamps(&mut self, group: &str, channel: &str) -> Result<Vec<Timestamp>> {
+        let convention = self.timestamp_convention;
+        let mut timestamps: Vec<Timestamp> = self.read_channel_data(group, channel)?;
+        for timestamp in &mut timestamps {
+            *timestamp = convention.to_utc(*timestamp);
+        }
+        Ok(timestamps)
+    }
+    
+    /// Read an extended precision channel as the nearest `f64`s (see
+    /// [`F80::to_f64`]); `read_channel_data::<F80>` gives the values unrounded
+    pub fn read_channel_f80_as_f64(&mut self, group: &str, channel: &str) -> Result<Vec<f64>> {
+        Ok(self.read_channel_data::<F80>(group, channel)?.into_iter().map(F80::to_f64).collect())
+    }
+    
+    /// Read a timestamp channel as `SystemTime`s, applying the file's timestamp convention
+    pub fn read_channel_system_times(&mut self, group: &str, channel: &str) -> Result<Vec<SystemTime>> {
+        Ok(self.read_channel_utc_timestamps(group, channel)?
+            .into_iter()
+            .map(|t| t.to_system_time())
+            .collect())
+    }
+    
+    /// Index of the first value of a timestamp channel at or after `timestamp`,
+    /// or the channel's length if every value is earlier
+    /// 
+    /// The channel must be in increasing order; on an unsorted channel the
+    /// result is some index, but not a meaningful one. The search bisects with
+    /// single-value reads until a few thousand values remain and reads those in
+    /// one chunk, so it touches a handful of small ranges instead of loading
+    /// the channel. Values are compared as stored, without the timestamp
+    /// convention applied. Encoded channels are decoded in full and searched.
+    pub fn find_index_by_timestamp(&mut self, group: &str, channel: &str, timestamp: Timestamp) -> Result<u64> {
+        const FINAL_CHUNK: u64 = 4096;
+        if self.channel_encoding(group, channel)?.is_some() {
+            let values: Vec<Timestamp> = self.read_channel_data(group, channel)?;
+            return Ok(values.partition_point(|value| *value < timestamp) as u64);
+        }
+        let channel_reader = self.channel_reader(group, channel)?;
+        let (mut low, mut high) = (0, channel_reader.total_values());
+        while high - low > FINAL_CHUNK {
+            let mid = low + (high - low) / 2;
+            let value = channel_reader.read_chunk::<Timestamp, _>(&mut self.file, &self.segments, mid, 1)?[0];
+            self.record_conversions(&channel_reader, mid, 1);
+            if value < timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        
+        let count = high - low;
+        self.advise_read(&channel_reader, low, count);
+        let values = channel_reader.read_chunk::<Timestamp, _>(&mut self.file, &self.segments, low, count as usize)?;
+        self.record_conversions(&channel_reader, low, count);
+        Ok(low + values.partition_point(|value| *value < timestamp) as u64)
+    }
+    
+    /// The first and last `n` values of a channel, with its type and length,
+    /// for file browser previews and quick sanity checks
+    /// 
+    /// Only the two ends are read, so the cost doesn't grow with the channel.
+    /// Encoded channels are the exception: they have to be decoded in full.
+    pub fn preview<T: TdmsValue>(&mut self, group: &str, channel: &str, n: usize) -> Result<ChannelPreview<T>> {
+        if let Some((_, data_type)) = self.channel_encoding(group, channel)? {
+            let mut first: Vec<T> = self.read_channel_data(group, channel)?;
+            let count = first.len() as u64;
+            let last = first.split_off(preview_last_start(count, n) as usize);
+            first.truncate(n);
+            return Ok(ChannelPreview { data_type, count, first, last });
+        }
+        
+        let channel_reader = self.channel_reader(group, channel)?;
+        channel_reader.check_value_type::<T>()?;
+        let count = channel_reader.total_values();
+        let last_start = preview_last_start(count, n);
+        let mut ends = Vec::with_capacity(2);
+        for (start, len) in [(0, (n as u64).min(count)), (last_start, count - last_start)] {
+            self.advise_read(&channel_reader, start, len);
+            ends.push(channel_reader.read_chunk::<T, _>(&mut self.file, &self.segments, start, len as usize)?);
+            self.record_conversions(&channel_reader, start, len);
+        }
+        let last = ends.pop().expect("two ends");
+        let first = ends.pop().expect("two ends");
+        Ok(ChannelPreview { data_type: channel_reader.data_type(), count, first, last })
+    }
+    
+    /// [`preview`](Self::preview) for string channels
+    pub fn preview_strings(&mut self, group: &str, channel: &str, n: usize) -> Result<ChannelPreview<String>> {
+        let channel_reader = self.channel_reader(group, channel)?;
+        if channel_reader.data_type() != DataType::String {
+            return Err(TdmsError::TypeMismatch {
+                expected: "String".to_string(),
+                found: format!("{:?}", channel_reader.data_type()),
+            });
+        }
+        let count = channel_reader.total_values();
+        let last_start = preview_last_start(count, n);
+        let mut ends = Vec::with_capacity(2);
+        for (start, len) in [(0, (n as u64).min(count)), (last_start, count - last_start)] {
+            self.advise_read(&channel_reader, start, len);
+            ends.push(channel_reader.read_string_chunk(&mut self.file, &self.segments, start, len as usize)?);
+        }
+        let last = ends.pop().expect("two ends");
+        let first = ends.pop().expect("two ends");
+        Ok(ChannelPreview { data_type: DataType::String, count, first, last })
     }
     
     /// Read string data from a channel (convenience method)
@@ -549,10 +2243,47 @@ impl<R: ReadSeek> TdmsReader<R> {
         let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
         let key_string = path.to_string();
         let channel_reader = self.channels.get(&path)
-            .map(|info| ChannelReader::new(key_string.clone(), info.clone()))
-            .ok_or_else(|| TdmsError::ChannelNotFound(key_string))?;
+            .map(|info| ChannelReader::new(key_string.clone(), info.clone()).with_string_encoding(self.string_encoding))
+            .ok_or(TdmsError::ChannelNotFound(key_string))?;
         
-        channel_reader.read_all_strings(&mut self.file, &self.segments)
+        self.advise_read(&channel_reader, 0, channel_reader.total_values());
+        let strings = channel_reader.read_all_strings(&mut self.file, &self.segments)?;
+        self.record_conversions(&channel_reader, 0, channel_reader.total_values());
+        Ok(strings)
+    }
+
+    /// Events of the event channel `name` with times in `range`, in the order
+    /// they were written (see [`crate::properties::events`])
+    /// 
+    /// The time channel is the one the message channel's
+    /// `tdms_rs_event_time_channel` property names, or `"<name> Time"`. A
+    /// message without a time (e.g. cut off at the end of a crashed file) is
+    /// left out.
+    pub fn read_events(&mut self, group: &str, name: &str, range: impl RangeBounds<Timestamp>) -> Result<Vec<Event>> {
+        let time_channel = self.get_channel_properties(group, name)
+            .and_then(|properties| properties.get(events::EVENT_TIME_CHANNEL))
+            .and_then(|property| property.value.as_str())
+            .map_or_else(|| events::time_channel_name(name), str::to_string);
+        let times: Vec<Timestamp> = self.read_channel_data(group, &time_channel)?;
+        let messages = self.read_channel_strings(group, name)?;
+        Ok(times.into_iter().zip(messages)
+            .filter(|(time, _)| range.contains(time))
+            .map(|(time, message)| Event { time, message })
+            .collect())
+    }
+
+    /// Read data from a channel by id (see [`read_channel_data`](Self::read_channel_data))
+    pub fn read_by_id<T: TdmsValue>(&mut self, id: ChannelId) -> Result<Vec<T>> {
+        let (group, channel) = self.channel_names(id)?;
+        let (group, channel) = (group.to_string(), channel.to_string());
+        self.read_channel_data(&group, &channel)
+    }
+    
+    /// Read string data from a channel by id (see [`read_channel_strings`](Self::read_channel_strings))
+    pub fn read_strings_by_id(&mut self, id: ChannelId) -> Result<Vec<String>> {
+        let (group, channel) = self.channel_names(id)?;
+        let (group, channel) = (group.to_string(), channel.to_string());
+        self.read_channel_strings(&group, &channel)
     }
 
     /// Get an iterator over the data in a channel, reading in chunks.
@@ -566,25 +2297,94 @@ impl<R: ReadSeek> TdmsReader<R> {
     /// * `group` - The group name
     /// * `channel` - The channel name
     /// * `chunk_size` - The number of values to read per iteration
-    pub fn iter_channel_data<T: Copy + Default>(
+    ///
+    /// Encoded channels can't be read in chunks and fail with
+    /// [`TdmsError::Unsupported`]; read them with
+    /// [`read_channel_data`](Self::read_channel_data) or
+    /// [`read_channel_range`](Self::read_channel_range).
+    pub fn iter_channel_data<T: TdmsValue>(
         &mut self,
         group: &str,
         channel: &str,
         chunk_size: usize,
     ) -> Result<TdmsIter<'_, T, R>> {
-        let path = ObjectPath::Channel { 
-            group: group.to_string(), 
-            channel: channel.to_string() 
-        };
-        let key_string = path.to_string();
-        
-        let channel_reader = self.channels.get(&path)
-            .map(|info| ChannelReader::new(key_string.clone(), info.clone()))
-            .ok_or_else(|| TdmsError::ChannelNotFound(key_string))?;
-            
+        self.reject_encoded(group, channel)?;
+        let channel_reader = self.channel_reader(group, channel)?;
         Ok(TdmsIter::new(self, channel_reader, chunk_size))
     }
 
+    /// Get an iterator over windows of `window` values, each starting `hop`
+    /// values after the previous one (see [`TdmsWindowIter`]). Encoded
+    /// channels are rejected as by [`iter_channel_data`](Self::iter_channel_data).
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::*;
+    /// 
+    /// let mut reader = TdmsReader::open("vibration.tdms").unwrap();
+    /// // 1024-point frames with 50% overlap, e.g. for a Welch spectrum
+    /// for frame in reader.iter_channel_windows::<f64>("Rack", "Accel", 1024, 512).unwrap() {
+    ///     let frame = frame.unwrap();
+    ///     # let _ = frame;
+    /// }
+    /// ```
+    pub fn iter_channel_windows<T: TdmsValue>(
+        &mut self,
+        group: &str,
+        channel: &str,
+        window: usize,
+        hop: usize,
+    ) -> Result<TdmsWindowIter<'_, T, R>> {
+        if window == 0 || hop == 0 {
+            return Err(TdmsError::Unsupported(format!("window of {} values with a hop of {}; both must be non-zero", window, hop)));
+        }
+        self.reject_encoded(group, channel)?;
+        let channel_reader = self.channel_reader(group, channel)?;
+        Ok(TdmsWindowIter::new(self, channel_reader, window, hop))
+    }
+
+    /// Get an iterator over several channels at once, yielding a chunk of up
+    /// to `chunk_size` values from each channel per step (see [`TdmsZipIter`]).
+    /// 
+    /// The channels must hold the same number of values; otherwise this fails
+    /// rather than silently pairing values up wrongly. Encoded channels are
+    /// rejected as by [`iter_channel_data`](Self::iter_channel_data).
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::*;
+    /// 
+    /// let mut reader = TdmsReader::open("power.tdms").unwrap();
+    /// for chunks in reader.iter_channels_zipped::<f64>(&[("Rack", "Voltage"), ("Rack", "Current")], 4096).unwrap() {
+    ///     let chunks = chunks.unwrap();
+    ///     let power: Vec<f64> = chunks[0].iter().zip(&chunks[1]).map(|(v, i)| v * i).collect();
+    ///     # let _ = power;
+    /// }
+    /// ```
+    pub fn iter_channels_zipped<T: TdmsValue>(
+        &mut self,
+        channels: &[(&str, &str)],
+        chunk_size: usize,
+    ) -> Result<TdmsZipIter<'_, T, R>> {
+        let readers = channels.iter()
+            .map(|(group, channel)| {
+                self.reject_encoded(group, channel)?;
+                self.channel_reader(group, channel)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(first) = readers.first() {
+            if let Some(other) = readers.iter().find(|r| r.total_values() != first.total_values()) {
+                return Err(TdmsError::Unsupported(format!(
+                    "can't iterate channels of different lengths together: {} has {} values, {} has {}",
+                    first.key(), first.total_values(), other.key(), other.total_values(),
+                )));
+            }
+        }
+        Ok(TdmsZipIter::new(self, readers, chunk_size))
+    }
+
     /// Get an iterator over the string data in a channel, reading in chunks.
     pub fn iter_channel_strings(
         &mut self,
@@ -592,19 +2392,69 @@ impl<R: ReadSeek> TdmsReader<R> {
         channel: &str,
         chunk_size: usize,
     ) -> Result<TdmsStringIter<'_, R>> {
-        let path = ObjectPath::Channel { 
-            group: group.to_string(), 
-            channel: channel.to_string() 
-        };
-        let key_string = path.to_string();
-        
-        let channel_reader = self.channels.get(&path)
-            .map(|info| ChannelReader::new(key_string.clone(), info.clone()))
-            .ok_or_else(|| TdmsError::ChannelNotFound(key_string))?;
-            
+        let channel_reader = self.channel_reader(group, channel)?;
         Ok(TdmsStringIter::new(self, channel_reader, chunk_size))
     }
 
+    /// Like [`iter_channel_data`](Self::iter_channel_data), but the iterator
+    /// takes ownership of the reader instead of borrowing it.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::*;
+    /// 
+    /// fn voltages(path: &str) -> Result<impl Iterator<Item = Result<Vec<f64>>>> {
+    ///     TdmsReader::open(path)?.into_iter_channel_data("Group1", "Voltage", 4096)
+    /// }
+    /// 
+    /// let peak = voltages("data.tdms").unwrap()
+    ///     .map(|chunk| chunk.map(|values| values.into_iter().fold(f64::MIN, f64::max)))
+    ///     .collect::<Result<Vec<f64>>>().unwrap();
+    /// ```
+    pub fn into_iter_channel_data<T: TdmsValue>(
+        self,
+        group: &str,
+        channel: &str,
+        chunk_size: usize,
+    ) -> Result<OwnedTdmsIter<T, R>> {
+        self.reject_encoded(group, channel)?;
+        let channel_reader = self.channel_reader(group, channel)?;
+        Ok(OwnedTdmsIter::new(self, channel_reader, chunk_size))
+    }
+
+    /// Like [`iter_channel_strings`](Self::iter_channel_strings), but the
+    /// iterator takes ownership of the reader instead of borrowing it.
+    pub fn into_iter_channel_strings(
+        self,
+        group: &str,
+        channel: &str,
+        chunk_size: usize,
+    ) -> Result<OwnedTdmsStringIter<R>> {
+        let channel_reader = self.channel_reader(group, channel)?;
+        Ok(OwnedTdmsStringIter::new(self, channel_reader, chunk_size))
+    }
+
+    /// Fail for encoded channels, whose frames can't be cut at arbitrary values
+    fn reject_encoded(&self, group: &str, channel: &str) -> Result<()> {
+        match self.channel_encoding(group, channel)? {
+            Some((encoding, _)) => Err(TdmsError::Unsupported(format!(
+                "'{}'/'{}' is stored with the {} encoding and can't be read in chunks; \
+                 use read_channel_data or read_channel_range",
+                group, channel, encoding.name(),
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn channel_reader(&self, group: &str, channel: &str) -> Result<ChannelReader> {
+        let path = ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() };
+        let key_string = path.to_string();
+        self.channels.get(&path)
+            .map(|info| ChannelReader::new(key_string.clone(), info.clone()).with_string_encoding(self.string_encoding))
+            .ok_or(TdmsError::ChannelNotFound(key_string))
+    }
+
     /// Manually read the next chunk of data from a low-level StreamingReader.
     /// 
     /// This is useful if you are managing the `StreamingReader` manually for
@@ -614,11 +2464,23 @@ impl<R: ReadSeek> TdmsReader<R> {
     /// # Arguments
     /// 
     /// * `stream` - The mutable StreamingReader to read from.
-    pub fn read_streaming_data<T: Copy + Default>(
+    ///
+    /// Fails with [`TdmsError::Unsupported`] for encoded channels (see
+    /// [`iter_channel_data`](Self::iter_channel_data)).
+    pub fn read_streaming_data<T: TdmsValue>(
         &mut self,
         stream: &mut StreamingReader
     ) -> Result<Option<Vec<T>>> {
-        stream.next(&mut self.file, &self.segments)
+        if let Ok(ObjectPath::Channel { group, channel }) = ObjectPath::from_string(stream.channel().key()) {
+            self.reject_encoded(&group, &channel)?;
+        }
+        let start = stream.position();
+        self.advise_read(stream.channel(), start, 2 * stream.chunk_size() as u64);
+        let chunk = stream.next::<T, _>(&mut self.file, &self.segments)?;
+        if let Some(chunk) = &chunk {
+            self.record_conversions(stream.channel(), start, chunk.len() as u64);
+        }
+        Ok(chunk)
     }
 
     /// Manually read the next chunk of string data from a low-level StreamingReader.
@@ -633,7 +2495,13 @@ impl<R: ReadSeek> TdmsReader<R> {
         &mut self,
         stream: &mut StreamingReader
     ) -> Result<Option<Vec<String>>> {
-        stream.next_strings(&mut self.file, &self.segments)
+        let start = stream.position();
+        self.advise_read(stream.channel(), start, 2 * stream.chunk_size() as u64);
+        let chunk = stream.next_strings(&mut self.file, &self.segments)?;
+        if let Some(chunk) = &chunk {
+            self.record_conversions(stream.channel(), start, chunk.len() as u64);
+        }
+        Ok(chunk)
     }
     
     // Helper methods for reading with endianness
@@ -665,10 +2533,25 @@ impl<R: ReadSeek> TdmsReader<R> {
         self.string_buffer.resize(length as usize, 0);
         self.file.read_exact(&mut self.string_buffer)?;
         
-        String::from_utf8(self.string_buffer.clone()).map_err(|_| TdmsError::InvalidUtf8)
+        self.string_encoding.decode(&self.string_buffer)
     }
 }
 
+/// Shift a timestamp by a signed number of seconds
+fn offset_by(timestamp: Timestamp, seconds: f64) -> Timestamp {
+    if seconds >= 0.0 {
+        timestamp.add_duration(Duration::from_secs_f64(seconds))
+    } else {
+        timestamp.sub_duration(Duration::from_secs_f64(-seconds))
+    }
+}
+
+/// Where the last `n` of `count` values start, without overlapping the first `n`
+fn preview_last_start(count: u64, n: usize) -> u64 {
+    let n = n as u64;
+    count.saturating_sub(n).max(n).min(count)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
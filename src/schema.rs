@@ -0,0 +1,396 @@
+// src/schema.rs
+//! Declaring what a file must contain and enforcing it while writing.
+//!
+//! A [`ChannelSchema`] lists the channels a test plan expects, with their
+//! data types and units, and optionally which channel property names are
+//! allowed. A writer given a schema with
+//! [`TdmsWriter::set_schema`](crate::TdmsWriter::set_schema) checks each
+//! channel as it is created and the whole file on every flush, and fails with
+//! [`TdmsError::SchemaViolation`] when the acquisition deviates from the plan.
+//!
+//! Property names starting with `tdms_rs_` belong to this crate's own
+//! conventions (encoded channels, alignment, sample accounting, ...) and are
+//! always allowed.
+//!
+//! Rather than writing a schema by hand, [`infer_schema`] captures one from a
+//! golden file. With the `json` or `yaml` feature, schemas can be saved next
+//! to a test plan and loaded again for later runs.
+
+use crate::error::{Result, TdmsError};
+use crate::metadata::{ChannelMetadata, ObjectPath};
+use crate::properties::wellknown;
+use crate::reader::TdmsReader;
+use crate::types::DataType;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Seek};
+use std::path::Path;
+
+/// Prefix of the property names this crate writes itself
+const OWN_PROPERTY_PREFIX: &str = "tdms_rs_";
+
+/// What one channel of a [`ChannelSchema`] must look like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelSpec {
+    pub data_type: DataType,
+    /// Required value of the `unit_string` property; `None` accepts any
+    pub unit: Option<String>,
+    /// Whether the channel must exist by the first flush
+    pub required: bool,
+}
+
+impl ChannelSpec {
+    /// A required channel of `data_type` with any unit
+    pub fn new(data_type: DataType) -> Self {
+        ChannelSpec { data_type, unit: None, required: true }
+    }
+
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// Allow the channel to be missing
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+}
+
+/// The channels, types, units and property names a file may have
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::{DataType, TdmsWriter};
+/// use tdms_rs::schema::{ChannelSchema, ChannelSpec};
+///
+/// let schema = ChannelSchema::new()
+///     .channel("Rack", "Pressure", ChannelSpec::new(DataType::DoubleFloat).unit("bar"))
+///     .channel("Rack", "Comment", ChannelSpec::new(DataType::String).optional())
+///     .allow_properties(["unit_string", "description"]);
+///
+/// let mut writer = TdmsWriter::create("run.tdms").unwrap();
+/// writer.set_schema(schema).unwrap();
+/// // Fails: the plan says Pressure is a DoubleFloat
+/// assert!(writer.create_channel("Rack", "Pressure", DataType::SingleFloat).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelSchema {
+    channels: BTreeMap<ObjectPath, ChannelSpec>,
+    allowed_properties: Option<BTreeSet<String>>,
+    allow_extra_channels: bool,
+}
+
+impl ChannelSchema {
+    /// A schema without channels, which allows no channels at all
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a channel
+    pub fn channel(mut self, group: impl Into<String>, channel: impl Into<String>, spec: ChannelSpec) -> Self {
+        self.channels.insert(ObjectPath::Channel { group: group.into(), channel: channel.into() }, spec);
+        self
+    }
+
+    /// Restrict channel property names to these, plus `unit_string` if any
+    /// channel has a unit. Without this any name is allowed.
+    pub fn allow_properties<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.allowed_properties.get_or_insert_with(BTreeSet::new)
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Accept channels the schema doesn't list
+    pub fn allow_extra_channels(mut self, allow: bool) -> Self {
+        self.allow_extra_channels = allow;
+        self
+    }
+
+    /// The listed channels, sorted by path
+    pub fn channels(&self) -> impl Iterator<Item = (&ObjectPath, &ChannelSpec)> {
+        self.channels.iter()
+    }
+
+    /// The spec of one channel
+    pub fn get(&self, group: &str, channel: &str) -> Option<&ChannelSpec> {
+        self.channels.get(&ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() })
+    }
+
+    /// Allowed channel property names, or `None` if any name is allowed
+    pub fn allowed_properties(&self) -> Option<&BTreeSet<String>> {
+        self.allowed_properties.as_ref()
+    }
+
+    pub fn allows_extra_channels(&self) -> bool {
+        self.allow_extra_channels
+    }
+
+    /// The schema a file satisfies: every channel required, with its data
+    /// type and `unit_string`, and channel property names restricted to the
+    /// ones the file uses. Encoded channels get their logical type.
+    pub fn infer<R: Read + Seek>(reader: &TdmsReader<R>) -> Result<Self> {
+        let mut schema = ChannelSchema::new().allow_properties(std::iter::empty::<String>());
+        for (group, group_properties) in reader.all_properties().groups {
+            for (channel, properties) in group_properties.channels {
+                let data_type = match reader.channel_encoding(group, channel)? {
+                    Some((_, logical)) => logical,
+                    None => reader.get_channel(&ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() }.to_string())
+                        .ok_or_else(|| TdmsError::ChannelNotFound(format!("{}/{}", group, channel)))?
+                        .data_type(),
+                };
+                let mut spec = ChannelSpec::new(data_type);
+                spec.unit = wellknown::unit_string(properties).map(str::to_string);
+                schema = schema.channel(group, channel, spec)
+                    .allow_properties(properties.keys()
+                        .filter(|name| !name.starts_with(OWN_PROPERTY_PREFIX) && *name != wellknown::UNIT_STRING));
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Check a channel about to be created with `data_type`
+    pub(crate) fn check_channel(&self, path: &ObjectPath, data_type: DataType) -> Result<()> {
+        match self.channels.get(path) {
+            Some(spec) if spec.data_type != data_type => Err(TdmsError::SchemaViolation(
+                format!("{} is {:?}, the schema says {:?}", path, data_type, spec.data_type))),
+            Some(_) => Ok(()),
+            None if self.allow_extra_channels => Ok(()),
+            None => Err(TdmsError::SchemaViolation(format!("{} is not in the schema", path))),
+        }
+    }
+
+    /// Check everything a writer is about to commit
+    pub(crate) fn check_channels(&self, channels: &HashMap<ObjectPath, ChannelMetadata>) -> Result<()> {
+        for (path, spec) in &self.channels {
+            let Some(metadata) = channels.get(path) else {
+                if spec.required {
+                    return Err(TdmsError::SchemaViolation(format!("required channel {} was not created", path)));
+                }
+                continue;
+            };
+            if let Some(unit) = &spec.unit {
+                let found = wellknown::unit_string(&metadata.properties);
+                if found != Some(unit.as_str()) {
+                    return Err(TdmsError::SchemaViolation(
+                        format!("{} has unit {:?}, the schema says {:?}", path, found, unit)));
+                }
+            }
+        }
+
+        if let Some(allowed) = &self.allowed_properties {
+            let mut paths: Vec<&ObjectPath> = channels.keys().collect();
+            paths.sort_unstable();
+            for path in paths {
+                let has_unit = self.channels.get(path).is_some_and(|spec| spec.unit.is_some());
+                let mut names: Vec<&String> = channels[path].properties.keys().collect();
+                names.sort_unstable();
+                for name in names {
+                    let permitted = allowed.contains(name)
+                        || name.starts_with(OWN_PROPERTY_PREFIX)
+                        || (has_unit && name == wellknown::UNIT_STRING);
+                    if !permitted {
+                        return Err(TdmsError::SchemaViolation(
+                            format!("property {:?} of {} is not allowed", name, path)));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Infer the schema of the file at `path` (see [`ChannelSchema::infer`])
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::TdmsWriter;
+/// use tdms_rs::schema::infer_schema;
+///
+/// let schema = infer_schema("golden.tdms").unwrap();
+/// let mut writer = TdmsWriter::create("run.tdms").unwrap();
+/// writer.set_schema(schema).unwrap();
+/// ```
+pub fn infer_schema(path: impl AsRef<Path>) -> Result<ChannelSchema> {
+    ChannelSchema::infer(&TdmsReader::open(path)?)
+}
+
+/// The serialized form of a [`ChannelSchema`]: channels as a list with
+/// readable type names rather than a map keyed by object path
+#[cfg(any(feature = "json", feature = "yaml"))]
+mod document {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct SchemaDocument {
+        channels: Vec<ChannelDocument>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        allowed_properties: Option<BTreeSet<String>>,
+        #[serde(default)]
+        allow_extra_channels: bool,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct ChannelDocument {
+        group: String,
+        channel: String,
+        data_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<String>,
+        #[serde(default = "required_default")]
+        required: bool,
+    }
+
+    fn required_default() -> bool {
+        true
+    }
+
+    impl From<&ChannelSchema> for SchemaDocument {
+        fn from(schema: &ChannelSchema) -> Self {
+            let channels = schema.channels.iter()
+                .filter_map(|(path, spec)| match path {
+                    ObjectPath::Channel { group, channel } => Some(ChannelDocument {
+                        group: group.clone(),
+                        channel: channel.clone(),
+                        data_type: spec.data_type.name().to_string(),
+                        unit: spec.unit.clone(),
+                        required: spec.required,
+                    }),
+                    _ => None,
+                })
+                .collect();
+            SchemaDocument {
+                channels,
+                allowed_properties: schema.allowed_properties.clone(),
+                allow_extra_channels: schema.allow_extra_channels,
+            }
+        }
+    }
+
+    impl TryFrom<SchemaDocument> for ChannelSchema {
+        type Error = TdmsError;
+
+        fn try_from(document: SchemaDocument) -> Result<Self> {
+            let mut schema = ChannelSchema {
+                channels: BTreeMap::new(),
+                allowed_properties: document.allowed_properties,
+                allow_extra_channels: document.allow_extra_channels,
+            };
+            for channel in document.channels {
+                let data_type = DataType::from_name(&channel.data_type).ok_or_else(|| TdmsError::InvalidSchema(
+                    format!("unknown data type {:?} for {}/{}", channel.data_type, channel.group, channel.channel)))?;
+                let spec = ChannelSpec { data_type, unit: channel.unit, required: channel.required };
+                schema = schema.channel(channel.group, channel.channel, spec);
+            }
+            Ok(schema)
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ChannelSchema {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&document::SchemaDocument::from(self))
+            .expect("schema documents always serialize")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let document: document::SchemaDocument = serde_json::from_str(json)
+            .map_err(|e| TdmsError::InvalidSchema(e.to_string()))?;
+        document.try_into()
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl ChannelSchema {
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(&document::SchemaDocument::from(self))
+            .expect("schema documents always serialize")
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let document: document::SchemaDocument = serde_yaml::from_str(yaml)
+            .map_err(|e| TdmsError::InvalidSchema(e.to_string()))?;
+        document.try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PropertyValue;
+
+    fn path(channel: &str) -> ObjectPath {
+        ObjectPath::Channel { group: "Rack".into(), channel: channel.into() }
+    }
+
+    #[test]
+    fn test_check_channel() {
+        let schema = ChannelSchema::new()
+            .channel("Rack", "Pressure", ChannelSpec::new(DataType::DoubleFloat));
+        assert!(schema.check_channel(&path("Pressure"), DataType::DoubleFloat).is_ok());
+        assert!(matches!(schema.check_channel(&path("Pressure"), DataType::I32), Err(TdmsError::SchemaViolation(_))));
+        assert!(schema.check_channel(&path("Other"), DataType::I32).is_err());
+        assert!(schema.allow_extra_channels(true).check_channel(&path("Other"), DataType::I32).is_ok());
+    }
+
+    #[test]
+    fn test_check_channels() {
+        let schema = ChannelSchema::new()
+            .channel("Rack", "Pressure", ChannelSpec::new(DataType::DoubleFloat).unit("bar"))
+            .channel("Rack", "Comment", ChannelSpec::new(DataType::String).optional())
+            .allow_properties(["description"]);
+
+        let mut channels = HashMap::new();
+        assert!(schema.check_channels(&channels).unwrap_err().to_string().contains("required"));
+
+        let mut pressure = ChannelMetadata::new("Rack", "Pressure", DataType::DoubleFloat);
+        channels.insert(path("Pressure"), pressure.clone());
+        assert!(schema.check_channels(&channels).unwrap_err().to_string().contains("unit"));
+
+        pressure.set_property(wellknown::UNIT_STRING, PropertyValue::String("bar".into()));
+        pressure.set_property("tdms_rs_start_index", PropertyValue::U64(0));
+        channels.insert(path("Pressure"), pressure.clone());
+        assert!(schema.check_channels(&channels).is_ok());
+
+        pressure.set_property("operator", PropertyValue::String("ana".into()));
+        channels.insert(path("Pressure"), pressure);
+        assert!(schema.check_channels(&channels).unwrap_err().to_string().contains("operator"));
+    }
+
+    #[cfg(any(feature = "json", feature = "yaml"))]
+    fn sample_schema() -> ChannelSchema {
+        ChannelSchema::new()
+            .channel("Rack", "Pressure", ChannelSpec::new(DataType::DoubleFloat).unit("bar"))
+            .channel("Rack", "Comment", ChannelSpec::new(DataType::String).optional())
+            .allow_properties(["description"])
+            .allow_extra_channels(true)
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_round_trip() {
+        let schema = sample_schema();
+        let json = schema.to_json();
+        assert!(json.contains("\"data_type\": \"f64\""));
+        assert_eq!(ChannelSchema::from_json(&json).unwrap(), schema);
+
+        let minimal = r#"{"channels": [{"group": "Rack", "channel": "Flow", "data_type": "i32"}]}"#;
+        let parsed = ChannelSchema::from_json(minimal).unwrap();
+        assert_eq!(parsed.get("Rack", "Flow"), Some(&ChannelSpec::new(DataType::I32)));
+        assert_eq!(parsed.allowed_properties(), None);
+
+        let bad = r#"{"channels": [{"group": "Rack", "channel": "Flow", "data_type": "int"}]}"#;
+        assert!(matches!(ChannelSchema::from_json(bad), Err(TdmsError::InvalidSchema(_))));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_round_trip() {
+        let schema = sample_schema();
+        assert_eq!(ChannelSchema::from_yaml(&schema.to_yaml()).unwrap(), schema);
+        assert!(ChannelSchema::from_yaml("channels: 3").is_err());
+    }
+}
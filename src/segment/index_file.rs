@@ -0,0 +1,98 @@
+// src/segment/index_file.rs
+use super::SegmentHeader;
+use crate::error::{TdmsError, Result};
+use crate::types::TocFlags;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Path of the index file that goes with a TDMS data file.
+///
+/// NI software appends `_index` to the full file name, so `run.tdms` gets
+/// `run.tdms_index` and `run.dat` gets `run.dat_index`. This only builds the
+/// path; it does not check that the file exists.
+pub fn index_path_for(path: impl AsRef<Path>) -> PathBuf {
+    let mut index_path = OsString::from(path.as_ref().as_os_str());
+    index_path.push("_index");
+    PathBuf::from(index_path)
+}
+
+/// The index file next to a data file, if there is one.
+///
+/// Looks for [`index_path_for`] first, then for the `<stem>.tdms_index` name
+/// older versions of this crate wrote for data files not ending in `.tdms`.
+pub fn find_index_file(path: impl AsRef<Path>) -> Option<PathBuf> {
+    let path = path.as_ref();
+    [index_path_for(path), path.with_extension("tdms_index")]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
+
+/// Write the index file for the TDMS file at `data_path`, replacing any file
+/// at `index_path`, and return the number of segments indexed.
+///
+/// Each segment's lead-in and metadata are copied with the tag changed to
+/// `TDSh`, as LabVIEW does, so files from writers that don't keep an index
+/// open quickly afterwards (see
+/// [`TdmsReader::open_indexed`](crate::TdmsReader::open_indexed)). Only the
+/// lead-ins and metadata are read; a final segment that is still being
+/// written is left out.
+pub fn write_index_file(data_path: impl AsRef<Path>, index_path: impl AsRef<Path>) -> Result<usize> {
+    let mut data = BufReader::new(File::open(data_path)?);
+    let data_size = data.seek(SeekFrom::End(0))?;
+    let mut index = BufWriter::new(File::create(index_path)?);
+
+    let mut offset = 0u64;
+    let mut segments = 0;
+    let mut metadata = Vec::new();
+    while data_size - offset >= SegmentHeader::LEAD_IN_SIZE as u64 {
+        let mut lead_in = [0u8; SegmentHeader::LEAD_IN_SIZE];
+        data.seek(SeekFrom::Start(offset))?;
+        data.read_exact(&mut lead_in)?;
+        if &lead_in[..4] != SegmentHeader::TDMS_TAG {
+            return Err(TdmsError::InvalidTag {
+                expected: "TDSm".to_string(),
+                found: String::from_utf8_lossy(&lead_in[..4]).to_string(),
+            });
+        }
+        let toc = TocFlags::new(u32::from_le_bytes(lead_in[4..8].try_into().unwrap()));
+        let read_u64 = |bytes: &[u8]| {
+            let bytes = bytes.try_into().unwrap();
+            if toc.is_big_endian() { u64::from_be_bytes(bytes) } else { u64::from_le_bytes(bytes) }
+        };
+        let next_segment_offset = read_u64(&lead_in[12..20]);
+        let metadata_size = read_u64(&lead_in[20..28]);
+
+        let available = data_size - offset - SegmentHeader::LEAD_IN_SIZE as u64;
+        if next_segment_offset == SegmentHeader::INCOMPLETE_MARKER
+            || next_segment_offset > available
+            || metadata_size > next_segment_offset
+        {
+            break;
+        }
+
+        metadata.resize(metadata_size as usize, 0);
+        data.read_exact(&mut metadata)?;
+        index.write_all(SegmentHeader::INDEX_TAG)?;
+        index.write_all(&lead_in[4..])?;
+        index.write_all(&metadata)?;
+        segments += 1;
+        offset += SegmentHeader::LEAD_IN_SIZE as u64 + next_segment_offset;
+    }
+    index.flush()?;
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_path_for() {
+        assert_eq!(index_path_for("run.tdms"), PathBuf::from("run.tdms_index"));
+        assert_eq!(index_path_for("dir/run.1.tdms"), PathBuf::from("dir/run.1.tdms_index"));
+        assert_eq!(index_path_for("run.dat"), PathBuf::from("run.dat_index"));
+        assert_eq!(index_path_for("run"), PathBuf::from("run_index"));
+    }
+}
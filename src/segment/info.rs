@@ -1,5 +1,5 @@
 // src/segment/info.rs
-use crate::types::TocFlags;
+use crate::types::{TocFlags, Timestamp};
 
 /// Segment information for reading
 #[derive(Debug, Clone)]
@@ -7,7 +7,31 @@ pub struct SegmentInfo {
     pub offset: u64,
     pub toc: TocFlags,
     pub is_big_endian: bool,
+    /// Version number from the lead-in: 4713 for TDMS 2.0, 4712 for 1.0
+    pub version: u32,
     pub metadata_size: u64,
     /// This is the true size of the raw data block (Next Segment Offset - metadata_size)
     pub total_raw_data_size: u64,
-}
\ No newline at end of file
+}
+impl SegmentInfo {
+    /// Whether the segment's raw data contains DAQmx formatted channels
+    pub fn has_daqmx_data(&self) -> bool {
+        self.toc.has_daqmx_data()
+    }
+}
+
+/// Estimated wall-clock interval covered by a segment's raw data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentTimeRange {
+    /// Time of the earliest sample
+    pub start: Timestamp,
+    /// Time just after the latest sample (start of the next sample period)
+    pub end: Timestamp,
+}
+
+impl SegmentTimeRange {
+    /// Whether this range overlaps the half-open interval `[start, end)`
+    pub fn overlaps(&self, start: Timestamp, end: Timestamp) -> bool {
+        self.start < end && start < self.end
+    }
+}
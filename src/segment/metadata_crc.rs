@@ -0,0 +1,114 @@
+// src/segment/metadata_crc.rs
+use crate::error::{TdmsError, Result};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Path of the metadata checksum sidecar that goes with a TDMS data file:
+/// `_metadata_crc` appended to the full file name, like [`index_path_for`](super::index_path_for)
+pub fn metadata_crc_path_for(path: impl AsRef<Path>) -> PathBuf {
+    let mut crc_path = OsString::from(path.as_ref().as_os_str());
+    crc_path.push("_metadata_crc");
+    PathBuf::from(crc_path)
+}
+
+/// CRC-32 (IEEE 802.3, as used by zip and PNG) of `bytes`
+pub fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+    !bytes.iter().fold(!0u32, |crc, &byte| TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+/// The checksum of one segment's metadata section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataCrcRecord {
+    /// Offset of the segment's lead-in in the data file
+    pub offset: u64,
+    pub metadata_size: u64,
+    pub crc: u32,
+}
+
+impl MetadataCrcRecord {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let record = MetadataCrcRecord {
+            offset: fields.next()?.parse().ok()?,
+            metadata_size: fields.next()?.parse().ok()?,
+            crc: u32::from_str_radix(fields.next()?, 16).ok()?,
+        };
+        fields.next().is_none().then_some(record)
+    }
+}
+
+/// Read the records of a metadata checksum sidecar, in the order written
+pub fn read_metadata_crc_file(path: impl AsRef<Path>) -> Result<Vec<MetadataCrcRecord>> {
+    let mut records = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        records.push(MetadataCrcRecord::parse(&line).ok_or_else(|| TdmsError::InvalidTag {
+            expected: "<offset> <metadata size> <crc32 hex>".to_string(),
+            found: line.clone(),
+        })?);
+    }
+    Ok(records)
+}
+
+/// Append-only sidecar of metadata checksums, one line per segment carrying
+/// metadata, flushed as each segment is committed:
+///
+/// ```text
+/// 0 212 5d3c0a7e
+/// 412 96 0b41c2f9
+/// ```
+pub(crate) struct MetadataCrcLog {
+    file: BufWriter<File>,
+}
+
+impl MetadataCrcLog {
+    /// Start a new sidecar, replacing any earlier one
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(MetadataCrcLog { file: BufWriter::new(File::create(path)?) })
+    }
+
+    pub fn record(&mut self, offset: u64, metadata: &[u8]) -> Result<()> {
+        writeln!(self.file, "{} {} {:08x}", offset, metadata.len(), crc32(metadata))?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_parse_record() {
+        assert_eq!(MetadataCrcRecord::parse("412 96 0b41c2f9"),
+            Some(MetadataCrcRecord { offset: 412, metadata_size: 96, crc: 0x0B41_C2F9 }));
+        assert_eq!(MetadataCrcRecord::parse("412 96"), None);
+        assert_eq!(MetadataCrcRecord::parse("412 96 0b41c2f9 1"), None);
+    }
+}
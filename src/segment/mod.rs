@@ -1,9 +1,14 @@
 // src/segment/mod.rs
 mod header;
 mod info;
+mod index_file;
+mod metadata_crc;
 
 pub use header::SegmentHeader;
-pub use info::SegmentInfo;
+pub use info::{SegmentInfo, SegmentTimeRange};
+pub use index_file::{index_path_for, find_index_file, write_index_file};
+pub use metadata_crc::{metadata_crc_path_for, crc32, read_metadata_crc_file, MetadataCrcRecord};
+pub(crate) use metadata_crc::MetadataCrcLog;
 
 #[derive(Debug)]
 pub struct Segment {
@@ -0,0 +1,319 @@
+// src/stitch.rs
+//! Merging files recorded in parallel into one run file.
+//!
+//! Distributed acquisition often produces one file per machine, each holding
+//! different channels of the same run. [`stitch_files`] combines them into a
+//! single file and lines the waveform channels up in time: every channel with
+//! `wf_start_time` and `wf_increment` is trimmed so it starts at (or just after)
+//! the latest start time of all sources, and its `wf_start_time` is updated to
+//! match. Channels without waveform timing are copied unchanged.
+//!
+//! [`stitch_files_with`] can also skip channels that were captured into more
+//! than one source, e.g. after a re-upload or with overlapping rotated files,
+//! as long as every copy holds the same values.
+
+use crate::error::{TdmsError, Result};
+use crate::metadata::ObjectPath;
+use crate::properties::{waveform, WaveformTiming};
+use crate::reader::TdmsReader;
+use crate::raw_data::RawDataBuffer;
+use crate::types::{Complex, DataType, F80, Property, PropertyValue, TdmsValue, Timestamp};
+use crate::writer::TdmsWriter;
+use crate::codec;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A channel copied by [`stitch_files`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StitchedChannel {
+    /// File the channel came from
+    pub source: PathBuf,
+    pub path: ObjectPath,
+    /// Leading samples dropped to align the channel with the common start
+    pub skipped_samples: u64,
+}
+
+/// Summary of a [`stitch_files`] run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StitchReport {
+    /// Common start time the waveform channels were aligned to, if any had timing
+    pub common_start: Option<Timestamp>,
+    pub channels: Vec<StitchedChannel>,
+    /// Later copies of channels skipped because their values matched the
+    /// copy already taken (see [`StitchOptions::skip_duplicates`])
+    pub duplicates: Vec<StitchedChannel>,
+}
+
+/// Options for [`stitch_files_with`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StitchOptions {
+    /// When a channel appears in more than one source, keep the first copy and
+    /// skip the others if their data type and values are identical, instead of
+    /// failing. Copies whose values differ are still an error. Properties are
+    /// not compared, and the comparison is of the source values before any
+    /// alignment. Values compare bitwise, so matching NaNs are identical. The
+    /// first copy of a duplicated channel is kept in memory for the rest of
+    /// the run, so later copies are compared without reading it again.
+    pub skip_duplicates: bool,
+}
+
+/// Merge files that hold different channels of the same run into `dest`.
+///
+/// File and group properties are merged with the first source taking precedence.
+/// A channel present in more than one source is an error, since there is no way
+/// to tell which recording is authoritative; see [`stitch_files_with`] to skip
+/// identical copies instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::stitch::stitch_files;
+///
+/// let report = stitch_files(&["rack_a.tdms", "rack_b.tdms"], "run.tdms").unwrap();
+/// for channel in &report.channels {
+///     println!("{} (dropped {} samples)", channel.path, channel.skipped_samples);
+/// }
+/// ```
+pub fn stitch_files<P: AsRef<Path>>(sources: &[P], dest: impl AsRef<Path>) -> Result<StitchReport> {
+    stitch_files_with(sources, dest, StitchOptions::default())
+}
+
+/// [`stitch_files`] with options.
+///
+/// # Example
+///
+/// ```no_run
+/// use tdms_rs::stitch::{stitch_files_with, StitchOptions};
+///
+/// let options = StitchOptions { skip_duplicates: true };
+/// let report = stitch_files_with(&["part_1.tdms", "part_1_reupload.tdms"], "run.tdms", options).unwrap();
+/// println!("skipped {} duplicate channels", report.duplicates.len());
+/// ```
+pub fn stitch_files_with<P: AsRef<Path>>(sources: &[P], dest: impl AsRef<Path>, options: StitchOptions) -> Result<StitchReport> {
+    let mut readers = Vec::with_capacity(sources.len());
+    for source in sources {
+        readers.push((source.as_ref().to_path_buf(), TdmsReader::open(source)?));
+    }
+
+    // The latest start of any timed channel is the first instant every source covers
+    let common_start = readers.iter()
+        .flat_map(|(_, reader)| {
+            reader.list_channels().into_iter().filter_map(move |key| {
+                let (group, channel) = split_channel_key(&key)?;
+                channel_timing(reader, &group, &channel).map(|(start, _)| start)
+            })
+        })
+        .max();
+
+    let mut writer = TdmsWriter::create(dest)?;
+    let mut report = StitchReport { common_start, ..Default::default() };
+    // Source each copied channel came from
+    let mut seen: HashMap<ObjectPath, usize> = HashMap::new();
+    // Values of the copies taken, read once a duplicate of them turns up
+    let mut taken: HashMap<ObjectPath, Option<ChannelContent>> = HashMap::new();
+
+    // File and group properties: first source wins
+    let mut file_properties = HashSet::new();
+    let mut group_properties = HashSet::new();
+    for (_, reader) in &readers {
+        for prop in reader.get_file_properties().values() {
+            if file_properties.insert(prop.name.clone()) {
+                writer.set_file_property(prop.name.clone(), prop.value.clone());
+            }
+        }
+        for group in reader.list_groups() {
+            for prop in reader.get_group_properties(&group).into_iter().flat_map(|p| p.values()) {
+                if group_properties.insert((group.clone(), prop.name.clone())) {
+                    writer.set_group_property(group.clone(), prop.name.clone(), prop.value.clone());
+                }
+            }
+        }
+    }
+
+    for index in 0..readers.len() {
+        let mut keys = readers[index].1.list_channels();
+        keys.sort();
+        for key in keys {
+            let Some((group, channel)) = split_channel_key(&key) else { continue };
+            let path = ObjectPath::Channel { group: group.clone(), channel: channel.clone() };
+            if let Some(&first) = seen.get(&path) {
+                let duplicate = options.skip_duplicates && {
+                    let first = match taken.entry(path.clone()) {
+                        std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                        std::collections::hash_map::Entry::Vacant(entry) => {
+                            entry.insert(ChannelContent::read(&mut readers[first].1, &group, &channel)?)
+                        }
+                    };
+                    first.is_some() && *first == ChannelContent::read(&mut readers[index].1, &group, &channel)?
+                };
+                if !duplicate {
+                    return Err(TdmsError::InvalidPath(format!("{} appears in more than one source", path)));
+                }
+                report.duplicates.push(StitchedChannel { source: readers[index].0.clone(), path, skipped_samples: 0 });
+                continue;
+            }
+
+            let (source, reader) = &mut readers[index];
+
+            let skip = match (common_start, channel_timing(reader, &group, &channel)) {
+                (Some(common), Some((start, increment))) => samples_before(start, increment, common),
+                _ => 0,
+            };
+            if !copy_channel(reader, &mut writer, &group, &channel, skip)? {
+                // Types without a typed read path (e.g. DAQmx raw data) are skipped,
+                // as in `defragment`
+                continue;
+            }
+            seen.insert(path.clone(), index);
+            report.channels.push(StitchedChannel { source: source.clone(), path, skipped_samples: skip });
+        }
+    }
+
+    writer.close()?;
+    Ok(report)
+}
+
+fn split_channel_key(key: &str) -> Option<(String, String)> {
+    match ObjectPath::from_string(key).ok()? {
+        ObjectPath::Channel { group, channel } => Some((group, channel)),
+        _ => None,
+    }
+}
+
+/// Start time and increment of a channel with usable waveform timing
+fn channel_timing(reader: &TdmsReader<BufReader<File>>, group: &str, channel: &str) -> Option<(Timestamp, f64)> {
+    let timing = WaveformTiming::from_properties(reader.get_channel_properties(group, channel)?)?;
+    let increment = timing.increment.filter(|inc| *inc > 0.0)?;
+    Some((timing.start_time?, increment))
+}
+
+/// Number of samples of a channel starting at `start` that fall before `common`
+fn samples_before(start: Timestamp, increment: f64, common: Timestamp) -> u64 {
+    let behind = common.diff(&start);
+    if behind <= 0.0 {
+        return 0;
+    }
+    // Tolerate rounding so a sample exactly at `common` is kept
+    (behind / increment - 1e-9).ceil() as u64
+}
+
+/// A channel's data type and values, with fixed-size values in their TDMS
+/// encoding so e.g. floats compare bitwise
+#[derive(Debug, PartialEq)]
+enum ChannelContent {
+    Strings(Vec<String>),
+    Values(DataType, Vec<u8>),
+}
+
+impl ChannelContent {
+    /// `None` if the channel's type can't be read
+    fn read(reader: &mut TdmsReader<BufReader<File>>, group: &str, channel: &str) -> Result<Option<Self>> {
+        let Some(data_type) = reader.value_data_type(group, channel)? else { return Ok(None) };
+        macro_rules! values {
+            ($t:ty) => {
+                Self::encode(&reader.read_channel_data::<$t>(group, channel)?)?
+            };
+        }
+        let content = match data_type {
+            DataType::String => ChannelContent::Strings(reader.read_channel_strings(group, channel)?),
+            DataType::I8 => values!(i8),
+            DataType::I16 => values!(i16),
+            DataType::I32 => values!(i32),
+            DataType::I64 => values!(i64),
+            DataType::U8 => values!(u8),
+            DataType::U16 => values!(u16),
+            DataType::U32 => values!(u32),
+            DataType::U64 => values!(u64),
+            DataType::SingleFloat => values!(f32),
+            DataType::DoubleFloat => values!(f64),
+            DataType::ExtendedFloat => values!(F80),
+            DataType::Boolean => values!(bool),
+            DataType::TimeStamp => values!(Timestamp),
+            DataType::ComplexSingleFloat => values!(Complex<f32>),
+            DataType::ComplexDoubleFloat => values!(Complex<f64>),
+            DataType::Void | DataType::DAQmxRawData => return Ok(None),
+        };
+        Ok(Some(content))
+    }
+
+    fn encode<T: TdmsValue>(values: &[T]) -> Result<Self> {
+        let mut buffer = RawDataBuffer::with_capacity(T::DATA_TYPE, std::mem::size_of_val(values));
+        T::write_values(values, &mut buffer)?;
+        Ok(ChannelContent::Values(T::DATA_TYPE, buffer.as_bytes().to_vec()))
+    }
+}
+
+/// Copy a channel's properties and data, dropping its first `skip` values.
+/// Returns `false` if the channel's type can't be copied.
+fn copy_channel(
+    reader: &mut TdmsReader<BufReader<File>>,
+    writer: &mut TdmsWriter,
+    group: &str,
+    channel: &str,
+    skip: u64,
+) -> Result<bool> {
+    let encoding = reader.channel_encoding(group, channel)?;
+    let Some(data_type) = reader.value_data_type(group, channel)? else { return Ok(false) };
+
+    macro_rules! copy_values {
+        ($t:ty) => {{
+            let data = reader.read_channel_data::<$t>(group, channel)?;
+            let skip = (skip as usize).min(data.len());
+            writer.write_channel_data(group, channel, &data[skip..])?;
+        }};
+    }
+
+    let copyable = matches!(data_type,
+        DataType::String | DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64
+        | DataType::U8 | DataType::U16 | DataType::U32 | DataType::U64
+        | DataType::SingleFloat | DataType::DoubleFloat | DataType::ExtendedFloat | DataType::Boolean | DataType::TimeStamp
+        | DataType::ComplexSingleFloat | DataType::ComplexDoubleFloat);
+    if !copyable {
+        return Ok(false);
+    }
+
+    match encoding {
+        Some((encoding, data_type)) => writer.create_encoded_channel(group, channel, data_type, encoding)?,
+        None => writer.create_channel(group, channel, data_type)?,
+    }
+
+    let mut properties = reader.get_channel_properties(group, channel).cloned().unwrap_or_default();
+    properties.remove(codec::ENCODING_PROPERTY);
+    properties.remove(codec::ORIGINAL_TYPE_PROPERTY);
+    if skip > 0 {
+        if let Some((start, increment)) = channel_timing(reader, group, channel) {
+            let start = start.add_duration(Duration::from_secs_f64(skip as f64 * increment));
+            properties.insert(waveform::WF_START_TIME.to_string(),
+                Property::new(waveform::WF_START_TIME, PropertyValue::Timestamp(start)));
+        }
+    }
+    writer.set_channel_properties(group, channel, properties.into_values())?;
+
+    match data_type {
+        DataType::String => {
+            let data = reader.read_channel_strings(group, channel)?;
+            let skip = (skip as usize).min(data.len());
+            writer.write_channel_strings(group, channel, &data[skip..])?;
+        }
+        DataType::I8 => copy_values!(i8),
+        DataType::I16 => copy_values!(i16),
+        DataType::I32 => copy_values!(i32),
+        DataType::I64 => copy_values!(i64),
+        DataType::U8 => copy_values!(u8),
+        DataType::U16 => copy_values!(u16),
+        DataType::U32 => copy_values!(u32),
+        DataType::U64 => copy_values!(u64),
+        DataType::SingleFloat => copy_values!(f32),
+        DataType::DoubleFloat => copy_values!(f64),
+        DataType::ExtendedFloat => copy_values!(F80),
+        DataType::Boolean => copy_values!(bool),
+        DataType::TimeStamp => copy_values!(Timestamp),
+        DataType::ComplexSingleFloat => copy_values!(Complex<f32>),
+        DataType::ComplexDoubleFloat => copy_values!(Complex<f64>),
+        _ => unreachable!("checked above"),
+    }
+    Ok(true)
+}
@@ -0,0 +1,406 @@
+// src/storage/encrypted.rs
+//! AES-256-GCM encrypted containers (requires the "encryption" feature).
+//!
+//! The container is a header followed by the TDMS bytes in fixed-size chunks,
+//! each sealed separately so any range can be read or rewritten without
+//! touching the rest:
+//!
+//! ```text
+//! "TDSe" | version u32 | chunk size u32 | key id length u32 | key id
+//! rewrites u64 | nonce (12) | ciphertext (chunk size) | tag (16)     chunk 0
+//! rewrites u64 | nonce (12) | ciphertext (chunk size) | tag (16)     chunk 1
+//! ...
+//! rewrites u64 | nonce (12) | ciphertext (up to chunk size) | tag (16)
+//! ```
+//!
+//! Integers are little-endian. Every chunk is authenticated together with the
+//! header, its own index, the number of times it has been rewritten and
+//! whether it is the final chunk, so edited, swapped or relabelled chunks fail
+//! to decrypt, and so does a container cut short at a chunk boundary. The key
+//! itself never touches the file; the header only names it, and a
+//! [`KeyProvider`] (a KMS client, a keyring, or any closure) supplies it.
+//!
+//! Chunks get a fresh random 96-bit nonce each time they are sealed, which
+//! keeps copies of a container that are written on independently safe. Random
+//! nonces limit a key to 2<sup>32</sup> seals (NIST SP 800-38D) across every
+//! container it encrypts. Each write seals the chunks it touches, and appends
+//! reseal the last chunk, so count one seal per write or flush. A writer that
+//! flushes every millisecond reaches the limit in about 50 days; use a key per
+//! container, or rotate keys well before then.
+
+use super::TdmsStorage;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use std::borrow::Cow;
+use std::io;
+
+const MAGIC: &[u8; 4] = b"TDSe";
+const VERSION: u32 = 1;
+const CHUNK_SIZE: u32 = 64 * 1024;
+// Largest chunk size accepted from a header, to bound allocations
+const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+// Longest key id, in bytes, likewise
+const MAX_KEY_ID_LEN: u32 = 1024;
+const REWRITES_SIZE: usize = 8;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+// Bytes a record adds to its chunk
+const RECORD_OVERHEAD: usize = REWRITES_SIZE + NONCE_SIZE + TAG_SIZE;
+
+/// Supplies the 256-bit key for a key id, e.g. by asking a KMS to unwrap a
+/// data key. Closures `Fn(&str) -> io::Result<[u8; 32]>` are providers.
+pub trait KeyProvider {
+    fn key(&self, key_id: &str) -> io::Result<[u8; 32]>;
+}
+
+impl<F: Fn(&str) -> io::Result<[u8; 32]>> KeyProvider for F {
+    fn key(&self, key_id: &str) -> io::Result<[u8; 32]> {
+        self(key_id)
+    }
+}
+
+/// A [`TdmsStorage`] that encrypts everything written to it into another
+/// storage (see the [module docs](self) for the format)
+pub struct EncryptedStorage<S> {
+    inner: S,
+    cipher: Aes256Gcm,
+    header: Vec<u8>,
+    key_id: String,
+    chunk_size: usize,
+    // Index, rewrite count and plaintext of the chunk written last, which
+    // appends keep rewriting
+    last_chunk: Option<(u64, u64, Vec<u8>)>,
+}
+
+impl<S: TdmsStorage> EncryptedStorage<S> {
+    /// Start a container in empty storage, encrypted with the key `keys`
+    /// gives for `key_id`
+    pub fn create(mut inner: S, key_id: &str, keys: &impl KeyProvider) -> io::Result<Self> {
+        if !inner.is_empty()? {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "encrypted container storage must start empty"));
+        }
+        if key_id.len() > MAX_KEY_ID_LEN as usize {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("key id is longer than {} bytes", MAX_KEY_ID_LEN)));
+        }
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&keys.key(key_id)?));
+        let mut header = Vec::with_capacity(16 + key_id.len());
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&CHUNK_SIZE.to_le_bytes());
+        header.extend_from_slice(&(key_id.len() as u32).to_le_bytes());
+        header.extend_from_slice(key_id.as_bytes());
+        inner.write_at(0, &header)?;
+        Ok(EncryptedStorage {
+            inner,
+            cipher,
+            header,
+            key_id: key_id.to_string(),
+            chunk_size: CHUNK_SIZE as usize,
+            last_chunk: None,
+        })
+    }
+
+    /// Open an existing container, fetching the key its header names from `keys`
+    pub fn open(inner: S, keys: &impl KeyProvider) -> io::Result<Self> {
+        let mut fixed = [0u8; 16];
+        if read_full_at(&inner, 0, &mut fixed)? < fixed.len() || &fixed[..4] != MAGIC {
+            return Err(invalid_data("not an encrypted TDMS container"));
+        }
+        let field = |at: usize| u32::from_le_bytes(fixed[at..at + 4].try_into().expect("4 bytes"));
+        if field(4) != VERSION {
+            return Err(invalid_data(format!("unsupported encrypted container version {}", field(4))));
+        }
+        let chunk_size = field(8);
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(invalid_data(format!("invalid chunk size {}", chunk_size)));
+        }
+        if field(12) > MAX_KEY_ID_LEN {
+            return Err(invalid_data(format!("key id length {} exceeds {} bytes", field(12), MAX_KEY_ID_LEN)));
+        }
+        let mut key_id = vec![0u8; field(12) as usize];
+        if read_full_at(&inner, fixed.len() as u64, &mut key_id)? < key_id.len() {
+            return Err(invalid_data("truncated encrypted container header"));
+        }
+        let key_id = String::from_utf8(key_id).map_err(|_| invalid_data("key id is not UTF-8"))?;
+
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&keys.key(&key_id)?));
+        let mut header = fixed.to_vec();
+        header.extend_from_slice(key_id.as_bytes());
+        Ok(EncryptedStorage {
+            inner,
+            cipher,
+            header,
+            key_id,
+            chunk_size: chunk_size as usize,
+            last_chunk: None,
+        })
+    }
+
+    /// The id of the key the container is encrypted with
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn record_size(&self) -> u64 {
+        (self.chunk_size + RECORD_OVERHEAD) as u64
+    }
+
+    fn record_offset(&self, index: u64) -> u64 {
+        self.header.len() as u64 + index * self.record_size()
+    }
+
+    /// Number of chunk records, counting a partial last one
+    fn chunk_count(&self) -> io::Result<u64> {
+        let body = self.inner.len()?.saturating_sub(self.header.len() as u64);
+        Ok(body.div_ceil(self.record_size()))
+    }
+
+    fn aad(&self, index: u64, rewrites: u64, is_final: bool) -> Vec<u8> {
+        let mut aad = self.header.clone();
+        aad.extend_from_slice(&index.to_le_bytes());
+        aad.extend_from_slice(&rewrites.to_le_bytes());
+        aad.push(is_final as u8);
+        aad
+    }
+
+    /// Rewrite count and plaintext of chunk `index`; past the end the chunk
+    /// is empty and has never been written
+    fn read_chunk(&self, index: u64) -> io::Result<(u64, Cow<'_, [u8]>)> {
+        if let Some((cached, rewrites, plaintext)) = &self.last_chunk {
+            if *cached == index {
+                return Ok((*rewrites, Cow::Borrowed(plaintext)));
+            }
+        }
+        let mut record = vec![0u8; self.record_size() as usize];
+        let count = read_full_at(&self.inner, self.record_offset(index), &mut record)?;
+        if count == 0 {
+            return Ok((0, Cow::Owned(Vec::new())));
+        }
+        if count < RECORD_OVERHEAD {
+            return Err(invalid_data(format!("encrypted chunk {} is truncated", index)));
+        }
+        let rewrites = u64::from_le_bytes(record[..REWRITES_SIZE].try_into().expect("8 bytes"));
+        let (nonce, ciphertext) = record[REWRITES_SIZE..count].split_at(NONCE_SIZE);
+        let is_final = index + 1 == self.chunk_count()?;
+        let payload = Payload { msg: ciphertext, aad: &self.aad(index, rewrites, is_final) };
+        self.cipher.decrypt(Nonce::from_slice(nonce), payload)
+            .map(|plaintext| (rewrites, Cow::Owned(plaintext)))
+            .map_err(|_| invalid_data(format!("encrypted chunk {} failed authentication", index)))
+    }
+
+    /// Seal `plaintext` as chunk `index`, replacing a chunk rewritten
+    /// `rewrites` times (0 for a new one)
+    fn write_chunk(&mut self, index: u64, rewrites: u64, plaintext: Vec<u8>, is_final: bool) -> io::Result<()> {
+        let rewrites = rewrites + 1;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload { msg: &plaintext, aad: &self.aad(index, rewrites, is_final) };
+        let ciphertext = self.cipher.encrypt(&nonce, payload)
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        let mut record = Vec::with_capacity(REWRITES_SIZE + NONCE_SIZE + ciphertext.len());
+        record.extend_from_slice(&rewrites.to_le_bytes());
+        record.extend_from_slice(&nonce);
+        record.extend_from_slice(&ciphertext);
+        self.inner.write_at(self.record_offset(index), &record)?;
+        self.last_chunk = Some((index, rewrites, plaintext));
+        Ok(())
+    }
+}
+
+impl<S: TdmsStorage> TdmsStorage for EncryptedStorage<S> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk_size = self.chunk_size as u64;
+        let mut done = 0;
+        while done < buf.len() {
+            let position = offset + done as u64;
+            let (_, chunk) = self.read_chunk(position / chunk_size)?;
+            let within = (position % chunk_size) as usize;
+            if within >= chunk.len() {
+                break;
+            }
+            let count = (chunk.len() - within).min(buf.len() - done);
+            buf[done..done + count].copy_from_slice(&chunk[within..within + count]);
+            done += count;
+        }
+        Ok(done)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        // Every chunk but the last is full, so a gap is filled with zeros
+        let len = self.len()?;
+        if offset > len {
+            self.write_at(len, &vec![0; (offset - len) as usize])?;
+        }
+
+        if buf.is_empty() {
+            return Ok(());
+        }
+
+        let chunk_size = self.chunk_size as u64;
+        let old_count = self.chunk_count()?;
+        let new_count = old_count.max((offset + buf.len() as u64).div_ceil(chunk_size));
+        // The old final chunk stops being final; reseal it unless this write
+        // rewrites it anyway
+        if new_count > old_count && old_count > 0 && offset / chunk_size >= old_count {
+            let (rewrites, chunk) = self.read_chunk(old_count - 1)?;
+            let chunk = chunk.into_owned();
+            self.write_chunk(old_count - 1, rewrites, chunk, false)?;
+        }
+
+        let mut done = 0;
+        while done < buf.len() {
+            let position = offset + done as u64;
+            let index = position / chunk_size;
+            let within = (position % chunk_size) as usize;
+            let count = (self.chunk_size - within).min(buf.len() - done);
+            let (rewrites, chunk) = self.read_chunk(index)?;
+            let mut chunk = chunk.into_owned();
+            if chunk.len() < within + count {
+                chunk.resize(within + count, 0);
+            }
+            chunk[within..within + count].copy_from_slice(&buf[done..done + count]);
+            self.write_chunk(index, rewrites, chunk, index + 1 == new_count)?;
+            done += count;
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        let body = self.inner.len()?.saturating_sub(self.header.len() as u64);
+        let last = body % self.record_size();
+        Ok(body / self.record_size() * self.chunk_size as u64 + last.saturating_sub(RECORD_OVERHEAD as u64))
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.inner.sync()
+    }
+}
+
+/// Read until `buf` is full or the storage ends, returning the bytes read
+fn read_full_at<S: TdmsStorage>(storage: &S, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+    let mut done = 0;
+    while done < buf.len() {
+        let count = storage.read_at(offset + done as u64, &mut buf[done..])?;
+        if count == 0 {
+            break;
+        }
+        done += count;
+    }
+    Ok(done)
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(key_id: &str) -> io::Result<[u8; 32]> {
+        match key_id {
+            "test" => Ok([7; 32]),
+            _ => Err(io::Error::new(io::ErrorKind::NotFound, "unknown key")),
+        }
+    }
+
+    #[test]
+    fn test_random_access_roundtrip() {
+        let mut storage = EncryptedStorage::create(Vec::new(), "test", &keys).unwrap();
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        storage.write_at(0, &data[..100_000]).unwrap();
+        storage.write_at(100_000, &data[100_000..]).unwrap();
+        // Patch across a chunk boundary, as the writer does with lead-ins
+        storage.write_at(65_530, &[0xAA; 12]).unwrap();
+        assert_eq!(storage.len().unwrap(), 200_000);
+
+        let inner = storage.into_inner();
+        assert!(inner.windows(4).all(|window| window != &data[1000..1004]));
+        let storage = EncryptedStorage::open(inner, &keys).unwrap();
+        assert_eq!(storage.key_id(), "test");
+        let mut read = vec![0u8; 200_010];
+        assert_eq!(storage.read_at(0, &mut read).unwrap(), 200_000);
+        let mut expected = data.clone();
+        expected[65_530..65_542].fill(0xAA);
+        assert_eq!(&read[..200_000], &expected[..]);
+    }
+
+    #[test]
+    fn test_gap_is_zero_filled() {
+        let mut storage = EncryptedStorage::create(Vec::new(), "test", &keys).unwrap();
+        storage.write_at(70_000, b"end").unwrap();
+        let mut read = vec![1u8; 70_003];
+        assert_eq!(storage.read_at(0, &mut read).unwrap(), 70_003);
+        assert!(read[..70_000].iter().all(|&byte| byte == 0));
+        assert_eq!(&read[70_000..], b"end");
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let mut storage = EncryptedStorage::create(Vec::new(), "test", &keys).unwrap();
+        storage.write_at(0, b"TDSm and some more").unwrap();
+        let mut inner = storage.into_inner();
+        let last = inner.len() - 1;
+        inner[last] ^= 1;
+        let storage = EncryptedStorage::open(inner, &keys).unwrap();
+        let error = storage.read_at(0, &mut [0u8; 4]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        assert_eq!(EncryptedStorage::create(Vec::new(), "other", &keys).err().unwrap().kind(), io::ErrorKind::NotFound);
+        assert!(EncryptedStorage::open(b"TDSm....".to_vec(), &keys).is_err());
+
+        // The rewrite count is authenticated too
+        let mut storage = EncryptedStorage::create(Vec::new(), "test", &keys).unwrap();
+        storage.write_at(0, b"first").unwrap();
+        storage.write_at(0, b"again").unwrap();
+        let mut inner = storage.into_inner();
+        let header_len = inner.len() - (RECORD_OVERHEAD + 5);
+        assert_eq!(inner[header_len], 2);
+        inner[header_len] = 1;
+        let storage = EncryptedStorage::open(inner, &keys).unwrap();
+        assert!(storage.read_at(0, &mut [0u8; 5]).is_err());
+    }
+
+    #[test]
+    fn test_truncation_is_detected() {
+        let mut storage = EncryptedStorage::create(Vec::new(), "test", &keys).unwrap();
+        for block in (0..200_000u32).collect::<Vec<_>>().chunks(10_000) {
+            let bytes: Vec<u8> = block.iter().map(|i| (i % 251) as u8).collect();
+            let len = storage.len().unwrap();
+            storage.write_at(len, &bytes).unwrap();
+        }
+        let inner = storage.into_inner();
+        let storage = EncryptedStorage::open(inner.clone(), &keys).unwrap();
+        assert_eq!(storage.read_at(0, &mut vec![0u8; 200_000]).unwrap(), 200_000);
+
+        // Dropping the final chunk leaves one that wasn't sealed as final
+        let record_size = CHUNK_SIZE as usize + RECORD_OVERHEAD;
+        let mut truncated = inner;
+        truncated.truncate(truncated.len() - (200_000 % CHUNK_SIZE as usize + RECORD_OVERHEAD));
+        let storage = EncryptedStorage::open(truncated.clone(), &keys).unwrap();
+        assert_eq!(storage.read_at(0, &mut [0u8; 100]).unwrap(), 100);
+        let error = storage.read_at(0, &mut vec![0u8; 200_000]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        truncated.truncate(truncated.len() - record_size);
+        let storage = EncryptedStorage::open(truncated, &keys).unwrap();
+        assert!(storage.read_at(0, &mut vec![0u8; 200_000]).is_err());
+    }
+
+    #[test]
+    fn test_key_id_length_is_bounded() {
+        let long = "k".repeat(MAX_KEY_ID_LEN as usize + 1);
+        assert_eq!(EncryptedStorage::create(Vec::new(), &long, &keys).err().unwrap().kind(), io::ErrorKind::InvalidInput);
+
+        // A header claiming a huge key id fails before allocating for it
+        let mut header = Vec::new();
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&CHUNK_SIZE.to_le_bytes());
+        header.extend_from_slice(&u32::MAX.to_le_bytes());
+        let error = EncryptedStorage::open(header, &keys).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,228 @@
+// src/storage/mod.rs
+//! Storage backends for readers and writers.
+//!
+//! A [`TdmsStorage`] is a flat, randomly addressable byte store: a file, a
+//! buffer in memory, an object in a bucket, an encrypted container. The
+//! reader and writer only ever need positioned reads and writes, its length
+//! and a way to make writes durable, so a new backend is one small trait
+//! implementation. [`StorageCursor`] adapts a backend to `Read + Write + Seek`
+//! for the core I/O paths. With the "encryption" feature, `EncryptedStorage`
+//! wraps another backend in an AES-256-GCM container.
+//!
+//! # Example
+//!
+//! ```
+//! use tdms_rs::*;
+//!
+//! // Write a file into memory and read it back
+//! let (mut data, mut index) = (Vec::new(), Vec::new());
+//! let mut writer = TdmsWriter::from_storage(&mut data, &mut index).unwrap();
+//! writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+//! writer.write_channel_data("Group", "Voltage", &[1.0f64, 2.0]).unwrap();
+//! writer.close().unwrap();
+//!
+//! let mut reader = TdmsReader::from_storage(data).unwrap();
+//! assert_eq!(reader.read_channel_data::<f64>("Group", "Voltage").unwrap(), [1.0, 2.0]);
+//! ```
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+#[cfg(feature = "encryption")]
+pub mod encrypted;
+#[cfg(feature = "encryption")]
+pub use encrypted::{EncryptedStorage, KeyProvider};
+
+/// A randomly addressable byte store that TDMS data can live in
+pub trait TdmsStorage {
+    /// Read into `buf` from `offset`, returning how many bytes were read: 0 at
+    /// or past the end, and possibly fewer than asked for near it
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Write all of `buf` at `offset`, growing the store if it ends earlier
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()>;
+
+    /// Current size in bytes
+    fn len(&self) -> io::Result<u64>;
+
+    fn is_empty(&self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Make everything written so far durable, as far as the backend can
+    fn sync(&mut self) -> io::Result<()>;
+}
+
+impl TdmsStorage for File {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let mut file = self;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read(buf)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        self.seek(SeekFrom::Start(offset))?;
+        self.write_all(buf)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.sync_data()
+    }
+}
+
+/// An in-memory store; syncing does nothing
+impl TdmsStorage for Vec<u8> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let start = offset.min(Vec::len(self) as u64) as usize;
+        let count = buf.len().min(Vec::len(self) - start);
+        buf[..count].copy_from_slice(&self[start..start + count]);
+        Ok(count)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        let start = usize::try_from(offset)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "offset beyond addressable memory"))?;
+        let end = start + buf.len();
+        if Vec::len(self) < end {
+            self.resize(end, 0);
+        }
+        self[start..end].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(Vec::len(self) as u64)
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: TdmsStorage + ?Sized> TdmsStorage for &mut S {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read_at(offset, buf)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        (**self).write_at(offset, buf)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        (**self).len()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        (**self).sync()
+    }
+}
+
+impl<S: TdmsStorage + ?Sized> TdmsStorage for Box<S> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read_at(offset, buf)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        (**self).write_at(offset, buf)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        (**self).len()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        (**self).sync()
+    }
+}
+
+/// A [`TdmsStorage`] with a position, for use as `Read + Write + Seek`
+#[derive(Debug, Default)]
+pub struct StorageCursor<S> {
+    storage: S,
+    position: u64,
+}
+
+impl<S: TdmsStorage> StorageCursor<S> {
+    /// A cursor at the start of `storage`
+    pub fn new(storage: S) -> Self {
+        StorageCursor { storage, position: 0 }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.storage
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+}
+
+impl<S: TdmsStorage> Read for StorageCursor<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.storage.read_at(self.position, buf)?;
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<S: TdmsStorage> Write for StorageCursor<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.storage.write_at(self.position, buf)?;
+        self.position += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    // Writes go straight to the storage; durability is `TdmsStorage::sync`
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: TdmsStorage> Seek for StorageCursor<S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(offset) => {
+                self.position = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(offset) => (self.storage.len()?, offset),
+            SeekFrom::Current(offset) => (self.position, offset),
+        };
+        self.position = base.checked_add_signed(offset)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative or overflowing position"))?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_storage_cursor() {
+        let mut cursor = StorageCursor::new(Vec::new());
+        cursor.write_all(b"abcdef").unwrap();
+        cursor.seek(SeekFrom::Start(8)).unwrap();
+        cursor.write_all(b"xy").unwrap();
+        assert_eq!(cursor.get_ref().as_slice(), b"abcdef\0\0xy");
+
+        cursor.seek(SeekFrom::End(-4)).unwrap();
+        let mut buf = [0u8; 8];
+        assert_eq!(cursor.read(&mut buf).unwrap(), 4);
+        assert_eq!(&buf[..4], b"\0\0xy");
+        assert_eq!(cursor.read(&mut buf).unwrap(), 0);
+        assert!(cursor.seek(SeekFrom::Current(-20)).is_err());
+    }
+}
@@ -18,6 +18,8 @@ pub enum DataType {
     U64 = 8,
     SingleFloat = 9,
     DoubleFloat = 10,
+    /// LabVIEW extended precision (x87 80-bit) float, see [`F80`]
+    ExtendedFloat = 11,
     String = 0x20,
     Boolean = 0x21,
     TimeStamp = 0x44,
@@ -41,6 +43,7 @@ impl DataType {
             DataType::I16 | DataType::U16 => Some(2),
             DataType::I32 | DataType::U32 | DataType::SingleFloat => Some(4),
             DataType::I64 | DataType::U64 | DataType::DoubleFloat => Some(8),
+            DataType::ExtendedFloat => Some(10),
             DataType::ComplexSingleFloat => Some(8),
             DataType::TimeStamp | DataType::ComplexDoubleFloat => Some(16),
             DataType::String | DataType::DAQmxRawData => None,
@@ -60,6 +63,7 @@ impl DataType {
             8 => Some(DataType::U64),
             9 => Some(DataType::SingleFloat),
             10 => Some(DataType::DoubleFloat),
+            11 => Some(DataType::ExtendedFloat),
             0x20 => Some(DataType::String),
             0x21 => Some(DataType::Boolean),
             0x44 => Some(DataType::TimeStamp),
@@ -89,7 +93,22 @@ impl DataType {
     
     /// Check if this is a floating point type
     pub fn is_float(&self) -> bool {
-        matches!(self, DataType::SingleFloat | DataType::DoubleFloat)
+        matches!(self, DataType::SingleFloat | DataType::DoubleFloat | DataType::ExtendedFloat)
+    }
+    
+    /// Check if every value of this type converts exactly to `target`: the
+    /// type itself, a wider integer that holds its whole range, or a float
+    /// whose mantissa does (integers up to 16 bits in `f32`, 32 bits in `f64`)
+    pub fn widens_to(&self, target: DataType) -> bool {
+        use DataType::*;
+        *self == target || matches!((self, target),
+            (I8, I16 | I32 | I64 | SingleFloat | DoubleFloat)
+            | (I16, I32 | I64 | SingleFloat | DoubleFloat)
+            | (I32, I64 | DoubleFloat)
+            | (U8, U16 | U32 | U64 | I16 | I32 | I64 | SingleFloat | DoubleFloat)
+            | (U16, U32 | U64 | I32 | I64 | SingleFloat | DoubleFloat)
+            | (U32, U64 | I64 | DoubleFloat)
+            | (SingleFloat, DoubleFloat))
     }
     
     /// Check if this is a complex type
@@ -111,6 +130,7 @@ impl DataType {
             DataType::U64 => "u64",
             DataType::SingleFloat => "f32",
             DataType::DoubleFloat => "f64",
+            DataType::ExtendedFloat => "f80",
             DataType::String => "string",
             DataType::Boolean => "bool",
             DataType::TimeStamp => "timestamp",
@@ -119,6 +139,281 @@ impl DataType {
             DataType::DAQmxRawData => "daqmx_raw",
         }
     }
+
+    /// Inverse of [`Self::name`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "void" => DataType::Void,
+            "i8" => DataType::I8,
+            "i16" => DataType::I16,
+            "i32" => DataType::I32,
+            "i64" => DataType::I64,
+            "u8" => DataType::U8,
+            "u16" => DataType::U16,
+            "u32" => DataType::U32,
+            "u64" => DataType::U64,
+            "f32" => DataType::SingleFloat,
+            "f64" => DataType::DoubleFloat,
+            "f80" => DataType::ExtendedFloat,
+            "string" => DataType::String,
+            "bool" => DataType::Boolean,
+            "timestamp" => DataType::TimeStamp,
+            "complex_f32" => DataType::ComplexSingleFloat,
+            "complex_f64" => DataType::ComplexDoubleFloat,
+            "daqmx_raw" => DataType::DAQmxRawData,
+            _ => return None,
+        })
+    }
+}
+
+mod sealed {
+    use super::{Complex, Timestamp};
+    use crate::error::Result;
+    use crate::raw_data::RawDataBuffer;
+    
+    /// Conversions for types whose in-memory form isn't their TDMS encoding.
+    /// The defaults copy memory as-is, which is right for plain numbers.
+    pub trait Sealed: Copy + Sized {
+        fn write_values(values: &[Self], buffer: &mut RawDataBuffer) -> Result<()> {
+            buffer.write_slice(values)
+        }
+        
+        /// Decode raw values, or `None` to copy the (already byte-swapped) bytes
+        fn decode_values(_bytes: &[u8], _is_big_endian: bool) -> Option<Vec<Self>> {
+            None
+        }
+    }
+    
+    impl Sealed for bool {
+        fn write_values(values: &[bool], buffer: &mut RawDataBuffer) -> Result<()> {
+            buffer.write_bools(values)
+        }
+        
+        // Any non-zero byte is true, as in LabVIEW
+        fn decode_values(bytes: &[u8], _is_big_endian: bool) -> Option<Vec<bool>> {
+            Some(bytes.iter().map(|&byte| byte != 0).collect())
+        }
+    }
+    
+    macro_rules! impl_sealed_complex {
+        ($($t:ty),*) => {
+            $(
+                impl Sealed for Complex<$t> {
+                    // Big-endian parts are swapped one at a time, not the
+                    // value as a whole, which would exchange them
+                    fn decode_values(bytes: &[u8], is_big_endian: bool) -> Option<Vec<Self>> {
+                        if !is_big_endian {
+                            return None;
+                        }
+                        const PART: usize = std::mem::size_of::<$t>();
+                        Some(bytes.chunks_exact(2 * PART).map(|chunk| Complex {
+                            re: <$t>::from_be_bytes(chunk[..PART].try_into().expect("part sized")),
+                            im: <$t>::from_be_bytes(chunk[PART..].try_into().expect("part sized")),
+                        }).collect())
+                    }
+                }
+            )*
+        };
+    }
+    
+    impl_sealed_complex!(f32, f64);
+    
+    /// Widening through `f64`, which is exact for every pair of types
+    /// [`DataType::widens_to`](super::DataType::widens_to) allows apart from a
+    /// type to itself, and that case never converts
+    pub trait Numeric: Sealed {
+        fn to_f64(self) -> f64;
+        fn from_f64(value: f64) -> Self;
+    }
+    
+    macro_rules! impl_numeric {
+        ($($t:ty),*) => {
+            $(
+                impl Numeric for $t {
+                    fn to_f64(self) -> f64 {
+                        self as f64
+                    }
+                    
+                    fn from_f64(value: f64) -> Self {
+                        value as $t
+                    }
+                }
+            )*
+        };
+    }
+    
+    impl_numeric!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+    
+    impl Sealed for Timestamp {
+        fn write_values(values: &[Timestamp], buffer: &mut RawDataBuffer) -> Result<()> {
+            buffer.write_timestamp_slice(values)
+        }
+        
+        fn decode_values(bytes: &[u8], is_big_endian: bool) -> Option<Vec<Timestamp>> {
+            Some(bytes.chunks_exact(16).map(|chunk| {
+                let chunk = chunk.try_into().expect("16 byte chunks");
+                if is_big_endian { Timestamp::from_bytes_be(chunk) } else { Timestamp::from_bytes_le(chunk) }
+            }).collect())
+        }
+    }
+}
+
+/// A Rust type that channel values can be read into.
+/// 
+/// Each implementation corresponds to one [`DataType`]; reads check the
+/// channel's type against it and fail with [`TdmsError::TypeMismatch`](crate::TdmsError::TypeMismatch)
+/// rather than reinterpreting the bytes of, say, an f64 channel as `i32`s.
+/// The trait is sealed: the set of types is fixed by the TDMS format.
+pub trait TdmsValue: sealed::Sealed + Copy + Default + 'static {
+    /// The TDMS data type stored as this Rust type
+    const DATA_TYPE: DataType;
+}
+
+/// A plain number type, which [`TdmsReader::read_channel_data_as`](crate::TdmsReader::read_channel_data_as)
+/// can read channels of any narrower type into (see [`DataType::widens_to`])
+pub trait NumericValue: TdmsValue + sealed::Numeric {}
+
+impl NumericValue for i8 {}
+impl NumericValue for i16 {}
+impl NumericValue for i32 {}
+impl NumericValue for i64 {}
+impl NumericValue for u8 {}
+impl NumericValue for u16 {}
+impl NumericValue for u32 {}
+impl NumericValue for u64 {}
+impl NumericValue for f32 {}
+impl NumericValue for f64 {}
+
+macro_rules! impl_tdms_value {
+    ($($t:ty => $data_type:ident),* $(,)?) => {
+        $(
+            impl TdmsValue for $t {
+                const DATA_TYPE: DataType = DataType::$data_type;
+            }
+        )*
+    };
+}
+
+macro_rules! impl_sealed {
+    ($($t:ty),*) => {
+        $(impl sealed::Sealed for $t {})*
+    };
+}
+
+impl_sealed!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64, F80);
+
+impl_tdms_value! {
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    f32 => SingleFloat,
+    f64 => DoubleFloat,
+    bool => Boolean,
+    Timestamp => TimeStamp,
+    Complex<f32> => ComplexSingleFloat,
+    Complex<f64> => ComplexDoubleFloat,
+    F80 => ExtendedFloat,
+}
+
+/// A complex number as stored by the `ComplexSingleFloat` (`Complex<f32>`)
+/// and `ComplexDoubleFloat` (`Complex<f64>`) data types: the real part
+/// followed by the imaginary part
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[repr(C)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+impl<T> Complex<T> {
+    pub fn new(re: T, im: T) -> Self {
+        Complex { re, im }
+    }
+}
+
+// Raw reads and writes copy complex values as their two parts back to back
+const _: () = assert!(std::mem::size_of::<Complex<f32>>() == 8);
+const _: () = assert!(std::mem::size_of::<Complex<f64>>() == 16);
+
+// SAFETY: `repr(C)` pairs of one float type have no padding and any bits are valid
+unsafe impl Zeroable for Complex<f32> {}
+unsafe impl Pod for Complex<f32> {}
+unsafe impl Zeroable for Complex<f64> {}
+unsafe impl Pod for Complex<f64> {}
+
+/// A LabVIEW extended precision float (`ExtendedFloat`) as stored: the 10
+/// little-endian bytes of an x87 80-bit float, a 64-bit mantissa with an
+/// explicit integer bit followed by the sign and 15-bit exponent.
+/// 
+/// Rust has no 80-bit float, so values are kept raw; [`to_f64`](Self::to_f64)
+/// converts them, rounding off the extra 11 bits of precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct F80(pub [u8; 10]);
+
+impl F80 {
+    const EXPONENT_BIAS: i32 = 16383;
+    
+    /// The nearest `f64`; values beyond its range become infinite or zero
+    pub fn to_f64(self) -> f64 {
+        let mantissa = u64::from_le_bytes(self.0[..8].try_into().expect("8 bytes"));
+        let sign_exponent = u16::from_le_bytes([self.0[8], self.0[9]]);
+        let sign = if sign_exponent & 0x8000 != 0 { -1.0 } else { 1.0 };
+        let exponent = i32::from(sign_exponent & 0x7FFF);
+        if exponent == 0x7FFF {
+            // Infinity has only the integer bit set
+            return if mantissa << 1 == 0 { sign * f64::INFINITY } else { f64::NAN };
+        }
+        // Denormals (exponent 0) share the scale of exponent 1. Scaling by
+        // powers of two in steps keeps every intermediate in range
+        let mut power = exponent.max(1) - Self::EXPONENT_BIAS - 63;
+        let mut value = sign * mantissa as f64;
+        while power != 0 {
+            let step = power.clamp(-1000, 1000);
+            value *= 2f64.powi(step);
+            power -= step;
+        }
+        value
+    }
+    
+    /// The exact extended precision equivalent of `value`
+    pub fn from_f64(value: f64) -> Self {
+        let bits = value.to_bits();
+        let sign = ((bits >> 63) as u16) << 15;
+        let exponent = ((bits >> 52) & 0x7FF) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (exponent, mantissa) = match exponent {
+            0 if fraction == 0 => (0, 0),
+            // Subnormal: normalize so the integer bit is set
+            0 => {
+                let shift = fraction.leading_zeros() as i32;
+                (63 - shift - 1074 + Self::EXPONENT_BIAS, fraction << shift)
+            }
+            0x7FF => (0x7FFF, (1 << 63) | (fraction << 11)),
+            _ => (exponent - 1023 + Self::EXPONENT_BIAS, (1 << 63) | (fraction << 11)),
+        };
+        let mut bytes = [0u8; 10];
+        bytes[..8].copy_from_slice(&mantissa.to_le_bytes());
+        bytes[8..].copy_from_slice(&(sign | exponent as u16).to_le_bytes());
+        F80(bytes)
+    }
+}
+
+/// Byte order of a segment's lead-in sizes, metadata and raw data
+/// 
+/// The table of contents itself is always little-endian; its
+/// [`TocFlags::BIG_ENDIAN`] flag says which order the rest of the segment uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Little,
+    /// The order of NI's big-endian targets, e.g. older cRIO and PXI controllers
+    Big,
 }
 
 /// Table of Contents flags
@@ -220,32 +515,69 @@ pub struct Timestamp {
     pub seconds: i64,
 }
 
+// Raw reads and `write_channel_data_unchecked` copy timestamps as 16 bytes
+const _: () = assert!(std::mem::size_of::<Timestamp>() == 16);
+
 impl Timestamp {
     const EPOCH_OFFSET_SECONDS: i64 = 2082844800; // 1904 to 1970
+    const NANOS_PER_SECOND: i128 = 1_000_000_000;
     
     pub fn now() -> Self {
-        let duration = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::ZERO);
-        
-        let unix_seconds = duration.as_secs() as i64;
-        let nanos = duration.subsec_nanos() as u64;
-        
-        let seconds = unix_seconds + Self::EPOCH_OFFSET_SECONDS;
-        let fractions = (nanos as u128 * (1u128 << 64) / 1_000_000_000) as u64;
-        
-        Timestamp { seconds, fractions }
+        Self::from_system_time(SystemTime::now())
     }
     
     pub fn from_system_time(time: SystemTime) -> Self {
-        let duration = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
-        let unix_seconds = duration.as_secs() as i64;
-        let nanos = duration.subsec_nanos() as u64;
-        
-        let seconds = unix_seconds + Self::EPOCH_OFFSET_SECONDS;
-        let fractions = (nanos as u128 * (1u128 << 64) / 1_000_000_000) as u64;
-        
-        Timestamp { seconds, fractions }
+        let epoch = Timestamp { seconds: Self::EPOCH_OFFSET_SECONDS, fractions: 0 };
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => epoch.add_duration(after),
+            Err(before) => epoch.sub_duration(before.duration()),
+        }
+    }
+    
+    /// Timestamp from nanoseconds since the Unix epoch (e.g. numpy `datetime64[ns]`)
+    pub fn from_unix_nanos(nanos: i64) -> Self {
+        let unix_seconds = nanos.div_euclid(Self::NANOS_PER_SECOND as i64);
+        let subsec_nanos = nanos.rem_euclid(Self::NANOS_PER_SECOND as i64) as u128;
+        Timestamp {
+            seconds: unix_seconds + Self::EPOCH_OFFSET_SECONDS,
+            // Round up so converting back yields the same nanosecond
+            fractions: ((subsec_nanos << 64).div_ceil(Self::NANOS_PER_SECOND as u128)) as u64,
+        }
+    }
+    
+    /// Nanoseconds since the Unix epoch, rounded down.
+    /// 
+    /// `i64` nanoseconds cover the years 1677 to 2262; timestamps outside that
+    /// range saturate.
+    pub fn to_unix_nanos(&self) -> i64 {
+        let unix_seconds = self.seconds as i128 - Self::EPOCH_OFFSET_SECONDS as i128;
+        let subsec_nanos = ((self.fractions as u128 * Self::NANOS_PER_SECOND as u128) >> 64) as i128;
+        (unix_seconds * Self::NANOS_PER_SECOND + subsec_nanos)
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+    
+    /// This timestamp moved forward by `duration`
+    pub fn add_duration(&self, duration: Duration) -> Self {
+        Self::from_units(self.units() + duration_units(duration))
+    }
+    
+    /// This timestamp moved back by `duration`
+    pub fn sub_duration(&self, duration: Duration) -> Self {
+        Self::from_units(self.units() - duration_units(duration))
+    }
+    
+    /// Seconds from `earlier` to `self`; negative if `self` is before `earlier`
+    pub fn diff(&self, earlier: &Timestamp) -> f64 {
+        (self.units() - earlier.units()) as f64 / UNITS_PER_SECOND
+    }
+    
+    /// Signed time since the TDMS epoch in units of 2^-64 s
+    fn units(&self) -> i128 {
+        ((self.seconds as i128) << 64) | self.fractions as i128
+    }
+    
+    fn from_units(units: i128) -> Self {
+        Timestamp { seconds: (units >> 64) as i64, fractions: units as u64 }
     }
     
     pub fn to_bytes_le(&self) -> [u8; 16] {
@@ -275,10 +607,38 @@ impl Timestamp {
     }
 
     pub fn to_system_time(&self) -> SystemTime {
-        let unix_seconds = self.seconds - Self::EPOCH_OFFSET_SECONDS;
-        let nanos = ((self.fractions as u128 * 1_000_000_000) / (1u128 << 64)) as u32;
-        let duration = Duration::new(unix_seconds as u64, nanos);
-        UNIX_EPOCH + duration
+        let since_unix = self.units() - ((Self::EPOCH_OFFSET_SECONDS as i128) << 64);
+        if since_unix >= 0 {
+            UNIX_EPOCH + units_duration(since_unix)
+        } else {
+            UNIX_EPOCH - units_duration(-since_unix)
+        }
+    }
+
+    /// UTC calendar date and time of day
+    pub(crate) fn to_civil(self) -> CivilTime {
+        let nanos = self.to_unix_nanos();
+        let secs = nanos.div_euclid(Self::NANOS_PER_SECOND as i64);
+        let (days, secs_of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+        // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+
+        CivilTime {
+            year: yoe + era * 400 + i64::from(month <= 2),
+            month: month as u32,
+            day: (doy - (153 * mp + 2) / 5 + 1) as u32,
+            hour: (secs_of_day / 3600) as u32,
+            minute: (secs_of_day / 60 % 60) as u32,
+            second: (secs_of_day % 60) as u32,
+            nanosecond: nanos.rem_euclid(Self::NANOS_PER_SECOND as i64) as u32,
+        }
     }
 
     #[cfg(test)]
@@ -291,6 +651,115 @@ impl Timestamp {
     }
 }
 
+/// A [`Timestamp`] broken down into UTC calendar fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CivilTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub nanosecond: u32,
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.seconds, self.fractions).cmp(&(other.seconds, other.fractions))
+    }
+}
+
+const UNITS_PER_SECOND: f64 = 18_446_744_073_709_551_616.0; // 2^64
+
+/// Duration in 2^-64 s units, rounded up so `units_duration` gives it back exactly
+fn duration_units(duration: Duration) -> i128 {
+    ((duration.as_secs() as i128) << 64)
+        + ((duration.subsec_nanos() as u128) << 64).div_ceil(Timestamp::NANOS_PER_SECOND as u128) as i128
+}
+
+/// Duration of a non-negative number of 2^-64 s units (rounded down to nanoseconds)
+fn units_duration(units: i128) -> Duration {
+    let nanos = ((units as u64 as u128 * Timestamp::NANOS_PER_SECOND as u128) >> 64) as u32;
+    Duration::new((units >> 64) as u64, nanos)
+}
+
+/// How the raw timestamps of a file map to real (UTC) time.
+///
+/// TDMS timestamps count seconds since 1904-01-01 00:00 UTC, but some loggers
+/// store local wall-clock time, or count from a different epoch. A convention
+/// describes such a file so its timestamps can be corrected on conversion.
+///
+/// # Example
+///
+/// ```
+/// use tdms_rs::{Timestamp, TimestampConvention};
+///
+/// // Logger that wrote local time in UTC+02:00
+/// let convention = TimestampConvention::local_time(2 * 3600);
+/// let raw = Timestamp { seconds: 3_000_007_200, fractions: 0 };
+/// assert_eq!(convention.to_utc(raw).seconds, 3_000_000_000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampConvention {
+    /// Epoch the raw seconds count from, as seconds relative to the Unix epoch
+    pub epoch_unix_seconds: i64,
+    /// Offset of the recorded clock from UTC in seconds (east positive)
+    pub utc_offset_seconds: i32,
+}
+
+impl TimestampConvention {
+    /// Standard TDMS: UTC seconds since 1904-01-01
+    pub const TDMS: TimestampConvention = TimestampConvention {
+        epoch_unix_seconds: -Timestamp::EPOCH_OFFSET_SECONDS,
+        utc_offset_seconds: 0,
+    };
+
+    /// UTC seconds since 1970-01-01, as written by loggers that store Unix time
+    /// in timestamp fields
+    pub const UNIX: TimestampConvention = TimestampConvention {
+        epoch_unix_seconds: 0,
+        utc_offset_seconds: 0,
+    };
+
+    /// Standard epoch, but recorded as local time at a fixed offset from UTC
+    pub fn local_time(utc_offset_seconds: i32) -> Self {
+        TimestampConvention { utc_offset_seconds, ..Self::TDMS }
+    }
+
+    /// Seconds to add to a raw timestamp to get a standard UTC TDMS timestamp
+    fn correction_seconds(&self) -> i64 {
+        self.epoch_unix_seconds + Timestamp::EPOCH_OFFSET_SECONDS - self.utc_offset_seconds as i64
+    }
+
+    /// Convert a raw timestamp from the file into a standard UTC TDMS timestamp
+    pub fn to_utc(&self, raw: Timestamp) -> Timestamp {
+        Timestamp { seconds: raw.seconds + self.correction_seconds(), fractions: raw.fractions }
+    }
+
+    /// Convert a standard UTC TDMS timestamp into this convention, e.g. to write
+    /// files an old logger's tools expect
+    pub fn from_utc(&self, utc: Timestamp) -> Timestamp {
+        Timestamp { seconds: utc.seconds - self.correction_seconds(), fractions: utc.fractions }
+    }
+
+    /// Convert a raw timestamp from the file to `SystemTime`
+    pub fn to_system_time(&self, raw: Timestamp) -> SystemTime {
+        self.to_utc(raw).to_system_time()
+    }
+}
+
+impl Default for TimestampConvention {
+    fn default() -> Self {
+        Self::TDMS
+    }
+}
+
 /// Property value that can be attached to objects
 #[derive(Debug, Clone)]
 pub enum PropertyValue {
@@ -352,25 +821,83 @@ impl PropertyValue {
         }
     }
     
+    /// Integer value, widening any integer variant (and accepting whole `u64`s that fit)
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            PropertyValue::I8(v) => Some(*v as i64),
+            PropertyValue::I16(v) => Some(*v as i64),
+            PropertyValue::I32(v) => Some(*v as i64),
+            PropertyValue::I64(v) => Some(*v),
+            PropertyValue::U8(v) => Some(*v as i64),
+            PropertyValue::U16(v) => Some(*v as i64),
+            PropertyValue::U32(v) => Some(*v as i64),
+            PropertyValue::U64(v) => i64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+    
+    /// Numeric value as `f64`, converting integer variants
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            PropertyValue::Float(v) => Some(*v as f64),
+            PropertyValue::Double(v) => Some(*v),
+            PropertyValue::U64(v) => Some(*v as f64),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+    
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropertyValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            PropertyValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+    
+    pub fn as_timestamp(&self) -> Option<Timestamp> {
+        match self {
+            PropertyValue::Timestamp(ts) => Some(*ts),
+            _ => None,
+        }
+    }
+    
     pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_with_endianness(writer, Endianness::Little)
+    }
+    
+    /// Write the value as a property value in the given byte order
+    pub fn write_with_endianness<W: std::io::Write>(&self, writer: &mut W, endianness: Endianness) -> std::io::Result<()> {
+        match endianness {
+            Endianness::Little => self.write_ordered::<LittleEndian, W>(writer, Timestamp::to_bytes_le),
+            Endianness::Big => self.write_ordered::<BigEndian, W>(writer, Timestamp::to_bytes_be),
+        }
+    }
+    
+    fn write_ordered<B: ByteOrder, W: std::io::Write>(&self, writer: &mut W, timestamp_bytes: fn(&Timestamp) -> [u8; 16]) -> std::io::Result<()> {
         use byteorder::WriteBytesExt;
         
         match self {
             PropertyValue::I8(v) => writer.write_i8(*v),
-            PropertyValue::I16(v) => writer.write_i16::<LittleEndian>(*v),
-            PropertyValue::I32(v) => writer.write_i32::<LittleEndian>(*v),
-            PropertyValue::I64(v) => writer.write_i64::<LittleEndian>(*v),
+            PropertyValue::I16(v) => writer.write_i16::<B>(*v),
+            PropertyValue::I32(v) => writer.write_i32::<B>(*v),
+            PropertyValue::I64(v) => writer.write_i64::<B>(*v),
             PropertyValue::U8(v) => writer.write_u8(*v),
-            PropertyValue::U16(v) => writer.write_u16::<LittleEndian>(*v),
-            PropertyValue::U32(v) => writer.write_u32::<LittleEndian>(*v),
-            PropertyValue::U64(v) => writer.write_u64::<LittleEndian>(*v),
-            PropertyValue::Float(v) => writer.write_f32::<LittleEndian>(*v),
-            PropertyValue::Double(v) => writer.write_f64::<LittleEndian>(*v),
+            PropertyValue::U16(v) => writer.write_u16::<B>(*v),
+            PropertyValue::U32(v) => writer.write_u32::<B>(*v),
+            PropertyValue::U64(v) => writer.write_u64::<B>(*v),
+            PropertyValue::Float(v) => writer.write_f32::<B>(*v),
+            PropertyValue::Double(v) => writer.write_f64::<B>(*v),
             PropertyValue::Boolean(v) => writer.write_u8(if *v { 1 } else { 0 }),
-            PropertyValue::Timestamp(ts) => writer.write_all(&ts.to_bytes_le()),
+            PropertyValue::Timestamp(ts) => writer.write_all(&timestamp_bytes(ts)),
             PropertyValue::String(s) => {
                 let bytes = s.as_bytes();
-                writer.write_u32::<LittleEndian>(bytes.len() as u32)?;
+                writer.write_u32::<B>(bytes.len() as u32)?;
                 writer.write_all(bytes)
             }
         }
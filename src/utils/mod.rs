@@ -2,3 +2,4 @@
 mod string_encoding;
 mod endian;
 
+pub use string_encoding::StringEncoding;
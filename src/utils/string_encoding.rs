@@ -1 +1,151 @@
-// src/utils/string_encoding.rs
\ No newline at end of file
+// src/utils/string_encoding.rs
+use crate::error::{TdmsError, Result};
+use std::fmt;
+
+/// Character encoding of the strings in a file: object names, string
+/// properties and string channel data.
+///
+/// The TDMS format stores UTF-8, but files written by LabVIEW on systems with
+/// a non-UTF-8 locale often hold strings in the system code page. A writer
+/// can declare the encoding it uses in the file property
+/// [`StringEncoding::PROPERTY`] (see
+/// [`TdmsWriter::set_string_encoding`](crate::TdmsWriter::set_string_encoding)),
+/// and readers transcode to UTF-8 when they find it. For files without the
+/// property, set [`ReaderOptions::string_encoding`](crate::ReaderOptions::string_encoding).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    #[default]
+    Utf8,
+    /// ISO-8859-1, where every byte is the code point of the same value
+    Latin1,
+    /// The Western European Windows code page
+    Windows1252,
+}
+
+/// Code points of Windows-1252 bytes 0x80..=0x9F. The five bytes the code
+/// page leaves undefined map to the C1 control of the same value.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl StringEncoding {
+    /// File property declaring the encoding, holding [`name`](Self::name)
+    pub const PROPERTY: &'static str = "tdms_rs_string_encoding";
+
+    /// IANA name of the encoding
+    pub fn name(self) -> &'static str {
+        match self {
+            StringEncoding::Utf8 => "UTF-8",
+            StringEncoding::Latin1 => "ISO-8859-1",
+            StringEncoding::Windows1252 => "windows-1252",
+        }
+    }
+
+    /// Parse an encoding name, ignoring case and accepting common aliases
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Some(StringEncoding::Utf8),
+            "iso-8859-1" | "iso8859-1" | "latin1" | "latin-1" => Some(StringEncoding::Latin1),
+            "windows-1252" | "cp1252" => Some(StringEncoding::Windows1252),
+            _ => None,
+        }
+    }
+
+    /// Decode bytes in this encoding.
+    ///
+    /// Only UTF-8 can fail, with [`TdmsError::InvalidUtf8`]; every byte has a
+    /// meaning in the single-byte encodings.
+    pub fn decode(self, bytes: &[u8]) -> Result<String> {
+        match self {
+            StringEncoding::Utf8 => String::from_utf8(bytes.to_vec()).map_err(|_| TdmsError::InvalidUtf8),
+            StringEncoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+            StringEncoding::Windows1252 => Ok(bytes.iter()
+                .map(|&b| match b {
+                    0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+                    _ => b as char,
+                })
+                .collect()),
+        }
+    }
+
+    /// Encode a string in this encoding.
+    ///
+    /// Fails with [`TdmsError::Unsupported`] for characters the encoding
+    /// can't represent.
+    pub fn encode(self, s: &str) -> Result<Vec<u8>> {
+        match self {
+            StringEncoding::Utf8 => Ok(s.as_bytes().to_vec()),
+            _ => s.chars().map(|c| self.encode_char(c)
+                .ok_or_else(|| TdmsError::Unsupported(format!("{:?} can't be encoded as {}", c, self))))
+                .collect(),
+        }
+    }
+
+    /// Encode a string, replacing characters the encoding can't represent with `?`
+    pub fn encode_lossy(self, s: &str) -> Vec<u8> {
+        match self {
+            StringEncoding::Utf8 => s.as_bytes().to_vec(),
+            _ => s.chars().map(|c| self.encode_char(c).unwrap_or(b'?')).collect(),
+        }
+    }
+
+    fn encode_char(self, c: char) -> Option<u8> {
+        let code = c as u32;
+        match self {
+            StringEncoding::Utf8 => None,
+            StringEncoding::Latin1 => u8::try_from(code).ok(),
+            StringEncoding::Windows1252 => match WINDOWS_1252_HIGH.iter().position(|&h| h == c) {
+                Some(index) => Some(0x80 + index as u8),
+                None if (0x80..=0x9F).contains(&code) => None,
+                None => u8::try_from(code).ok(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for StringEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_byte_roundtrip() {
+        let text = "Temp. °C – Ölstand ½ €";
+        let bytes = StringEncoding::Windows1252.encode(text).unwrap();
+        assert_eq!(bytes.len(), text.chars().count());
+        assert_eq!(bytes[bytes.len() - 1], 0x80);
+        assert_eq!(StringEncoding::Windows1252.decode(&bytes).unwrap(), text);
+
+        // Latin-1 has no euro sign or en dash
+        assert!(matches!(StringEncoding::Latin1.encode(text), Err(TdmsError::Unsupported(_))));
+        assert_eq!(StringEncoding::Latin1.encode_lossy("½ €"), vec![0xBD, b' ', b'?']);
+        let bytes = StringEncoding::Latin1.encode("Ölstand °C").unwrap();
+        assert_eq!(bytes[0], 0xD6);
+        assert_eq!(StringEncoding::Latin1.decode(&bytes).unwrap(), "Ölstand °C");
+
+        // Every byte decodes, and decoding then encoding gives the bytes back
+        let all: Vec<u8> = (0..=255).collect();
+        for encoding in [StringEncoding::Latin1, StringEncoding::Windows1252] {
+            let decoded = encoding.decode(&all).unwrap();
+            assert_eq!(encoding.encode(&decoded).unwrap(), all);
+        }
+        assert!(matches!(StringEncoding::Utf8.decode(&[0xD6]), Err(TdmsError::InvalidUtf8)));
+    }
+
+    #[test]
+    fn test_names() {
+        for encoding in [StringEncoding::Utf8, StringEncoding::Latin1, StringEncoding::Windows1252] {
+            assert_eq!(StringEncoding::from_name(encoding.name()), Some(encoding));
+        }
+        assert_eq!(StringEncoding::from_name("CP1252"), Some(StringEncoding::Windows1252));
+        assert_eq!(StringEncoding::from_name("Shift_JIS"), None);
+    }
+}
@@ -4,10 +4,12 @@
 #[cfg(feature = "async")]
 use crate::error::{TdmsError, Result};
 #[cfg(feature = "async")]
-use crate::types::{DataType, PropertyValue};
+use crate::types::{DataType, Property, PropertyValue, TdmsValue};
 #[cfg(feature = "async")]
 use crate::writer::TdmsWriter;
 #[cfg(feature = "async")]
+use crate::writer::watchdog::{Watchdog, WriterStall};
+#[cfg(feature = "async")]
 use std::path::Path;
 #[cfg(feature = "async")]
 use tokio::sync::mpsc;
@@ -17,6 +19,8 @@ use tokio::task;
 use parking_lot::Mutex;
 #[cfg(feature = "async")]
 use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::time::Duration;
 use bytemuck;
 
 /// Command for async writer
@@ -46,17 +50,54 @@ enum WriteCommand {
         name: String,
         value: PropertyValue,
     },
+    SetFileProperties {
+        properties: Vec<Property>,
+    },
+    SetGroupProperty {
+        group: String,
+        name: String,
+        value: PropertyValue,
+    },
+    CreateGroup {
+        group: String,
+        response: tokio::sync::oneshot::Sender<Result<()>>,
+    },
+    SetChannelProperties {
+        group: String,
+        channel: String,
+        properties: Vec<Property>,
+        response: tokio::sync::oneshot::Sender<Result<()>>,
+    },
     Flush {
         response: tokio::sync::oneshot::Sender<Result<()>>,
     },
     Close,
 }
 
+impl WriteCommand {
+    /// Name reported when the command stalls
+    fn name(&self) -> &'static str {
+        match self {
+            WriteCommand::CreateChannel { .. } => "create_channel",
+            WriteCommand::WriteData { .. } => "write_channel_data",
+            WriteCommand::WriteStrings { .. } => "write_channel_strings",
+            WriteCommand::SetFileProperty { .. } => "set_file_property",
+            WriteCommand::SetFileProperties { .. } => "set_file_properties",
+            WriteCommand::SetGroupProperty { .. } => "set_group_property",
+            WriteCommand::CreateGroup { .. } => "create_group",
+            WriteCommand::SetChannelProperties { .. } => "set_channel_properties",
+            WriteCommand::Flush { .. } => "flush",
+            WriteCommand::Close => "close",
+        }
+    }
+}
+
 /// Async TDMS writer for concurrent access
 #[cfg(feature = "async")]
 /// Async TDMS writer for concurrent access
 pub struct AsyncTdmsWriter {
     command_tx: mpsc::UnboundedSender<WriteCommand>,
+    watchdog: Watchdog,
     handle: Arc<Mutex<Option<task::JoinHandle<Result<()>>>>>,
 }
 
@@ -65,12 +106,15 @@ impl AsyncTdmsWriter {
         let (command_tx, command_rx) = mpsc::unbounded_channel();
         
         let writer = TdmsWriter::create(path)?;
+        let watchdog = Watchdog::default();
+        let task_watchdog = watchdog.clone();
         let handle = task::spawn_blocking(move || {
-            Self::writer_task(writer, command_rx)
+            Self::writer_task(writer, command_rx, task_watchdog)
         });
         
         Ok(AsyncTdmsWriter {
             command_tx,
+            watchdog,
             handle: Arc::new(Mutex::new(Some(handle))),
         })
     }
@@ -78,8 +122,11 @@ impl AsyncTdmsWriter {
     fn writer_task(
         mut writer: TdmsWriter,
         mut command_rx: mpsc::UnboundedReceiver<WriteCommand>,
+        watchdog: Watchdog,
     ) -> Result<()> {
+        let _guard = watchdog.task_guard();
         while let Some(command) = command_rx.blocking_recv() {
+            watchdog.started(command.name());
             match command {
                 WriteCommand::CreateChannel { group, channel, data_type, response } => {
                     let result = writer.create_channel(&group, &channel, data_type);
@@ -96,15 +143,28 @@ impl AsyncTdmsWriter {
                 WriteCommand::SetFileProperty { name, value } => {
                     writer.set_file_property(name, value);
                 }
+                WriteCommand::SetFileProperties { properties } => {
+                    writer.set_file_properties(properties);
+                }
+                WriteCommand::SetGroupProperty { group, name, value } => {
+                    writer.set_group_property(group, name, value);
+                }
+                WriteCommand::CreateGroup { group, response } => {
+                    let _ = response.send(writer.create_group(group));
+                }
+                WriteCommand::SetChannelProperties { group, channel, properties, response } => {
+                    let _ = response.send(writer.set_channel_properties(&group, &channel, properties));
+                }
                 WriteCommand::Flush { response } => {
                     let result = writer.flush();
                     let _ = response.send(result);
                 }
                 WriteCommand::Close => {
-                    writer.flush()?;
+                    writer.force_flush()?;
                     break;
                 }
             }
+            watchdog.finished();
         }
         
         Ok(())
@@ -129,24 +189,46 @@ impl AsyncTdmsWriter {
             DataType::U64 => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, u64>(data)),
             DataType::SingleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, f32>(data)),
             DataType::DoubleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, f64>(data)),
+            DataType::ExtendedFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::F80>(data)),
             DataType::Boolean => {
                 // bool is not Pod, so we can't cast it directly.
-                // Instead, we convert the incoming bytes to a slice of bools,
-                // then map them to u8s.
+                // The bytes came from a `Vec<bool>`, so they are valid bools.
                 let bools: &[bool] = unsafe {
                     std::slice::from_raw_parts(
                         data.as_ptr() as *const bool,
                         data.len(),
                     )
                 };
-                let bytes: Vec<u8> = bools.iter().map(|&b| b as u8).collect();
-                writer.write_channel_data(group, channel, &bytes)
+                writer.write_channel_data(group, channel, bools)
             }
             DataType::TimeStamp => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::Timestamp>(data)),
+            DataType::ComplexSingleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::Complex<f32>>(data)),
+            DataType::ComplexDoubleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::Complex<f64>>(data)),
             _ => Err(TdmsError::Unsupported(format!("Async write for {:?}", data_type))),
         }
     }
 
+    /// Watch for commands that take longer than `timeout`, e.g. a flush hung
+    /// on an unresponsive network mount (see [`crate::writer::watchdog`]).
+    /// 
+    /// `on_stall` runs on the current runtime once per stall. While a command
+    /// is stalled, new commands fail with [`TdmsError::WriterStalled`] instead
+    /// of queueing up in memory; closing is still queued.
+    pub fn set_watchdog(&self, timeout: Duration, on_stall: impl Fn(WriterStall) + Send + Sync + 'static) -> Result<()> {
+        self.watchdog.watch(timeout, on_stall)
+    }
+    
+    /// The command the writer task has been stuck in for longer than the
+    /// watchdog timeout, if any
+    pub fn stall(&self) -> Option<WriterStall> {
+        self.watchdog.stall()
+    }
+    
+    fn send(&self, command: WriteCommand) -> Result<()> {
+        self.watchdog.admit()?;
+        self.command_tx.send(command).map_err(|_| TdmsError::WriterClosed)
+    }
+    
     pub async fn create_channel(
         &self,
         group: impl Into<String>,
@@ -154,22 +236,28 @@ impl AsyncTdmsWriter {
         data_type: DataType,
     ) -> Result<()> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
-        self.command_tx.send(WriteCommand::CreateChannel {
+        self.send(WriteCommand::CreateChannel {
             group: group.into(),
             channel: channel.into(),
             data_type,
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
     
-    pub async fn write_channel_data<T: Copy + Send + 'static>(
+    pub async fn write_channel_data<T: TdmsValue + Send>(
         &self,
         group: impl Into<String>,
         channel: impl Into<String>,
         data: Vec<T>,
         data_type: DataType,
     ) -> Result<()> {
+        if data_type != T::DATA_TYPE {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", data_type),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
         
         let bytes = unsafe {
@@ -179,13 +267,13 @@ impl AsyncTdmsWriter {
             ).to_vec()
         };
         
-        self.command_tx.send(WriteCommand::WriteData {
+        self.send(WriteCommand::WriteData {
             group: group.into(),
             channel: channel.into(),
             data: bytes,
             data_type,
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
@@ -198,38 +286,88 @@ impl AsyncTdmsWriter {
     ) -> Result<()> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
         
-        self.command_tx.send(WriteCommand::WriteStrings {
+        self.send(WriteCommand::WriteStrings {
             group: group.into(),
             channel: channel.into(),
             strings,
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
     
     pub fn set_file_property(&self, name: impl Into<String>, value: PropertyValue) -> Result<()> {
-        self.command_tx.send(WriteCommand::SetFileProperty {
+        self.send(WriteCommand::SetFileProperty {
             name: name.into(),
             value,
-        }).map_err(|_| TdmsError::WriterClosed)
+        })
+    }
+    
+    pub fn set_file_properties(&self, properties: impl IntoIterator<Item = Property>) -> Result<()> {
+        self.send(WriteCommand::SetFileProperties {
+            properties: properties.into_iter().collect(),
+        })
+    }
+    
+    pub fn set_group_property(&self, group: impl Into<String>, name: impl Into<String>, value: PropertyValue) -> Result<()> {
+        self.send(WriteCommand::SetGroupProperty {
+            group: group.into(),
+            name: name.into(),
+            value,
+        })
+    }
+    
+    pub async fn create_group(&self, group: impl Into<String>) -> Result<()> {
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        self.send(WriteCommand::CreateGroup {
+            group: group.into(),
+            response: response_tx,
+        })?;
+        response_rx.await.map_err(|_| TdmsError::WriterClosed)?
+    }
+    
+    pub async fn set_channel_property(
+        &self,
+        group: impl Into<String>,
+        channel: impl Into<String>,
+        name: impl Into<String>,
+        value: PropertyValue,
+    ) -> Result<()> {
+        self.set_channel_properties(group, channel, [Property::new(name, value)]).await
+    }
+    
+    pub async fn set_channel_properties(
+        &self,
+        group: impl Into<String>,
+        channel: impl Into<String>,
+        properties: impl IntoIterator<Item = Property>,
+    ) -> Result<()> {
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        self.send(WriteCommand::SetChannelProperties {
+            group: group.into(),
+            channel: channel.into(),
+            properties: properties.into_iter().collect(),
+            response: response_tx,
+        })?;
+        response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
     
     pub async fn flush(&self) -> Result<()> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
         
-        self.command_tx.send(WriteCommand::Flush {
+        self.send(WriteCommand::Flush {
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
     
     pub async fn close(&self) -> Result<()> {
-        self.command_tx.send(WriteCommand::Close)
-            .map_err(|_| TdmsError::WriterClosed)?;
+        self.watchdog.admit_always();
+        self.command_tx.send(WriteCommand::Close).map_err(|_| TdmsError::WriterClosed)?;
         
-        if let Some(handle) = self.handle.lock().take() {
+        let handle = self.handle.lock().take();
+        if let Some(handle) = handle {
             handle.await.map_err(|_| TdmsError::WriterClosed)??;
         }
         
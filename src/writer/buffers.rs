@@ -0,0 +1,70 @@
+// src/writer/buffers.rs
+use crate::metadata::{ChannelId, ObjectPath};
+use crate::raw_data::RawDataBuffer;
+use std::collections::HashMap;
+
+/// Raw data buffers and value counts of the writer's channels, kept in
+/// creation order so a [`ChannelId`] can reach them without hashing names
+#[derive(Default)]
+pub(crate) struct ChannelBuffers {
+    ids: HashMap<ObjectPath, ChannelId>,
+    entries: Vec<(ObjectPath, RawDataBuffer)>,
+    values_written: Vec<u64>,
+}
+
+impl ChannelBuffers {
+    /// Add a channel's buffer, returning its id
+    pub fn insert(&mut self, path: ObjectPath, buffer: RawDataBuffer) -> ChannelId {
+        if let Some(&id) = self.ids.get(&path) {
+            self.entries[id.index()].1 = buffer;
+            return id;
+        }
+        let id = ChannelId::new(self.entries.len());
+        self.ids.insert(path.clone(), id);
+        self.entries.push((path, buffer));
+        self.values_written.push(0);
+        id
+    }
+    
+    pub fn id(&self, path: &ObjectPath) -> Option<ChannelId> {
+        self.ids.get(path).copied()
+    }
+    
+    pub fn get(&self, path: &ObjectPath) -> Option<&RawDataBuffer> {
+        self.id(path).map(|id| &self.entries[id.index()].1)
+    }
+    
+    pub fn get_mut(&mut self, path: &ObjectPath) -> Option<&mut RawDataBuffer> {
+        let id = self.id(path)?;
+        Some(&mut self.entries[id.index()].1)
+    }
+    
+    /// Path, buffer and values-written counter of a channel by id
+    pub fn by_id_mut(&mut self, id: ChannelId) -> Option<(&ObjectPath, &mut RawDataBuffer, &mut u64)> {
+        let (path, buffer) = self.entries.get_mut(id.index())?;
+        Some((path, buffer, &mut self.values_written[id.index()]))
+    }
+    
+    pub fn values(&self) -> impl Iterator<Item = &RawDataBuffer> {
+        self.entries.iter().map(|(_, buffer)| buffer)
+    }
+    
+    pub fn iter(&self) -> impl Iterator<Item = (&ObjectPath, &RawDataBuffer)> {
+        self.entries.iter().map(|(path, buffer)| (path, buffer))
+    }
+    
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&ObjectPath, &mut RawDataBuffer, &mut u64)> {
+        self.entries.iter_mut().zip(&mut self.values_written)
+            .map(|((path, buffer), written)| (&*path, buffer, written))
+    }
+    
+    /// Values written to a channel so far, including ones not yet flushed
+    pub fn values_written(&self, path: &ObjectPath) -> u64 {
+        self.id(path).map_or(0, |id| self.values_written[id.index()])
+    }
+    
+    pub fn values_written_mut(&mut self, path: &ObjectPath) -> Option<&mut u64> {
+        let id = self.id(path)?;
+        Some(&mut self.values_written[id.index()])
+    }
+}
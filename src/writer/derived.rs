@@ -0,0 +1,156 @@
+// src/writer/derived.rs
+//! Channels computed from another channel as data is written.
+//!
+//! A derived channel is registered with
+//! [`TdmsWriter::create_derived_channel`](crate::TdmsWriter::create_derived_channel).
+//! At every flush the source channel's pending values are converted to `f64`
+//! and handed to the channel's [`Derivation`], whose output is written to the
+//! derived channel in the same segment. Derivations keep their own state
+//! between flushes, so windows may span any number of them.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use tdms_rs::*;
+//! use tdms_rs::writer::derived::{LinearScale, WindowRms};
+//!
+//! let mut writer = TdmsWriter::create("run.tdms").unwrap();
+//! writer.create_channel("Rack", "Vibration", DataType::I16).unwrap();
+//! writer.create_derived_channel("Rack", "Vibration RMS", "Rack", "Vibration", WindowRms::new(1000)).unwrap();
+//! writer.create_derived_channel("Rack", "Vibration (g)", "Rack", "Vibration", LinearScale::new(0.001, 0.0)).unwrap();
+//! // Or any closure
+//! writer.create_derived_channel("Rack", "Peak", "Rack", "Vibration", |input: &[f64], output: &mut Vec<f64>| {
+//!     output.extend(input.iter().copied().reduce(f64::max));
+//! }).unwrap();
+//! ```
+
+use crate::metadata::ObjectPath;
+use crate::raw_data::RawDataBuffer;
+use crate::types::DataType;
+
+/// Property recording the source channel of a derived channel
+pub const DERIVED_FROM_PROPERTY: &str = "tdms_rs_derived_from";
+
+/// Computes a derived channel's values from its source channel's
+pub trait Derivation: Send + Sync {
+    /// Append the values derived from the source values written since the
+    /// last flush to `output`, which starts empty
+    fn derive(&mut self, input: &[f64], output: &mut Vec<f64>);
+}
+
+impl<F: FnMut(&[f64], &mut Vec<f64>) + Send + Sync> Derivation for F {
+    fn derive(&mut self, input: &[f64], output: &mut Vec<f64>) {
+        self(input, output)
+    }
+}
+
+/// Root mean square of each block of `window` source values, one value per
+/// complete block. Values of an incomplete block wait for the next flush.
+#[derive(Debug, Clone)]
+pub struct WindowRms {
+    window: usize,
+    sum_of_squares: f64,
+    count: usize,
+}
+
+impl WindowRms {
+    /// # Panics
+    ///
+    /// If `window` is zero
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "RMS window must not be empty");
+        WindowRms { window, sum_of_squares: 0.0, count: 0 }
+    }
+}
+
+impl Derivation for WindowRms {
+    fn derive(&mut self, input: &[f64], output: &mut Vec<f64>) {
+        for value in input {
+            self.sum_of_squares += value * value;
+            self.count += 1;
+            if self.count == self.window {
+                output.push((self.sum_of_squares / self.window as f64).sqrt());
+                self.sum_of_squares = 0.0;
+                self.count = 0;
+            }
+        }
+    }
+}
+
+/// `slope * value + intercept` of every source value, e.g. a copy in other units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearScale {
+    pub slope: f64,
+    pub intercept: f64,
+}
+
+impl LinearScale {
+    pub fn new(slope: f64, intercept: f64) -> Self {
+        LinearScale { slope, intercept }
+    }
+}
+
+impl Derivation for LinearScale {
+    fn derive(&mut self, input: &[f64], output: &mut Vec<f64>) {
+        output.extend(input.iter().map(|value| self.slope * value + self.intercept));
+    }
+}
+
+/// A registered derived channel
+pub(crate) struct DerivedChannel {
+    pub path: ObjectPath,
+    pub source: ObjectPath,
+    pub derivation: Box<dyn Derivation>,
+    /// Values at the start of the source's buffer already derived, so a
+    /// commit that fails and is retried doesn't derive them twice
+    pub derived_values: u64,
+}
+
+/// The values buffered for a numeric channel as `f64`, or `None` for other types
+pub(crate) fn buffered_values_as_f64(buffer: &RawDataBuffer) -> Option<Vec<f64>> {
+    // Buffers hold values in native byte order until they are written
+    macro_rules! convert {
+        ($t:ty) => {
+            buffer.as_bytes()
+                .chunks_exact(std::mem::size_of::<$t>())
+                .map(|chunk| <$t>::from_ne_bytes(chunk.try_into().expect("value sized")) as f64)
+                .collect()
+        };
+    }
+    Some(match buffer.data_type() {
+        DataType::I8 => convert!(i8),
+        DataType::I16 => convert!(i16),
+        DataType::I32 => convert!(i32),
+        DataType::I64 => convert!(i64),
+        DataType::U8 => convert!(u8),
+        DataType::U16 => convert!(u16),
+        DataType::U32 => convert!(u32),
+        DataType::U64 => convert!(u64),
+        DataType::SingleFloat => convert!(f32),
+        DataType::DoubleFloat => convert!(f64),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_rms_spans_calls() {
+        let mut rms = WindowRms::new(4);
+        let mut output = Vec::new();
+        rms.derive(&[3.0, -3.0, 3.0], &mut output);
+        assert!(output.is_empty());
+        rms.derive(&[-3.0, 1.0, 1.0, 1.0, 1.0, 5.0], &mut output);
+        assert_eq!(output, [3.0, 1.0]);
+    }
+
+    #[test]
+    fn test_buffered_values_as_f64() {
+        let mut buffer = RawDataBuffer::new(DataType::I16);
+        buffer.write_slice(&[-2i16, 7]).unwrap();
+        assert_eq!(buffered_values_as_f64(&buffer), Some(vec![-2.0, 7.0]));
+        assert_eq!(buffered_values_as_f64(&RawDataBuffer::new(DataType::String)), None);
+    }
+}
@@ -0,0 +1,112 @@
+// src/writer/journal.rs
+use crate::error::Result;
+use crate::metadata::ObjectPath;
+use crate::types::Timestamp;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// How a segment commit changed the data file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CommitKind {
+    /// A new segment (lead-in, metadata and optional raw data) was written
+    Segment,
+    /// Raw data was appended to the previous segment and its lead-in patched
+    Append,
+}
+
+impl CommitKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            CommitKind::Segment => "segment",
+            CommitKind::Append => "append",
+        }
+    }
+}
+
+/// A single committed segment as recorded in the journal
+pub(crate) struct CommitRecord<'a> {
+    pub kind: CommitKind,
+    pub file: &'a Path,
+    pub offset: u64,
+    pub next_segment_offset: u64,
+    pub metadata_size: u64,
+    pub channels: &'a [ObjectPath],
+    pub time: Timestamp,
+}
+
+/// Append-only sidecar journal of segment commits.
+///
+/// Each commit is one JSON object per line, flushed immediately, so the journal
+/// survives a crash that leaves the data file in an inconsistent state:
+///
+/// ```text
+/// {"event":"segment","file":"run.tdms","offset":0,"next_segment_offset":412,"metadata_size":212,"channels":["/'Data'/'Values'"],"unix_time":1700000000.125}
+/// ```
+pub(crate) struct Journal {
+    file: BufWriter<File>,
+}
+
+impl Journal {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Journal { file: BufWriter::new(file) })
+    }
+
+    pub fn record(&mut self, record: &CommitRecord) -> Result<()> {
+        let unix_time = record.time.to_system_time()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        let channels: Vec<String> = record.channels.iter()
+            .map(|path| json_string(&path.to_string()))
+            .collect();
+
+        writeln!(
+            self.file,
+            "{{\"event\":\"{}\",\"file\":{},\"offset\":{},\"next_segment_offset\":{},\"metadata_size\":{},\"channels\":[{}],\"unix_time\":{:.6}}}",
+            record.kind.as_str(),
+            json_string(&record.file.to_string_lossy()),
+            record.offset,
+            record.next_segment_offset,
+            record.metadata_size,
+            channels.join(","),
+            unix_time,
+        )?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// Quote and escape a string as a JSON string literal
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string_escaping() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("/'a\"b'"), "\"/'a\\\"b'\"");
+        assert_eq!(json_string("C:\\data\n"), "\"C:\\\\data\\n\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+}
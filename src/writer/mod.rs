@@ -1,16 +1,32 @@
 // src/writer/mod.rs
 mod sync_writer;
 mod rotating_writer;
+mod journal;
+mod names;
+mod plan;
+mod overhead;
+mod sink;
+mod buffers;
+pub mod telemetry;
+pub mod derived;
 
 #[cfg(feature = "async")]
 mod async_writer;
 #[cfg(feature = "async")]
 mod rotating_async_writer;
+#[cfg(feature = "async")]
+pub mod watchdog;
 
-pub use sync_writer::TdmsWriter;
-pub use rotating_writer::RotatingTdmsWriter;
+pub use sync_writer::{TdmsWriter, DurabilityMode, DurabilityPolicy, FlushWindow};
+pub use rotating_writer::{RotatingTdmsWriter, RotatingWriterBuilder, RotationNaming};
+pub use plan::{SegmentAction, SegmentPlan, SegmentReason};
+pub use overhead::MetadataOverhead;
+pub use sink::ChannelSink;
+pub use names::{NamePolicy, MAX_NAME_LENGTH, validate_name, sanitize_name};
 
 #[cfg(feature = "async")]
 pub use async_writer::AsyncTdmsWriter;
 #[cfg(feature = "async")]
-pub use rotating_async_writer::AsyncRotatingTdmsWriter;
\ No newline at end of file
+pub use rotating_async_writer::AsyncRotatingTdmsWriter;
+#[cfg(feature = "async")]
+pub use watchdog::WriterStall;
\ No newline at end of file
@@ -0,0 +1,100 @@
+// src/writer/names.rs
+use crate::error::{TdmsError, Result};
+
+/// Longest group, channel or property name, in characters, the writer accepts
+/// under [`NamePolicy::Reject`].
+///
+/// TDMS itself stores names with a 32-bit length, but LabVIEW and DIAdem refuse
+/// or truncate object names beyond this.
+pub const MAX_NAME_LENGTH: usize = 255;
+
+/// Name used by [`NamePolicy::Sanitize`] in place of an empty name
+const EMPTY_NAME_REPLACEMENT: &str = "Untitled";
+
+/// What the writer does with group, channel and property names that other
+/// TDMS readers may refuse.
+///
+/// A name is invalid if it is empty, longer than [`MAX_NAME_LENGTH`] characters
+/// or contains control characters (including NUL). `'` and `/` are allowed:
+/// path components are quoted and `'` is escaped as `''`, so both round-trip.
+/// A `/` in a group name is also how virtual group hierarchies are written
+/// (see [`GroupTree`](crate::metadata::GroupTree)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamePolicy {
+    /// Fail with [`TdmsError::InvalidName`]
+    Reject,
+    /// Replace control characters with `_`, truncate overlong names and name
+    /// empty objects `"Untitled"`
+    Sanitize,
+    /// Write names unchanged, as earlier versions did
+    #[default]
+    Allow,
+}
+
+impl NamePolicy {
+    /// Apply the policy to a name, returning the name to write
+    pub fn apply(self, name: String) -> Result<String> {
+        match self {
+            NamePolicy::Allow => Ok(name),
+            NamePolicy::Reject => {
+                validate_name(&name)?;
+                Ok(name)
+            }
+            NamePolicy::Sanitize => Ok(sanitize_name(&name)),
+        }
+    }
+}
+
+/// Check a name against the rules described on [`NamePolicy`]
+pub fn validate_name(name: &str) -> Result<()> {
+    let reason = if name.is_empty() {
+        "name is empty".to_string()
+    } else if name.chars().count() > MAX_NAME_LENGTH {
+        format!("name is longer than {} characters", MAX_NAME_LENGTH)
+    } else if name.chars().any(char::is_control) {
+        "name contains control characters".to_string()
+    } else {
+        return Ok(());
+    };
+
+    Err(TdmsError::InvalidName { name: name.to_string(), reason })
+}
+
+/// Rewrite a name so it passes [`validate_name`]
+pub fn sanitize_name(name: &str) -> String {
+    let sanitized: String = name.chars()
+        .map(|c| if c.is_control() { '_' } else { c })
+        .take(MAX_NAME_LENGTH)
+        .collect();
+
+    if sanitized.is_empty() {
+        EMPTY_NAME_REPLACEMENT.to_string()
+    } else {
+        sanitized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("Voltage").is_ok());
+        assert!(validate_name("it's a/b").is_ok());
+        assert!(validate_name(&"é".repeat(MAX_NAME_LENGTH)).is_ok());
+
+        assert!(validate_name("").is_err());
+        assert!(validate_name("bad\0name").is_err());
+        assert!(validate_name("line\nbreak").is_err());
+        assert!(validate_name(&"x".repeat(MAX_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("bad\0name\t"), "bad_name_");
+        assert_eq!(sanitize_name(""), "Untitled");
+        assert_eq!(sanitize_name(&"x".repeat(300)).len(), MAX_NAME_LENGTH);
+        assert_eq!(sanitize_name("/'ok'/"), "/'ok'/");
+    }
+}
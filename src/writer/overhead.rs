@@ -0,0 +1,77 @@
+// src/writer/overhead.rs
+use std::fmt;
+
+/// Bytes a writer has spent on lead-ins and metadata versus raw data.
+///
+/// Returned by [`TdmsWriter::metadata_overhead`](crate::TdmsWriter::metadata_overhead)
+/// and counted for the current file only. Every object in a segment's metadata
+/// repeats its full path, so files with thousands of long channel names that
+/// change their channel list or properties often spend much of their size on
+/// path strings; [`path_bytes`](Self::path_bytes) shows how much.
+/// [`defragment`](crate::defragment) rewrites such a file with the metadata in
+/// a single segment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetadataOverhead {
+    /// Segments written (appends to the previous segment are not counted)
+    pub segments: u64,
+    /// Bytes of segment lead-ins (28 per segment)
+    pub lead_in_bytes: u64,
+    pub metadata_bytes: u64,
+    /// Part of `metadata_bytes` spent on object path strings, including their
+    /// length prefixes
+    pub path_bytes: u64,
+    pub raw_data_bytes: u64,
+}
+
+impl MetadataOverhead {
+    /// Total size of the segments written
+    pub fn total_bytes(&self) -> u64 {
+        self.lead_in_bytes + self.metadata_bytes + self.raw_data_bytes
+    }
+
+    /// Share of the file spent on lead-ins and metadata rather than raw data, in percent
+    pub fn metadata_overhead_percent(&self) -> f64 {
+        percent(self.lead_in_bytes + self.metadata_bytes, self.total_bytes())
+    }
+
+    /// Share of the metadata spent on object paths, in percent
+    pub fn path_percent(&self) -> f64 {
+        percent(self.path_bytes, self.metadata_bytes)
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    match total {
+        0 => 0.0,
+        total => part as f64 * 100.0 / total as f64,
+    }
+}
+
+impl fmt::Display for MetadataOverhead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} segments, {} bytes ({:.1}% lead-in and metadata, {:.1}% of metadata in paths)",
+            self.segments, self.total_bytes(), self.metadata_overhead_percent(), self.path_percent())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentages() {
+        assert_eq!(MetadataOverhead::default().metadata_overhead_percent(), 0.0);
+        assert_eq!(MetadataOverhead::default().path_percent(), 0.0);
+
+        let overhead = MetadataOverhead {
+            segments: 1,
+            lead_in_bytes: 28,
+            metadata_bytes: 72,
+            path_bytes: 18,
+            raw_data_bytes: 300,
+        };
+        assert_eq!(overhead.total_bytes(), 400);
+        assert_eq!(overhead.metadata_overhead_percent(), 25.0);
+        assert_eq!(overhead.path_percent(), 25.0);
+    }
+}
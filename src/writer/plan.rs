@@ -0,0 +1,81 @@
+// src/writer/plan.rs
+use crate::metadata::ObjectPath;
+use std::fmt;
+
+/// What the next `flush` will do with the pending data and metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentAction {
+    /// Nothing is pending
+    Nothing,
+    /// Raw data is appended to the previous segment, whose lead-in is patched
+    Append,
+    /// A new segment is written
+    NewSegment,
+}
+
+/// Why the next flush has to write a new segment instead of appending
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentReason {
+    /// Nothing has been written to the file yet
+    FirstSegment,
+    /// A file property was set
+    FilePropertiesChanged,
+    /// A property of this group was set
+    GroupPropertiesChanged(String),
+    /// A property of this channel was set
+    ChannelPropertiesChanged(ObjectPath),
+    /// The channel's value count or type differs from its last segment
+    /// (always the case for string channels)
+    IndexChanged(ObjectPath),
+    /// Data is written for a different set or order of channels than last time
+    ChannelListChanged,
+    /// The previous segment holds only metadata, so there is nothing to append to
+    PreviousSegmentHasNoData,
+    /// Appending would grow the segment past the writer's maximum segment size
+    SegmentSizeLimit,
+}
+
+impl fmt::Display for SegmentReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SegmentReason::FirstSegment => write!(f, "first segment of the file"),
+            SegmentReason::FilePropertiesChanged => write!(f, "file properties changed"),
+            SegmentReason::GroupPropertiesChanged(group) => write!(f, "properties of group '{}' changed", group),
+            SegmentReason::ChannelPropertiesChanged(path) => write!(f, "properties of {} changed", path),
+            SegmentReason::IndexChanged(path) => write!(f, "raw data index of {} changed", path),
+            SegmentReason::ChannelListChanged => write!(f, "channels written differ from the previous segment"),
+            SegmentReason::PreviousSegmentHasNoData => write!(f, "previous segment has no raw data"),
+            SegmentReason::SegmentSizeLimit => write!(f, "segment would exceed the maximum segment size"),
+        }
+    }
+}
+
+/// Explanation of the next segment commit, from [`TdmsWriter::explain_next_segment`](crate::TdmsWriter::explain_next_segment)
+///
+/// Segments are only appended to when the same channels are written with the same
+/// number of values and no properties changed. Each reason listed here costs a new
+/// segment, and with it metadata overhead and read-time fragmentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentPlan {
+    pub action: SegmentAction,
+    /// Everything that prevents appending; empty unless `action` is `NewSegment`
+    pub reasons: Vec<SegmentReason>,
+    /// Channels with pending raw data, in write order
+    pub channels: Vec<ObjectPath>,
+}
+
+impl fmt::Display for SegmentPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.action {
+            SegmentAction::Nothing => write!(f, "nothing to write"),
+            SegmentAction::Append => write!(f, "append raw data for {} channel(s)", self.channels.len()),
+            SegmentAction::NewSegment => {
+                write!(f, "new segment")?;
+                for (i, reason) in self.reasons.iter().enumerate() {
+                    write!(f, "{}{}", if i == 0 { ": " } else { "; " }, reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
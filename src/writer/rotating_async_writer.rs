@@ -1,15 +1,47 @@
 // src/writer/rotating_async_writer.rs
 #![cfg(feature = "async")]
 use crate::error::{Result, TdmsError};
-use crate::writer::rotating_writer::RotatingTdmsWriter;
-use crate::types::{DataType, PropertyValue};
-use std::path::Path;
-use tokio::sync::mpsc;
+use crate::writer::rotating_writer::{RotatingTdmsWriter, RotatingWriterBuilder};
+use crate::writer::watchdog::{Watchdog, WriterStall};
+use crate::types::{DataType, Property, PropertyValue, TdmsValue};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, Semaphore};
 use tokio::task;
 use parking_lot::Mutex;
 use std::sync::Arc;
 use bytemuck;
 
+type PendingHooks = Vec<task::JoinHandle<()>>;
+type RotateHook = Arc<dyn Fn(PathBuf) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Runs the `on_rotate` hook for finished files on the runtime, never on the
+/// writer thread
+struct RotateHookRunner {
+    hook: RotateHook,
+    limit: Arc<Semaphore>,
+    runtime: Handle,
+    running: PendingHooks,
+}
+
+impl RotateHookRunner {
+    fn start(&mut self, files: Vec<PathBuf>) {
+        self.running.retain(|handle| !handle.is_finished());
+        for path in files {
+            let hook = self.hook.clone();
+            let limit = self.limit.clone();
+            self.running.push(self.runtime.spawn(async move {
+                // The semaphore is never closed
+                let _permit = limit.acquire_owned().await.ok();
+                hook(path).await;
+            }));
+        }
+    }
+}
+
 enum WriteCommand {
     CreateChannel {
         group: String,
@@ -34,36 +66,126 @@ enum WriteCommand {
         name: String,
         value: PropertyValue,
     },
+    SetFileProperties {
+        properties: Vec<Property>,
+    },
+    SetGroupProperty {
+        group: String,
+        name: String,
+        value: PropertyValue,
+    },
+    CreateGroup {
+        group: String,
+        response: tokio::sync::oneshot::Sender<Result<()>>,
+    },
+    SetChannelProperties {
+        group: String,
+        channel: String,
+        properties: Vec<Property>,
+        response: tokio::sync::oneshot::Sender<Result<()>>,
+    },
     Flush {
         response: tokio::sync::oneshot::Sender<Result<()>>,
     },
+    SetRotateHook(RotateHookRunner),
     Close,
 }
 
+impl WriteCommand {
+    /// Name reported when the command stalls
+    fn name(&self) -> &'static str {
+        match self {
+            WriteCommand::CreateChannel { .. } => "create_channel",
+            WriteCommand::WriteData { .. } => "write_channel_data",
+            WriteCommand::WriteStrings { .. } => "write_channel_strings",
+            WriteCommand::SetFileProperty { .. } => "set_file_property",
+            WriteCommand::SetFileProperties { .. } => "set_file_properties",
+            WriteCommand::SetGroupProperty { .. } => "set_group_property",
+            WriteCommand::CreateGroup { .. } => "create_group",
+            WriteCommand::SetChannelProperties { .. } => "set_channel_properties",
+            WriteCommand::Flush { .. } => "flush",
+            WriteCommand::SetRotateHook(_) => "on_rotate",
+            WriteCommand::Close => "close",
+        }
+    }
+}
+
 pub struct AsyncRotatingTdmsWriter {
     command_tx: mpsc::UnboundedSender<WriteCommand>,
-    handle: Arc<Mutex<Option<task::JoinHandle<Result<()>>>>>,
+    watchdog: Watchdog,
+    /// Yields the hooks still running when the writer task ends
+    handle: Arc<Mutex<Option<task::JoinHandle<Result<PendingHooks>>>>>,
 }
 
 impl AsyncRotatingTdmsWriter {
     pub async fn new(path: impl AsRef<Path>, max_size_bytes: u64) -> Result<Self> {
+        Self::from_builder(RotatingTdmsWriter::builder(path).max_size(max_size_bytes)).await
+    }
+
+    /// Create a writer with the rotation limits and file naming of `builder` (see
+    /// [`RotatingWriterBuilder`]). Its `on_rotate` callback runs on the
+    /// blocking writer task; [`on_rotate`](Self::on_rotate) runs async hooks
+    /// on the runtime instead.
+    pub async fn from_builder(builder: RotatingWriterBuilder) -> Result<Self> {
         let (command_tx, command_rx) = mpsc::unbounded_channel();
-        let writer = RotatingTdmsWriter::new(path, max_size_bytes)?;
+        let writer = builder.build()?;
+        let watchdog = Watchdog::default();
+        let task_watchdog = watchdog.clone();
         let handle = task::spawn_blocking(move || {
-            Self::writer_task(writer, command_rx)
+            Self::writer_task(writer, command_rx, task_watchdog)
         });
 
         Ok(AsyncRotatingTdmsWriter {
             command_tx,
+            watchdog,
             handle: Arc::new(Mutex::new(Some(handle))),
         })
     }
 
+    /// Run `hook` with the path of every finished file: each file the writer
+    /// rotates away from, and the last one once [`close`](Self::close) has
+    /// closed it. At most `max_concurrent` hooks run at a time, as tasks on
+    /// the current runtime, so slow uploads never hold up writing;
+    /// [`close`](Self::close) waits for all of them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> tdms_rs::Result<()> {
+    /// use tdms_rs::AsyncRotatingTdmsWriter;
+    ///
+    /// let writer = AsyncRotatingTdmsWriter::new("capture", 64 << 20).await?;
+    /// writer.on_rotate(2, |path| async move {
+    ///     // upload `path` somewhere, then
+    ///     let _ = tokio::fs::remove_file(&path).await;
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_rotate<F, Fut>(&self, max_concurrent: usize, hook: F) -> Result<()>
+    where
+        F: Fn(PathBuf) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let runner = RotateHookRunner {
+            hook: Arc::new(move |path| Box::pin(hook(path))),
+            limit: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            runtime: Handle::try_current()
+                .map_err(|_| TdmsError::Unsupported("on_rotate outside a Tokio runtime".into()))?,
+            running: Vec::new(),
+        };
+        self.send(WriteCommand::SetRotateHook(runner))
+    }
+
     fn writer_task(
         mut writer: RotatingTdmsWriter,
         mut command_rx: mpsc::UnboundedReceiver<WriteCommand>,
-    ) -> Result<()> {
+        watchdog: Watchdog,
+    ) -> Result<PendingHooks> {
+        let mut hook: Option<RotateHookRunner> = None;
+        let _guard = watchdog.task_guard();
         while let Some(command) = command_rx.blocking_recv() {
+            watchdog.started(command.name());
             match command {
                 WriteCommand::CreateChannel { group, channel, data_type, response } => {
                     let result = writer.create_channel(&group, &channel, data_type);
@@ -80,17 +202,42 @@ impl AsyncRotatingTdmsWriter {
                 WriteCommand::SetFileProperty { name, value } => {
                     writer.set_file_property(name, value);
                 }
+                WriteCommand::SetFileProperties { properties } => {
+                    writer.set_file_properties(properties);
+                }
+                WriteCommand::SetGroupProperty { group, name, value } => {
+                    writer.set_group_property(group, name, value);
+                }
+                WriteCommand::CreateGroup { group, response } => {
+                    let _ = response.send(writer.create_group(group));
+                }
+                WriteCommand::SetChannelProperties { group, channel, properties, response } => {
+                    let _ = response.send(writer.set_channel_properties(&group, &channel, properties));
+                }
                 WriteCommand::Flush { response } => {
                     let result = writer.flush();
                     let _ = response.send(result);
                 }
+                WriteCommand::SetRotateHook(runner) => {
+                    hook = Some(runner);
+                }
                 WriteCommand::Close => {
-                    writer.flush()?;
+                    writer.force_flush()?;
                     break;
                 }
             }
+            watchdog.finished();
+            if let Some(hook) = &mut hook {
+                hook.start(writer.take_finished_files());
+            }
         }
-        Ok(())
+        let last = writer.current_path();
+        writer.close()?;
+        let Some(mut hook) = hook else {
+            return Ok(Vec::new());
+        };
+        hook.start(vec![last]);
+        Ok(hook.running)
     }
 
     fn handle_write_data(
@@ -111,6 +258,7 @@ impl AsyncRotatingTdmsWriter {
             DataType::U64 => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, u64>(data)),
             DataType::SingleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, f32>(data)),
             DataType::DoubleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, f64>(data)),
+            DataType::ExtendedFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::F80>(data)),
             DataType::Boolean => {
                 let bools: &[bool] = unsafe {
                     std::slice::from_raw_parts(
@@ -118,14 +266,36 @@ impl AsyncRotatingTdmsWriter {
                         data.len(),
                     )
                 };
-                let bytes: Vec<u8> = bools.iter().map(|&b| b as u8).collect();
-                writer.write_channel_data(group, channel, &bytes)
+                writer.write_channel_data(group, channel, bools)
             }
             DataType::TimeStamp => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::Timestamp>(data)),
+            DataType::ComplexSingleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::Complex<f32>>(data)),
+            DataType::ComplexDoubleFloat => writer.write_channel_data(group, channel, bytemuck::cast_slice::<u8, crate::types::Complex<f64>>(data)),
             _ => Err(TdmsError::Unsupported(format!("Async write for {:?}", data_type))),
         }
     }
 
+    /// Watch for commands that take longer than `timeout`, e.g. a flush hung
+    /// on an unresponsive network mount (see [`crate::writer::watchdog`]).
+    /// 
+    /// `on_stall` runs on the current runtime once per stall. While a command
+    /// is stalled, new commands fail with [`TdmsError::WriterStalled`] instead
+    /// of queueing up in memory; closing is still queued.
+    pub fn set_watchdog(&self, timeout: Duration, on_stall: impl Fn(WriterStall) + Send + Sync + 'static) -> Result<()> {
+        self.watchdog.watch(timeout, on_stall)
+    }
+    
+    /// The command the writer task has been stuck in for longer than the
+    /// watchdog timeout, if any
+    pub fn stall(&self) -> Option<WriterStall> {
+        self.watchdog.stall()
+    }
+    
+    fn send(&self, command: WriteCommand) -> Result<()> {
+        self.watchdog.admit()?;
+        self.command_tx.send(command).map_err(|_| TdmsError::WriterClosed)
+    }
+    
     pub async fn create_channel(
         &self,
         group: impl Into<String>,
@@ -133,22 +303,28 @@ impl AsyncRotatingTdmsWriter {
         data_type: DataType,
     ) -> Result<()> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
-        self.command_tx.send(WriteCommand::CreateChannel {
+        self.send(WriteCommand::CreateChannel {
             group: group.into(),
             channel: channel.into(),
             data_type,
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
 
-    pub async fn write_channel_data<T: Copy + Send + 'static>(
+    pub async fn write_channel_data<T: TdmsValue + Send>(
         &self,
         group: impl Into<String>,
         channel: impl Into<String>,
         data: Vec<T>,
         data_type: DataType,
     ) -> Result<()> {
+        if data_type != T::DATA_TYPE {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", data_type),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
         let bytes = unsafe {
             std::slice::from_raw_parts(
@@ -156,13 +332,13 @@ impl AsyncRotatingTdmsWriter {
                 data.len() * std::mem::size_of::<T>(),
             ).to_vec()
         };
-        self.command_tx.send(WriteCommand::WriteData {
+        self.send(WriteCommand::WriteData {
             group: group.into(),
             channel: channel.into(),
             data: bytes,
             data_type,
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
 
@@ -173,34 +349,92 @@ impl AsyncRotatingTdmsWriter {
         strings: Vec<String>,
     ) -> Result<()> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
-        self.command_tx.send(WriteCommand::WriteStrings {
+        self.send(WriteCommand::WriteStrings {
             group: group.into(),
             channel: channel.into(),
             strings,
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
 
     pub fn set_file_property(&self, name: impl Into<String>, value: PropertyValue) -> Result<()> {
-        self.command_tx.send(WriteCommand::SetFileProperty {
+        self.send(WriteCommand::SetFileProperty {
             name: name.into(),
             value,
-        }).map_err(|_| TdmsError::WriterClosed)
+        })
+    }
+
+    pub fn set_file_properties(&self, properties: impl IntoIterator<Item = Property>) -> Result<()> {
+        self.send(WriteCommand::SetFileProperties {
+            properties: properties.into_iter().collect(),
+        })
+    }
+
+    pub fn set_group_property(&self, group: impl Into<String>, name: impl Into<String>, value: PropertyValue) -> Result<()> {
+        self.send(WriteCommand::SetGroupProperty {
+            group: group.into(),
+            name: name.into(),
+            value,
+        })
+    }
+
+    pub async fn create_group(&self, group: impl Into<String>) -> Result<()> {
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        self.send(WriteCommand::CreateGroup {
+            group: group.into(),
+            response: response_tx,
+        })?;
+        response_rx.await.map_err(|_| TdmsError::WriterClosed)?
+    }
+
+    pub async fn set_channel_property(
+        &self,
+        group: impl Into<String>,
+        channel: impl Into<String>,
+        name: impl Into<String>,
+        value: PropertyValue,
+    ) -> Result<()> {
+        self.set_channel_properties(group, channel, [Property::new(name, value)]).await
+    }
+
+    pub async fn set_channel_properties(
+        &self,
+        group: impl Into<String>,
+        channel: impl Into<String>,
+        properties: impl IntoIterator<Item = Property>,
+    ) -> Result<()> {
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        self.send(WriteCommand::SetChannelProperties {
+            group: group.into(),
+            channel: channel.into(),
+            properties: properties.into_iter().collect(),
+            response: response_tx,
+        })?;
+        response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
 
     pub async fn flush(&self) -> Result<()> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
-        self.command_tx.send(WriteCommand::Flush {
+        self.send(WriteCommand::Flush {
             response: response_tx,
-        }).map_err(|_| TdmsError::WriterClosed)?;
+        })?;
         response_rx.await.map_err(|_| TdmsError::WriterClosed)?
     }
 
     pub async fn close(&self) -> Result<()> {
+        self.watchdog.admit_always();
         self.command_tx.send(WriteCommand::Close).map_err(|_| TdmsError::WriterClosed)?;
-        if let Some(handle) = self.handle.lock().take() {
-            handle.await.map_err(|_| TdmsError::WriterClosed)??;
+        let handle = self.handle.lock().take();
+        if let Some(handle) = handle {
+            for hook in handle.await.map_err(|_| TdmsError::WriterClosed)?? {
+                // A panicking hook panics here rather than going unnoticed
+                if let Err(e) = hook.await {
+                    if e.is_panic() {
+                        std::panic::resume_unwind(e.into_panic());
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -1,78 +1,615 @@
 // src/writer/rotating_writer.rs
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
-use crate::error::Result;
-use crate::writer::sync_writer::TdmsWriter;
-use crate::types::{DataType, PropertyValue};
+use crate::error::{Result, TdmsError};
+use crate::writer::sync_writer::{TdmsWriter, DurabilityMode, DurabilityPolicy, FlushWindow};
+use crate::writer::names::NamePolicy;
+use crate::writer::plan::SegmentPlan;
+use crate::writer::overhead::MetadataOverhead;
+use crate::writer::sink::ChannelSink;
+use crate::writer::telemetry;
+use crate::metadata::ChannelId;
+use crate::properties::SampleAccounting;
+use crate::schema::ChannelSchema;
+use crate::types::{DataType, Property, PropertyValue, TdmsValue, Timestamp};
+use crate::clock::Clock;
+use crate::codec::ChannelEncoding;
+use crate::utils::StringEncoding;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
+
+type RotateCallback = Box<dyn Fn(&Path) + Send + Sync>;
 
 /// A TDMS writer that rotates to a new file when the current file
-/// exceeds a specified size.
+/// exceeds a specified size, or on the other limits of a [`RotatingWriterBuilder`].
 pub struct RotatingTdmsWriter {
     base_path: PathBuf,
-    max_size_bytes: u64,
+    naming: RotationNaming,
+    limits: RotationLimits,
     current_file_index: u32,
+    current_path: PathBuf,
     writer: TdmsWriter,
+    // When the current file was started, by the writer's clock
+    opened_at: Timestamp,
+    on_rotate: Option<RotateCallback>,
+    /// Files rotated away from and not yet taken
+    finished: Vec<PathBuf>,
+    /// Every file of the set so far, including the current one
+    produced: HashSet<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct RotationLimits {
+    max_size_bytes: Option<u64>,
+    interval: Option<Duration>,
+    max_age: Option<Duration>,
+    max_samples: Option<u64>,
+    max_segments: Option<usize>,
+}
+
+/// How a [`RotatingTdmsWriter`] names the files of a set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RotationNaming {
+    /// A numeric suffix on the base path: `capture.tdms`, `capture.1.tdms`,
+    /// `capture.2.tdms`, ...
+    #[default]
+    Suffix,
+    /// A file name template, resolved next to the base path. Placeholders:
+    ///
+    /// - `{stem}`: the file name of the base path without its extension
+    /// - `{index}`: the position of the file in the set, from 0; `{index:4}`
+    ///   pads it with zeros to four digits
+    /// - `{timestamp}`: when the file was started, by the writer's clock, as
+    ///   `20240229T123456Z`; `{timestamp:%Y-%m-%d_%H%M}` takes a strftime
+    ///   pattern of `%Y %m %d %H %M %S %f %%`, always in UTC
+    ///
+    /// `{{` and `}}` are literal braces. The template has to contain `{index}`
+    /// or `{timestamp}`. A template never replaces an existing file: building
+    /// the writer, or rotating, onto a name that is already taken fails, as
+    /// does a rotation that would reuse the name of an earlier file of the set.
+    /// Numeric suffixes overwrite files left over from an earlier run.
+    Template(String),
+}
+
+impl RotationNaming {
+    /// Name files from `template` (see [`RotationNaming::Template`])
+    pub fn template(template: impl Into<String>) -> Self {
+        RotationNaming::Template(template.into())
+    }
+
+    /// Create the empty file at `path` if templates name the set, failing if
+    /// it exists, so a file that appeared since the name was chosen isn't
+    /// overwritten. Suffix-named sets overwrite.
+    fn claim(&self, path: &Path) -> Result<()> {
+        if let RotationNaming::Template(_) = self {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    return Err(TdmsError::InvalidPath(format!(
+                        "rotation naming would overwrite the existing file {}", path.display()
+                    )));
+                }
+                claimed => drop(claimed?),
+            }
+        }
+        Ok(())
+    }
+
+    /// Path of the file at `index` of the set, started at `opened_at`
+    fn path(&self, base_path: &Path, index: u32, opened_at: Timestamp) -> Result<PathBuf> {
+        let template = match self {
+            RotationNaming::Suffix if index == 0 => return Ok(base_path.with_extension("tdms")),
+            RotationNaming::Suffix => return Ok(base_path.with_extension(format!("{}.tdms", index))),
+            RotationNaming::Template(template) => template,
+        };
+        let invalid = |reason: &str| TdmsError::InvalidPath(format!("rotation naming {:?}: {}", template, reason));
+
+        let mut name = String::new();
+        let mut varies = false;
+        let mut rest = template.as_str();
+        while let Some(pos) = rest.find(['{', '}']) {
+            name.push_str(&rest[..pos]);
+            let brace = &rest[pos..];
+            if brace.starts_with("{{") || brace.starts_with("}}") {
+                name.push_str(&brace[..1]);
+                rest = &brace[2..];
+                continue;
+            }
+            let end = match brace.find('}') {
+                Some(end) if brace.starts_with('{') => end,
+                _ => return Err(invalid("unmatched brace")),
+            };
+            let (field, spec) = match brace[1..end].split_once(':') {
+                Some((field, spec)) => (field, Some(spec)),
+                None => (&brace[1..end], None),
+            };
+            match (field, spec) {
+                ("stem", None) => {
+                    let stem = base_path.file_stem().ok_or_else(|| invalid("base path has no file name"))?;
+                    name.push_str(&stem.to_string_lossy());
+                }
+                ("index", None) => {
+                    write!(name, "{}", index).unwrap();
+                    varies = true;
+                }
+                ("index", Some(width)) => {
+                    let width: usize = width.parse().map_err(|_| invalid("index width is not a number"))?;
+                    write!(name, "{:0width$}", index, width = width).unwrap();
+                    varies = true;
+                }
+                ("timestamp", spec) => {
+                    format_time(&mut name, opened_at, spec.unwrap_or("%Y%m%dT%H%M%SZ"))
+                        .map_err(|field| invalid(&format!("unsupported timestamp field %{}", field)))?;
+                    varies = true;
+                }
+                _ => return Err(invalid(&format!("unknown placeholder {{{}}}", &brace[1..end]))),
+            }
+            rest = &brace[end + 1..];
+        }
+        name.push_str(rest);
+
+        if !varies {
+            return Err(invalid("needs an {index} or {timestamp} placeholder"));
+        }
+        Ok(base_path.with_file_name(name))
+    }
+}
+
+/// Append `time` formatted with the strftime subset of [`RotationNaming::Template`],
+/// or return the unsupported field
+fn format_time(out: &mut String, time: Timestamp, pattern: &str) -> std::result::Result<(), char> {
+    let t = time.to_civil();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => write!(out, "{:04}", t.year),
+            Some('m') => write!(out, "{:02}", t.month),
+            Some('d') => write!(out, "{:02}", t.day),
+            Some('H') => write!(out, "{:02}", t.hour),
+            Some('M') => write!(out, "{:02}", t.minute),
+            Some('S') => write!(out, "{:02}", t.second),
+            Some('f') => write!(out, "{:09}", t.nanosecond),
+            Some('%') => write!(out, "%"),
+            Some(other) => return Err(other),
+            None => return Err('%'),
+        }
+        .unwrap();
+    }
+    Ok(())
+}
+
+/// Configures when a [`RotatingTdmsWriter`] moves on to a new file.
+///
+/// The writer rotates as soon as any of the set limits is reached. Limits are
+/// checked before each write, so a file can run past a limit by one write and
+/// a writer that writes nothing doesn't rotate. Times come from the writer's
+/// [`Clock`].
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tdms_rs::RotatingTdmsWriter;
+///
+/// let mut writer = RotatingTdmsWriter::builder("capture")
+///     .max_size(512 << 20)
+///     .interval(Duration::from_secs(3600))
+///     .on_rotate(|path| println!("finished {}", path.display()))
+///     .build()
+///     .unwrap();
+/// ```
+pub struct RotatingWriterBuilder {
+    base_path: PathBuf,
+    naming: RotationNaming,
+    limits: RotationLimits,
+    clock: Option<Arc<dyn Clock>>,
+    on_rotate: Option<RotateCallback>,
+}
+
+impl RotatingWriterBuilder {
+    /// Rotate once the current file is larger than `bytes`
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.limits.max_size_bytes = Some(bytes);
+        self
+    }
+
+    /// Rotate when the wall clock crosses a multiple of `interval` since the
+    /// Unix epoch, e.g. at the top of every hour for one hour, so files line
+    /// up with calendar time whenever the writer was started
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.limits.interval = Some(interval).filter(|interval| !interval.is_zero());
+        self
+    }
+
+    /// Rotate once the current file was started `age` ago
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.limits.max_age = Some(age);
+        self
+    }
+
+    /// Rotate once any channel holds `samples` values in the current file
+    pub fn max_samples(mut self, samples: u64) -> Self {
+        self.limits.max_samples = Some(samples);
+        self
+    }
+
+    /// Rotate once the current file holds `segments` committed segments
+    pub fn max_segments(mut self, segments: usize) -> Self {
+        self.limits.max_segments = Some(segments);
+        self
+    }
+
+    /// How to name the files of the set (see [`RotationNaming`])
+    pub fn naming(mut self, naming: RotationNaming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Time source for the time limits and file names (see [`RotatingTdmsWriter::set_clock`])
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Call `callback` with the path of each file rotated away from, right
+    /// after the writer has moved on, and of the last file once
+    /// [`close`](RotatingTdmsWriter::close) has finished it, e.g. to upload or
+    /// compress them. It runs on the writing thread, so slow work belongs on
+    /// another one.
+    pub fn on_rotate(mut self, callback: impl Fn(&Path) + Send + Sync + 'static) -> Self {
+        self.on_rotate = Some(Box::new(callback));
+        self
+    }
+
+    /// Create the first file of the set
+    pub fn build(self) -> Result<RotatingTdmsWriter> {
+        let opened_at = self.clock.as_ref().map_or_else(Timestamp::now, |clock| clock.now());
+        let path = self.naming.path(&self.base_path, 0, opened_at)?;
+        self.naming.claim(&path)?;
+        let mut writer = TdmsWriter::create(&path)?;
+        if let Some(clock) = self.clock {
+            writer.set_clock(clock);
+        }
+        Ok(RotatingTdmsWriter {
+            base_path: self.base_path,
+            naming: self.naming,
+            limits: self.limits,
+            current_file_index: 0,
+            current_path: path.clone(),
+            opened_at,
+            writer,
+            on_rotate: self.on_rotate,
+            finished: Vec::new(),
+            produced: HashSet::from([path]),
+        })
+    }
 }
 
 impl RotatingTdmsWriter {
     /// Creates a new rotating TDMS writer.
     ///
-    /// The `base_path` is the path to the file, excluding any numeric suffix.
+    /// The `base_path` is the path to the file, excluding any numeric suffix
+    /// (see [`RotationNaming::Suffix`]).
     /// The `max_size_bytes` is the maximum size of a single file in bytes.
     pub fn new(base_path: impl AsRef<Path>, max_size_bytes: u64) -> Result<Self> {
-        let base_path = base_path.as_ref().to_path_buf();
-        let writer = TdmsWriter::create(Self::get_path(&base_path, 0))?;
-        Ok(Self {
-            base_path,
-            max_size_bytes,
-            current_file_index: 0,
-            writer,
-        })
+        Self::builder(base_path).max_size(max_size_bytes).build()
     }
 
-    fn get_path(base_path: &Path, index: u32) -> PathBuf {
-        if index == 0 {
-            base_path.with_extension("tdms")
-        } else {
-            base_path.with_extension(format!("{}.tdms", index))
+    /// Configure a rotating writer with limits other than size (see
+    /// [`RotatingWriterBuilder`]). Without any limit it never rotates.
+    pub fn builder(base_path: impl AsRef<Path>) -> RotatingWriterBuilder {
+        RotatingWriterBuilder {
+            base_path: base_path.as_ref().to_path_buf(),
+            naming: RotationNaming::default(),
+            limits: RotationLimits::default(),
+            clock: None,
+            on_rotate: None,
         }
     }
 
     fn rotate_if_needed(&mut self) -> Result<()> {
-        if self.writer.file_size()? > self.max_size_bytes {
+        if self.rotation_due()? {
+            let opened_at = self.writer.now();
+            let new_path = self.naming.path(&self.base_path, self.current_file_index + 1, opened_at)?;
+            if self.produced.contains(&new_path) {
+                return Err(TdmsError::InvalidPath(format!(
+                    "rotation would overwrite {}; the naming template needs a finer timestamp or {{index}}",
+                    new_path.display()
+                )));
+            }
+            self.naming.claim(&new_path)?;
+            if let Err(err) = self.writer.reset_for_new_file(&new_path) {
+                // Give a claimed name back, so a retry can take it again
+                if matches!(self.naming, RotationNaming::Template(_)) {
+                    std::fs::remove_file(&new_path).ok();
+                }
+                return Err(err);
+            }
+            self.produced.insert(new_path.clone());
+            let old_path = std::mem::replace(&mut self.current_path, new_path);
             self.current_file_index += 1;
-            let new_path = Self::get_path(&self.base_path, self.current_file_index);
-            self.writer.reset_for_new_file(new_path)?;
+            self.opened_at = opened_at;
+            if let Some(callback) = &self.on_rotate {
+                callback(&old_path);
+            }
+            self.finished.push(old_path);
+            telemetry::rotated();
         }
         Ok(())
     }
 
+    fn rotation_due(&mut self) -> Result<bool> {
+        let limits = self.limits;
+        let now = self.writer.now().to_unix_nanos();
+        let opened_at = self.opened_at.to_unix_nanos();
+        if limits.interval.is_some_and(|interval| {
+            let interval = interval.as_nanos() as i64;
+            now.div_euclid(interval) != opened_at.div_euclid(interval)
+        }) {
+            return Ok(true);
+        }
+        if limits.max_age.is_some_and(|age| now - opened_at >= age.as_nanos() as i64) {
+            return Ok(true);
+        }
+        if limits.max_samples.is_some_and(|samples| self.writer.max_values_in_file() >= samples) {
+            return Ok(true);
+        }
+        if limits.max_segments.is_some_and(|segments| self.writer.metadata_overhead().segments as usize >= segments) {
+            return Ok(true);
+        }
+        match limits.max_size_bytes {
+            Some(max) => Ok(self.writer.file_size()? > max),
+            None => Ok(false),
+        }
+    }
+
+    /// Path of the file currently being written
+    pub fn current_path(&self) -> PathBuf {
+        self.current_path.clone()
+    }
+
+    /// Paths of the files rotated away from since the last call, oldest
+    /// first. These are complete and no longer touched by the writer.
+    pub fn take_finished_files(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// See [`TdmsWriter::set_byte_quota`]; the quota covers every file of the set
+    pub fn set_byte_quota(&mut self, max_bytes: Option<u64>) {
+        self.writer.set_byte_quota(max_bytes);
+    }
+
+    /// Bytes written counted against the quota, across every file of the set
+    pub fn bytes_written(&self) -> u64 {
+        self.writer.bytes_written()
+    }
+
+    /// See [`TdmsWriter::set_heartbeat`]; each file of the set gets its own
+    /// checkpoints, counting only its own values
+    pub fn set_heartbeat(&mut self, interval: Option<Duration>) {
+        self.writer.set_heartbeat(interval);
+    }
+
+    pub fn set_durability_mode(&mut self, mode: DurabilityMode) {
+        self.writer.set_durability_mode(mode);
+    }
+
+    /// See [`TdmsWriter::set_durability_policy`]; each finished file is also
+    /// synced before the next one starts unless the policy is `Never`
+    pub fn set_durability_policy(&mut self, policy: DurabilityPolicy) {
+        self.writer.set_durability_policy(policy);
+    }
+
+    pub fn sync_all(&mut self) -> Result<()> {
+        self.writer.sync_all()
+    }
+
+    /// Replace the time source; the age of the current file is measured
+    /// from now on
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.writer.set_clock(clock);
+        self.opened_at = self.writer.now();
+    }
+
+    pub fn now(&self) -> Timestamp {
+        self.writer.now()
+    }
+
+    /// See [`TdmsWriter::set_deterministic`]; a pinned clock also restarts
+    /// the age of the current file
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.writer.set_deterministic(deterministic);
+        self.opened_at = self.writer.now();
+    }
+
+    /// See [`TdmsWriter::omit_library_info`]; call before the first write
+    pub fn omit_library_info(&mut self) {
+        self.writer.omit_library_info();
+    }
+
+    /// See [`TdmsWriter::set_string_encoding`]; applies to every file of the set
+    pub fn set_string_encoding(&mut self, encoding: StringEncoding) -> Result<()> {
+        self.writer.set_string_encoding(encoding)
+    }
+
+    /// See [`TdmsWriter::enable_metadata_crc`]; each file of the set gets its own sidecar
+    pub fn enable_metadata_crc(&mut self) -> Result<()> {
+        self.writer.enable_metadata_crc()
+    }
+
+    pub fn enable_journal(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.writer.enable_journal(path)
+    }
+
     pub fn set_file_property(&mut self, name: impl Into<String>, value: PropertyValue) {
         self.writer.set_file_property(name, value);
     }
 
+    pub fn set_file_properties(&mut self, properties: impl IntoIterator<Item = Property>) {
+        self.writer.set_file_properties(properties);
+    }
+
+    pub fn set_experiment_start_time(&mut self, start: Timestamp) {
+        self.writer.set_experiment_start_time(start);
+    }
+
+    pub fn set_experiment_time(&mut self, time: Timestamp) {
+        self.writer.set_experiment_time(time);
+    }
+
     pub fn set_group_property(&mut self, group: impl Into<String>, name: impl Into<String>, value: PropertyValue) {
         self.writer.set_group_property(group, name, value);
     }
 
+    pub fn set_expected_sample_rate(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, rate: f64) -> Result<()> {
+        self.writer.set_expected_sample_rate(group, channel, rate)
+    }
+
+    pub fn record_sample_accounting(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<SampleAccounting> {
+        self.writer.record_sample_accounting(group, channel)
+    }
+
+    pub fn record_all_sample_accounting(&mut self) -> Result<()> {
+        self.writer.record_all_sample_accounting()
+    }
+
+    pub fn set_schema(&mut self, schema: ChannelSchema) -> Result<()> {
+        self.writer.set_schema(schema)
+    }
+
+    pub fn create_group(&mut self, group: impl Into<String>) -> Result<()> {
+        self.writer.create_group(group)
+    }
+
     pub fn create_channel(&mut self, group: impl Into<String>, channel: impl Into<String>, data_type: DataType) -> Result<()> {
         self.writer.create_channel(group, channel, data_type)
     }
 
+    pub fn create_encoded_channel(&mut self, group: impl Into<String>, channel: impl Into<String>, data_type: DataType, encoding: ChannelEncoding) -> Result<()> {
+        self.writer.create_encoded_channel(group, channel, data_type, encoding)
+    }
+
+    pub fn create_late_channel(&mut self, group: impl Into<String>, channel: impl Into<String>, data_type: DataType, start_index: u64) -> Result<()> {
+        self.writer.create_late_channel(group, channel, data_type, start_index)
+    }
+
     pub fn set_channel_property(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, name: impl Into<String>, value: PropertyValue) -> Result<()> {
         self.writer.set_channel_property(group, channel, name, value)
     }
 
-    pub fn write_channel_data<T: Copy>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, data: &[T]) -> Result<()> {
+    pub fn set_channel_properties(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, properties: impl IntoIterator<Item = Property>) -> Result<()> {
+        self.writer.set_channel_properties(group, channel, properties)
+    }
+
+    pub fn write_channel_data<T: TdmsValue>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, data: &[T]) -> Result<()> {
         self.rotate_if_needed()?;
         self.writer.write_channel_data(group, channel, data)
     }
 
+    pub fn write_channel_data_unchecked<T: Copy>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, data: &[T]) -> Result<()> {
+        self.rotate_if_needed()?;
+        self.writer.write_channel_data_unchecked(group, channel, data)
+    }
+
+    /// A sink for one channel (see [`TdmsWriter::channel_sink`]). The size
+    /// limit is checked when the sink is created, not on each push.
+    pub fn channel_sink<T: TdmsValue>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<ChannelSink<'_, T>> {
+        self.rotate_if_needed()?;
+        self.writer.channel_sink(group, channel)
+    }
+
     pub fn write_channel_strings(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, data: &[impl AsRef<str>]) -> Result<()> {
         self.rotate_if_needed()?;
         self.writer.write_channel_strings(group, channel, data)
     }
 
+    pub fn create_event_channel(&mut self, group: impl Into<String>, name: impl Into<String>) -> Result<()> {
+        self.writer.create_event_channel(group, name)
+    }
+
+    pub fn write_event(&mut self, group: impl AsRef<str>, name: impl AsRef<str>, time: Timestamp, message: &str) -> Result<()> {
+        self.rotate_if_needed()?;
+        self.writer.write_event(group, name, time, message)
+    }
+
+    /// Id of a channel (see [`TdmsWriter::channel_id`]); ids survive rotation
+    pub fn channel_id(&self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<ChannelId> {
+        self.writer.channel_id(group, channel)
+    }
+
+    pub fn write_by_id<T: TdmsValue>(&mut self, id: ChannelId, data: &[T]) -> Result<()> {
+        self.rotate_if_needed()?;
+        self.writer.write_by_id(id, data)
+    }
+
+    pub fn write_strings_by_id(&mut self, id: ChannelId, data: &[impl AsRef<str>]) -> Result<()> {
+        self.rotate_if_needed()?;
+        self.writer.write_strings_by_id(id, data)
+    }
+
+    pub fn pending_samples(&self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<u64> {
+        self.writer.pending_samples(group, channel)
+    }
+
+    pub fn values_written(&self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<u64> {
+        self.writer.values_written(group, channel)
+    }
+
+    /// Metadata overhead of the current file (see [`TdmsWriter::metadata_overhead`])
+    pub fn metadata_overhead(&self) -> MetadataOverhead {
+        self.writer.metadata_overhead()
+    }
+
+    pub fn set_name_policy(&mut self, policy: NamePolicy) {
+        self.writer.set_name_policy(policy);
+    }
+
+    pub fn set_property_coalescing(&mut self, enabled: bool) {
+        self.writer.set_property_coalescing(enabled);
+    }
+
+    pub fn set_max_segment_size(&mut self, max_bytes: Option<u64>) {
+        self.writer.set_max_segment_size(max_bytes);
+    }
+
+    pub fn explain_next_segment(&self) -> SegmentPlan {
+        self.writer.explain_next_segment()
+    }
+
+    pub fn discard_pending(&mut self) {
+        self.writer.discard_pending();
+    }
+
     pub fn flush(&mut self) -> Result<()> {
         self.writer.flush()
     }
+
+    pub fn force_flush(&mut self) -> Result<()> {
+        self.writer.force_flush()
+    }
+
+    pub fn set_flush_window(&mut self, window: Option<FlushWindow>) {
+        self.writer.set_flush_window(window);
+    }
+
+    /// Flush and close the current file (see [`TdmsWriter::close`])
+    pub fn close(self) -> Result<()> {
+        let last = self.current_path;
+        self.writer.close()?;
+        if let Some(callback) = &self.on_rotate {
+            callback(&last);
+        }
+        Ok(())
+    }
+
+    /// Flush and return the current data file (see [`TdmsWriter::into_inner`])
+    pub fn into_inner(self) -> Result<File> {
+        self.writer.into_inner()
+    }
 }
@@ -0,0 +1,53 @@
+// src/writer/sink.rs
+use crate::error::Result;
+use crate::raw_data::RawDataBuffer;
+use crate::types::TdmsValue;
+use std::marker::PhantomData;
+
+/// A handle for appending values to one channel without looking it up on
+/// every call.
+/// 
+/// Returned by [`TdmsWriter::channel_sink`](crate::TdmsWriter::channel_sink),
+/// which resolves the channel and checks `T` against its data type once. The
+/// sink borrows the writer, so drop it before flushing.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// # use tdms_rs::*;
+/// # let mut writer = TdmsWriter::create("out.tdms").unwrap();
+/// writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+/// let mut sink = writer.channel_sink::<f64>("Group", "Voltage").unwrap();
+/// for i in 0..1_000_000 {
+///     sink.push((i as f64 * 0.001).sin()).unwrap();
+/// }
+/// writer.flush().unwrap();
+/// ```
+pub struct ChannelSink<'w, T: TdmsValue> {
+    buffer: &'w mut RawDataBuffer,
+    values_written: &'w mut u64,
+    _phantom: PhantomData<T>,
+}
+
+impl<'w, T: TdmsValue> ChannelSink<'w, T> {
+    pub(crate) fn new(buffer: &'w mut RawDataBuffer, values_written: &'w mut u64) -> Self {
+        ChannelSink { buffer, values_written, _phantom: PhantomData }
+    }
+    
+    /// Append one value
+    pub fn push(&mut self, value: T) -> Result<()> {
+        self.push_slice(std::slice::from_ref(&value))
+    }
+    
+    /// Append several values
+    pub fn push_slice(&mut self, values: &[T]) -> Result<()> {
+        T::write_values(values, self.buffer)?;
+        *self.values_written += values.len() as u64;
+        Ok(())
+    }
+    
+    /// Number of values buffered for the channel that have not been written yet
+    pub fn pending(&self) -> u64 {
+        self.buffer.value_count()
+    }
+}
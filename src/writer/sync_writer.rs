@@ -1,25 +1,97 @@
 // src/writer/sync_writer.rs
 use crate::error::{TdmsError, Result};
-use crate::types::{DataType, TocFlags, Property, PropertyValue};
-use crate::metadata::{ObjectPath, ChannelMetadata, RawDataIndex};
-use crate::segment::SegmentHeader;
+use crate::types::{DataType, Endianness, TocFlags, Property, PropertyValue, TdmsValue, Timestamp};
+use crate::clock::{Clock, ManualClock, SystemClock};
+use crate::properties::{alignment, checkpoint, events, experiment, sampling, wellknown};
+use crate::properties::sampling::SampleAccounting;
+use crate::schema::ChannelSchema;
+use crate::codec::{self, ChannelEncoding};
+use crate::metadata::{ObjectPath, ChannelId, ChannelMetadata, RawDataIndex};
+use crate::segment::{SegmentHeader, MetadataCrcLog, index_path_for, metadata_crc_path_for};
 use crate::raw_data::RawDataBuffer;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{Write, BufWriter, Seek, SeekFrom};
-use std::path::Path;
-use byteorder::{WriteBytesExt, LittleEndian};
+use crate::utils::StringEncoding;
+use crate::storage::{StorageCursor, TdmsStorage};
+#[cfg(feature = "encryption")]
+use crate::storage::{EncryptedStorage, KeyProvider};
+use crate::reader::{TdmsReader, ReaderOptions, IncompleteSegmentPolicy};
+use crate::writer::journal::{Journal, CommitKind, CommitRecord};
+use crate::writer::names::{self, NamePolicy};
+use crate::writer::plan::{SegmentAction, SegmentPlan, SegmentReason};
+use crate::writer::overhead::MetadataOverhead;
+use crate::writer::sink::ChannelSink;
+use crate::writer::buffers::ChannelBuffers;
+use crate::writer::derived::{self, Derivation, DerivedChannel};
+use crate::writer::telemetry;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Write, BufReader, BufWriter, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use byteorder::{WriteBytesExt, BigEndian, LittleEndian};
+
+/// Controls how a segment is committed to disk.
+///
+/// Every segment is written with an incomplete-marker lead-in which is patched with
+/// the real sizes once the body is in place. The mode decides whether that patch is
+/// ordered against the body on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurabilityMode {
+    /// Rely on OS buffering. Fastest, but after a power loss the lead-in may
+    /// describe data that never reached the disk.
+    #[default]
+    Buffered,
+    /// Write the segment body, fsync, then patch the lead-in and fsync again.
+    /// A crash at any point leaves either the previous committed state or a segment
+    /// still carrying the incomplete marker, never a lead-in pointing past real data.
+    Transactional,
+}
+
+/// Controls how often committed data is fsynced.
+///
+/// Independent of [`DurabilityMode`], which orders each commit against the disk:
+/// this bounds how much committed data a power loss can take with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurabilityPolicy {
+    /// Leave syncing to the OS, [`TdmsWriter::sync_all`] and closing
+    #[default]
+    Never,
+    /// Sync after every segment, or append to one
+    EverySegment,
+    /// Sync once at least this many bytes were committed since the last sync
+    EveryBytes(u64),
+}
+
+/// Window within which [`TdmsWriter::flush`] calls are coalesced into one commit.
+///
+/// A flush is deferred while less than `max_bytes` of raw data is pending and
+/// less than `max_age` has passed since the last commit (measured with the
+/// writer's [`Clock`]). Deferred data stays buffered and goes out with the first
+/// flush after the window closes, or on [`TdmsWriter::force_flush`], `close` or drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlushWindow {
+    pub max_age: Duration,
+    pub max_bytes: usize,
+}
 
 /// Synchronous TDMS file writer with incremental metadata optimization
-pub struct TdmsWriter {
-    data_file: BufWriter<File>,
-    index_file: BufWriter<File>,
+///
+/// Writes go to files by default, or to any other [`TdmsStorage`] given to
+/// [`from_storage`](TdmsWriter::from_storage).
+///
+/// The writer is `Send + Sync` (if its storage is), so it can be moved into a
+/// worker thread or stored in an async task. For concurrent producers use
+/// `AsyncTdmsWriter`.
+pub struct TdmsWriter<S: TdmsStorage = File> {
+    data_path: PathBuf,
+    data_file: BufWriter<StorageCursor<S>>,
+    index_file: BufWriter<StorageCursor<S>>,
     
     // Object hierarchy
     file_properties: HashMap<String, Property>,
     groups: HashMap<String, HashMap<String, Property>>,
     channels: HashMap<ObjectPath, ChannelMetadata>,
-    channel_buffers: HashMap<ObjectPath, RawDataBuffer>,
+    channel_buffers: ChannelBuffers,
     channel_order: Vec<ObjectPath>,
     
     // State tracking
@@ -39,23 +111,298 @@ pub struct TdmsWriter {
     // Track whether the current segment has raw data
     // (cannot append raw data to a metadata-only segment)
     current_segment_has_raw_data: bool,
+    current_segment_metadata_size: u64,
+    // Raw data bytes in the current segment, including appends
+    current_segment_raw_size: u64,
+    max_segment_size: Option<u64>,
+    // Bytes written to the current file, by kind
+    overhead: MetadataOverhead,
+    // Cap on bytes written to data files, and bytes written so far, both
+    // carried across reset_for_new_file
+    byte_quota: Option<u64>,
+    bytes_written: u64,
+
+    durability: DurabilityMode,
+    durability_policy: DurabilityPolicy,
+    // bytes_written at the last sync
+    synced_bytes: u64,
+    // Segment whose lead-in still carries the incomplete marker, while it is written
+    open_segment: Option<OpenSegment>,
+    journal: Option<Journal>,
+    // Sidecar of metadata checksums for the current data file
+    metadata_crc: Option<MetadataCrcLog>,
+    deterministic: bool,
+    string_encoding: StringEncoding,
+    endianness: Endianness,
+    clock: Arc<dyn Clock>,
+    // Whether the clock came from set_clock, which deterministic output keeps
+    clock_injected: bool,
+
+    // Channels using the encoded-channel extension, with their
+    // not-yet-encoded values in the logical data type
+    encoded_channels: HashMap<ObjectPath, EncodedChannel>,
+    // Channels computed from another channel at each flush, in creation order
+    derived_channels: Vec<DerivedChannel>,
+    name_policy: NamePolicy,
+    flush_window: Option<FlushWindow>,
+    last_commit: Timestamp,
+    // Skip re-writing properties set to the value they already have
+    coalesce_properties: bool,
+    // Expected rate, and the time and values written when it was set, of
+    // channels with sample accounting
+    sample_rates: HashMap<ObjectPath, (f64, Timestamp, u64)>,
+    schema: Option<ChannelSchema>,
+    // Checkpoint interval, when the last checkpoint was recorded, and each
+    // channel's values committed to the current file
+    heartbeat: Option<Duration>,
+    last_checkpoint: Option<Timestamp>,
+    committed_values: HashMap<ObjectPath, u64>,
+    // Name rejected by an infallible setter, reported by the next write_segment
+    rejected_name: Option<TdmsError>,
+    // Set once close()/into_inner() has flushed, so drop doesn't flush again
+    closed: bool,
+}
+
+/// Where a segment being written starts, and what its lead-in will say once committed
+#[derive(Clone, Copy)]
+struct OpenSegment {
+    data_start: u64,
+    index_start: u64,
+    total_size: u64,
+    metadata_size: u64,
+}
+
+struct EncodedChannel {
+    encoding: ChannelEncoding,
+    staging: RawDataBuffer,
+    // Values encoded into the channel buffer but not yet written
+    encoded_values: u64,
 }
 
 impl TdmsWriter {
     pub fn create(path: impl AsRef<Path>) -> Result<Self> {
         let data_path = path.as_ref();
-        let index_path = data_path.with_extension("tdms_index");
+        let index_path = index_path_for(data_path);
         
         let data_file = File::create(data_path)?;
         let index_file = File::create(index_path)?;
+        Ok(Self::with_storage(data_path.to_path_buf(), data_file, index_file))
+    }
+    
+    /// Reopen a file written earlier and continue adding segments to it.
+    /// 
+    /// The file's properties, groups and channels, with their data types,
+    /// properties and last raw data indexes, are restored from its index file
+    /// if that matches the data file, or else from the data file, in which
+    /// case the index is rewritten (see [`TdmsReader::open_indexed`]). Writing
+    /// then continues with incremental metadata; the first segment starts a
+    /// new object list. Encoded channels stay encoded, while derived channels
+    /// come back as ordinary channels.
+    /// 
+    /// Fails with [`TdmsError::IncompleteSegment`] if the file ends in a segment
+    /// a writer didn't finish.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use tdms_rs::TdmsWriter;
+    /// 
+    /// let mut writer = TdmsWriter::open_append("soak_test.tdms").unwrap();
+    /// writer.write_channel_data("Rack", "Temperature", &[21.5f64, 21.6]).unwrap();
+    /// writer.close().unwrap();
+    /// ```
+    pub fn open_append(path: impl AsRef<Path>) -> Result<Self> {
+        let data_path = path.as_ref();
+        let index_path = index_path_for(data_path);
+        let options = ReaderOptions { allow_incomplete: IncompleteSegmentPolicy::Error, ..Default::default() };
+        let mut reader = TdmsReader::open_indexed_with_options(data_path, options)?;
+        // The index may also have come from a legacy name
+        if !reader.opened_from_index() || !index_path.is_file() {
+            reader.rewrite_index_file(&index_path)?;
+        }
+        
+        let open = |path: &Path| OpenOptions::new().read(true).write(true).open(path);
+        let mut writer = Self::with_storage(data_path.to_path_buf(), open(data_path)?, open(&index_path)?);
+        writer.restore_from(&mut reader)?;
+        writer.data_file.seek(SeekFrom::End(0))?;
+        writer.index_file.seek(SeekFrom::End(0))?;
+        Ok(writer)
+    }
+    
+    /// Take over the objects of an existing file, as if this writer had written it
+    fn restore_from(&mut self, reader: &mut TdmsReader<BufReader<File>>) -> Result<()> {
+        self.file_properties = reader.file_properties.clone();
+        self.string_encoding = self.file_properties.get(StringEncoding::PROPERTY)
+            .and_then(|p| p.value.as_str())
+            .and_then(StringEncoding::from_name)
+            .unwrap_or_default();
+        if let Some(last) = reader.segments().last() {
+            self.endianness = if last.is_big_endian { Endianness::Big } else { Endianness::Little };
+        }
+        self.groups = reader.groups.clone();
+        
+        let channels: Vec<(ChannelMetadata, u64, Option<RawDataIndex>)> = reader.channels_in_order()
+            .map(|(path, info)| {
+                let mut metadata = ChannelMetadata::new(path.group().unwrap(), path.channel().unwrap(), info.data_type);
+                metadata.properties = info.properties.clone();
+                // DAQmx indexes can't be repeated by this writer
+                let last_index = info.last_segment()?
+                    .filter(|_| info.daqmx.is_none())
+                    .map(|last| RawDataIndex::with_size(info.data_type, last.value_count, last.byte_size));
+                Ok((metadata, info.total_values, last_index))
+            })
+            .collect::<Result<_>>()?;
+        for (metadata, total_values, last_index) in channels {
+            let path = metadata.path.clone();
+            let (group, channel) = (path.group().unwrap(), path.channel().unwrap());
+            let mut values_written = total_values;
+            if let Some((encoding, logical_type)) = reader.channel_encoding(group, channel)? {
+                values_written = reader.encoded_value_count(group, channel)?;
+                self.encoded_channels.insert(path.clone(), EncodedChannel {
+                    encoding,
+                    staging: RawDataBuffer::new(logical_type),
+                    encoded_values: 0,
+                });
+            }
+            
+            self.insert_channel(path.clone(), metadata.data_type);
+            self.channels.insert(path.clone(), metadata);
+            *self.channel_buffers.values_written_mut(&path).unwrap() = values_written;
+            self.committed_values.insert(path.clone(), values_written);
+            if let Some(index) = last_index {
+                self.last_channel_indices.insert(path, index);
+            }
+        }
         
-        Ok(TdmsWriter {
-            data_file: BufWriter::new(data_file),
-            index_file: BufWriter::new(index_file),
-            file_properties: HashMap::new(),
+        self.is_first_segment = false;
+        Ok(())
+    }
+    
+    /// Create a writer whose segments use `endianness` for their sizes,
+    /// metadata and raw data, e.g. [`Endianness::Big`] to match files from
+    /// big-endian NI targets. [`create`](Self::create) writes little-endian.
+    pub fn create_with_endianness(path: impl AsRef<Path>, endianness: Endianness) -> Result<Self> {
+        let mut writer = Self::create(path)?;
+        writer.endianness = endianness;
+        Ok(writer)
+    }
+    
+    /// Record a CRC-32 of every segment's metadata section in a sidecar next
+    /// to the data file (see [`metadata_crc_path_for`]).
+    /// 
+    /// Damaged metadata can make the rest of a file unreadable, or worse,
+    /// misread, without any other sign; [`TdmsReader::check_metadata_crc`](crate::TdmsReader::check_metadata_crc)
+    /// detects it. Each data file started by
+    /// [`reset_for_new_file`](Self::reset_for_new_file) gets its own sidecar.
+    /// Checksums can only be enabled before the first segment is written.
+    pub fn enable_metadata_crc(&mut self) -> Result<()> {
+        if !self.is_first_segment {
+            return Err(TdmsError::Unsupported(
+                "metadata checksums must be enabled before the first segment".to_string()));
+        }
+        self.metadata_crc = Some(MetadataCrcLog::create(metadata_crc_path_for(&self.data_path))?);
+        Ok(())
+    }
+    
+    /// Flush all pending data, close the index file and hand back the data file.
+    /// 
+    /// The returned handle is the only one left open on the data file once this
+    /// returns, and is positioned at the end of the written data.
+    pub fn into_inner(mut self) -> Result<File> {
+        self.closed = true;
+        self.force_flush()?;
+        let mut file = self.data_file.get_ref().get_ref().try_clone()?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(file)
+    }
+
+    /// Resets the writer to use a new file, carrying over all metadata.
+    pub fn reset_for_new_file(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.force_flush()?;
+        if self.durability_policy != DurabilityPolicy::Never {
+            self.sync_files()?;
+        }
+
+        let data_path = path.as_ref();
+        let index_path = index_path_for(data_path);
+
+        let data_file = File::create(data_path)?;
+        let index_file = File::create(index_path)?;
+
+        self.data_path = data_path.to_path_buf();
+        self.data_file = BufWriter::new(StorageCursor::new(data_file));
+        self.index_file = BufWriter::new(StorageCursor::new(index_file));
+        if self.metadata_crc.is_some() {
+            self.metadata_crc = Some(MetadataCrcLog::create(metadata_crc_path_for(data_path))?);
+        }
+
+        self.is_first_segment = true;
+        self.current_segment_start = 0;
+        self.current_index_segment_start = 0;
+
+        self.file_properties_modified = true;
+        for group_name in self.groups.keys() {
+            self.groups_modified.insert(group_name.clone(), true);
+        }
+        for channel_metadata in self.channels.values_mut() {
+            channel_metadata.properties_modified = true;
+            channel_metadata.index_changed = true;
+        }
+
+        self.last_channel_indices.clear();
+        self.last_written_channels.clear();
+        self.current_segment_has_raw_data = false;
+        self.current_segment_raw_size = 0;
+        self.overhead = MetadataOverhead::default();
+        self.committed_values.clear();
+        self.last_checkpoint = None;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl TdmsWriter<EncryptedStorage<File>> {
+    /// Create a file and its index as AES-256-GCM encrypted containers (see
+    /// [`EncryptedStorage`]), using the key `keys` gives for `key_id`
+    /// 
+    /// Read the file back with [`TdmsReader::open_encrypted`](crate::TdmsReader::open_encrypted).
+    pub fn create_encrypted(path: impl AsRef<Path>, key_id: &str, keys: &impl KeyProvider) -> Result<Self> {
+        // Chunks are read back when they are patched, so not `File::create`
+        let create = |path: &Path| OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path);
+        let data_path = path.as_ref();
+        let data = EncryptedStorage::create(create(data_path)?, key_id, keys)?;
+        let index = EncryptedStorage::create(create(&index_path_for(data_path))?, key_id, keys)?;
+        Ok(Self::with_storage(data_path.to_path_buf(), data, index))
+    }
+}
+
+impl<S: TdmsStorage> TdmsWriter<S> {
+    /// Create a writer on any storage backend, with `data` receiving the
+    /// TDMS file and `index` its index
+    /// 
+    /// Features tied to paths next to the data file, such as metadata
+    /// checksums and rotation, need a file writer made by [`create`](TdmsWriter::create).
+    pub fn from_storage(data: S, index: S) -> Result<Self> {
+        Ok(Self::with_storage(PathBuf::new(), data, index))
+    }
+    
+    fn with_storage(data_path: PathBuf, data: S, index: S) -> Self {
+        let file_properties = [
+            (wellknown::LIBRARY, env!("CARGO_PKG_NAME")),
+            (wellknown::LIBRARY_VERSION, env!("CARGO_PKG_VERSION")),
+        ].into_iter()
+            .map(|(name, value)| (name.to_string(), Property::new(name, PropertyValue::String(value.to_string()))))
+            .collect();
+        
+        TdmsWriter {
+            data_path,
+            data_file: BufWriter::new(StorageCursor::new(data)),
+            index_file: BufWriter::new(StorageCursor::new(index)),
+            file_properties,
             groups: HashMap::new(),
             channels: HashMap::new(),
-            channel_buffers: HashMap::new(),
+            channel_buffers: ChannelBuffers::default(),
             channel_order: Vec::new(),
             is_first_segment: true,
             current_segment_start: 0,
@@ -65,30 +412,314 @@ impl TdmsWriter {
             last_channel_indices: HashMap::new(),
             last_written_channels: Vec::new(),
             current_segment_has_raw_data: false,
-        })
+            current_segment_metadata_size: 0,
+            current_segment_raw_size: 0,
+            max_segment_size: None,
+            overhead: MetadataOverhead::default(),
+            byte_quota: None,
+            bytes_written: 0,
+            durability: DurabilityMode::default(),
+            durability_policy: DurabilityPolicy::default(),
+            synced_bytes: 0,
+            open_segment: None,
+            journal: None,
+            metadata_crc: None,
+            deterministic: false,
+            string_encoding: StringEncoding::default(),
+            endianness: Endianness::default(),
+            clock: Arc::new(SystemClock),
+            clock_injected: false,
+            encoded_channels: HashMap::new(),
+            derived_channels: Vec::new(),
+            name_policy: NamePolicy::default(),
+            coalesce_properties: true,
+            sample_rates: HashMap::new(),
+            schema: None,
+            heartbeat: None,
+            last_checkpoint: None,
+            committed_values: HashMap::new(),
+            flush_window: None,
+            last_commit: SystemClock.now(),
+            rejected_name: None,
+            closed: false,
+        }
+    }
+    
+    /// The byte order of the segments this writer produces
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+    
+    /// Leave out the `library` and `library_version` file properties that
+    /// identify this crate as the producer, e.g. for golden files that must not
+    /// change between crate versions. Has no effect once the first segment is
+    /// written.
+    pub fn omit_library_info(&mut self) {
+        if self.is_first_segment {
+            self.file_properties.remove(wellknown::LIBRARY);
+            self.file_properties.remove(wellknown::LIBRARY_VERSION);
+        }
+    }
+    
+    /// Set how segments are committed to disk (see [`DurabilityMode`])
+    pub fn set_durability_mode(&mut self, mode: DurabilityMode) {
+        self.durability = mode;
+    }
+    
+    /// The current durability mode
+    pub fn durability_mode(&self) -> DurabilityMode {
+        self.durability
+    }
+    
+    /// Set how often committed data is fsynced (see [`DurabilityPolicy`])
+    pub fn set_durability_policy(&mut self, policy: DurabilityPolicy) {
+        self.durability_policy = policy;
+    }
+    
+    /// The current durability policy
+    pub fn durability_policy(&self) -> DurabilityPolicy {
+        self.durability_policy
+    }
+    
+    /// Commit pending data, ignoring any flush window, and fsync the data and
+    /// index files
+    pub fn sync_all(&mut self) -> Result<()> {
+        self.force_flush()?;
+        self.sync_files()
+    }
+    
+    /// Replace the time source used for anything the writer stamps with the current time
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.last_commit = clock.now();
+        self.clock = clock;
+        self.clock_injected = true;
+    }
+    
+    /// The current time according to the writer's clock
+    pub fn now(&self) -> Timestamp {
+        self.clock.now()
+    }
+    
+    /// Make identical inputs produce byte-identical files, e.g. for
+    /// golden-file regression tests.
+    ///
+    /// By default groups, channels and properties are written in hash-map order,
+    /// and the writer's time comes from the system clock, which varies between
+    /// runs. With deterministic output enabled objects and properties are
+    /// sorted by name, and unless a clock was injected with
+    /// [`set_clock`](Self::set_clock) the writer's clock is pinned to the TDMS
+    /// epoch. Checkpoint times, sample accounting and flush-window ages then
+    /// don't depend on when the writer runs, so segments are cut only by
+    /// flushes and the byte limits of flush windows and
+    /// [`set_max_segment_size`](Self::set_max_segment_size), whatever the
+    /// sizes of the individual writes in between.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+        if !self.clock_injected {
+            self.clock = if deterministic {
+                Arc::new(ManualClock::new(Timestamp::default()))
+            } else {
+                Arc::new(SystemClock)
+            };
+            self.last_commit = self.clock.now();
+        }
+    }
+    
+    /// Write strings in `encoding` instead of UTF-8 and declare it in the file
+    /// property [`StringEncoding::PROPERTY`], for partners whose tools expect
+    /// their system code page.
+    /// 
+    /// String channel data with characters the encoding can't represent is
+    /// rejected when written. In names and property values such characters
+    /// are replaced with `?`. The encoding can only be chosen before the first
+    /// segment is written.
+    pub fn set_string_encoding(&mut self, encoding: StringEncoding) -> Result<()> {
+        if !self.is_first_segment {
+            return Err(TdmsError::Unsupported(
+                "the string encoding can't change after the first segment".to_string()));
+        }
+        self.string_encoding = encoding;
+        self.set_file_property(StringEncoding::PROPERTY, PropertyValue::String(encoding.name().to_string()));
+        Ok(())
+    }
+    
+    /// The encoding strings are written in
+    pub fn string_encoding(&self) -> StringEncoding {
+        self.string_encoding
+    }
+    
+    /// Record every segment commit in an append-only sidecar journal.
+    ///
+    /// Each line is a JSON object with the data file, segment offset, lead-in sizes,
+    /// the channels carrying raw data and the wall-clock time of the commit. The
+    /// journal is kept across [`reset_for_new_file`](Self::reset_for_new_file), so a
+    /// single journal can cover a whole set of rotated files.
+    pub fn enable_journal(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.journal = Some(Journal::open(path)?);
+        Ok(())
+    }
+    
+    /// Set how invalid group, channel and property names are handled (see [`NamePolicy`])
+    pub fn set_name_policy(&mut self, policy: NamePolicy) {
+        self.name_policy = policy;
+    }
+    
+    /// The current name policy
+    pub fn name_policy(&self) -> NamePolicy {
+        self.name_policy
+    }
+    
+    /// Apply the name policy in a setter that can't fail; a rejected name is
+    /// reported by the next `write_segment`/`flush`
+    fn accept_name(&mut self, name: String) -> Option<String> {
+        match self.name_policy.apply(name) {
+            Ok(name) => Some(name),
+            Err(e) => {
+                self.rejected_name.get_or_insert(e);
+                None
+            }
+        }
+    }
+    
+    /// Path of an existing channel, with names sanitized the same way they
+    /// were when the channel was created
+    fn channel_path(&self, group: &str, channel: &str) -> ObjectPath {
+        match self.name_policy {
+            NamePolicy::Sanitize => ObjectPath::Channel {
+                group: names::sanitize_name(group),
+                channel: names::sanitize_name(channel),
+            },
+            _ => ObjectPath::Channel { group: group.to_string(), channel: channel.to_string() },
+        }
+    }
+    
+    /// Set whether setting a property to the value it already has is ignored.
+    /// 
+    /// Enabled by default, so re-applying the same properties doesn't force new
+    /// metadata into the next segment. Disable to write every property set.
+    pub fn set_property_coalescing(&mut self, enabled: bool) {
+        self.coalesce_properties = enabled;
+    }
+    
+    /// Whether unchanged property values are coalesced
+    pub fn property_coalescing(&self) -> bool {
+        self.coalesce_properties
+    }
+    
+    /// Limit the raw data bytes in a single segment; `None` (the default) means
+    /// no limit.
+    /// 
+    /// Appends that would grow the current segment past the limit start a new
+    /// segment instead, and a flush with more pending data than the limit is
+    /// split across several segments, each holding a share of every channel's
+    /// values. String and encoded channels can't be split and go out whole with
+    /// the first part. Some consumers, notably 32-bit readers, handle very large
+    /// segments poorly.
+    pub fn set_max_segment_size(&mut self, max_bytes: Option<u64>) {
+        self.max_segment_size = max_bytes.map(|max| max.max(1));
+    }
+    
+    /// The raw data size limit per segment
+    pub fn max_segment_size(&self) -> Option<u64> {
+        self.max_segment_size
+    }
+    
+    /// Cap the bytes written to data files; `None` (the default) means no cap.
+    /// 
+    /// The count covers lead-ins, metadata and raw data, and carries over
+    /// [`reset_for_new_file`](Self::reset_for_new_file), so a rotating writer's
+    /// quota covers its whole set of files. A commit that would cross the cap
+    /// writes nothing and fails with [`TdmsError::QuotaExceeded`], keeping the
+    /// pending data buffered; the files written so far stay valid. Flushes
+    /// split by [`set_max_segment_size`](Self::set_max_segment_size) may write
+    /// their first parts before failing.
+    pub fn set_byte_quota(&mut self, max_bytes: Option<u64>) {
+        self.byte_quota = max_bytes;
+    }
+    
+    /// The byte quota
+    pub fn byte_quota(&self) -> Option<u64> {
+        self.byte_quota
+    }
+    
+    /// Bytes written to data files counted against the quota, across files
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+    
+    /// Fail unless `requested` more bytes fit the quota
+    fn check_quota(&self, requested: u64) -> Result<()> {
+        match self.byte_quota {
+            Some(limit) if self.bytes_written.saturating_add(requested) > limit => Err(TdmsError::QuotaExceeded {
+                limit,
+                used: self.bytes_written,
+                requested,
+            }),
+            _ => Ok(()),
+        }
     }
     
     /// Set a file-level property
+    /// 
+    /// If the name policy rejects `name`, the property is dropped and the next
+    /// `flush` returns the error.
     pub fn set_file_property(&mut self, name: impl Into<String>, value: PropertyValue) {
-        let name = name.into();
+        let Some(name) = self.accept_name(name.into()) else { return };
+        if self.coalesce_properties && self.file_properties.get(&name).is_some_and(|p| p.value == value) {
+            return;
+        }
         self.file_properties.insert(name.clone(), Property::new(name, value));
         self.file_properties_modified = true;
     }
     
+    /// Set several file-level properties at once
+    pub fn set_file_properties(&mut self, properties: impl IntoIterator<Item = Property>) {
+        for property in properties {
+            self.set_file_property(property.name, property.value);
+        }
+    }
+    
+    /// Set the `NI_ExpStartTimeStamp` file property
+    pub fn set_experiment_start_time(&mut self, start: Timestamp) {
+        self.set_file_property(experiment::NI_EXP_START_TIME_STAMP, PropertyValue::Timestamp(start));
+    }
+    
+    /// Set the `NI_ExpTimeStamp` file property (time of the last update)
+    pub fn set_experiment_time(&mut self, time: Timestamp) {
+        self.set_file_property(experiment::NI_EXP_TIME_STAMP, PropertyValue::Timestamp(time));
+    }
+    
     /// Set a group-level property
+    /// 
+    /// Names rejected by the name policy are reported like for [`Self::set_file_property`].
     pub fn set_group_property(&mut self, group: impl Into<String>, name: impl Into<String>, value: PropertyValue) {
-        let group = group.into();
-        let name = name.into();
-        self.groups.entry(group.clone())
-            .or_insert_with(HashMap::new)
-            .insert(name.clone(), Property::new(name, value));
+        let Some(group) = self.accept_name(group.into()) else { return };
+        let Some(name) = self.accept_name(name.into()) else { return };
+        let properties = self.groups.entry(group.clone()).or_default();
+        if self.coalesce_properties && properties.get(&name).is_some_and(|p| p.value == value) {
+            return;
+        }
+        properties.insert(name.clone(), Property::new(name, value));
         self.groups_modified.insert(group, true);
     }
     
+    /// Create a group, which is written even if it never gets any channels.
+    /// 
+    /// Groups are also created implicitly by [`Self::set_group_property`] and
+    /// [`Self::create_channel`]; creating an existing group does nothing.
+    pub fn create_group(&mut self, group: impl Into<String>) -> Result<()> {
+        let group = self.name_policy.apply(group.into())?;
+        if !self.groups.contains_key(&group) {
+            self.groups.insert(group.clone(), HashMap::new());
+            self.groups_modified.insert(group, true);
+        }
+        Ok(())
+    }
+    
     /// Create or get a channel
     pub fn create_channel(&mut self, group: impl Into<String>, channel: impl Into<String>, data_type: DataType) -> Result<()> {
-        let group = group.into();
-        let channel = channel.into();
+        let group = self.name_policy.apply(group.into())?;
+        let channel = self.name_policy.apply(channel.into())?;
         let path = ObjectPath::Channel { group, channel };
         
         if let Some(existing) = self.channels.get(&path) {
@@ -100,54 +731,581 @@ impl TdmsWriter {
             }
             return Ok(());
         }
+        if let Some(schema) = &self.schema {
+            schema.check_channel(&path, data_type)?;
+        }
         
+        self.insert_channel(path, data_type);
+        Ok(())
+    }
+    
+    fn insert_channel(&mut self, path: ObjectPath, data_type: DataType) {
         // Ensure group exists
         if let ObjectPath::Channel { group, .. } = &path {
-            self.groups.entry(group.clone()).or_insert_with(HashMap::new);
+            self.groups.entry(group.clone()).or_default();
         }
         
         let metadata = ChannelMetadata::new(path.group().unwrap().to_string(), path.channel().unwrap().to_string(), data_type);
         self.channel_buffers.insert(path.clone(), RawDataBuffer::new(data_type));
         self.channels.insert(path.clone(), metadata);
         self.channel_order.push(path);
+    }
+    
+    /// Validate channels against `schema` from now on.
+    /// 
+    /// Channels are checked when they are created, and every flush checks
+    /// that required channels exist and that units and property names match
+    /// (see [`crate::schema`]). Fails if a channel created earlier already
+    /// contradicts the schema.
+    pub fn set_schema(&mut self, schema: ChannelSchema) -> Result<()> {
+        for path in &self.channel_order {
+            let data_type = match self.encoded_channels.get(path) {
+                Some(encoded) => encoded.staging.data_type(),
+                None => self.channels[path].data_type,
+            };
+            schema.check_channel(path, data_type)?;
+        }
+        self.schema = Some(schema);
+        Ok(())
+    }
+    
+    /// The schema channels are validated against, if any
+    pub fn schema(&self) -> Option<&ChannelSchema> {
+        self.schema.as_ref()
+    }
+    
+    /// Create a channel that joins a recording already in progress.
+    /// 
+    /// `start_index` is the number of samples the channel missed, usually
+    /// [`values_written`](Self::values_written) of a channel that was there from
+    /// the start. It is stored as the `tdms_rs_start_index` property (see
+    /// [`crate::properties::alignment`]) so readers can pad the channel with
+    /// [`TdmsReader::read_channel_data_padded`](crate::TdmsReader::read_channel_data_padded).
+    /// 
+    /// The channel's data is buffered like any other. The first flush that
+    /// includes it changes the set of channels with data, so it starts a new
+    /// segment with a new object list; later flushes append as usual.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// # let mut writer = TdmsWriter::create("run.tdms").unwrap();
+    /// let start = writer.values_written("Rack", "Pressure").unwrap();
+    /// writer.create_late_channel("Rack", "Temperature", DataType::DoubleFloat, start).unwrap();
+    /// ```
+    pub fn create_late_channel(&mut self, group: impl Into<String>, channel: impl Into<String>,
+                               data_type: DataType, start_index: u64) -> Result<()> {
+        let (group, channel) = (group.into(), channel.into());
+        self.create_channel(group.clone(), channel.clone(), data_type)?;
+        self.set_channel_properties(group, channel, [alignment::start_index_property(start_index)])
+    }
+    
+    /// Set the rate a channel is expected to run at, in samples per second,
+    /// and start counting its samples for [`Self::record_sample_accounting`].
+    /// 
+    /// The rate is stored in the `tdms_rs_expected_sample_rate` property (see
+    /// [`crate::properties::sampling`]). Setting it again restarts the count.
+    pub fn set_expected_sample_rate(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>,
+                                    rate: f64) -> Result<()> {
+        let (group, channel) = (group.as_ref(), channel.as_ref());
+        let written = self.values_written(group, channel)?;
+        let path = self.channel_path(group, channel);
+        self.sample_rates.insert(path, (rate, self.clock.now(), written));
+        self.set_channel_properties(group, channel, [sampling::expected_sample_rate_property(rate)])
+    }
+    
+    /// Record how many samples a channel should have by now according to its
+    /// expected rate, and how many it got, as channel properties.
+    /// 
+    /// Call this periodically, e.g. before each flush; every record is kept
+    /// in the property history of the file. Fails with
+    /// [`TdmsError::Unsupported`] if the channel has no expected rate.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// # let mut writer = TdmsWriter::create("run.tdms").unwrap();
+    /// writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+    /// writer.set_expected_sample_rate("Rack", "Pressure", 1000.0).unwrap();
+    /// // ... acquire and write ...
+    /// let accounting = writer.record_sample_accounting("Rack", "Pressure").unwrap();
+    /// if accounting.dropped() > 0 {
+    ///     eprintln!("dropped {} samples", accounting.dropped());
+    /// }
+    /// writer.flush().unwrap();
+    /// ```
+    pub fn record_sample_accounting(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<SampleAccounting> {
+        let (group, channel) = (group.as_ref(), channel.as_ref());
+        let written = self.values_written(group, channel)?;
+        let path = self.channel_path(group, channel);
+        let Some(&(rate, since, baseline)) = self.sample_rates.get(&path) else {
+            return Err(TdmsError::Unsupported(format!("{} has no expected sample rate", path)));
+        };
+        let now = self.clock.now();
+        let elapsed = (now.to_unix_nanos() - since.to_unix_nanos()).max(0) as f64 / 1e9;
+        let accounting = SampleAccounting {
+            expected_rate: rate,
+            expected: (rate * elapsed).floor().max(0.0) as u64,
+            actual: written - baseline,
+            at: Some(now),
+        };
+        self.set_channel_properties(group, channel, accounting.to_properties())?;
+        Ok(accounting)
+    }
+    
+    /// [`Self::record_sample_accounting`] for every channel with an expected rate
+    pub fn record_all_sample_accounting(&mut self) -> Result<()> {
+        let mut paths: Vec<ObjectPath> = self.sample_rates.keys().cloned().collect();
+        paths.sort_unstable();
+        for path in paths {
+            if let ObjectPath::Channel { group, channel } = path {
+                self.record_sample_accounting(group, channel)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Number of values written to a channel so far, including ones not yet flushed
+    pub fn values_written(&self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<u64> {
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        if !self.channels.contains_key(&path) {
+            return Err(TdmsError::ChannelNotFound(path.to_string()));
+        }
+        Ok(self.channel_buffers.values_written(&path))
+    }
+    
+    /// Most values any channel has in the current file, pending ones included
+    pub(crate) fn max_values_in_file(&self) -> u64 {
+        self.channel_order.iter()
+            .map(|path| self.committed_values.get(path).copied().unwrap_or(0) + self.pending_values(path))
+            .max()
+            .unwrap_or(0)
+    }
+    
+    /// Create a channel stored with the encoded-channel extension.
+    ///
+    /// Values are written and read back as `data_type`, but stored as encoded
+    /// frames in a `U8` channel tagged with marker properties (see [`crate::codec`]).
+    /// Each segment holds one frame per channel, so larger segments encode better.
+    pub fn create_encoded_channel(&mut self, group: impl Into<String>, channel: impl Into<String>,
+                                  data_type: DataType, encoding: ChannelEncoding) -> Result<()> {
+        let group = self.name_policy.apply(group.into())?;
+        let channel = self.name_policy.apply(channel.into())?;
+        let path = ObjectPath::Channel { group: group.clone(), channel: channel.clone() };
+        
+        if !encoding.is_available() {
+            return Err(encoding.unavailable());
+        }
+        if !encoding.supports(data_type) {
+            return Err(TdmsError::Unsupported(
+                format!("{} encoding does not support {:?}", encoding.name(), data_type)));
+        }
+        if let Some(existing) = self.encoded_channels.get(&path) {
+            if existing.staging.data_type() != data_type || existing.encoding != encoding {
+                return Err(TdmsError::TypeMismatch {
+                    expected: format!("{:?} ({})", existing.staging.data_type(), existing.encoding.name()),
+                    found: format!("{:?} ({})", data_type, encoding.name()),
+                });
+            }
+            return Ok(());
+        }
+        if self.channels.contains_key(&path) {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", self.channels[&path].data_type),
+                found: format!("{:?} ({})", data_type, encoding.name()),
+            });
+        }
+        
+        if let Some(schema) = &self.schema {
+            schema.check_channel(&path, data_type)?;
+        }
+        self.insert_channel(path.clone(), DataType::U8);
+        let metadata = self.channels.get_mut(&path).unwrap();
+        metadata.set_property(codec::ENCODING_PROPERTY, PropertyValue::String(encoding.name().to_string()));
+        metadata.set_property(codec::ORIGINAL_TYPE_PROPERTY, PropertyValue::U32(data_type as u32));
+        self.encoded_channels.insert(path, EncodedChannel { encoding, staging: RawDataBuffer::new(data_type), encoded_values: 0 });
         
         Ok(())
     }
     
+    /// Create a `DoubleFloat` channel whose values `derivation` computes from
+    /// the values written to a numeric source channel, at each flush.
+    ///
+    /// The derived values go into the same segment as the source values they
+    /// came from. The channel records its source in the
+    /// [`DERIVED_FROM_PROPERTY`](derived::DERIVED_FROM_PROPERTY) property and
+    /// takes other properties like any channel; don't write to it directly.
+    /// A derived channel can be the source of another one created after it.
+    /// See [`crate::writer::derived`].
+    pub fn create_derived_channel(&mut self, group: impl Into<String>, channel: impl Into<String>,
+                                  source_group: impl AsRef<str>, source_channel: impl AsRef<str>,
+                                  derivation: impl Derivation + 'static) -> Result<()> {
+        let source = self.channel_path(source_group.as_ref(), source_channel.as_ref());
+        let source_type = match self.encoded_channels.get(&source) {
+            Some(encoded) => encoded.staging.data_type(),
+            None => self.channels.get(&source)
+                .ok_or_else(|| TdmsError::ChannelNotFound(source.to_string()))?
+                .data_type,
+        };
+        if !source_type.is_numeric() {
+            return Err(TdmsError::Unsupported(
+                format!("derived channels need a numeric source, {} is {:?}", source, source_type)));
+        }
+        let group = self.name_policy.apply(group.into())?;
+        let channel = self.name_policy.apply(channel.into())?;
+        let path = ObjectPath::Channel { group, channel };
+        if self.channels.contains_key(&path) {
+            return Err(TdmsError::Unsupported(format!("{} already exists", path)));
+        }
+        if let Some(schema) = &self.schema {
+            schema.check_channel(&path, DataType::DoubleFloat)?;
+        }
+        
+        self.insert_channel(path.clone(), DataType::DoubleFloat);
+        self.channels.get_mut(&path).unwrap()
+            .set_property(derived::DERIVED_FROM_PROPERTY, PropertyValue::String(source.to_string()));
+        self.derived_channels.push(DerivedChannel { path, source, derivation: Box::new(derivation), derived_values: 0 });
+        Ok(())
+    }
+    
     /// Set a channel property
     pub fn set_channel_property(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, 
                                  name: impl Into<String>, value: PropertyValue) -> Result<()> {
-        let path = ObjectPath::Channel { group: group.as_ref().to_string(), channel: channel.as_ref().to_string() };
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        let name = self.name_policy.apply(name.into())?;
         let metadata = self.channels.get_mut(&path)
             .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
         
         metadata.set_property(name, value);
+        if !self.coalesce_properties {
+            metadata.properties_modified = true;
+        }
+        Ok(())
+    }
+    
+    /// Set several channel properties at once, e.g. from a property profile
+    pub fn set_channel_properties(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>,
+                                  properties: impl IntoIterator<Item = Property>) -> Result<()> {
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        let policy = self.name_policy;
+        let coalesce = self.coalesce_properties;
+        let metadata = self.channels.get_mut(&path)
+            .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
+        
+        for property in properties {
+            metadata.set_property(policy.apply(property.name)?, property.value);
+            if !coalesce {
+                metadata.properties_modified = true;
+            }
+        }
         Ok(())
     }
     
     /// Write data to a channel (generic for fixed-size types)
-    pub fn write_channel_data<T: Copy>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, 
-                                        data: &[T]) -> Result<()> {
-        let path = ObjectPath::Channel { group: group.as_ref().to_string(), channel: channel.as_ref().to_string() };
-        let buffer = self.channel_buffers.get_mut(&path)
+    /// 
+    /// `T` must be the Rust type of the channel's declared data type (see
+    /// [`TdmsValue`]); writing `&[u32]` to an `I32` channel fails with
+    /// [`TdmsError::TypeMismatch`].
+    pub fn write_channel_data<T: TdmsValue>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, 
+                                             data: &[T]) -> Result<()> {
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        let declared = match self.encoded_channels.get(&path) {
+            Some(encoded) => encoded.staging.data_type(),
+            None => self.channels.get(&path)
+                .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?
+                .data_type,
+        };
+        if declared != T::DATA_TYPE {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", declared),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
+        
+        self.write_values(path, data.len(), |buffer| T::write_values(data, buffer))
+    }
+    
+    /// A handle for writing values of type `T` to a channel in a tight loop.
+    /// 
+    /// The channel is looked up and `T` checked against its data type here,
+    /// once, instead of on every write as with
+    /// [`write_channel_data`](Self::write_channel_data). See [`ChannelSink`].
+    pub fn channel_sink<T: TdmsValue>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<ChannelSink<'_, T>> {
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        let id = self.channel_buffers.id(&path)
+            .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
+        let (_, buffer, values_written) = self.channel_buffers.by_id_mut(id).unwrap();
+        let buffer = match self.encoded_channels.get_mut(&path) {
+            Some(encoded) => &mut encoded.staging,
+            None => buffer,
+        };
+        if buffer.data_type() != T::DATA_TYPE {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", buffer.data_type()),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
+        
+        Ok(ChannelSink::new(buffer, values_written))
+    }
+    
+    /// Write data to a channel checking only that `T` has the size of the
+    /// channel's data type.
+    /// 
+    /// For writing values whose bit pattern already is the channel's type, e.g.
+    /// raw `u16` words into an `I16` channel or a `#[repr(C)]` type laid out like
+    /// a [`Timestamp`]. Prefer [`write_channel_data`](Self::write_channel_data).
+    pub fn write_channel_data_unchecked<T: Copy>(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, 
+                                                 data: &[T]) -> Result<()> {
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        self.write_values(path, data.len(), |buffer| buffer.write_slice(data))
+    }
+    
+    /// Buffer `count` values for a channel with `write`
+    fn write_values(&mut self, path: ObjectPath, count: usize,
+                    write: impl FnOnce(&mut RawDataBuffer) -> Result<()>) -> Result<()> {
+        let id = self.channel_buffers.id(&path)
             .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
+        let (buffer, values_written) = self.buffer_by_id(id)?;
+        write(buffer)?;
+        *values_written += count as u64;
+        Ok(())
+    }
+    
+    /// The buffer values for a channel go into, which is the staging buffer
+    /// for encoded channels, and its values-written counter
+    fn buffer_by_id(&mut self, id: ChannelId) -> Result<(&mut RawDataBuffer, &mut u64)> {
+        let (path, buffer, values_written) = self.channel_buffers.by_id_mut(id)
+            .ok_or_else(|| TdmsError::ChannelNotFound(format!("{:?}", id)))?;
+        // Skip hashing the path when there are no encoded channels at all
+        if self.encoded_channels.is_empty() {
+            return Ok((buffer, values_written));
+        }
+        match self.encoded_channels.get_mut(path) {
+            Some(encoded) => Ok((&mut encoded.staging, values_written)),
+            None => Ok((buffer, values_written)),
+        }
+    }
+    
+    /// Id of an existing channel for [`write_by_id`](Self::write_by_id).
+    /// 
+    /// Ids stay valid for the life of the writer, across flushes and file
+    /// rotations.
+    pub fn channel_id(&self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<ChannelId> {
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        self.channel_buffers.id(&path)
+            .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))
+    }
+    
+    /// Write data to a channel by id.
+    /// 
+    /// Same as [`write_channel_data`](Self::write_channel_data) without building
+    /// the channel's path and looking it up by name on every call.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// # let mut writer = TdmsWriter::create("out.tdms").unwrap();
+    /// writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+    /// let voltage = writer.channel_id("Group", "Voltage").unwrap();
+    /// for block in 0..1000 {
+    ///     writer.write_by_id(voltage, &[block as f64; 64]).unwrap();
+    /// }
+    /// ```
+    pub fn write_by_id<T: TdmsValue>(&mut self, id: ChannelId, data: &[T]) -> Result<()> {
+        let (buffer, values_written) = self.buffer_by_id(id)?;
+        if buffer.data_type() != T::DATA_TYPE {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", buffer.data_type()),
+                found: std::any::type_name::<T>().to_string(),
+            });
+        }
         
-        buffer.write_slice(data)
+        T::write_values(data, buffer)?;
+        *values_written += data.len() as u64;
+        Ok(())
+    }
+    
+    /// Write string data to a channel by id (see [`write_by_id`](Self::write_by_id))
+    pub fn write_strings_by_id(&mut self, id: ChannelId, data: &[impl AsRef<str>]) -> Result<()> {
+        let encoding = self.string_encoding;
+        let (buffer, values_written) = self.buffer_by_id(id)?;
+        buffer.write_encoded_strings(data, encoding)?;
+        *values_written += data.len() as u64;
+        Ok(())
     }
     
     /// Write string data to a channel
     pub fn write_channel_strings(&mut self, group: impl AsRef<str>, channel: impl AsRef<str>, 
                                   data: &[impl AsRef<str>]) -> Result<()> {
-        let path = ObjectPath::Channel { group: group.as_ref().to_string(), channel: channel.as_ref().to_string() };
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
         let buffer = self.channel_buffers.get_mut(&path)
             .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
         
-        buffer.write_strings(data)
+        buffer.write_encoded_strings(data, self.string_encoding)?;
+        *self.channel_buffers.values_written_mut(&path).unwrap() += data.len() as u64;
+        Ok(())
+    }
+    
+    /// Create an event channel (see [`crate::properties::events`]): a `String`
+    /// channel `name` for the messages and a `TimeStamp` channel
+    /// `"<name> Time"` for when they happened
+    pub fn create_event_channel(&mut self, group: impl Into<String>, name: impl Into<String>) -> Result<()> {
+        let (group, name) = (group.into(), name.into());
+        let time_channel = events::time_channel_name(&name);
+        self.create_channel(group.clone(), time_channel.clone(), DataType::TimeStamp)?;
+        self.create_channel(group.clone(), name.clone(), DataType::String)?;
+        self.set_channel_property(group, name, events::EVENT_TIME_CHANNEL, PropertyValue::String(time_channel))
+    }
+    
+    /// Buffer one event on an event channel made with
+    /// [`create_event_channel`](Self::create_event_channel).
+    /// 
+    /// The message and its time are buffered together, so they are always
+    /// written in the same segment; if either is rejected, neither is kept.
+    pub fn write_event(&mut self, group: impl AsRef<str>, name: impl AsRef<str>, time: Timestamp, message: &str) -> Result<()> {
+        let (group, name) = (group.as_ref(), name.as_ref());
+        let time_channel = events::time_channel_name(name);
+        let time_path = self.channel_path(group, &time_channel);
+        let time_type = self.channels.get(&time_path)
+            .ok_or_else(|| TdmsError::ChannelNotFound(time_path.to_string()))?
+            .data_type;
+        if time_type != DataType::TimeStamp {
+            return Err(TdmsError::TypeMismatch {
+                expected: format!("{:?}", time_type),
+                found: std::any::type_name::<Timestamp>().to_string(),
+            });
+        }
+        // The message is the half that can fail (type or encoding), so it goes first
+        self.write_channel_strings(group, name, &[message])?;
+        self.write_channel_data(group, &time_channel, &[time])
+    }
+    
+    /// Number of values buffered for a channel that have not been written yet
+    pub fn pending_samples(&self, group: impl AsRef<str>, channel: impl AsRef<str>) -> Result<u64> {
+        let path = self.channel_path(group.as_ref(), channel.as_ref());
+        if let Some(encoded) = self.encoded_channels.get(&path) {
+            return Ok(encoded.staging.value_count());
+        }
+        let buffer = self.channel_buffers.get(&path)
+            .ok_or_else(|| TdmsError::ChannelNotFound(path.to_string()))?;
+        
+        Ok(buffer.value_count())
+    }
+    
+    /// Drop all buffered-but-unwritten channel data.
+    ///
+    /// Pending property changes are kept and will go out with the next segment;
+    /// only raw data is discarded.
+    pub fn discard_pending(&mut self) {
+        for (path, buffer, written) in self.channel_buffers.iter_mut() {
+            if !self.encoded_channels.contains_key(path) {
+                *written -= buffer.value_count();
+            }
+            buffer.clear();
+        }
+        for (path, encoded) in &mut self.encoded_channels {
+            if let Some(written) = self.channel_buffers.values_written_mut(path) {
+                *written -= encoded.staging.value_count() + encoded.encoded_values;
+            }
+            encoded.staging.clear();
+            encoded.encoded_values = 0;
+        }
+        // Discarded values that were pending when a rate was set don't count
+        // against it
+        for (path, (_, _, baseline)) in &mut self.sample_rates {
+            *baseline = (*baseline).min(self.channel_buffers.values_written(path));
+        }
+        self.reset_derived_progress(|_| true);
     }
     
     /// Write buffered data to file
     pub fn write_segment(&mut self) -> Result<()> {
+        if let Some(err) = self.rejected_name.take() {
+            return Err(err);
+        }
+        if let Some(schema) = &self.schema {
+            schema.check_channels(&self.channels)?;
+        }
+        self.derive_channels()?;
+        self.encode_staged_channels()?;
+        // Raw data alone is a lower bound on what the commit needs
+        self.check_quota(self.pending_bytes() as u64)?;
+        
+        match self.max_segment_size {
+            Some(max) if self.pending_bytes() as u64 > max => self.write_split_segments(max),
+            _ => self.write_segment_pass(),
+        }
+    }
+    
+    /// Write the pending data in parts of at most `max` raw bytes (see
+    /// [`set_max_segment_size`](Self::set_max_segment_size))
+    fn write_split_segments(&mut self, max: u64) -> Result<()> {
+        // Fixed-size, unencoded channels are split by value count
+        let mut split: Vec<(ObjectPath, RawDataBuffer, u64)> = Vec::new();
+        for path in &self.channel_order {
+            if self.encoded_channels.contains_key(path) {
+                continue;
+            }
+            let Some(buffer) = self.channel_buffers.get_mut(path) else { continue };
+            let Some(value_size) = buffer.data_type().fixed_size() else { continue };
+            if buffer.value_count() > 0 && value_size > 0 {
+                let taken = std::mem::replace(buffer, RawDataBuffer::new(buffer.data_type()));
+                split.push((path.clone(), taken, value_size as u64));
+            }
+        }
+        self.reset_derived_progress(|source| split.iter().any(|(path, _, _)| path == source));
+        
+        let unsplit = self.pending_bytes() as u64;
+        let part_bytes = |parts: u64| -> u64 {
+            split.iter().map(|(_, b, size)| b.value_count().div_ceil(parts) * size).sum()
+        };
+        let max_values = split.iter().map(|(_, b, _)| b.value_count()).max().unwrap_or(1);
+        let mut parts = (part_bytes(1) + unsplit).div_ceil(max).max(1);
+        while parts < max_values && part_bytes(parts) + unsplit > max {
+            parts += 1;
+        }
+        
+        // First value of `part` in a buffer of `count` values
+        let part_start = |count: u64, part: u64| (part * count.div_ceil(parts)).min(count);
+        for part in 0..parts {
+            for (path, full, value_size) in &split {
+                let start = part_start(full.value_count(), part);
+                let end = part_start(full.value_count(), part + 1);
+                if end > start {
+                    let bytes = &full.as_bytes()[(start * value_size) as usize..(end * value_size) as usize];
+                    self.channel_buffers.get_mut(path).unwrap().extend_raw(bytes, end - start);
+                }
+            }
+            if let Err(err) = self.write_segment_pass() {
+                // The failed part is still buffered; queue the parts after it
+                // behind it, so nothing is lost and a retry writes them in order
+                for (path, full, value_size) in &split {
+                    let start = part_start(full.value_count(), part + 1);
+                    let rest = &full.as_bytes()[(start * value_size) as usize..];
+                    self.channel_buffers.get_mut(path).unwrap().extend_raw(rest, full.value_count() - start);
+                }
+                // Those values were all derived before the split
+                for derived in &mut self.derived_channels {
+                    if split.iter().any(|(path, _, _)| *path == derived.source) {
+                        derived.derived_values = self.channel_buffers.get(&derived.source).map_or(0, |b| b.value_count());
+                    }
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Write everything currently buffered as one segment or append
+    fn write_segment_pass(&mut self) -> Result<()> {
         let has_raw_data = self.channel_buffers.values().any(|b| b.value_count() > 0);
+        if has_raw_data && self.checkpoint_due() {
+            self.record_checkpoint();
+        }
         let has_property_changes = self.determine_property_changes();
         
         if !has_raw_data && !has_property_changes {
@@ -158,8 +1316,8 @@ impl TdmsWriter {
         // Get the list of channels we are *actually* writing data for in this pass
         let current_written_channels: Vec<ObjectPath> = self.channel_order.iter()
             .filter(|path| {
-                self.channel_buffers.get(*path)
-                    .map_or(false, |b| b.value_count() > 0)
+                self.channel_buffers.get(path)
+                    .is_some_and(|b| b.value_count() > 0)
             })
             .cloned()
             .collect();
@@ -171,28 +1329,16 @@ impl TdmsWriter {
 
         // Update raw data indices and check if any have changed
         let mut has_index_changes = false;
-        for (path, buffer) in &self.channel_buffers {
+        for (path, buffer) in self.channel_buffers.iter() {
             if buffer.value_count() > 0 {
                 let metadata = self.channels.get_mut(path).unwrap();
-                let new_index = if buffer.data_type() == DataType::String {
-                    RawDataIndex::with_size(
-                        buffer.data_type(),
-                        buffer.value_count(),
-                        buffer.byte_len() as u64,
-                    )
-                } else {
-                    RawDataIndex::new(buffer.data_type(), buffer.value_count())
-                };
-                
-                // Check if index changed
-                if buffer.data_type() == DataType::String {
-                    metadata.index_changed = true;
-                } else if let Some(last_index) = self.last_channel_indices.get(path) {
-                    metadata.index_changed = new_index.number_of_values != last_index.number_of_values
-                        || new_index.data_type as u32 != last_index.data_type as u32;
-                } else {
-                    metadata.index_changed = true;
-                }
+                let (new_index, index_changed) = next_index(
+                    buffer.data_type(),
+                    buffer.value_count(),
+                    buffer.byte_len() as u64,
+                    self.last_channel_indices.get(path),
+                );
+                metadata.index_changed = index_changed;
 
                 if metadata.index_changed {
                     has_index_changes = true;
@@ -203,8 +1349,11 @@ impl TdmsWriter {
         }
         
         let has_metadata_to_write = has_property_changes || has_index_changes || new_obj_list_required;
+        let exceeds_max_size = self.max_segment_size.is_some_and(|max| {
+            self.current_segment_raw_size + self.pending_bytes() as u64 > max
+        });
         
-        if has_raw_data && !has_metadata_to_write && self.current_segment_has_raw_data {
+        if has_raw_data && !has_metadata_to_write && self.current_segment_has_raw_data && !exceeds_max_size {
             self.append_raw_data_only(&current_written_channels)?;
         } else {
             self.write_full_segment(has_raw_data, new_obj_list_required, &current_written_channels)?;
@@ -217,10 +1366,161 @@ impl TdmsWriter {
         }
         
         // Clear buffers and reset flags for next pass
+        for path in &self.channel_order {
+            let count = self.pending_values(path);
+            if count > 0 {
+                *self.committed_values.entry(path.clone()).or_default() += count;
+            }
+        }
+        for encoded in self.encoded_channels.values_mut() {
+            encoded.encoded_values = 0;
+        }
         self.clear_buffers();
         self.reset_modification_flags();
         self.is_first_segment = false;
 
+        self.apply_durability_policy()
+    }
+    
+    /// Describe what the next `flush` will write and why, without writing anything.
+    /// 
+    /// Use this to find out why a write pattern produces many small segments.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use tdms_rs::*;
+    /// # let mut writer = TdmsWriter::create("data.tdms").unwrap();
+    /// writer.write_channel_data("Group", "Channel", &[1.0f64, 2.0]).unwrap();
+    /// let plan = writer.explain_next_segment();
+    /// if plan.action == SegmentAction::NewSegment {
+    ///     println!("{}", plan);
+    /// }
+    /// ```
+    pub fn explain_next_segment(&self) -> SegmentPlan {
+        // Pending (data type, value count, byte length) per channel with data, in
+        // write order. Encoded channels get their frame once the segment is written.
+        let pending: Vec<(&ObjectPath, DataType, u64, u64)> = self.channel_order.iter()
+            .filter_map(|path| {
+                let buffer = self.channel_buffers.get(path)?;
+                // Frames are stored as U8 values
+                let frame_len = self.encoded_channels.get(path)
+                    .filter(|encoded| encoded.staging.value_count() > 0)
+                    .and_then(|encoded| codec::encode_frame(
+                        encoded.encoding,
+                        encoded.staging.data_type(),
+                        encoded.staging.as_bytes(),
+                        encoded.staging.value_count(),
+                    ).ok())
+                    .map_or(0, |frame| frame.len() as u64);
+                let value_count = buffer.value_count() + frame_len;
+                let byte_len = buffer.byte_len() as u64 + frame_len;
+                (value_count > 0).then_some((path, buffer.data_type(), value_count, byte_len))
+            })
+            .collect();
+        let channels: Vec<ObjectPath> = pending.iter().map(|(path, ..)| (*path).clone()).collect();
+        let has_raw_data = !channels.is_empty();
+        
+        let mut reasons = Vec::new();
+        if self.is_first_segment {
+            reasons.push(SegmentReason::FirstSegment);
+        }
+        if self.file_properties_modified {
+            reasons.push(SegmentReason::FilePropertiesChanged);
+        }
+        let mut groups: Vec<&String> = self.groups_modified.iter()
+            .filter(|(_, &modified)| modified)
+            .map(|(group, _)| group)
+            .collect();
+        groups.sort();
+        reasons.extend(groups.into_iter().map(|g| SegmentReason::GroupPropertiesChanged(g.clone())));
+        reasons.extend(self.channel_order.iter()
+            .filter(|path| self.channels.get(*path).is_some_and(|c| c.properties_modified))
+            .map(|path| SegmentReason::ChannelPropertiesChanged(path.clone())));
+        
+        if !has_raw_data && reasons.is_empty() {
+            return SegmentPlan { action: SegmentAction::Nothing, reasons, channels };
+        }
+        
+        if has_raw_data && !self.is_first_segment && self.last_written_channels != channels {
+            reasons.push(SegmentReason::ChannelListChanged);
+        }
+        for (path, data_type, value_count, byte_len) in &pending {
+            let (_, changed) = next_index(*data_type, *value_count, *byte_len, self.last_channel_indices.get(*path));
+            if changed {
+                reasons.push(SegmentReason::IndexChanged((*path).clone()));
+            }
+        }
+        if has_raw_data && reasons.is_empty() && !self.current_segment_has_raw_data {
+            reasons.push(SegmentReason::PreviousSegmentHasNoData);
+        }
+        if let Some(max) = self.max_segment_size {
+            let pending_bytes: u64 = pending.iter().map(|(.., byte_len)| byte_len).sum();
+            if has_raw_data && self.current_segment_raw_size + pending_bytes > max {
+                reasons.push(SegmentReason::SegmentSizeLimit);
+            }
+        }
+        
+        let action = if reasons.is_empty() { SegmentAction::Append } else { SegmentAction::NewSegment };
+        SegmentPlan { action, reasons, channels }
+    }
+    
+    /// Compute the derived channels' values from their sources' pending values
+    fn derive_channels(&mut self) -> Result<()> {
+        let mut output = Vec::new();
+        for index in 0..self.derived_channels.len() {
+            let source = &self.derived_channels[index].source;
+            let buffer = match self.encoded_channels.get(source) {
+                Some(encoded) => &encoded.staging,
+                None => self.channel_buffers.get(source).unwrap(),
+            };
+            let (pending, done) = (buffer.value_count(), self.derived_channels[index].derived_values);
+            if pending <= done {
+                continue;
+            }
+            let input = derived::buffered_values_as_f64(buffer).unwrap_or_default();
+            output.clear();
+            let new_input = input.get(done as usize..).unwrap_or_default();
+            self.derived_channels[index].derivation.derive(new_input, &mut output);
+            if !output.is_empty() {
+                let path = self.derived_channels[index].path.clone();
+                self.write_values(path, output.len(), |buffer| buffer.write_slice(&output))?;
+            }
+            self.derived_channels[index].derived_values = pending;
+        }
+        Ok(())
+    }
+    
+    /// Start derivation over for the sources whose buffers were emptied
+    fn reset_derived_progress(&mut self, emptied: impl Fn(&ObjectPath) -> bool) {
+        for derived in &mut self.derived_channels {
+            if emptied(&derived.source) {
+                derived.derived_values = 0;
+            }
+        }
+    }
+    
+    /// Turn the staged values of encoded channels into one frame each
+    fn encode_staged_channels(&mut self) -> Result<()> {
+        for (path, encoded) in &mut self.encoded_channels {
+            if encoded.staging.value_count() == 0 {
+                continue;
+            }
+            let frame = codec::encode_frame(
+                encoded.encoding,
+                encoded.staging.data_type(),
+                encoded.staging.as_bytes(),
+                encoded.staging.value_count(),
+            )?;
+            self.channel_buffers.get_mut(path).unwrap().write_slice(&frame)?;
+            encoded.encoded_values += encoded.staging.value_count();
+            encoded.staging.clear();
+            for derived in &mut self.derived_channels {
+                if derived.source == *path {
+                    derived.derived_values = 0;
+                }
+            }
+        }
         Ok(())
     }
     
@@ -236,27 +1536,45 @@ impl TdmsWriter {
         let raw_data_size: u64 = current_written_channels.iter()
             .map(|path| self.channel_buffers.get(path).map_or(0, |b| b.byte_len() as u64))
             .sum();
+        self.check_quota(raw_data_size)?;
         
-        // Update segment header in both files
+        // Write raw data first so the lead-in never describes bytes that are not there
         let current_pos = self.data_file.stream_position()?;
+        write_raw_data(&mut self.data_file, current_written_channels, &self.channel_buffers, self.endianness)?;
+        self.sync_body()?;
+        
         // This is safe because is_first_segment is false
         let current_segment_size = current_pos - self.current_segment_start - SegmentHeader::LEAD_IN_SIZE as u64;
         let new_segment_size = current_segment_size + raw_data_size;
+        self.current_segment_raw_size += raw_data_size;
+        self.overhead.raw_data_bytes += raw_data_size;
+        self.bytes_written += raw_data_size;
+        telemetry::raw_data_appended(raw_data_size);
         
-        // Update data file
+        // Commit: update segment header in both files
+        let end_pos = self.data_file.stream_position()?;
         self.data_file.seek(SeekFrom::Start(self.current_segment_start + 12))?;
-        self.data_file.write_u64::<LittleEndian>(new_segment_size)?;
-        self.data_file.seek(SeekFrom::Start(current_pos))?;
-        
-        // Write raw data
-        write_raw_data(&mut self.data_file, current_written_channels, &self.channel_buffers)?;
+        write_u64(&mut self.data_file, new_segment_size, self.endianness)?;
+        self.data_file.seek(SeekFrom::Start(end_pos))?;
+        self.sync_body()?;
         
-        // Update index file
         let index_pos = self.index_file.stream_position()?;
         self.index_file.seek(SeekFrom::Start(self.current_index_segment_start + 12))?;
-        self.index_file.write_u64::<LittleEndian>(new_segment_size)?;
+        write_u64(&mut self.index_file, new_segment_size, self.endianness)?;
         self.index_file.seek(SeekFrom::Start(index_pos))?;
         
+        if let Some(journal) = &mut self.journal {
+            journal.record(&CommitRecord {
+                kind: CommitKind::Append,
+                file: &self.data_path,
+                offset: self.current_segment_start,
+                next_segment_offset: new_segment_size,
+                metadata_size: self.current_segment_metadata_size,
+                channels: current_written_channels,
+                time: self.clock.now(),
+            })?;
+        }
+        
         Ok(())
     }
     
@@ -275,17 +1593,12 @@ impl TdmsWriter {
         if new_obj_list {
             toc.set_new_obj_list(true);
         }
+        toc.set_big_endian(self.endianness == Endianness::Big);
         
-        // Track segment starts
-        self.current_segment_start = self.data_file.stream_position()?;
-        self.current_index_segment_start = self.index_file.stream_position()?;
-        
-        // Write lead-ins with incomplete markers
-        write_lead_in(&mut self.data_file, SegmentHeader::TDMS_TAG, toc)?;
-        write_lead_in(&mut self.index_file, SegmentHeader::INDEX_TAG, toc)?;
-        
-        // Write metadata to both files
-        let metadata_start = self.data_file.stream_position()?;
+        // Render the metadata up front so the whole segment's size is known
+        // before anything is written
+        let mut metadata = Vec::new();
+        let mut path_bytes = 0;
         if toc.has_metadata() {
             let context = MetadataContext {
                 is_first_segment: self.is_first_segment,
@@ -296,32 +1609,137 @@ impl TdmsWriter {
                 channels: &self.channels,
                 active_channels_for_segment: current_written_channels,
                 channel_buffers: &self.channel_buffers,
+                deterministic: self.deterministic,
+                string_encoding: self.string_encoding,
+                endianness: self.endianness,
             };
-            write_metadata(&mut self.data_file, new_obj_list, &context)?;
-            write_metadata(&mut self.index_file, new_obj_list, &context)?;
+            path_bytes = write_metadata(&mut metadata, new_obj_list, &context)?;
         }
-        let metadata_end = self.data_file.stream_position()?;
-        let metadata_size = metadata_end - metadata_start;
+        let metadata_size = metadata.len() as u64;
+        let expected_raw_size: u64 = if has_raw_data {
+            current_written_channels.iter()
+                .map(|path| self.channel_buffers.get(path).map_or(0, |b| b.byte_len() as u64))
+                .sum()
+        } else {
+            0
+        };
+        self.check_quota(SegmentHeader::LEAD_IN_SIZE as u64 + metadata_size + expected_raw_size)?;
+        
+        // Track segment starts
+        self.current_segment_start = self.data_file.stream_position()?;
+        self.current_index_segment_start = self.index_file.stream_position()?;
+        
+        // Write lead-ins with incomplete markers
+        self.open_segment = Some(OpenSegment {
+            data_start: self.current_segment_start,
+            index_start: self.current_index_segment_start,
+            total_size: metadata_size + expected_raw_size,
+            metadata_size,
+        });
+        write_lead_in(&mut self.data_file, SegmentHeader::TDMS_TAG, toc, self.endianness)?;
+        write_lead_in(&mut self.index_file, SegmentHeader::INDEX_TAG, toc, self.endianness)?;
+        
+        // Write metadata to both files
+        self.data_file.write_all(&metadata)?;
+        self.index_file.write_all(&metadata)?;
         
         // Write raw data only to data file
         let raw_data_start = self.data_file.stream_position()?;
         if has_raw_data {
-            write_raw_data(&mut self.data_file, current_written_channels, &self.channel_buffers)?;
+            write_raw_data(&mut self.data_file, current_written_channels, &self.channel_buffers, self.endianness)?;
         }
         let raw_data_end = self.data_file.stream_position()?;
         let raw_data_size = raw_data_end - raw_data_start;
         
         let total_size = metadata_size + raw_data_size;
+        self.sync_body()?;
+        
+        // Update lead-ins (the commit point)
+        update_lead_in(&mut self.data_file, self.current_segment_start, total_size, metadata_size, self.endianness)?;
+        self.sync_body()?;
+        update_lead_in(&mut self.index_file, self.current_index_segment_start, total_size, metadata_size, self.endianness)?;
+        self.open_segment = None;
+        self.current_segment_metadata_size = metadata_size;
+        self.current_segment_raw_size = raw_data_size;
+        self.overhead.segments += 1;
+        telemetry::segment_written(SegmentHeader::LEAD_IN_SIZE as u64 + total_size);
+        self.overhead.lead_in_bytes += SegmentHeader::LEAD_IN_SIZE as u64;
+        self.overhead.metadata_bytes += metadata_size;
+        self.overhead.path_bytes += path_bytes;
+        self.overhead.raw_data_bytes += raw_data_size;
+        self.bytes_written += SegmentHeader::LEAD_IN_SIZE as u64 + total_size;
         
-        // Update lead-ins
-        update_lead_in(&mut self.data_file, self.current_segment_start, total_size, metadata_size)?;
-        update_lead_in(&mut self.index_file, self.current_index_segment_start, total_size, metadata_size)?;
+        if let (Some(log), true) = (&mut self.metadata_crc, toc.has_metadata()) {
+            log.record(self.current_segment_start, &metadata)?;
+        }
+        
+        if let Some(journal) = &mut self.journal {
+            let channels: &[ObjectPath] = if has_raw_data { current_written_channels } else { &[] };
+            journal.record(&CommitRecord {
+                kind: CommitKind::Segment,
+                file: &self.data_path,
+                offset: self.current_segment_start,
+                next_segment_offset: total_size,
+                metadata_size,
+                channels,
+                time: self.clock.now(),
+            })?;
+        }
         
         Ok(())
     }
     
+    /// In transactional mode, push everything written so far to stable storage
+    fn sync_body(&mut self) -> Result<()> {
+        if self.durability == DurabilityMode::Transactional {
+            self.data_file.flush()?;
+            self.data_file.get_mut().get_mut().sync()?;
+        }
+        Ok(())
+    }
+    
+    /// Flush both files and push them to stable storage
+    fn sync_files(&mut self) -> Result<()> {
+        self.data_file.flush()?;
+        self.data_file.get_mut().get_mut().sync()?;
+        self.index_file.flush()?;
+        self.index_file.get_mut().get_mut().sync()?;
+        self.synced_bytes = self.bytes_written;
+        Ok(())
+    }
+    
+    /// Sync after a commit if the durability policy asks for it
+    fn apply_durability_policy(&mut self) -> Result<()> {
+        let due = match self.durability_policy {
+            DurabilityPolicy::Never => false,
+            DurabilityPolicy::EverySegment => true,
+            DurabilityPolicy::EveryBytes(bytes) => self.bytes_written - self.synced_bytes >= bytes,
+        };
+        if due {
+            self.sync_files()?;
+        }
+        Ok(())
+    }
+    
+    /// Commit a segment whose writing was cut short by an error or a panic.
+    /// 
+    /// If its whole body reached the file the lead-ins get their real sizes;
+    /// otherwise the incomplete marker stays and readers treat the segment as
+    /// the truncated end of the file.
+    fn finalize_open_segment(&mut self) -> Result<()> {
+        let Some(open) = self.open_segment.take() else { return Ok(()) };
+        let body_end = open.data_start + SegmentHeader::LEAD_IN_SIZE as u64 + open.total_size;
+        if self.data_file.stream_position()? == body_end {
+            update_lead_in(&mut self.data_file, open.data_start, open.total_size, open.metadata_size, self.endianness)?;
+            update_lead_in(&mut self.index_file, open.index_start, open.total_size, open.metadata_size, self.endianness)?;
+        }
+        self.data_file.flush()?;
+        self.index_file.flush()?;
+        Ok(())
+    }
+    
     fn clear_buffers(&mut self) {
-        for (path, buffer) in &mut self.channel_buffers {
+        for (path, buffer, _) in self.channel_buffers.iter_mut() {
             if buffer.value_count() > 0 {
                 if let Some(metadata) = self.channels.get(path) {
                     if let Some(index) = &metadata.current_index {
@@ -331,6 +1749,7 @@ impl TdmsWriter {
                 buffer.clear();
             }
         }
+        self.reset_derived_progress(|_| true);
     }
     
     fn reset_modification_flags(&mut self) {
@@ -341,58 +1760,172 @@ impl TdmsWriter {
         }
     }
     
+    /// Commit pending data and metadata to disk.
+    /// 
+    /// With a [`FlushWindow`] set, the commit is skipped while the window is open.
     pub fn flush(&mut self) -> Result<()> {
+        if let Some(window) = self.flush_window {
+            let since_commit = self.clock.now().to_system_time()
+                .duration_since(self.last_commit.to_system_time())
+                .unwrap_or_default();
+            if self.pending_bytes() < window.max_bytes && since_commit < window.max_age {
+                return Ok(());
+            }
+        }
+        self.force_flush()
+    }
+    
+    /// Commit pending data and metadata to disk, ignoring any flush window
+    pub fn force_flush(&mut self) -> Result<()> {
+        let started = Instant::now();
         self.write_segment()?;
         self.data_file.flush()?;
         self.index_file.flush()?;
+        self.last_commit = self.clock.now();
+        telemetry::flushed(started.elapsed());
+        Ok(())
+    }
+    
+    /// Coalesce `flush` calls within a window (see [`FlushWindow`]); `None`
+    /// commits on every flush
+    pub fn set_flush_window(&mut self, window: Option<FlushWindow>) {
+        self.flush_window = window;
+    }
+    
+    /// The current flush window
+    pub fn flush_window(&self) -> Option<FlushWindow> {
+        self.flush_window
+    }
+    
+    /// Bytes of raw data buffered for the next commit
+    fn pending_bytes(&self) -> usize {
+        self.channel_buffers.values().map(|b| b.byte_len()).sum::<usize>()
+            + self.encoded_channels.values().map(|e| e.staging.byte_len()).sum::<usize>()
+    }
+
+    /// Values of a channel in its buffer, which for encoded channels holds
+    /// frames rather than values
+    fn pending_values(&self, path: &ObjectPath) -> u64 {
+        match self.encoded_channels.get(path) {
+            Some(encoded) => encoded.encoded_values,
+            None => self.channel_buffers.get(path).map_or(0, |b| b.value_count()),
+        }
+    }
+    
+    /// Record checkpoints every `interval` of writer clock time, or stop with
+    /// `None` (the default).
+    /// 
+    /// A checkpoint sets the `tdms_rs_last_checkpoint_time` file property and
+    /// each channel's `tdms_rs_checkpoint_samples` to its values in the file
+    /// including the segment being written (see
+    /// [`crate::properties::checkpoint`]). Checkpoints ride along with data:
+    /// the first commit after the interval has passed carries one, so a writer
+    /// with nothing to write records none. Each one costs a segment's worth of
+    /// metadata, so intervals of seconds rather than milliseconds are typical.
+    pub fn set_heartbeat(&mut self, interval: Option<Duration>) {
+        self.heartbeat = interval;
+    }
+    
+    /// The checkpoint interval
+    pub fn heartbeat(&self) -> Option<Duration> {
+        self.heartbeat
+    }
+    
+    fn checkpoint_due(&self) -> bool {
+        let Some(interval) = self.heartbeat else { return false };
+        self.last_checkpoint.map_or(true, |last| {
+            let elapsed = self.clock.now().to_unix_nanos() - last.to_unix_nanos();
+            elapsed >= interval.as_nanos() as i64
+        })
+    }
+    
+    /// Set the checkpoint properties for a commit of the pending values
+    fn record_checkpoint(&mut self) {
+        let now = self.clock.now();
+        self.last_checkpoint = Some(now);
+        let time = checkpoint::checkpoint_time_property(now);
+        self.file_properties.insert(time.name.clone(), time);
+        self.file_properties_modified = true;
+        
+        let counts: Vec<(ObjectPath, u64)> = self.channels.keys()
+            .map(|path| (path.clone(), self.committed_values.get(path).copied().unwrap_or(0) + self.pending_values(path)))
+            .collect();
+        for (path, count) in counts {
+            let property = checkpoint::checkpoint_samples_property(count);
+            let metadata = self.channels.get_mut(&path).unwrap();
+            metadata.set_property(property.name, property.value);
+            if !self.coalesce_properties {
+                metadata.properties_modified = true;
+            }
+        }
+    }
+
+    /// Flush all pending data and close the data and index files.
+    /// 
+    /// Dropping the writer does the same but has to ignore errors; use this
+    /// when a failed final flush must be noticed. That includes a name the
+    /// [`NamePolicy`] rejected in a setter that can't fail, such as
+    /// [`set_file_property`](Self::set_file_property), which dropping the
+    /// writer leaves out silently.
+    pub fn close(mut self) -> Result<()> {
+        self.closed = true;
+        self.force_flush()?;
+        if self.durability_policy != DurabilityPolicy::Never {
+            self.sync_files()?;
+        }
         Ok(())
     }
 
     /// Returns the current size of the data file on disk.
+    /// 
+    /// This flushes first; data held back by a flush window is not counted.
     pub fn file_size(&mut self) -> Result<u64> {
         self.flush()?;
-        let file = self.data_file.get_ref();
-        Ok(file.metadata()?.len())
+        Ok(self.data_file.get_ref().get_ref().len()?)
     }
 
-    /// Resets the writer to use a new file, carrying over all metadata.
-    pub fn reset_for_new_file(&mut self, path: impl AsRef<Path>) -> Result<()> {
-        self.flush()?;
-
-        let data_path = path.as_ref();
-        let index_path = data_path.with_extension("tdms_index");
-
-        let data_file = File::create(data_path)?;
-        let index_file = File::create(index_path)?;
-
-        self.data_file = BufWriter::new(data_file);
-        self.index_file = BufWriter::new(index_file);
+    /// Bytes spent on lead-ins, metadata and object paths versus raw data in the
+    /// current file, counting committed segments only.
+    /// 
+    /// After [`reset_for_new_file`](Self::reset_for_new_file) this starts over.
+    /// For an existing file, [`TdmsReader::index_stats`](crate::TdmsReader::index_stats)
+    /// gives the same split from the index.
+    pub fn metadata_overhead(&self) -> MetadataOverhead {
+        self.overhead
+    }
 
-        self.is_first_segment = true;
-        self.current_segment_start = 0;
-        self.current_index_segment_start = 0;
+}
 
-        self.file_properties_modified = true;
-        for group_name in self.groups.keys() {
-            self.groups_modified.insert(group_name.clone(), true);
+/// Flushes pending data like `close`, ignoring errors. If a commit was cut
+/// short, including by a panic unwinding through the writer, the pending data
+/// is left alone and the interrupted segment is finalized instead.
+impl<S: TdmsStorage> Drop for TdmsWriter<S> {
+    fn drop(&mut self) {
+        // Nobody is left to return a rejected name to; the data is still flushed
+        self.rejected_name = None;
+        if self.open_segment.is_some() {
+            let _ = self.finalize_open_segment();
+        } else if !self.closed {
+            let _ = self.force_flush();
         }
-        for channel_metadata in self.channels.values_mut() {
-            channel_metadata.properties_modified = true;
-            channel_metadata.index_changed = true;
+        if !self.closed && self.durability_policy != DurabilityPolicy::Never {
+            let _ = self.sync_files();
         }
-
-        self.last_channel_indices.clear();
-        self.last_written_channels.clear();
-        self.current_segment_has_raw_data = false;
-
-        Ok(())
     }
 }
 
-impl Drop for TdmsWriter {
-    fn drop(&mut self) {
-        let _ = self.flush();
+/// Raw data index for a channel's pending values, and whether it differs from
+/// the channel's index in the previous segment
+fn next_index(data_type: DataType, value_count: u64, byte_len: u64, last: Option<&RawDataIndex>) -> (RawDataIndex, bool) {
+    if data_type == DataType::String {
+        // String sizes vary, so their index is always rewritten
+        return (RawDataIndex::with_size(data_type, value_count, byte_len), true);
     }
+    let index = RawDataIndex::new(data_type, value_count);
+    let changed = last.map_or(true, |last| {
+        index.number_of_values != last.number_of_values || index.data_type as u32 != last.data_type as u32
+    });
+    (index, changed)
 }
 
 struct MetadataContext<'a> {
@@ -403,85 +1936,108 @@ struct MetadataContext<'a> {
     groups_modified: &'a HashMap<String, bool>,
     channels: &'a HashMap<ObjectPath, ChannelMetadata>,
     active_channels_for_segment: &'a [ObjectPath],
-    channel_buffers: &'a HashMap<ObjectPath, RawDataBuffer>,
+    channel_buffers: &'a ChannelBuffers,
+    deterministic: bool,
+    string_encoding: StringEncoding,
+    endianness: Endianness,
 }
 
-fn write_lead_in<W: Write>(writer: &mut W, tag: &[u8; 4], toc: TocFlags) -> Result<()> {
+fn write_lead_in<W: Write>(writer: &mut W, tag: &[u8; 4], toc: TocFlags, endianness: Endianness) -> Result<()> {
     writer.write_all(tag)?;
+    // The ToC is little-endian even in big-endian segments
     writer.write_u32::<LittleEndian>(toc.raw_value())?;
-    writer.write_u32::<LittleEndian>(SegmentHeader::VERSION)?;
-    writer.write_u64::<LittleEndian>(SegmentHeader::INCOMPLETE_MARKER)?;
-    writer.write_u64::<LittleEndian>(0)?; // Metadata offset placeholder
+    write_u32(writer, SegmentHeader::VERSION, endianness)?;
+    write_u64(writer, SegmentHeader::INCOMPLETE_MARKER, endianness)?;
+    write_u64(writer, 0, endianness)?; // Metadata offset placeholder
     Ok(())
 }
 
 fn update_lead_in<W: Write + Seek>(writer: &mut W, segment_start: u64,
-                                   total_size: u64, metadata_size: u64) -> Result<()> {
+                                   total_size: u64, metadata_size: u64, endianness: Endianness) -> Result<()> {
     let current_pos = writer.stream_position()?;
     writer.seek(SeekFrom::Start(segment_start + 12))?;
-    writer.write_u64::<LittleEndian>(total_size)?;
-    writer.write_u64::<LittleEndian>(metadata_size)?;
+    write_u64(writer, total_size, endianness)?;
+    write_u64(writer, metadata_size, endianness)?;
     writer.seek(SeekFrom::Start(current_pos))?;
     Ok(())
 }
 
-fn write_metadata<W: Write>(writer: &mut W, new_obj_list: bool, context: &MetadataContext) -> Result<()> {
+/// Returns the bytes spent on object paths
+fn write_metadata<W: Write>(writer: &mut W, new_obj_list: bool, context: &MetadataContext) -> Result<u64> {
     let mut objects_to_write = Vec::new();
 
     if new_obj_list {
         objects_to_write.push(ObjectPath::Root);
 
-        let mut active_groups = HashSet::new();
-        for group_name in context.groups.keys() {
-            active_groups.insert(group_name.as_str());
-        }
+        // Collected in a stable order so the data and index files get identical metadata
+        let mut active_groups: Vec<&str> = context.groups.keys().map(String::as_str).collect();
         for path in context.active_channels_for_segment {
             if let Some(group_name) = path.group() {
-                active_groups.insert(group_name);
+                if !active_groups.contains(&group_name) {
+                    active_groups.push(group_name);
+                }
             }
         }
         
+        if context.deterministic {
+            active_groups.sort_unstable();
+        }
         for group_name in active_groups {
             objects_to_write.push(ObjectPath::Group(group_name.to_string()));
         }
         
         objects_to_write.extend(context.active_channels_for_segment.iter().cloned());
+        // Channels without data here still need their changed properties written
+        let mut modified_channels: Vec<ObjectPath> = context.channels.values()
+            .filter(|metadata| metadata.properties_modified && !context.active_channels_for_segment.contains(&metadata.path))
+            .map(|metadata| metadata.path.clone())
+            .collect();
+        if context.deterministic {
+            modified_channels.sort_unstable_by_key(|path| path.to_string());
+        }
+        objects_to_write.extend(modified_channels);
     } else {
         if context.file_properties_modified {
             objects_to_write.push(ObjectPath::Root);
         }
-        for (group_name, modified) in context.groups_modified.iter() {
-            if *modified {
-                objects_to_write.push(ObjectPath::Group(group_name.clone()));
-            }
+        let mut modified_groups: Vec<&String> = context.groups_modified.iter()
+            .filter(|(_, modified)| **modified)
+            .map(|(group_name, _)| group_name)
+            .collect();
+        if context.deterministic {
+            modified_groups.sort_unstable();
+        }
+        for group_name in modified_groups {
+            objects_to_write.push(ObjectPath::Group(group_name.clone()));
         }
         
-        let mut channels_to_write = HashSet::new();
-        
+        // Collected in a stable order so the data and index files get identical metadata
+        let mut channels_to_write: Vec<ObjectPath> = context.active_channels_for_segment.to_vec();
         for metadata in context.channels.values() {
-            if metadata.properties_modified {
-                channels_to_write.insert(metadata.path.clone());
+            if metadata.properties_modified && !channels_to_write.contains(&metadata.path) {
+                channels_to_write.push(metadata.path.clone());
             }
         }
         
-        for path in context.active_channels_for_segment {
-            channels_to_write.insert(path.clone());
+        if context.deterministic {
+            channels_to_write.sort_unstable_by_key(|path| path.to_string());
         }
-        
         objects_to_write.extend(channels_to_write);
     }
 
-    writer.write_u32::<LittleEndian>(objects_to_write.len() as u32)?;
+    write_u32(writer, objects_to_write.len() as u32, context.endianness)?;
 
+    let mut path_bytes = 0;
     for path in objects_to_write {
-        write_object(writer, &path, context)?;
+        path_bytes += write_object(writer, &path, context)?;
     }
 
-    Ok(())
+    Ok(path_bytes)
 }
 
-fn write_object<W: Write>(writer: &mut W, path: &ObjectPath, context: &MetadataContext) -> Result<()> {
-    write_string(writer, &path.to_string())?;
+/// Returns the bytes spent on the object's path
+fn write_object<W: Write>(writer: &mut W, path: &ObjectPath, context: &MetadataContext) -> Result<u64> {
+    let path_bytes = write_string(writer, &path.to_string(), context.string_encoding, context.endianness)?;
 
     match path {
         ObjectPath::Channel { .. } => {
@@ -490,36 +2046,37 @@ fn write_object<W: Write>(writer: &mut W, path: &ObjectPath, context: &MetadataC
             if let Some(buffer) = context.channel_buffers.get(path) {
                 if buffer.value_count() > 0 {
                     if !metadata.index_changed && !context.is_first_segment {
-                        writer.write_u32::<LittleEndian>(RawDataIndex::MATCHES_PREVIOUS)?;
+                        write_u32(writer, RawDataIndex::MATCHES_PREVIOUS, context.endianness)?;
                     } else {
-                        write_raw_data_index(writer, metadata.current_index.as_ref().unwrap())?;
+                        write_raw_data_index(writer, metadata.current_index.as_ref().unwrap(), context.endianness)?;
                     }
                 } else {
-                    writer.write_u32::<LittleEndian>(RawDataIndex::NO_RAW_DATA)?;
+                    write_u32(writer, RawDataIndex::NO_RAW_DATA, context.endianness)?;
                 }
             } else {
-                writer.write_u32::<LittleEndian>(RawDataIndex::NO_RAW_DATA)?;
+                write_u32(writer, RawDataIndex::NO_RAW_DATA, context.endianness)?;
             }
         }
         _ => {
-            writer.write_u32::<LittleEndian>(RawDataIndex::NO_RAW_DATA)?;
+            write_u32(writer, RawDataIndex::NO_RAW_DATA, context.endianness)?;
         }
     }
 
     write_properties(writer, path, context)?;
 
-    Ok(())
+    Ok(path_bytes)
 }
 
-fn write_raw_data_index<W: Write>(writer: &mut W, index: &RawDataIndex) -> Result<()> {
-    let index_length = if index.data_type == DataType::String { 24u32 } else { 16u32 };
-    writer.write_u32::<LittleEndian>(index_length)?;
-    writer.write_u32::<LittleEndian>(index.data_type as u32)?;
-    writer.write_u32::<LittleEndian>(index.array_dimension)?;
-    writer.write_u64::<LittleEndian>(index.number_of_values)?;
+fn write_raw_data_index<W: Write>(writer: &mut W, index: &RawDataIndex, endianness: Endianness) -> Result<()> {
+    // The index length counts its own 4 bytes
+    let index_length = if index.data_type == DataType::String { 28u32 } else { 20u32 };
+    write_u32(writer, index_length, endianness)?;
+    write_u32(writer, index.data_type as u32, endianness)?;
+    write_u32(writer, index.array_dimension, endianness)?;
+    write_u64(writer, index.number_of_values, endianness)?;
 
     if index.data_type == DataType::String {
-        writer.write_u64::<LittleEndian>(index.total_size_bytes)?;
+        write_u64(writer, index.total_size_bytes, endianness)?;
     }
 
     Ok(())
@@ -529,37 +2086,104 @@ fn write_properties<W: Write>(writer: &mut W, path: &ObjectPath, context: &Metad
     let empty_properties = HashMap::new();
     
     let properties = match path {
-        ObjectPath::Root => &context.file_properties,
+        ObjectPath::Root => context.file_properties,
         ObjectPath::Group(name) => context.groups.get(name).unwrap_or(&empty_properties),
         ObjectPath::Channel { .. } => &context.channels.get(path).unwrap().properties,
     };
 
-    writer.write_u32::<LittleEndian>(properties.len() as u32)?;
+    write_u32(writer, properties.len() as u32, context.endianness)?;
+
+    let mut properties: Vec<&Property> = properties.values().collect();
+    if context.deterministic {
+        properties.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    }
+    // Readers need the string encoding before any other non-ASCII string. The
+    // library identification follows in a fixed order, so files without other
+    // properties are identical from run to run.
+    let mut pinned = 0;
+    for name in [StringEncoding::PROPERTY, wellknown::LIBRARY, wellknown::LIBRARY_VERSION] {
+        if let Some(index) = properties.iter().position(|p| p.name == name) {
+            let property = properties.remove(index);
+            properties.insert(pinned, property);
+            pinned += 1;
+        }
+    }
 
-    for prop in properties.values() {
-        write_string(writer, &prop.name)?;
-        writer.write_u32::<LittleEndian>(prop.value.data_type() as u32)?;
-        prop.value.write_to(writer)?;
+    for prop in properties {
+        write_string(writer, &prop.name, context.string_encoding, context.endianness)?;
+        write_u32(writer, prop.value.data_type() as u32, context.endianness)?;
+        match &prop.value {
+            PropertyValue::String(value) => { write_string(writer, value, context.string_encoding, context.endianness)?; }
+            value => value.write_with_endianness(writer, context.endianness)?,
+        }
     }
 
     Ok(())
 }
 
 fn write_raw_data<W: Write>(writer: &mut W, channel_order: &[ObjectPath],
-                            channel_buffers: &HashMap<ObjectPath, RawDataBuffer>) -> Result<()> {
+                            channel_buffers: &ChannelBuffers, endianness: Endianness) -> Result<()> {
     for path in channel_order {
         if let Some(buffer) = channel_buffers.get(path) {
             if buffer.value_count() > 0 {
-                writer.write_all(buffer.as_bytes())?;
+                match endianness {
+                    Endianness::Little => writer.write_all(buffer.as_bytes())?,
+                    Endianness::Big => writer.write_all(&big_endian_bytes(buffer))?,
+                }
             }
         }
     }
     Ok(())
 }
 
-fn write_string<W: Write>(writer: &mut W, s: &str) -> Result<()> {
-    let bytes = s.as_bytes();
-    writer.write_u32::<LittleEndian>(bytes.len() as u32)?;
-    writer.write_all(bytes)?;
+/// A buffer's values, which are always held little-endian, byte-swapped to big-endian
+fn big_endian_bytes(buffer: &RawDataBuffer) -> Vec<u8> {
+    let mut bytes = buffer.as_bytes().to_vec();
+    let data_type = buffer.data_type();
+    let (swapped, width) = match data_type {
+        // Only the offsets ahead of the text are numbers
+        DataType::String => (buffer.value_count() as usize * 4, 4),
+        // Real and imaginary parts are swapped separately
+        DataType::ComplexSingleFloat => (bytes.len(), 4),
+        DataType::ComplexDoubleFloat => (bytes.len(), 8),
+        // A little-endian timestamp reversed is its big-endian form
+        _ => (bytes.len(), data_type.fixed_size().unwrap_or(1)),
+    };
+    if width > 1 {
+        for value in bytes[..swapped].chunks_exact_mut(width) {
+            value.reverse();
+        }
+    }
+    bytes
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32, endianness: Endianness) -> Result<()> {
+    match endianness {
+        Endianness::Little => writer.write_u32::<LittleEndian>(value)?,
+        Endianness::Big => writer.write_u32::<BigEndian>(value)?,
+    }
+    Ok(())
+}
+
+fn write_u64<W: Write>(writer: &mut W, value: u64, endianness: Endianness) -> Result<()> {
+    match endianness {
+        Endianness::Little => writer.write_u64::<LittleEndian>(value)?,
+        Endianness::Big => writer.write_u64::<BigEndian>(value)?,
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Returns the bytes written, including the length prefix
+fn write_string<W: Write>(writer: &mut W, s: &str, encoding: StringEncoding, endianness: Endianness) -> Result<u64> {
+    let encoded;
+    let bytes = match encoding {
+        StringEncoding::Utf8 => s.as_bytes(),
+        _ => {
+            encoded = encoding.encode_lossy(s);
+            &encoded
+        }
+    };
+    write_u32(writer, bytes.len() as u32, endianness)?;
+    writer.write_all(bytes)?;
+    Ok(4 + bytes.len() as u64)
+}
@@ -0,0 +1,58 @@
+// src/writer/telemetry.rs
+//! Writer health metrics.
+//!
+//! With the `metrics` feature, writers report through the [`metrics`](https://docs.rs/metrics)
+//! facade, so any installed recorder (e.g. a Prometheus exporter) picks them
+//! up. Without it the calls compile to nothing. The values are process-wide
+//! totals over all writers:
+//!
+//! | Name | Kind | Meaning |
+//! |------|------|---------|
+//! | `tdms_writer_bytes_written_total` | counter | Bytes written to data files |
+//! | `tdms_writer_segments_total` | counter | Segments started |
+//! | `tdms_writer_flush_seconds` | histogram | Time taken by each flush |
+//! | `tdms_writer_rotations_total` | counter | Files started by rotating writers |
+
+#[cfg(feature = "metrics")]
+use metrics::{counter, histogram};
+use std::time::Duration;
+
+/// Bytes written to data files: lead-ins, metadata and raw data
+pub const BYTES_WRITTEN: &str = "tdms_writer_bytes_written_total";
+/// Segments started
+pub const SEGMENTS: &str = "tdms_writer_segments_total";
+/// Seconds taken by each flush
+pub const FLUSH_SECONDS: &str = "tdms_writer_flush_seconds";
+/// Files started by rotating writers
+pub const ROTATIONS: &str = "tdms_writer_rotations_total";
+
+/// A new segment of `bytes` in total was written
+pub(crate) fn segment_written(bytes: u64) {
+    #[cfg(feature = "metrics")]
+    {
+        counter!(SEGMENTS).increment(1);
+        counter!(BYTES_WRITTEN).increment(bytes);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = bytes;
+}
+
+/// `bytes` of raw data were appended to the current segment
+pub(crate) fn raw_data_appended(bytes: u64) {
+    #[cfg(feature = "metrics")]
+    counter!(BYTES_WRITTEN).increment(bytes);
+    #[cfg(not(feature = "metrics"))]
+    let _ = bytes;
+}
+
+pub(crate) fn flushed(elapsed: Duration) {
+    #[cfg(feature = "metrics")]
+    histogram!(FLUSH_SECONDS).record(elapsed.as_secs_f64());
+    #[cfg(not(feature = "metrics"))]
+    let _ = elapsed;
+}
+
+pub(crate) fn rotated() {
+    #[cfg(feature = "metrics")]
+    counter!(ROTATIONS).increment(1);
+}
@@ -0,0 +1,189 @@
+// src/writer/watchdog.rs
+#![cfg(feature = "async")]
+//! Detecting async writer tasks that stop making progress.
+//!
+//! The async writers hand every command to a single blocking task. If a write
+//! hangs, e.g. on a slow disk or an unresponsive network mount, commands keep
+//! queueing up behind it in memory. With a watchdog set (see
+//! [`AsyncTdmsWriter::set_watchdog`](crate::AsyncTdmsWriter::set_watchdog)),
+//! a command running longer than the timeout marks the writer as stalled:
+//! the callback is told once, and new commands fail with
+//! [`TdmsError::WriterStalled`] until the command finishes.
+
+use crate::error::{Result, TdmsError};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+/// A writer command that has been running for longer than the watchdog timeout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriterStall {
+    /// The command the writer task is stuck in, e.g. `"flush"`
+    pub command: &'static str,
+    /// How long it has been running
+    pub stalled_for: Duration,
+    /// Commands waiting behind it
+    pub queued: usize,
+}
+
+impl From<WriterStall> for TdmsError {
+    fn from(stall: WriterStall) -> Self {
+        TdmsError::WriterStalled { command: stall.command, stalled_for: stall.stalled_for, queued: stall.queued }
+    }
+}
+
+/// Progress of a writer task, shared between the task and the writer handle
+#[derive(Clone, Default)]
+pub(crate) struct Watchdog {
+    state: Arc<State>,
+}
+
+#[derive(Default)]
+struct State {
+    running: Mutex<Option<(&'static str, Instant)>>,
+    queued: AtomicUsize,
+    timeout: Mutex<Option<Duration>>,
+    ended: AtomicBool,
+    ticker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Watchdog {
+    /// Account for a command about to be queued, or fail if the writer is stalled
+    pub fn admit(&self) -> Result<()> {
+        if let Some(stall) = self.stall() {
+            return Err(stall.into());
+        }
+        self.state.queued.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Account for a command queued regardless of stalls, such as closing
+    pub fn admit_always(&self) {
+        self.state.queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The writer task took `command` off the queue and is running it
+    pub fn started(&self, command: &'static str) {
+        self.state.queued.fetch_sub(1, Ordering::Relaxed);
+        *self.state.running.lock() = Some((command, Instant::now()));
+    }
+
+    /// The writer task finished its current command
+    pub fn finished(&self) {
+        *self.state.running.lock() = None;
+    }
+
+    /// Marks the writer task as gone when dropped, even if it panics
+    pub fn task_guard(&self) -> TaskGuard {
+        TaskGuard(self.clone())
+    }
+
+    /// The current stall, if a command has been running longer than the timeout
+    pub fn stall(&self) -> Option<WriterStall> {
+        let timeout = (*self.state.timeout.lock())?;
+        let (command, since) = (*self.state.running.lock())?;
+        let stalled_for = since.elapsed();
+        (stalled_for > timeout).then(|| WriterStall {
+            command,
+            stalled_for,
+            queued: self.state.queued.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Start watching with `timeout`, calling `on_stall` on the current
+    /// runtime once for every stall. Watching again replaces the previous
+    /// timeout and callback.
+    pub fn watch(&self, timeout: Duration, on_stall: impl Fn(WriterStall) + Send + Sync + 'static) -> Result<()> {
+        let runtime = Handle::try_current()
+            .map_err(|_| TdmsError::Unsupported("watchdog outside a Tokio runtime".into()))?;
+        *self.state.timeout.lock() = Some(timeout);
+        let watchdog = self.clone();
+        let ticker = runtime.spawn(async move {
+            let mut ticker = tokio::time::interval((timeout / 4).max(Duration::from_millis(1)));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut reported = false;
+            while !watchdog.state.ended.load(Ordering::Relaxed) {
+                ticker.tick().await;
+                match watchdog.stall() {
+                    Some(stall) if !reported => {
+                        on_stall(stall);
+                        reported = true;
+                    }
+                    Some(_) => {}
+                    None => reported = false,
+                }
+            }
+        });
+        if let Some(previous) = self.state.ticker.lock().replace(ticker) {
+            previous.abort();
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct TaskGuard(Watchdog);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.0.finished();
+        self.0.state.ended.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_needs_timeout_and_running_command() {
+        let watchdog = Watchdog::default();
+        watchdog.admit().unwrap();
+        watchdog.started("flush");
+        assert_eq!(watchdog.stall(), None);
+
+        *watchdog.state.timeout.lock() = Some(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(matches!(watchdog.admit(), Err(TdmsError::WriterStalled { command: "flush", .. })));
+        watchdog.admit_always();
+        let stall = watchdog.stall().unwrap();
+        assert_eq!((stall.command, stall.queued), ("flush", 1));
+
+        watchdog.finished();
+        assert_eq!(watchdog.stall(), None);
+        watchdog.admit().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_stalled_command_calls_back_once() {
+        let watchdog = Watchdog::default();
+        let first = Arc::new(AtomicUsize::new(0));
+        let stalls = Arc::new(Mutex::new(Vec::new()));
+        let counter = first.clone();
+        watchdog.watch(Duration::from_millis(20), move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }).unwrap();
+        let seen = stalls.clone();
+        watchdog.watch(Duration::from_millis(20), move |stall| seen.lock().push(stall)).unwrap();
+
+        // A command that blocks its thread well past the timeout, as a hung
+        // write would
+        let task = watchdog.clone();
+        tokio::task::spawn_blocking(move || {
+            let _guard = task.task_guard();
+            task.admit().unwrap();
+            task.started("flush");
+            std::thread::sleep(Duration::from_millis(200));
+            task.finished();
+        }).await.unwrap();
+
+        let stalls = stalls.lock();
+        assert_eq!(stalls.len(), 1);
+        assert_eq!(stalls[0].command, "flush");
+        assert!(stalls[0].stalled_for > Duration::from_millis(20));
+        // The replaced callback no longer runs
+        assert_eq!(first.load(Ordering::Relaxed), 0);
+    }
+}
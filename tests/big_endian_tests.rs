@@ -0,0 +1,182 @@
+// tests/big_endian_tests.rs
+//! Reading segments flagged with the big-endian ToC bit.
+use tdms_rs::*;
+
+const TOC_METADATA: u32 = 1 << 1;
+const TOC_NEW_OBJ_LIST: u32 = 1 << 2;
+const TOC_RAW_DATA: u32 = 1 << 3;
+const TOC_BIG_ENDIAN: u32 = 1 << 6;
+
+fn put_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Build one big-endian segment holding a string channel and an i32 channel
+fn be_segment(strings: &[&str], ints: &[i32], unit: &str) -> Vec<u8> {
+    let string_bytes: usize = strings.iter().map(|s| s.len()).sum();
+
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&2u32.to_be_bytes());
+
+    put_string(&mut meta, "/'Log'/'Message'");
+    meta.extend_from_slice(&28u32.to_be_bytes());
+    meta.extend_from_slice(&(DataType::String as u32).to_be_bytes());
+    meta.extend_from_slice(&1u32.to_be_bytes());
+    meta.extend_from_slice(&(strings.len() as u64).to_be_bytes());
+    meta.extend_from_slice(&((strings.len() * 4 + string_bytes) as u64).to_be_bytes());
+    meta.extend_from_slice(&0u32.to_be_bytes());
+
+    put_string(&mut meta, "/'Log'/'Code'");
+    meta.extend_from_slice(&20u32.to_be_bytes());
+    meta.extend_from_slice(&(DataType::I32 as u32).to_be_bytes());
+    meta.extend_from_slice(&1u32.to_be_bytes());
+    meta.extend_from_slice(&(ints.len() as u64).to_be_bytes());
+    meta.extend_from_slice(&1u32.to_be_bytes());
+    put_string(&mut meta, "unit_string");
+    meta.extend_from_slice(&(DataType::String as u32).to_be_bytes());
+    put_string(&mut meta, unit);
+
+    let mut raw = Vec::new();
+    let mut end = 0u32;
+    for s in strings {
+        end += s.len() as u32;
+        raw.extend_from_slice(&end.to_be_bytes());
+    }
+    for s in strings {
+        raw.extend_from_slice(s.as_bytes());
+    }
+    for v in ints {
+        raw.extend_from_slice(&v.to_be_bytes());
+    }
+
+    let mut segment = Vec::new();
+    segment.extend_from_slice(b"TDSm");
+    segment.extend_from_slice(&(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_BIG_ENDIAN).to_le_bytes());
+    segment.extend_from_slice(&4713u32.to_be_bytes());
+    segment.extend_from_slice(&((meta.len() + raw.len()) as u64).to_be_bytes());
+    segment.extend_from_slice(&(meta.len() as u64).to_be_bytes());
+    segment.extend(meta);
+    segment.extend(raw);
+    segment
+}
+
+fn write_be_file(name: &str) -> String {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = format!("test_output/{}", name);
+    let mut bytes = be_segment(&["alpha", "", "gamma"], &[1, -2, 300_000], "V");
+    bytes.extend(be_segment(&["δέλτα", "e"], &[i32::MIN, 5], "mV"));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn test_big_endian_strings_and_numbers() {
+    let path = write_be_file("big_endian_strings.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.segment_count(), 2);
+
+    let strings = reader.read_channel_strings("Log", "Message").unwrap();
+    assert_eq!(strings, vec!["alpha", "", "gamma", "δέλτα", "e"]);
+
+    let codes: Vec<i32> = reader.read_channel_data("Log", "Code").unwrap();
+    assert_eq!(codes, vec![1, -2, 300_000, i32::MIN, 5]);
+
+    let unit = &reader.get_channel_properties("Log", "Code").unwrap()["unit_string"];
+    assert_eq!(unit.value, PropertyValue::String("mV".into()));
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_big_endian_string_chunks() {
+    let path = write_be_file("big_endian_string_chunks.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+
+    // Chunks straddle the segment boundary and start mid-segment
+    let chunks: Vec<Vec<String>> = reader.iter_channel_strings("Log", "Message", 2).unwrap()
+        .collect::<Result<_>>().unwrap();
+    assert_eq!(chunks, vec![
+        vec!["alpha".to_string(), String::new()],
+        vec!["gamma".to_string(), "δέλτα".to_string()],
+        vec!["e".to_string()],
+    ]);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_big_endian_conversion_stats() {
+    let path = write_be_file("big_endian_conversion_stats.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let swapped = |bytes: u64| if cfg!(target_endian = "big") { 0 } else { bytes };
+
+    let _: Vec<i32> = reader.read_channel_data("Log", "Code").unwrap();
+    let stats = reader.channel_conversion_stats("Log", "Code");
+    assert_eq!(stats, ConversionStats { bytes_read: 20, bytes_swapped: swapped(20), ..Default::default() });
+
+    // Only the string offsets need swapping
+    reader.read_channel_strings("Log", "Message").unwrap();
+    let stats = reader.channel_conversion_stats("Log", "Message");
+    assert_eq!((stats.bytes_read, stats.bytes_swapped), (41, swapped(20)));
+
+    assert_eq!(reader.conversion_stats().bytes_read, 61);
+    reader.reset_conversion_stats();
+    assert_eq!(reader.conversion_stats(), ConversionStats::default());
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_writer_big_endian_roundtrip() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/big_endian_written.tdms";
+    let start = Timestamp::from_unix_nanos(1_700_000_000_123_456_789);
+    {
+        let mut writer = TdmsWriter::create_with_endianness(path, Endianness::Big).unwrap();
+        assert_eq!(writer.endianness(), Endianness::Big);
+        writer.set_file_property("started", PropertyValue::Timestamp(start));
+        writer.create_channel("Rack", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Rack", "Count", DataType::U16).unwrap();
+        writer.create_channel("Rack", "Time", DataType::TimeStamp).unwrap();
+        writer.create_channel("Rack", "Note", DataType::String).unwrap();
+        writer.create_channel("Rack", "Phasor", DataType::ComplexDoubleFloat).unwrap();
+        writer.set_channel_property("Rack", "Voltage", "gain", PropertyValue::Double(2.5)).unwrap();
+        writer.set_channel_property("Rack", "Count", "limit", PropertyValue::I64(-7)).unwrap();
+        for block in 0..3u16 {
+            writer.write_channel_data("Rack", "Voltage", &[f64::from(block), -1.5]).unwrap();
+            writer.write_channel_data("Rack", "Count", &[block, 0xABCD]).unwrap();
+            writer.write_channel_data("Rack", "Time", &[start]).unwrap();
+            writer.write_channel_strings("Rack", "Note", &["ok", "δ"]).unwrap();
+            writer.write_channel_data("Rack", "Phasor", &[Complex::new(f64::from(block), 0.25)]).unwrap();
+            writer.flush().unwrap();
+        }
+        // Unchanged layout: appended to the previous segment
+        for _ in 0..2 {
+            writer.write_channel_data("Rack", "Voltage", &[9.0]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let bytes = std::fs::read(path).unwrap();
+    assert_ne!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()) & TOC_BIG_ENDIAN, 0);
+    assert_eq!(u32::from_be_bytes(bytes[8..12].try_into().unwrap()), 4713);
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.check_index_file(index_path_for(path)).unwrap(), IndexFileStatus::Consistent);
+    assert_eq!(reader.file_properties["started"].value, PropertyValue::Timestamp(start));
+    let properties = reader.get_channel_properties("Rack", "Count").unwrap();
+    assert_eq!(properties["limit"].value, PropertyValue::I64(-7));
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "Voltage").unwrap(), [0.0, -1.5, 1.0, -1.5, 2.0, -1.5, 9.0, 9.0]);
+    assert_eq!(reader.segment_count(), 4);
+    assert_eq!(reader.read_channel_data::<u16>("Rack", "Count").unwrap(), [0, 0xABCD, 1, 0xABCD, 2, 0xABCD]);
+    assert_eq!(reader.read_channel_data::<Timestamp>("Rack", "Time").unwrap(), [start; 3]);
+    assert_eq!(reader.read_channel_strings("Rack", "Note").unwrap(), ["ok", "δ", "ok", "δ", "ok", "δ"]);
+    // Real and imaginary parts are swapped separately
+    let phasors: Vec<Complex<f64>> = reader.read_channel_data("Rack", "Phasor").unwrap();
+    assert_eq!(phasors, [Complex::new(0.0, 0.25), Complex::new(1.0, 0.25), Complex::new(2.0, 0.25)]);
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(index_path_for(path)).ok();
+}
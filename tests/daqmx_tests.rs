@@ -0,0 +1,257 @@
+// tests/daqmx_tests.rs
+//! Files mixing standard channels with DAQmx raw data channels.
+use tdms_rs::*;
+
+const TOC_METADATA: u32 = 1 << 1;
+const TOC_NEW_OBJ_LIST: u32 = 1 << 2;
+const TOC_RAW_DATA: u32 = 1 << 3;
+const TOC_DAQMX: u32 = 1 << 7;
+
+fn put_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn segment(toc: u32, meta: Vec<u8>, raw: Vec<u8>) -> Vec<u8> {
+    let mut segment = Vec::new();
+    segment.extend_from_slice(b"TDSm");
+    segment.extend_from_slice(&toc.to_le_bytes());
+    segment.extend_from_slice(&4713u32.to_le_bytes());
+    segment.extend_from_slice(&((meta.len() + raw.len()) as u64).to_le_bytes());
+    segment.extend_from_slice(&(meta.len() as u64).to_le_bytes());
+    segment.extend(meta);
+    segment.extend(raw);
+    segment
+}
+
+fn put_standard_channel(meta: &mut Vec<u8>, path: &str, values: u64) {
+    put_string(meta, path);
+    meta.extend_from_slice(&20u32.to_le_bytes());
+    meta.extend_from_slice(&(DataType::DoubleFloat as u32).to_le_bytes());
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    meta.extend_from_slice(&values.to_le_bytes());
+    meta.extend_from_slice(&0u32.to_le_bytes());
+}
+
+/// A DAQmx channel with one i16 format changing scaler in a 4 byte raw buffer
+fn put_daqmx_channel(meta: &mut Vec<u8>, path: &str, values: u64, byte_offset: u32) {
+    put_daqmx_channel_with_scale(meta, path, values, byte_offset, None);
+}
+
+/// Like `put_daqmx_channel`, optionally with a linear `NI_Scale[0]` of (slope, intercept)
+fn put_daqmx_channel_with_scale(meta: &mut Vec<u8>, path: &str, values: u64, byte_offset: u32,
+                                linear: Option<(f64, f64)>) {
+    put_string(meta, path);
+    meta.extend_from_slice(&0x6912_0000u32.to_le_bytes());
+    meta.extend_from_slice(&(DataType::DAQmxRawData as u32).to_le_bytes());
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    meta.extend_from_slice(&values.to_le_bytes());
+    meta.extend_from_slice(&1u32.to_le_bytes()); // scaler count
+    meta.extend_from_slice(&3u32.to_le_bytes()); // DAQmx i16
+    meta.extend_from_slice(&0u32.to_le_bytes()); // raw buffer index
+    meta.extend_from_slice(&byte_offset.to_le_bytes());
+    meta.extend_from_slice(&0u32.to_le_bytes()); // sample format bitmap
+    meta.extend_from_slice(&0u32.to_le_bytes()); // scale id
+    meta.extend_from_slice(&1u32.to_le_bytes()); // raw data width count
+    meta.extend_from_slice(&4u32.to_le_bytes());
+    let Some((slope, intercept)) = linear else {
+        meta.extend_from_slice(&0u32.to_le_bytes()); // properties
+        return;
+    };
+    meta.extend_from_slice(&5u32.to_le_bytes());
+    let put_double = |meta: &mut Vec<u8>, name: &str, value: f64| {
+        put_string(meta, name);
+        meta.extend_from_slice(&(DataType::DoubleFloat as u32).to_le_bytes());
+        meta.extend_from_slice(&value.to_le_bytes());
+    };
+    put_double(meta, "NI_Scale[0]_Linear_Slope", slope);
+    put_double(meta, "NI_Scale[0]_Linear_Y_Intercept", intercept);
+    put_string(meta, "NI_Number_Of_Scales");
+    meta.extend_from_slice(&(DataType::U32 as u32).to_le_bytes());
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    for (name, value) in [("NI_Scale[0]_Scale_Type", "Linear"), ("NI_Scaling_Status", "unscaled")] {
+        put_string(meta, name);
+        meta.extend_from_slice(&(DataType::String as u32).to_le_bytes());
+        put_string(meta, value);
+    }
+}
+
+fn write_mixed_file(name: &str) -> String {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = format!("test_output/{}", name);
+
+    // Standard segment
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    put_standard_channel(&mut meta, "/'Data'/'Temp'", 2);
+    let raw: Vec<u8> = [20.5f64, 21.0].iter().flat_map(|v| v.to_le_bytes()).collect();
+    let mut bytes = segment(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA, meta, raw);
+
+    // DAQmx segment: a standard channel followed by two channels sharing one raw buffer
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&3u32.to_le_bytes());
+    put_standard_channel(&mut meta, "/'Data'/'Temp'", 1);
+    put_daqmx_channel_with_scale(&mut meta, "/'Data'/'AI0'", 3, 0, Some((2.0, 0.5)));
+    put_daqmx_channel(&mut meta, "/'Data'/'AI1'", 3, 2);
+    let mut raw: Vec<u8> = 22.5f64.to_le_bytes().to_vec();
+    for (a, b) in [(1i16, -1i16), (2, -2), (3, -3)] {
+        raw.extend_from_slice(&a.to_le_bytes());
+        raw.extend_from_slice(&b.to_le_bytes());
+    }
+    bytes.extend(segment(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_DAQMX, meta, raw));
+
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn test_mixed_daqmx_file() {
+    let path = write_mixed_file("daqmx_mixed.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+
+    assert!(reader.has_daqmx_data());
+    let flags: Vec<bool> = reader.segments().iter().map(|s| s.has_daqmx_data()).collect();
+    assert_eq!(flags, vec![false, true]);
+
+    let mut channels = reader.list_channels();
+    channels.sort();
+    assert_eq!(channels, vec!["/'Data'/'AI0'", "/'Data'/'AI1'", "/'Data'/'Temp'"]);
+    assert!(reader.is_daqmx_channel("Data", "AI0"));
+    assert!(!reader.is_daqmx_channel("Data", "Temp"));
+
+    let info = reader.get_channel("/'Data'/'AI1'").unwrap();
+    assert!(info.is_daqmx());
+    let daqmx = info.daqmx_info().unwrap();
+    assert_eq!(daqmx.record_width(), 4);
+    assert_eq!(daqmx.scalers[0].data_type, Some(DataType::I16));
+    assert_eq!(daqmx.scalers[0].kind, DaqmxScalerKind::FormatChanging { byte_offset: 2 });
+
+    let temp: Vec<f64> = reader.read_channel_data("Data", "Temp").unwrap();
+    assert_eq!(temp, vec![20.5, 21.0, 22.5]);
+    let ai0: Vec<i16> = reader.read_channel_data("Data", "AI0").unwrap();
+    assert_eq!(ai0, vec![1, 2, 3]);
+    let ai1: Vec<i16> = reader.read_channel_data("Data", "AI1").unwrap();
+    assert_eq!(ai1, vec![-1, -2, -3]);
+
+    // Reading with the wrong width is rejected rather than misinterpreting records
+    assert!(matches!(
+        reader.read_channel_data::<i32>("Data", "AI0"),
+        Err(TdmsError::TypeMismatch { .. })
+    ));
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_defragment_bakes_daqmx_scaling() {
+    let path = write_mixed_file("daqmx_bake.tdms");
+    let baked = "test_output/daqmx_baked.tdms";
+    defragment_with(&path, baked, &DefragOptions::new().bake_daqmx_scaling(true)).unwrap();
+
+    let mut reader = TdmsReader::open(baked).unwrap();
+    assert!(!reader.has_daqmx_data());
+    assert_eq!(reader.get_channel("/'Data'/'AI0'").unwrap().data_type(), DataType::DoubleFloat);
+    let ai0: Vec<f64> = reader.read_channel_data("Data", "AI0").unwrap();
+    assert_eq!(ai0, vec![2.5, 4.5, 6.5]);
+    let properties = reader.get_channel_properties("Data", "AI0").unwrap();
+    assert_eq!(properties["NI_Scaling_Status"].value, PropertyValue::String("scaled".into()));
+    assert!(properties.contains_key("NI_Scale[0]_Linear_Slope"));
+
+    // Unscaled channels are converted as they are
+    let ai1: Vec<f64> = reader.read_channel_data("Data", "AI1").unwrap();
+    assert_eq!(ai1, vec![-1.0, -2.0, -3.0]);
+    let temp: Vec<f64> = reader.read_channel_data("Data", "Temp").unwrap();
+    assert_eq!(temp, vec![20.5, 21.0, 22.5]);
+
+    // Without baking, raw DAQmx channels can't be copied and are reported
+    let report = defragment_with(&path, baked, &DefragOptions::new()).unwrap();
+    let channel = |name: &str| ObjectPath::Channel { group: "Data".into(), channel: name.into() };
+    assert_eq!(report.channels, [channel("Temp")]);
+    let mut skipped = report.skipped.clone();
+    skipped.sort_by_key(|path| path.to_string());
+    assert_eq!(skipped, [channel("AI0"), channel("AI1")]);
+    let reader = TdmsReader::open(baked).unwrap();
+    assert!(reader.get_channel("/'Data'/'AI0'").is_none());
+
+    std::fs::remove_file(&path).ok();
+    std::fs::remove_file(baked).ok();
+    std::fs::remove_file(format!("{}_index", baked)).ok();
+}
+
+#[test]
+fn test_daqmx_chunked_read() {
+    let path = write_mixed_file("daqmx_chunks.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+
+    let chunks: Vec<Vec<i16>> = reader.iter_channel_data("Data", "AI1", 2).unwrap()
+        .collect::<Result<_>>().unwrap();
+    assert_eq!(chunks, vec![vec![-1, -2], vec![-3]]);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_daqmx_conversion_stats() {
+    let path = write_mixed_file("daqmx_conversion_stats.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+
+    let _: Vec<f64> = reader.read_channel_data("Data", "Temp").unwrap();
+    assert_eq!(reader.channel_conversion_stats("Data", "Temp"),
+        ConversionStats { bytes_read: 24, ..Default::default() });
+
+    // Chunked reads add up to the same as one full read
+    for chunk in reader.iter_channel_data::<i16>("Data", "AI0", 2).unwrap() {
+        chunk.unwrap();
+    }
+    assert_eq!(reader.channel_conversion_stats("Data", "AI0"),
+        ConversionStats { bytes_read: 6, bytes_swapped: 0, bytes_deinterleaved: 6, bytes_daqmx: 6 });
+
+    let total = reader.conversion_stats();
+    assert_eq!((total.bytes_read, total.bytes_daqmx), (30, 6));
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_unknown_daqmx_index_is_skipped() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/daqmx_unknown_index.tdms";
+
+    let standard_segment = |values: &[f64]| {
+        let mut meta = Vec::new();
+        meta.extend_from_slice(&1u32.to_le_bytes());
+        put_standard_channel(&mut meta, "/'Data'/'Temp'", values.len() as u64);
+        let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        segment(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA, meta, raw)
+    };
+
+    // A channel with a DAQmx index variant of unknown layout, followed by
+    // bytes the reader can't interpret
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    put_string(&mut meta, "/'Data'/'AI0'");
+    meta.extend_from_slice(&0x6914_0000u32.to_le_bytes());
+    meta.extend_from_slice(&[0xAB; 40]);
+    let unknown = segment(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_DAQMX, meta, vec![0; 16]);
+    // Raw data reusing the unknown layout
+    let inherited = segment(TOC_RAW_DATA | TOC_DAQMX, Vec::new(), vec![0; 16]);
+
+    let mut bytes = standard_segment(&[1.0, 2.0]);
+    bytes.extend(unknown);
+    bytes.extend(inherited);
+    bytes.extend(standard_segment(&[3.0]));
+    std::fs::write(path, bytes).unwrap();
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    let segments: Vec<usize> = reader.warnings().iter().map(|w| w.segment).collect();
+    assert_eq!(segments, vec![1, 2]);
+    assert!(reader.warnings()[0].message.contains("0x69140000"));
+
+    let temp: Vec<f64> = reader.read_channel_data("Data", "Temp").unwrap();
+    assert_eq!(temp, vec![1.0, 2.0, 3.0]);
+
+    let report = tdms_rs::conformance::check_conformance(path).unwrap();
+    assert_eq!(report.issues.len(), 1);
+
+    std::fs::remove_file(path).ok();
+}
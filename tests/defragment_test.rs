@@ -73,7 +73,8 @@ fn test_defragment_file() {
 
         // Check file properties (final values)
         let file_props = reader.get_file_properties();
-        assert_eq!(file_props.len(), 2);
+        // The two set by the test, plus the writer's library identification
+        assert_eq!(file_props.len(), 4);
         assert_eq!(
             file_props.get("file_title").unwrap().value,
             PropertyValue::String("Fragmented File".into())
@@ -110,4 +111,107 @@ fn test_defragment_file() {
 
     cleanup_test_file(&source_path);
     cleanup_test_file(&dest_path);
-}
\ No newline at end of file
+}
+#[test]
+fn test_defragment_excluding_channels_and_groups() {
+    let source_path = setup_test_file("fragmented_excluding.tdms");
+    let dest_path = setup_test_file("defragmented_excluding.tdms");
+    {
+        let mut writer = TdmsWriter::create(&source_path).unwrap();
+        writer.set_group_property("Diagnostics", "level", PropertyValue::I32(3));
+        writer.create_channel("Diagnostics", "Heap", DataType::U64).unwrap();
+        writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Rack", "Scratch", DataType::I32).unwrap();
+        for i in 0..3 {
+            writer.write_channel_data("Diagnostics", "Heap", &[i as u64]).unwrap();
+            writer.write_channel_data("Rack", "Pressure", &[i as f64]).unwrap();
+            writer.write_channel_data("Rack", "Scratch", &[i]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let options = DefragOptions::new()
+        .exclude_group("Diagnostics")
+        .exclude_channel("Rack", "Scratch")
+        .exclude_channel("Rack", "Missing");
+    defragment_with(&source_path, &dest_path, &options).unwrap();
+
+    let mut reader = TdmsReader::open(&dest_path).unwrap();
+    assert_eq!(reader.segment_count(), 1);
+    assert_eq!(reader.list_groups(), vec!["Rack"]);
+    assert_eq!(reader.list_channels(), vec!["/'Rack'/'Pressure'"]);
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "Pressure").unwrap(), vec![0.0, 1.0, 2.0]);
+
+    cleanup_test_file(&source_path);
+    cleanup_test_file(&dest_path);
+}
+
+#[test]
+fn test_defragment_pipeline_options() {
+    let source_path = setup_test_file("fragmented_pipeline.tdms");
+    let dest_path = setup_test_file("defragmented_pipeline.tdms");
+    create_fragmented_file(&source_path).unwrap();
+
+    let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let options = DefragOptions::new()
+        .include_channel("Group1", "ChannelA")
+        .set_property(ObjectPath::Root, "file_title", PropertyValue::String("Cleaned".into()))
+        .remove_property(ObjectPath::Root, "author")
+        .set_property(ObjectPath::Channel { group: "Group1".into(), channel: "ChannelA".into() },
+                      "unit", PropertyValue::String("V".into()))
+        .chunk_values(Some(4))
+        .verify(true)
+        .on_progress(move |p| seen.lock().unwrap().push((p.values_copied, p.channels_done)));
+    let report = defrag::defragment_with(&source_path, &dest_path, &options).unwrap();
+    assert_eq!(report.channels, [ObjectPath::Channel { group: "Group1".into(), channel: "ChannelA".into() }]);
+    assert_eq!(report.values_copied, 9);
+    assert_eq!(*progress.lock().unwrap(), [(4, 0), (8, 0), (9, 0), (9, 1)]);
+
+    let mut reader = TdmsReader::open(&dest_path).unwrap();
+    // The two full chunks share a segment
+    assert_eq!(reader.segment_count(), 2);
+    assert_eq!(reader.list_channels(), vec!["/'Group1'/'ChannelA'"]);
+    assert_eq!(reader.read_channel_data::<i32>("Group1", "ChannelA").unwrap(), (1..=9).collect::<Vec<_>>());
+    let file_props = reader.get_file_properties();
+    assert_eq!(file_props["file_title"].value, PropertyValue::String("Cleaned".into()));
+    assert!(!file_props.contains_key("author"));
+    assert_eq!(reader.get_channel_properties("Group1", "ChannelA").unwrap()["unit"].value, PropertyValue::String("V".into()));
+
+    cleanup_test_file(&source_path);
+    cleanup_test_file(&dest_path);
+}
+
+#[test]
+fn test_defragment_encoded_channel() {
+    let source_path = setup_test_file("fragmented_encoded.tdms");
+    let dest_path = setup_test_file("defragmented_encoded.tdms");
+    let counts: Vec<i32> = (0..1000).map(|i| i / 10).collect();
+    {
+        let mut writer = TdmsWriter::create(&source_path).unwrap();
+        writer.create_encoded_channel("Log", "Counts", DataType::I32, ChannelEncoding::DeltaVarint).unwrap();
+        writer.set_channel_property("Log", "Counts", "unit", PropertyValue::String("events".into())).unwrap();
+        for chunk in counts.chunks(250) {
+            writer.write_channel_data("Log", "Counts", chunk).unwrap();
+            writer.flush().unwrap();
+        }
+    }
+
+    for options in [DefragOptions::new().verify(true), DefragOptions::new().chunk_values(Some(300)).verify(true)] {
+        let report = defrag::defragment_with(&source_path, &dest_path, &options).unwrap();
+        assert_eq!(report.values_copied, 1000);
+
+        let mut reader = TdmsReader::open(&dest_path).unwrap();
+        assert_eq!(
+            reader.channel_encoding("Log", "Counts").unwrap(),
+            Some((ChannelEncoding::DeltaVarint, DataType::I32))
+        );
+        assert_eq!(reader.read_channel_data::<i32>("Log", "Counts").unwrap(), counts);
+        let properties = reader.get_channel_properties("Log", "Counts").unwrap();
+        assert_eq!(properties["unit"].value, PropertyValue::String("events".into()));
+    }
+
+    cleanup_test_file(&source_path);
+    cleanup_test_file(&dest_path);
+}
@@ -0,0 +1,100 @@
+// tests/extended_float_tests.rs
+//! Reading LabVIEW extended precision (80-bit) channels and properties.
+use tdms_rs::*;
+
+const TOC_METADATA: u32 = 1 << 1;
+const TOC_NEW_OBJ_LIST: u32 = 1 << 2;
+const TOC_RAW_DATA: u32 = 1 << 3;
+const TOC_BIG_ENDIAN: u32 = 1 << 6;
+
+/// 1 + 2^-63: needs all 64 mantissa bits, so it only survives unrounded
+const ONE_PLUS_ULP: F80 = F80([1, 0, 0, 0, 0, 0, 0, 0x80, 0xFF, 0x3F]);
+
+/// Build one segment holding an `ExtendedFloat` channel with an
+/// `ExtendedFloat` property, in either byte order
+fn extended_segment(values: &[F80], gain: F80, big_endian: bool) -> Vec<u8> {
+    let u32_bytes = |v: u32| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+    let u64_bytes = |v: u64| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+    let f80_bytes = |v: &F80| {
+        let mut bytes = v.0;
+        if big_endian {
+            bytes.reverse();
+        }
+        bytes
+    };
+    let put_string = |buf: &mut Vec<u8>, s: &str| {
+        buf.extend_from_slice(&u32_bytes(s.len() as u32));
+        buf.extend_from_slice(s.as_bytes());
+    };
+
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&u32_bytes(1));
+    put_string(&mut meta, "/'Lab'/'Precise'");
+    meta.extend_from_slice(&u32_bytes(20));
+    meta.extend_from_slice(&u32_bytes(DataType::ExtendedFloat as u32));
+    meta.extend_from_slice(&u32_bytes(1));
+    meta.extend_from_slice(&u64_bytes(values.len() as u64));
+    meta.extend_from_slice(&u32_bytes(1));
+    put_string(&mut meta, "gain");
+    meta.extend_from_slice(&u32_bytes(DataType::ExtendedFloat as u32));
+    meta.extend_from_slice(&f80_bytes(&gain));
+
+    let raw: Vec<u8> = values.iter().flat_map(f80_bytes).collect();
+
+    let mut toc = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    if big_endian {
+        toc |= TOC_BIG_ENDIAN;
+    }
+    let mut segment = Vec::new();
+    segment.extend_from_slice(b"TDSm");
+    segment.extend_from_slice(&toc.to_le_bytes());
+    segment.extend_from_slice(&u32_bytes(4713));
+    segment.extend_from_slice(&u64_bytes((meta.len() + raw.len()) as u64));
+    segment.extend_from_slice(&u64_bytes(meta.len() as u64));
+    segment.extend(meta);
+    segment.extend(raw);
+    segment
+}
+
+#[test]
+fn test_read_extended_float_channel() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let values = [F80::from_f64(1.5), ONE_PLUS_ULP, F80::from_f64(-1e-310)];
+    for big_endian in [false, true] {
+        let path = format!("test_output/extended_float_{}.tdms", if big_endian { "be" } else { "le" });
+        let mut bytes = extended_segment(&values, F80::from_f64(0.25), big_endian);
+        bytes.extend(extended_segment(&values[..1], F80::from_f64(4.0), big_endian));
+        std::fs::write(&path, bytes).unwrap();
+
+        let mut reader = TdmsReader::open(&path).unwrap();
+        assert_eq!(reader.get_channel("/'Lab'/'Precise'").unwrap().data_type(), DataType::ExtendedFloat);
+        assert_eq!(reader.read_channel_f80_as_f64("Lab", "Precise").unwrap(), [1.5, 1.0, -1e-310, 1.5]);
+        // The raw values keep the bits f64 can't hold
+        let raw: Vec<F80> = reader.read_channel_data("Lab", "Precise").unwrap();
+        assert_eq!(raw, [values[0], values[1], values[2], values[0]]);
+        assert!(matches!(reader.read_channel_data::<f64>("Lab", "Precise"), Err(TdmsError::TypeMismatch { .. })));
+        let gain = &reader.get_channel_properties("Lab", "Precise").unwrap()["gain"];
+        assert_eq!(gain.value, PropertyValue::Double(4.0));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
+
+#[test]
+fn test_write_extended_float_channel() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/extended_float_written.tdms";
+    for endianness in [Endianness::Little, Endianness::Big] {
+        {
+            let mut writer = TdmsWriter::create_with_endianness(path, endianness).unwrap();
+            writer.create_channel("Lab", "Precise", DataType::ExtendedFloat).unwrap();
+            writer.write_channel_data("Lab", "Precise", &[ONE_PLUS_ULP, F80::from_f64(-3.0)]).unwrap();
+            writer.close().unwrap();
+        }
+
+        let mut reader = TdmsReader::open(path).unwrap();
+        assert_eq!(reader.read_channel_data::<F80>("Lab", "Precise").unwrap(), [ONE_PLUS_ULP, F80::from_f64(-3.0)]);
+    }
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(index_path_for(path)).ok();
+}
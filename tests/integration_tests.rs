@@ -29,4 +29,1471 @@ fn test_write_and_read_roundtrip() {
     
     std::fs::remove_file(path).ok();
     std::fs::remove_file(format!("{}_index", path)).ok();
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_read_checks_value_type() {
+    let path = "test_output/value_type_check.tdms";
+    fs::create_dir_all("test_output").unwrap();
+
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group1", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Group1", "Voltage", &[1.5f64, 2.5]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    // Same width, different type: rejected instead of reinterpreting the bytes
+    assert!(matches!(
+        reader.read_channel_data::<i64>("Group1", "Voltage"),
+        Err(TdmsError::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        reader.read_channel_data::<i32>("Group1", "Voltage"),
+        Err(TdmsError::TypeMismatch { .. })
+    ));
+    assert!(reader.iter_channel_data::<u64>("Group1", "Voltage", 1).unwrap().next().unwrap().is_err());
+    assert_eq!(f64::DATA_TYPE, DataType::DoubleFloat);
+    assert_eq!(reader.read_channel_data::<f64>("Group1", "Voltage").unwrap(), vec![1.5, 2.5]);
+
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn test_read_channel_data_unchecked() {
+    let path = "test_output/read_unchecked.tdms";
+    fs::create_dir_all("test_output").unwrap();
+
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group1", "Counter", DataType::I64).unwrap();
+        writer.create_channel("Group1", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Group1", "Counter", &[-1i64, 7]).unwrap();
+        writer.write_channel_data("Group1", "Voltage", &[1.5f64]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert!(matches!(reader.read_channel_data::<u64>("Group1", "Counter"), Err(TdmsError::TypeMismatch { .. })));
+    assert_eq!(reader.read_channel_data_unchecked::<u64>("Group1", "Counter").unwrap(), [u64::MAX, 7]);
+    assert_eq!(reader.read_channel_data_unchecked::<u64>("Group1", "Voltage").unwrap(), [1.5f64.to_bits()]);
+    assert_eq!(reader.read_channel_data_unchecked::<f64>("Group1", "Voltage").unwrap(), [1.5]);
+    // The size still has to match
+    assert!(matches!(reader.read_channel_data_unchecked::<i32>("Group1", "Counter"), Err(TdmsError::TypeMismatch { .. })));
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_read_channel_data_as() {
+    let path = "test_output/read_channel_data_as.tdms";
+    fs::create_dir_all("test_output").unwrap();
+
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group1", "Counts", DataType::I16).unwrap();
+        writer.create_channel("Group1", "Level", DataType::SingleFloat).unwrap();
+        writer.create_channel("Group1", "Total", DataType::U64).unwrap();
+        writer.write_channel_data("Group1", "Counts", &[-32768i16, 0, 32767]).unwrap();
+        writer.write_channel_data("Group1", "Level", &[0.1f32, -2.5]).unwrap();
+        writer.write_channel_data("Group1", "Total", &[u64::MAX]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.read_channel_data_as::<f64>("Group1", "Counts").unwrap(), [-32768.0, 0.0, 32767.0]);
+    assert_eq!(reader.read_channel_data_as::<i32>("Group1", "Counts").unwrap(), [-32768, 0, 32767]);
+    assert_eq!(reader.read_channel_data_as::<i16>("Group1", "Counts").unwrap(), [-32768, 0, 32767]);
+    assert_eq!(reader.read_channel_data_as::<f64>("Group1", "Level").unwrap(), [0.1f32 as f64, -2.5]);
+    assert_eq!(reader.read_channel_data_as::<u64>("Group1", "Total").unwrap(), [u64::MAX]);
+    // Lossy conversions are refused
+    for result in [
+        reader.read_channel_data_as::<u32>("Group1", "Counts").map(|_| ()),
+        reader.read_channel_data_as::<i8>("Group1", "Counts").map(|_| ()),
+        reader.read_channel_data_as::<i64>("Group1", "Level").map(|_| ()),
+        reader.read_channel_data_as::<f64>("Group1", "Total").map(|_| ()),
+    ] {
+        assert!(matches!(result, Err(TdmsError::TypeMismatch { .. })));
+    }
+    assert!(matches!(
+        reader.read_channel_data_as::<f64>("Group1", "Missing"),
+        Err(TdmsError::ChannelNotFound(_))
+    ));
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_virtual_group_hierarchy() {
+    use tdms_rs::metadata::join_group_path;
+
+    let path = "test_output/virtual_groups.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        let engine = join_group_path(&["Vehicle", "Powertrain", "Engine"]);
+        writer.create_channel(engine.as_str(), "RPM", DataType::F64).unwrap();
+        writer.create_channel("Vehicle/Chassis", "Speed", DataType::F64).unwrap();
+        writer.write_channel_data(engine.as_str(), "RPM", &[800.0f64, 900.0]).unwrap();
+        writer.write_channel_data("Vehicle/Chassis", "Speed", &[0.0f64, 1.0]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    let tree = reader.group_tree();
+    let vehicle = tree.find("Vehicle").unwrap();
+    assert!(!vehicle.is_group());
+    assert_eq!(vehicle.descendant_groups(), vec!["Vehicle/Chassis", "Vehicle/Powertrain/Engine"]);
+
+    let engine = tree.find("Vehicle/Powertrain/Engine").unwrap();
+    assert_eq!(engine.channels(), ["RPM"]);
+    let rpm: Vec<f64> = reader.read_channel_data(engine.path(), "RPM").unwrap();
+    assert_eq!(rpm, vec![800.0, 900.0]);
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/virtual_groups.tdms_index").ok();
+}
+
+#[test]
+fn test_labview_array_columns() {
+    use tdms_rs::properties::labview::{NI_ARRAY_COLUMN, NI_CHANNEL_LENGTH};
+
+    let path = "test_output/labview_array.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        for (column, name) in ["X", "Y", "Z"].iter().enumerate().rev() {
+            writer.create_channel("Array", *name, DataType::F64).unwrap();
+            writer.set_channel_property("Array", *name, NI_ARRAY_COLUMN, PropertyValue::I32(column as i32)).unwrap();
+            writer.set_channel_property("Array", *name, NI_CHANNEL_LENGTH, PropertyValue::U64(3)).unwrap();
+            writer.write_channel_data("Array", *name, &[column as f64; 3]).unwrap();
+        }
+        writer.flush().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    let columns = reader.array_columns("Array");
+    let names: Vec<&str> = columns.iter().map(|c| c.channel.as_str()).collect();
+    assert_eq!(names, vec!["X", "Y", "Z"]);
+    assert!(columns.iter().all(|c| c.length == Some(3)));
+    assert!(reader.array_columns("Missing").is_empty());
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/labview_array.tdms_index").ok();
+}
+
+#[test]
+fn test_experiment_timestamps() {
+    let path = "test_output/experiment_timestamps.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    let start = Timestamp { seconds: 3_800_000_000, fractions: 0 };
+    let end = Timestamp { seconds: 3_800_000_600, fractions: 1 << 63 };
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_experiment_start_time(start);
+        writer.create_channel("G", "C", DataType::I16).unwrap();
+        writer.write_channel_data("G", "C", &[1i16, 2]).unwrap();
+        writer.flush().unwrap();
+        writer.set_experiment_time(end);
+        writer.flush().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    let stamps = reader.experiment_timestamps();
+    assert_eq!(stamps.start, Some(start));
+    assert_eq!(stamps.time, Some(end));
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/experiment_timestamps.tdms_index").ok();
+}
+
+#[test]
+fn test_flexlogger_profile_roundtrip() {
+    use tdms_rs::properties::{FlexLoggerChannel, FlexLoggerFile, Scale, Scaling, WaveformTiming};
+
+    let path = "test_output/flexlogger_profile.tdms";
+    fs::create_dir_all("test_output").unwrap();
+
+    let mut file_meta = FlexLoggerFile { name: Some("Endurance run".into()), ..Default::default() };
+    file_meta.test_properties.insert("Operator".into(), PropertyValue::String("QA".into()));
+    let channel_meta = FlexLoggerChannel {
+        display_name: Some("Strain 1".into()),
+        unit: Some("µε".into()),
+        timing: Some(WaveformTiming { increment: Some(0.01), ..Default::default() }),
+        scaling: Some(Scaling {
+            scales: vec![Scale::Linear { slope: 2.0, intercept: -1.0, input_source: -1 }],
+            already_scaled: false,
+        }),
+        ..Default::default()
+    };
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_file_properties(file_meta.to_properties());
+        writer.create_channel("Strain", "ai0", DataType::F32).unwrap();
+        writer.set_channel_properties("Strain", "ai0", channel_meta.to_properties()).unwrap();
+        writer.write_channel_data("Strain", "ai0", &[1.0f32, 2.0]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.flexlogger_file(), file_meta);
+    let channel = reader.flexlogger_channel("Strain", "ai0").unwrap();
+    assert_eq!(channel, channel_meta);
+    assert_eq!(channel.sample_rate(), Some(100.0));
+    assert_eq!(channel.scaling.unwrap().apply(2.0), Some(3.0));
+    assert!(reader.flexlogger_channel("Strain", "missing").is_none());
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/flexlogger_profile.tdms_index").ok();
+}
+
+#[test]
+fn test_encoded_channel_roundtrip() {
+    use tdms_rs::codec::{ENCODING_PROPERTY, ORIGINAL_TYPE_PROPERTY};
+
+    let path = "test_output/encoded_channel.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    let vibration: Vec<f32> = (0..20_000).map(|i| ((i as f32) * 0.001).sin() * 0.01).collect();
+    let counter: Vec<u32> = (0..20_000).map(|i| i / 100).collect();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_encoded_channel("Archive", "Accel", DataType::SingleFloat, ChannelEncoding::ShuffleRle).unwrap();
+        writer.create_encoded_channel("Archive", "Counter", DataType::U32, ChannelEncoding::ShuffleRle).unwrap();
+        writer.create_channel("Archive", "Plain", DataType::I16).unwrap();
+        assert!(writer.create_encoded_channel("Archive", "Text", DataType::String, ChannelEncoding::ShuffleRle).is_err());
+        assert!(writer.create_encoded_channel("Archive", "Plain", DataType::I16, ChannelEncoding::ShuffleRle).is_err());
+
+        for (v, c) in vibration.chunks(5000).zip(counter.chunks(5000)) {
+            writer.write_channel_data("Archive", "Accel", v).unwrap();
+            writer.write_channel_data("Archive", "Counter", c).unwrap();
+            writer.write_channel_data("Archive", "Plain", &[1i16, 2]).unwrap();
+            assert_eq!(writer.pending_samples("Archive", "Counter").unwrap(), 5000);
+            writer.flush().unwrap();
+        }
+    }
+
+    let raw_size = (vibration.len() * 4 + counter.len() * 4) as u64;
+    assert!(fs::metadata(path).unwrap().len() < raw_size);
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert_eq!(
+        reader.channel_encoding("Archive", "Accel").unwrap(),
+        Some((ChannelEncoding::ShuffleRle, DataType::SingleFloat))
+    );
+    assert_eq!(reader.channel_encoding("Archive", "Plain").unwrap(), None);
+
+    let props = reader.get_channel_properties("Archive", "Counter").unwrap();
+    assert!(props.contains_key(ENCODING_PROPERTY));
+    assert!(props.contains_key(ORIGINAL_TYPE_PROPERTY));
+
+    let accel: Vec<f32> = reader.read_channel_data("Archive", "Accel").unwrap();
+    assert_eq!(accel, vibration);
+    let read_counter: Vec<u32> = reader.read_channel_data("Archive", "Counter").unwrap();
+    assert_eq!(read_counter, counter);
+    let plain: Vec<i16> = reader.read_channel_data("Archive", "Plain").unwrap();
+    assert_eq!(plain.len(), 8);
+
+    // Wrong element size is rejected rather than reinterpreted
+    assert!(reader.read_channel_data::<f64>("Archive", "Accel").is_err());
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/encoded_channel.tdms_index").ok();
+}
+
+/// A plain byte channel carrying the encoded channel markers and `frames` as data
+fn write_crafted_encoded_channel(path: &str, encoding: ChannelEncoding, frames: &[u8]) {
+    use tdms_rs::codec::{ENCODING_PROPERTY, ORIGINAL_TYPE_PROPERTY};
+
+    let mut writer = TdmsWriter::create(path).unwrap();
+    writer.create_channel("Archive", "Crafted", DataType::U8).unwrap();
+    writer.set_channel_properties("Archive", "Crafted", vec![
+        Property::new(ENCODING_PROPERTY, PropertyValue::String(encoding.name().into())),
+        Property::new(ORIGINAL_TYPE_PROPERTY, PropertyValue::I32(DataType::I32 as i32)),
+    ]).unwrap();
+    writer.write_channel_data("Archive", "Crafted", frames).unwrap();
+    writer.close().unwrap();
+}
+
+/// A frame header declaring `value_count` values and an empty payload
+fn crafted_frame(value_count: u64) -> Vec<u8> {
+    let mut frame = 0u32.to_le_bytes().to_vec();
+    frame.extend_from_slice(&value_count.to_le_bytes());
+    frame
+}
+
+#[test]
+fn test_encoded_channel_rejects_crafted_counts() {
+    let path = "test_output/encoded_crafted.tdms";
+    fs::create_dir_all("test_output").unwrap();
+
+    for (encoding, value_count) in [
+        (ChannelEncoding::ShuffleRle, u64::MAX / 2),
+        (ChannelEncoding::ShuffleRle, 1 << 40),
+        (ChannelEncoding::DeltaVarint, u64::MAX / 2),
+        (ChannelEncoding::DeltaVarint, 1 << 40),
+    ] {
+        write_crafted_encoded_channel(path, encoding, &crafted_frame(value_count));
+        let mut reader = TdmsReader::open(path).unwrap();
+        assert!(matches!(
+            reader.read_channel_data::<i32>("Archive", "Crafted"),
+            Err(TdmsError::CorruptData(_))
+        ));
+    }
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/encoded_crafted.tdms_index").ok();
+}
+
+#[test]
+fn test_delta_varint_channel() {
+    let path = "test_output/delta_varint_channel.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    let counter: Vec<u64> = (0..50_000u64).map(|i| 1_000_000_000 + i).collect();
+    let status: Vec<i32> = (0..50_000).map(|i| if i % 10_000 < 5_000 { -1 } else { 3 }).collect();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_encoded_channel("Log", "Counter", DataType::U64, ChannelEncoding::DeltaVarint).unwrap();
+        writer.create_encoded_channel("Log", "Status", DataType::I32, ChannelEncoding::DeltaVarint).unwrap();
+        assert!(writer.create_encoded_channel("Log", "Temp", DataType::DoubleFloat, ChannelEncoding::DeltaVarint).is_err());
+
+        writer.write_channel_data("Log", "Counter", &counter).unwrap();
+        writer.write_channel_data("Log", "Status", &status).unwrap();
+        writer.flush().unwrap();
+    }
+
+    // ~1 byte per value instead of 12
+    assert!(fs::metadata(path).unwrap().len() < 110_000);
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    let read_counter: Vec<u64> = reader.read_channel_data("Log", "Counter").unwrap();
+    assert_eq!(read_counter, counter);
+    let read_status: Vec<i32> = reader.read_channel_data("Log", "Status").unwrap();
+    assert_eq!(read_status, status);
+    let range: Vec<u64> = reader.read_channel_range("Log", "Counter", 49_990, 10).unwrap();
+    assert_eq!(range, &counter[49_990..]);
+
+    // Frames can't be cut into chunks, so the chunked readers refuse rather
+    // than hand back encoded bytes
+    assert!(reader.iter_channel_data::<u64>("Log", "Counter", 1000).is_err());
+    assert!(reader.iter_channel_windows::<i32>("Log", "Status", 100, 50).is_err());
+    assert!(reader.iter_channels_zipped::<i32>(&[("Log", "Status")], 1000).is_err());
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/delta_varint_channel.tdms_index").ok();
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd_channel() {
+    let path = "test_output/zstd_channel.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    let pressure: Vec<f64> = (0..40_000).map(|i| 101_325.0 + ((i / 50) as f64)).collect();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_encoded_channel("Rig", "Pressure", DataType::DoubleFloat, ChannelEncoding::Zstd).unwrap();
+        assert!(writer.create_encoded_channel("Rig", "Name", DataType::String, ChannelEncoding::Zstd).is_err());
+        for chunk in pressure.chunks(10_000) {
+            writer.write_channel_data("Rig", "Pressure", chunk).unwrap();
+            writer.flush().unwrap();
+        }
+    }
+
+    assert!(fs::metadata(path).unwrap().len() < (pressure.len() * 8 / 4) as u64);
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert_eq!(
+        reader.channel_encoding("Rig", "Pressure").unwrap(),
+        Some((ChannelEncoding::Zstd, DataType::DoubleFloat))
+    );
+    let read: Vec<f64> = reader.read_channel_data("Rig", "Pressure").unwrap();
+    assert_eq!(read, pressure);
+    let range: Vec<f64> = reader.read_channel_range("Rig", "Pressure", 9_995, 10).unwrap();
+    assert_eq!(range, &pressure[9_995..10_005]);
+    assert!(reader.iter_channel_data::<f64>("Rig", "Pressure", 1000).is_err());
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/zstd_channel.tdms_index").ok();
+}
+
+#[test]
+fn test_stitch_files_aligns_waveforms() {
+    use tdms_rs::properties::WaveformTiming;
+    use tdms_rs::stitch::stitch_files;
+
+    fs::create_dir_all("test_output").unwrap();
+    let rack_a = "test_output/stitch_rack_a.tdms";
+    let rack_b = "test_output/stitch_rack_b.tdms";
+    let merged = "test_output/stitch_merged.tdms";
+
+    let start = Timestamp { seconds: 3_800_000_000, fractions: 0 };
+    let later = Timestamp { seconds: start.seconds, fractions: 1 << 62 }; // +0.25 s
+    let timing = |start| WaveformTiming { start_time: Some(start), increment: Some(0.125), ..Default::default() };
+    {
+        let mut writer = TdmsWriter::create(rack_a).unwrap();
+        writer.set_file_property("operator", PropertyValue::String("A".into()));
+        writer.create_channel("Run", "Temp", DataType::DoubleFloat).unwrap();
+        writer.set_channel_properties("Run", "Temp", timing(start).to_properties()).unwrap();
+        writer.write_channel_data("Run", "Temp", &[0.0f64, 1.0, 2.0, 3.0, 4.0]).unwrap();
+        writer.close().unwrap();
+
+        let mut writer = TdmsWriter::create(rack_b).unwrap();
+        writer.set_file_property("operator", PropertyValue::String("B".into()));
+        writer.create_channel("Run", "Pressure", DataType::I32).unwrap();
+        writer.set_channel_properties("Run", "Pressure", timing(later).to_properties()).unwrap();
+        writer.write_channel_data("Run", "Pressure", &[10i32, 11, 12]).unwrap();
+        writer.create_channel("Run", "Events", DataType::String).unwrap();
+        writer.write_channel_strings("Run", "Events", &["start", "stop"]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let report = stitch_files(&[rack_a, rack_b], merged).unwrap();
+    assert_eq!(report.common_start, Some(later));
+    let skipped: Vec<(String, u64)> = report.channels.iter()
+        .map(|c| (c.path.to_string(), c.skipped_samples))
+        .collect();
+    assert_eq!(skipped, vec![
+        ("/'Run'/'Temp'".to_string(), 2),
+        ("/'Run'/'Events'".to_string(), 0),
+        ("/'Run'/'Pressure'".to_string(), 0),
+    ]);
+
+    let mut reader = TdmsReader::open(merged).unwrap();
+    assert_eq!(reader.get_file_properties()["operator"].value, PropertyValue::String("A".into()));
+    let temp: Vec<f64> = reader.read_channel_data("Run", "Temp").unwrap();
+    assert_eq!(temp, vec![2.0, 3.0, 4.0]);
+    let pressure: Vec<i32> = reader.read_channel_data("Run", "Pressure").unwrap();
+    assert_eq!(pressure, vec![10, 11, 12]);
+    assert_eq!(reader.read_channel_strings("Run", "Events").unwrap(), vec!["start", "stop"]);
+    let temp_timing = WaveformTiming::from_properties(reader.get_channel_properties("Run", "Temp").unwrap()).unwrap();
+    assert_eq!(temp_timing.start_time, Some(later));
+
+    // The same channel in two sources can't be merged
+    assert!(matches!(stitch_files(&[rack_a, rack_a], merged), Err(TdmsError::InvalidPath(_))));
+
+    for path in [rack_a, rack_b, merged] {
+        fs::remove_file(path).ok();
+        fs::remove_file(std::path::Path::new(path).with_extension("tdms_index")).ok();
+    }
+}
+
+#[test]
+fn test_stitch_files_skips_duplicates() {
+    use tdms_rs::stitch::{stitch_files_with, StitchOptions};
+
+    fs::create_dir_all("test_output").unwrap();
+    let shared = "test_output/stitch_dup_shared.tdms";
+    let other = "test_output/stitch_dup_other.tdms";
+    let changed = "test_output/stitch_dup_changed.tdms";
+    let merged = "test_output/stitch_dup_merged.tdms";
+
+    let write = |path: &str, last: f64, extra: bool| {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Run", "Temp", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Run", "Temp", &[0.0f64, f64::NAN, last]).unwrap();
+        writer.create_channel("Run", "Wide", DataType::ExtendedFloat).unwrap();
+        writer.write_channel_data("Run", "Wide", &[F80::from_f64(1.5), F80::from_f64(-2.0)]).unwrap();
+        writer.create_channel("Run", "Phasor", DataType::ComplexDoubleFloat).unwrap();
+        writer.write_channel_data("Run", "Phasor", &[Complex::new(1.0f64, -1.0), Complex::new(0.5, 2.0)]).unwrap();
+        writer.create_channel("Run", "Phasor32", DataType::ComplexSingleFloat).unwrap();
+        writer.write_channel_data("Run", "Phasor32", &[Complex::new(3.0f32, 4.0)]).unwrap();
+        if extra {
+            writer.create_channel("Run", "Extra", DataType::I32).unwrap();
+            writer.write_channel_data("Run", "Extra", &[7i32]).unwrap();
+        }
+        writer.close().unwrap();
+    };
+    write(shared, 2.0, false);
+    write(other, 2.0, true);
+    write(changed, 2.5, false);
+
+    // Identical copies in later sources are skipped, NaNs included
+    let options = StitchOptions { skip_duplicates: true };
+    let report = stitch_files_with(&[shared, other, shared], merged, options).unwrap();
+    assert_eq!(report.channels.len(), 5);
+    assert_eq!(report.duplicates.len(), 8);
+    let mut from_other: Vec<String> = report.duplicates.iter()
+        .filter(|d| d.source == std::path::Path::new(other))
+        .map(|d| d.path.to_string())
+        .collect();
+    from_other.sort();
+    assert_eq!(from_other, vec!["/'Run'/'Phasor'", "/'Run'/'Phasor32'", "/'Run'/'Temp'", "/'Run'/'Wide'"]);
+
+    let mut reader = TdmsReader::open(merged).unwrap();
+    let temp: Vec<f64> = reader.read_channel_data("Run", "Temp").unwrap();
+    assert_eq!((temp[0], temp[2]), (0.0, 2.0));
+    assert!(temp[1].is_nan());
+    let phasor: Vec<Complex<f64>> = reader.read_channel_data("Run", "Phasor").unwrap();
+    assert_eq!(phasor, vec![Complex::new(1.0, -1.0), Complex::new(0.5, 2.0)]);
+    let extra: Vec<i32> = reader.read_channel_data("Run", "Extra").unwrap();
+    assert_eq!(extra, vec![7]);
+
+    // Copies with different values are still an error
+    assert!(matches!(stitch_files_with(&[shared, changed], merged, options), Err(TdmsError::InvalidPath(_))));
+
+    for path in [shared, other, changed, merged] {
+        fs::remove_file(path).ok();
+        fs::remove_file(std::path::Path::new(path).with_extension("tdms_index")).ok();
+    }
+}
+
+#[test]
+fn test_diff_files_with_tolerances() {
+    use tdms_rs::diff::{diff_files, diff_files_with, Difference, DiffOptions, NanPolicy, Tolerance};
+
+    fs::create_dir_all("test_output").unwrap();
+    let expected = "test_output/diff_expected.tdms";
+    let actual = "test_output/diff_actual.tdms";
+    let write = |path: &str, mean: f64, extra: bool| {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_file_property("revision", PropertyValue::I32(if extra { 2 } else { 1 }));
+        writer.create_channel("Results", "Samples", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Results", "Samples", &[1.0f64, f64::NAN, 3.0]).unwrap();
+        writer.create_channel("Results", "Mean", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Results", "Mean", &[mean]).unwrap();
+        writer.create_channel("Results", "Count", DataType::I32).unwrap();
+        writer.write_channel_data("Results", "Count", &[3i32]).unwrap();
+        if extra {
+            writer.create_channel("Results", "Extra", DataType::U8).unwrap();
+            writer.write_channel_data("Results", "Extra", &[1u8]).unwrap();
+        }
+        writer.close().unwrap();
+    };
+    write(expected, 2.0, false);
+    write(actual, f64::from_bits(2.0f64.to_bits() + 2), true);
+
+    let diff = diff_files(expected, expected).unwrap();
+    assert!(diff.is_identical(), "{:?}", diff.differences);
+    assert_eq!(diff.channels_compared, 3);
+
+    let diff = diff_files(expected, actual).unwrap();
+    assert!(diff.differences.contains(&Difference::OnlyInRight(
+        ObjectPath::Channel { group: "Results".into(), channel: "Extra".into() })));
+    assert!(diff.differences.iter().any(|d| matches!(d, Difference::Property { name, .. } if name == "revision")));
+    assert!(diff.differences.iter().any(|d| matches!(d,
+        Difference::Values { path, first_index: 0, count: 1, .. } if path.channel() == Some("Mean"))));
+    assert_eq!(diff.differences.len(), 3);
+
+    let options = DiffOptions { ignore_properties: true, ..Default::default() }
+        .channel_tolerance("Results", "Mean", Tolerance::ulps(2));
+    let diff = diff_files_with(expected, actual, &options).unwrap();
+    assert_eq!(diff.differences, vec![Difference::OnlyInRight(
+        ObjectPath::Channel { group: "Results".into(), channel: "Extra".into() })]);
+
+    let options = DiffOptions { nan: NanPolicy::NotEqual, ..Default::default() };
+    let diff = diff_files_with(expected, expected, &options).unwrap();
+    assert!(matches!(&diff.differences[..],
+        [Difference::Values { first_index: 1, count: 1, max_difference: None, .. }]));
+
+    for path in [expected, actual] {
+        fs::remove_file(path).ok();
+        fs::remove_file(index_path_for(path)).ok();
+    }
+}
+
+#[test]
+fn test_string_encoding_declaration() {
+    fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/string_encoding.tdms";
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_string_encoding(StringEncoding::Windows1252).unwrap();
+        writer.set_file_property("title", PropertyValue::String("Prüfstand €".into()));
+        writer.create_channel("Öl", "Temperatur °C", DataType::String).unwrap();
+        writer.write_channel_strings("Öl", "Temperatur °C", &["kalt", "heiß"]).unwrap();
+        assert!(matches!(writer.write_channel_strings("Öl", "Temperatur °C", &["冷"]), Err(TdmsError::Unsupported(_))));
+        writer.flush().unwrap();
+        assert!(writer.set_string_encoding(StringEncoding::Utf8).is_err());
+        writer.close().unwrap();
+    }
+
+    // Names and values are stored in the code page, not as UTF-8
+    let bytes = fs::read(path).unwrap();
+    assert!(bytes.windows(4).any(|w| w == b"hei\xDF"));
+    assert!(!bytes.windows(2).any(|w| w == "ß".as_bytes()));
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.get_file_properties()["title"].value, PropertyValue::String("Prüfstand €".into()));
+    assert_eq!(reader.read_channel_strings("Öl", "Temperatur °C").unwrap(), vec!["kalt", "heiß"]);
+
+    // An explicit encoding overrides the declared one
+    let options = ReaderOptions { string_encoding: Some(StringEncoding::Latin1), ..Default::default() };
+    let reader = TdmsReader::open_with_options(path, options).unwrap();
+    assert_eq!(reader.get_file_properties()["title"].value, PropertyValue::String("Prüfstand \u{80}".into()));
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_spilled_segment_index() {
+    fs::create_dir_all("test_output/spill").unwrap();
+    let path = "test_output/spilled_index.tdms";
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group", "Values", DataType::U32).unwrap();
+        writer.create_channel("Group", "Labels", DataType::String).unwrap();
+        for i in 0..9000u32 {
+            writer.write_channel_data("Group", "Values", &[i, i * 2]).unwrap();
+            if i % 1000 == 0 {
+                writer.write_channel_strings("Group", "Labels", &[format!("#{}", i)]).unwrap();
+            }
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let options = ReaderOptions { spill_dir: Some("test_output/spill".into()), ..Default::default() };
+    let mut reader = TdmsReader::open_with_options(path, options).unwrap();
+    // The spill file never shows up in the directory
+    assert_eq!(fs::read_dir("test_output/spill").unwrap().count(), 0);
+
+    assert_eq!(reader.list_channels().len(), 2);
+    let values: Vec<u32> = reader.read_channel_data("Group", "Values").unwrap();
+    assert_eq!(values.len(), 18000);
+    assert!(values.chunks(2).enumerate().all(|(i, pair)| pair == [i as u32, i as u32 * 2]));
+    assert_eq!(reader.read_channel_strings("Group", "Labels").unwrap()[8], "#8000");
+
+    let channel = reader.get_channel("/'Group'/'Values'").unwrap();
+    let mut stream = StreamingReader::new(channel, 5000);
+    stream.seek(17000);
+    let tail = reader.read_streaming_data::<u32>(&mut stream).unwrap().unwrap();
+    assert_eq!(tail.len(), 1000);
+    assert_eq!(tail[0], 8500);
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[cfg(feature = "compact-index")]
+#[test]
+fn test_compact_segment_index() {
+    fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/compact_index.tdms";
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group", "Values", DataType::U16).unwrap();
+        for i in 0..500u16 {
+            writer.write_channel_data("Group", "Values", &[i; 3]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let plain = TdmsReader::open(path).unwrap();
+    let channel = plain.get_channel("/'Group'/'Values'").unwrap();
+    assert_eq!(channel.get_segment_data(499).unwrap().value_count, 3);
+    assert_eq!(channel.segment(499).unwrap().unwrap().value_count, 3);
+
+    let options = ReaderOptions { compact_index: true, ..Default::default() };
+    let mut reader = TdmsReader::open_with_options(path, options).unwrap();
+    let channel = reader.get_channel("/'Group'/'Values'").unwrap();
+    assert_eq!(channel.segment_count(), 500);
+    assert!(channel.get_segment_data(0).is_none());
+    assert_eq!(channel.segment(499).unwrap().unwrap().value_count, 3);
+    let values: Vec<u16> = reader.read_channel_data("Group", "Values").unwrap();
+    assert!(values.chunks(3).enumerate().all(|(i, triple)| triple == [i as u16; 3]));
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_incomplete_final_segment() {
+    fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/incomplete_segment.tdms";
+    let chunked = "test_output/incomplete_segment_chunked.tdms";
+    let live = "test_output/incomplete_segment_live.tdms";
+    for (file, new_segments) in [(path, true), (chunked, false)] {
+        let mut writer = TdmsWriter::create(file).unwrap();
+        writer.create_channel("Group", "Values", DataType::U32).unwrap();
+        for block in 0..3u32 {
+            // A changed property starts a new segment; otherwise the data is
+            // appended to the previous one as another chunk
+            if new_segments {
+                writer.set_channel_property("Group", "Values", "block", PropertyValue::U32(block)).unwrap();
+            }
+            let values: Vec<u32> = (block * 100..(block + 1) * 100).collect();
+            writer.write_channel_data("Group", "Values", &values).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+    let complete = fs::read(path).unwrap();
+    let open = |policy| {
+        let options = ReaderOptions { allow_incomplete: policy, ..Default::default() };
+        TdmsReader::open_with_options(live, options)
+    };
+
+    // Cut off in the middle of the last segment's raw data
+    fs::write(live, &complete[..complete.len() - 150]).unwrap();
+    let mut reader = open(IncompleteSegmentPolicy::Include).unwrap();
+    let last_segment = reader.incomplete_segment().unwrap();
+    assert_eq!(reader.read_channel_data::<u32>("Group", "Values").unwrap().len(), 200);
+    assert!(matches!(open(IncompleteSegmentPolicy::Error),
+                     Err(TdmsError::IncompleteSegment(offset)) if offset == last_segment));
+
+    // All data written, but the lead-in not yet patched
+    let mut bytes = complete.clone();
+    bytes[last_segment as usize + 12..last_segment as usize + 20].fill(0xFF);
+    fs::write(live, &bytes).unwrap();
+    let mut reader = open(IncompleteSegmentPolicy::Include).unwrap();
+    assert_eq!(reader.incomplete_segment(), Some(last_segment));
+    assert_eq!(reader.segment_count(), 3);
+    assert_eq!(reader.read_channel_data::<u32>("Group", "Values").unwrap(), (0..300).collect::<Vec<_>>());
+    let mut reader = open(IncompleteSegmentPolicy::Ignore).unwrap();
+    assert_eq!(reader.segment_count(), 2);
+    assert_eq!(reader.read_channel_data::<u32>("Group", "Values").unwrap(), (0..200).collect::<Vec<_>>());
+
+    // Only the lead-in written so far
+    fs::write(live, &complete[..last_segment as usize + 28]).unwrap();
+    let reader = open(IncompleteSegmentPolicy::Include).unwrap();
+    assert_eq!(reader.segment_count(), 2);
+    assert_eq!(reader.incomplete_segment(), Some(last_segment));
+
+    // Cut off in the middle of a segment's last chunk
+    let bytes = fs::read(chunked).unwrap();
+    fs::write(live, &bytes[..bytes.len() - 150]).unwrap();
+    let mut reader = open(IncompleteSegmentPolicy::Include).unwrap();
+    assert_eq!(reader.segment_count(), 1);
+    assert_eq!(reader.read_channel_data::<u32>("Group", "Values").unwrap(), (0..200).collect::<Vec<_>>());
+
+    assert_eq!(TdmsReader::open(path).unwrap().incomplete_segment(), None);
+    for file in [path, chunked, live] {
+        fs::remove_file(file).ok();
+        fs::remove_file(index_path_for(file)).ok();
+    }
+}
+
+#[test]
+fn test_all_properties() {
+    fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/all_properties.tdms";
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.omit_library_info();
+        writer.set_file_property("title", PropertyValue::String("Catalog".into()));
+        writer.set_group_property("Rack", "location", PropertyValue::String("bay 3".into()));
+        writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+        writer.set_channel_property("Rack", "Pressure", "unit_string", PropertyValue::String("bar".into())).unwrap();
+        writer.create_channel("Rack", "Flow", DataType::DoubleFloat).unwrap();
+        writer.create_group("Empty").unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[1.0]).unwrap();
+        writer.write_channel_data("Rack", "Flow", &[2.0]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    let all = reader.all_properties();
+    assert_eq!(all.file["title"].value, PropertyValue::String("Catalog".into()));
+    assert_eq!(all.groups.keys().copied().collect::<Vec<_>>(), vec!["Empty", "Rack"]);
+    assert!(all.groups["Empty"].channels.is_empty());
+
+    let rack = &all.groups["Rack"];
+    assert_eq!(rack.properties["location"].value, PropertyValue::String("bay 3".into()));
+    assert_eq!(rack.channels.keys().copied().collect::<Vec<_>>(), vec!["Flow", "Pressure"]);
+    assert!(rack.channels["Flow"].is_empty());
+    assert_eq!(rack.channels["Pressure"]["unit_string"].value, PropertyValue::String("bar".into()));
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_display_names_and_descriptions() {
+    fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/display_names.tdms";
+    let text = |s: &str| PropertyValue::String(s.into());
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_file_property("name", text("run 7"));
+        writer.set_file_property("description", text("Cold start"));
+        writer.set_group_property("Rack", "name", text("Rack A"));
+        writer.set_group_property("Rack", "description", text(""));
+        writer.create_channel("Rack", "ai0", DataType::I32).unwrap();
+        writer.create_channel("Rack", "ai1", DataType::I32).unwrap();
+        writer.create_channel("Rack", "ai2", DataType::I32).unwrap();
+        writer.create_channel("Spare", "ai3", DataType::I32).unwrap();
+        writer.set_channel_property("Rack", "ai0", "name", text("Pressure")).unwrap();
+        writer.set_channel_property("Rack", "ai0", "NI_ChannelName", text("ignored")).unwrap();
+        writer.set_channel_property("Rack", "ai0", "description", text("Inlet pressure")).unwrap();
+        writer.set_channel_property("Rack", "ai1", "NI_ChannelName", text("Flow")).unwrap();
+        writer.set_channel_property("Rack", "ai2", "name", text("")).unwrap();
+        for (group, channel) in [("Rack", "ai0"), ("Rack", "ai1"), ("Rack", "ai2"), ("Spare", "ai3")] {
+            writer.write_channel_data(group, channel, &[1i32]).unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.file_display_name(), Some("run 7"));
+    assert_eq!(reader.file_description(), Some("Cold start"));
+    assert_eq!(reader.group_display_name("Rack"), Some("Rack A"));
+    assert_eq!(reader.group_display_name("Spare"), Some("Spare"));
+    assert_eq!(reader.group_display_name("Missing"), None);
+    assert_eq!(reader.group_description("Rack"), None);
+    assert_eq!(reader.channel_display_name("Rack", "ai0"), Some("Pressure"));
+    assert_eq!(reader.channel_display_name("Rack", "ai1"), Some("Flow"));
+    assert_eq!(reader.channel_display_name("Rack", "ai2"), Some("ai2"));
+    assert_eq!(reader.channel_display_name("Rack", "missing"), None);
+    assert_eq!(reader.channel_description("Rack", "ai0"), Some("Inlet pressure"));
+    assert_eq!(reader.channel_description("Rack", "ai1"), None);
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_channel_info() {
+    fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/channel_info.tdms";
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Rack", "Strain", DataType::I16).unwrap();
+        writer.create_encoded_channel("Rack", "Counter", DataType::I64, ChannelEncoding::DeltaVarint).unwrap();
+        writer.set_channel_property("Rack", "Strain", "unit_string", PropertyValue::String("µε".into())).unwrap();
+        writer.set_channel_property("Rack", "Strain", "wf_increment", PropertyValue::Double(0.001)).unwrap();
+        writer.set_channel_property("Rack", "Counter", "NI_UnitDescription", PropertyValue::String("counts".into())).unwrap();
+        let scaling = properties::Scaling {
+            scales: vec![properties::Scale::Linear { slope: 2.0, intercept: 0.5, input_source: -1 }],
+            already_scaled: false,
+        };
+        writer.set_channel_properties("Rack", "Strain", scaling.to_properties()).unwrap();
+        for block in 0..3 {
+            writer.write_channel_data("Rack", "Strain", &[block as i16; 4]).unwrap();
+            writer.write_channel_data("Rack", "Counter", &[block as i64]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    let strain = reader.channel_info("Rack", "Strain").unwrap();
+    assert_eq!(strain.data_type, DataType::I16);
+    assert_eq!(strain.length, Some(12));
+    assert_eq!(strain.encoding, None);
+    assert_eq!(strain.unit.as_deref(), Some("µε"));
+    assert_eq!(strain.scaling.unwrap().apply(1.0), Some(2.5));
+    assert_eq!(strain.waveform.and_then(|w| w.sample_rate()), Some(1000.0));
+    assert_eq!(strain.daqmx, None);
+    assert_eq!((strain.fragmentation.chunk_count, strain.fragmentation.total_values), (3, 12));
+
+    let counter = reader.channel_info("Rack", "Counter").unwrap();
+    assert_eq!(counter.data_type, DataType::I64);
+    assert_eq!(counter.length, None);
+    assert_eq!(counter.encoding, Some(ChannelEncoding::DeltaVarint));
+    assert_eq!(counter.unit.as_deref(), Some("counts"));
+    assert_eq!(counter.scaling, None);
+
+    assert!(matches!(reader.channel_info("Rack", "Missing"), Err(TdmsError::ChannelNotFound(_))));
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_writer_info() {
+    fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/writer_info.tdms";
+    let anonymous = "test_output/writer_info_anonymous.tdms";
+    for (file, identify) in [(path, true), (anonymous, false)] {
+        let mut writer = TdmsWriter::create(file).unwrap();
+        if !identify {
+            writer.omit_library_info();
+        }
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        writer.write_channel_data("Group", "Values", &[1i32, 2]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.writer_info(), WriterInfo {
+        library: Some("tdms-rs".to_string()),
+        library_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        format_versions: vec![4713],
+    });
+    assert_eq!(reader.segments()[0].version, 4713);
+
+    // A TDMS 1.0 lead-in is reported as such
+    let mut bytes = fs::read(anonymous).unwrap();
+    bytes[8..12].copy_from_slice(&4712u32.to_le_bytes());
+    fs::write(anonymous, bytes).unwrap();
+    let reader = TdmsReader::open(anonymous).unwrap();
+    assert_eq!(reader.writer_info(), WriterInfo { format_versions: vec![4712], ..Default::default() });
+
+    for file in [path, anonymous] {
+        fs::remove_file(file).ok();
+        fs::remove_file(index_path_for(file)).ok();
+    }
+}
+
+#[test]
+fn test_timestamp_convention() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let path = "test_output/timestamp_convention.tdms";
+    fs::create_dir_all("test_output").unwrap();
+
+    // An old logger wrote Unix seconds into a timestamp channel
+    let unix = Timestamp { seconds: 1_700_000_000, fractions: 1 << 63 };
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Log", "Time", DataType::TimeStamp).unwrap();
+        writer.write_channel_data("Log", "Time", &[unix]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.timestamp_convention(), TimestampConvention::TDMS);
+    let raw: Vec<Timestamp> = reader.read_channel_data("Log", "Time").unwrap();
+    assert_eq!(raw, vec![unix]);
+
+    reader.set_timestamp_convention(TimestampConvention::UNIX);
+    let times = reader.read_channel_system_times("Log", "Time").unwrap();
+    assert_eq!(times, vec![UNIX_EPOCH + Duration::from_millis(1_700_000_000_500)]);
+    assert_eq!(reader.read_channel_utc_timestamps("Log", "Time").unwrap()[0].seconds, 1_700_000_000 + 2_082_844_800);
+
+    // Local time at UTC-05:00 is five hours behind UTC
+    let convention = TimestampConvention::local_time(-5 * 3600);
+    let utc = Timestamp::now();
+    let local = convention.from_utc(utc);
+    assert_eq!(local.seconds, utc.seconds - 5 * 3600);
+    assert_eq!(convention.to_utc(local), utc);
+    reader.set_timestamp_convention(convention);
+    assert_eq!(reader.to_system_time(local), utc.to_system_time());
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/timestamp_convention.tdms_index").ok();
+}
+
+#[test]
+fn test_property_history() {
+    let path = "test_output/property_history.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_file_property("phase", PropertyValue::String("setup".into()));
+        writer.create_channel("Rig", "Temp", DataType::DoubleFloat).unwrap();
+        for (i, status) in ["idle", "heating", "heating", "hold"].iter().enumerate() {
+            writer.set_channel_property("Rig", "Temp", "status", PropertyValue::String(status.to_string())).unwrap();
+            writer.set_group_property("Rig", "setpoint", PropertyValue::Double(20.0 + 10.0 * (i / 2) as f64));
+            writer.write_channel_data("Rig", "Temp", &[i as f64]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    let status: Vec<(usize, String)> = reader.property_history("Rig", "Temp", "status").iter()
+        .map(|(segment, value)| (*segment, value.as_str().unwrap().to_string()))
+        .collect();
+    // Segment 2 rewrites the channel's properties alongside the group change
+    assert_eq!(status, vec![(0, "idle".into()), (1, "heating".into()), (2, "heating".into()), (3, "hold".into())]);
+
+    let setpoints: Vec<usize> = reader.group_property_history("Rig", "setpoint").iter().map(|(s, _)| *s).collect();
+    assert_eq!(setpoints, vec![0, 2]);
+    assert_eq!(reader.file_property_history("phase").len(), 1);
+    assert!(reader.property_history("Rig", "Temp", "missing").is_empty());
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/property_history.tdms_index").ok();
+}
+
+#[test]
+fn test_segment_time_ranges() {
+    use std::time::Duration;
+    use tdms_rs::properties::WaveformTiming;
+
+    let path = "test_output/segment_time_ranges.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    let t0 = Timestamp { seconds: 3_800_000_000, fractions: 0 };
+    let at = |secs: f64| t0.add_duration(Duration::from_secs_f64(secs));
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Daq", "AI0", DataType::DoubleFloat).unwrap();
+        let timing = WaveformTiming { start_time: Some(t0), increment: Some(0.5), ..Default::default() };
+        writer.set_channel_properties("Daq", "AI0", timing.to_properties()).unwrap();
+        writer.write_channel_data("Daq", "AI0", &[0.0f64; 4]).unwrap();
+        writer.flush().unwrap();
+        writer.write_channel_data("Daq", "AI0", &[0.0f64; 3]).unwrap();
+        writer.flush().unwrap();
+        writer.write_channel_data("Daq", "AI0", &[0.0f64; 3]).unwrap();
+        writer.flush().unwrap();
+
+        // Untimed data only
+        writer.create_channel("Daq", "Notes", DataType::String).unwrap();
+        writer.write_channel_strings("Daq", "Notes", &["pause"]).unwrap();
+        writer.flush().unwrap();
+
+        // A new acquisition restarts the waveform
+        writer.set_channel_property("Daq", "AI0", "wf_start_time", PropertyValue::Timestamp(at(100.0))).unwrap();
+        writer.write_channel_data("Daq", "AI0", &[0.0f64; 2]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    let ranges: Vec<Option<(Timestamp, Timestamp)>> = reader.segment_time_ranges().unwrap().iter()
+        .map(|r| r.map(|r| (r.start, r.end)))
+        .collect();
+    assert_eq!(ranges, vec![
+        Some((at(0.0), at(2.0))),
+        Some((at(2.0), at(5.0))),
+        None,
+        Some((at(100.0), at(101.0))),
+    ]);
+    assert_eq!(reader.segments_in_time_range(at(1.5), at(2.5)).unwrap(), vec![0, 1]);
+    assert_eq!(reader.segments_in_time_range(at(100.5), at(200.0)).unwrap(), vec![3]);
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/segment_time_ranges.tdms_index").ok();
+}
+
+#[test]
+fn test_check_conformance() {
+    use tdms_rs::conformance::{check_conformance, ConformanceRule};
+
+    let path = "test_output/conformance.tdms";
+    fs::create_dir_all("test_output").unwrap();
+
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_file_property("title", PropertyValue::String("Conformance".into()));
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        writer.create_channel("Group", "Labels", DataType::String).unwrap();
+        for i in 0..3 {
+            writer.write_channel_data("Group", "Values", &[i, i + 1, i + 2]).unwrap();
+            writer.write_channel_strings("Group", "Labels", &["a", "bc"]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.set_channel_property("Group", "Values", "unit", PropertyValue::String("V".into())).unwrap();
+        writer.write_channel_data("Group", "Values", &[7, 8]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let report = check_conformance(path).unwrap();
+    assert!(report.is_conformant(), "{}", report);
+    assert!(report.index_checked);
+    assert!(report.segments_checked >= 2);
+
+    // Corrupt the ToC of the first segment in the data file only
+    let mut bytes = fs::read(path).unwrap();
+    bytes[4] &= !(1 << 2);
+    let corrupted = "test_output/conformance_corrupted.tdms";
+    fs::write(corrupted, &bytes).unwrap();
+    fs::copy("test_output/conformance.tdms_index", "test_output/conformance_corrupted.tdms_index").unwrap();
+
+    let report = check_conformance(corrupted).unwrap();
+    assert!(!report.is_conformant());
+    assert_eq!(report.issues_for(ConformanceRule::TableOfContents).count(), 1);
+    assert!(report.issues_for(ConformanceRule::IndexFile).any(|issue| issue.segment == Some(0)));
+
+    // Truncated file
+    fs::write(corrupted, &bytes[..bytes.len() - 3]).unwrap();
+    let report = check_conformance(corrupted).unwrap();
+    assert!(report.issues_for(ConformanceRule::LeadIn).count() > 0);
+}
+
+#[test]
+fn test_index_file_naming() {
+    use tdms_rs::conformance::check_conformance;
+
+    let path = "test_output/index_naming.dat";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        writer.write_channel_data("Group", "Values", &[1, 2, 3]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let index_path = index_path_for(path);
+    assert_eq!(index_path, std::path::Path::new("test_output/index_naming.dat_index"));
+    assert!(index_path.exists());
+    assert_eq!(find_index_file(path), Some(index_path.clone()));
+    assert!(check_conformance(path).unwrap().index_checked);
+
+    // Index written under the older `<stem>.tdms_index` name
+    let legacy_path = "test_output/index_naming.tdms_index";
+    fs::rename(&index_path, legacy_path).unwrap();
+    assert_eq!(find_index_file(path), Some(legacy_path.into()));
+    assert!(check_conformance(path).unwrap().index_checked);
+
+    fs::remove_file(path).ok();
+    fs::remove_file(legacy_path).ok();
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_hints() {
+    let path = "test_output/mmap_hints.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group", "Values", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Group", "Labels", DataType::String).unwrap();
+        for i in 0..4 {
+            let values: Vec<f64> = (0..10_000).map(|j| (i * 10_000 + j) as f64).collect();
+            writer.write_channel_data("Group", "Values", &values).unwrap();
+            writer.write_channel_strings("Group", "Labels", &[format!("block {i}")]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let expected: Vec<f64> = (0..40_000).map(|i| i as f64).collect();
+    let hints = MmapHints { sequential: true, will_need: true };
+    let mut reader = TdmsReader::open_mmap_with(path, hints).unwrap();
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Values").unwrap(), expected);
+    assert_eq!(reader.read_channel_strings("Group", "Labels").unwrap()[3], "block 3");
+
+    let mut streamed = Vec::new();
+    for chunk in reader.iter_channel_data::<f64>("Group", "Values", 7_000).unwrap() {
+        streamed.extend(chunk.unwrap());
+    }
+    assert_eq!(streamed, expected);
+
+    reader.set_mmap_hints(MmapHints::default());
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Values").unwrap(), expected);
+
+    fs::remove_file(path).ok();
+    fs::remove_file("test_output/mmap_hints.tdms_index").ok();
+}
+
+#[test]
+fn test_export_csv() {
+    use tdms_rs::export::export_csv;
+
+    let path = "test_output/export.tdms";
+    let out_dir = "test_output/export_csv";
+    fs::create_dir_all("test_output").unwrap();
+    let _ = fs::remove_dir_all(out_dir);
+
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Run 1", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Run 1", "Count", DataType::U16).unwrap();
+        writer.create_channel("Run 1", "Note", DataType::String).unwrap();
+        writer.create_channel("Other/Group", "Flag", DataType::Boolean).unwrap();
+        writer.write_channel_data("Run 1", "Voltage", &[1.5f64, -2.0, 0.25]).unwrap();
+        writer.write_channel_data("Run 1", "Count", &[7u16, 8]).unwrap();
+        writer.write_channel_strings("Run 1", "Note", &["ok", "a, \"b\"", ""]).unwrap();
+        writer.write_channel_data("Other/Group", "Flag", &[true]).unwrap();
+        writer.create_channel("Other:Group", "Flag", DataType::Boolean).unwrap();
+        writer.write_channel_data("Other:Group", "Flag", &[false]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let written = export_csv(path, out_dir).unwrap();
+    assert_eq!(written.len(), 3);
+
+    let run = fs::read_to_string(format!("{}/Run 1.csv", out_dir)).unwrap();
+    assert_eq!(run, "Count,Note,Voltage\n7,ok,1.5\n8,\"a, \"\"b\"\"\",-2\n,,0.25\n");
+
+    let other = fs::read_to_string(format!("{}/Other_Group.csv", out_dir)).unwrap();
+    assert_eq!(other, "Flag\ntrue\n");
+    // Both groups sanitize to the same name; the later one is renamed rather
+    // than overwriting the first
+    let other = fs::read_to_string(format!("{}/Other_Group_2.csv", out_dir)).unwrap();
+    assert_eq!(other, "Flag\nfalse\n");
+}
+
+#[test]
+fn test_export_csv_column_naming() {
+    use tdms_rs::export::{export_csv_with, ColumnNaming};
+
+    let path = "test_output/export_naming.tdms";
+    let out_dir = "test_output/export_naming_csv";
+    fs::create_dir_all("test_output").unwrap();
+    let _ = fs::remove_dir_all(out_dir);
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        for (channel, label) in [("P1", "Pressure"), ("P2", "Pressure"), ("T1", "")] {
+            writer.create_channel("Run", channel, DataType::DoubleFloat).unwrap();
+            writer.set_channel_property("Run", channel, "NI_ChannelName", PropertyValue::String(label.into())).unwrap();
+            writer.write_channel_data("Run", channel, &[1.0f64]).unwrap();
+        }
+        writer.set_channel_property("Run", "P1", "unit_string", PropertyValue::String("bar".into())).unwrap();
+        writer.close().unwrap();
+    }
+
+    let naming = ColumnNaming::new().name_property("NI_ChannelName").include_units(true);
+    export_csv_with(path, out_dir, &naming).unwrap();
+    let run = fs::read_to_string(format!("{}/Run.csv", out_dir)).unwrap();
+    assert_eq!(run.lines().next(), Some("Pressure [bar],Pressure,T1"));
+
+    export_csv_with(path, out_dir, &ColumnNaming::new().name_property("NI_ChannelName")).unwrap();
+    let run = fs::read_to_string(format!("{}/Run.csv", out_dir)).unwrap();
+    assert_eq!(run.lines().next(), Some("Pressure,Pressure_2,T1"));
+
+    fs::remove_file(path).unwrap();
+    fs::remove_file(index_path_for(path)).unwrap();
+}
+
+#[test]
+fn test_write_arrow_stream() {
+    use tdms_rs::export::write_arrow_stream;
+
+    let path = "test_output/export_arrow.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Run", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Run", "Note", DataType::String).unwrap();
+        writer.write_channel_data("Run", "Voltage", &[1.5f64, -2.0, 0.25]).unwrap();
+        writer.write_channel_strings("Run", "Note", &["ok"]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    let mut stream = Vec::new();
+    write_arrow_stream(&mut reader, &[("Run", "Voltage"), ("Run", "Note")], &mut stream).unwrap();
+
+    // Schema message, one record batch, end-of-stream marker
+    let mut messages = 0;
+    let mut at = 0;
+    loop {
+        assert_eq!(stream[at..at + 4], [0xFF; 4]);
+        let metadata_len = u32::from_le_bytes(stream[at + 4..at + 8].try_into().unwrap()) as usize;
+        if metadata_len == 0 {
+            assert_eq!(at + 8, stream.len());
+            break;
+        }
+        assert_eq!(metadata_len % 8, 0);
+        at += 8 + metadata_len;
+        if messages == 1 {
+            // Voltage: three f64 values; Note: validity, offsets, data
+            assert_eq!(stream[at..at + 8], 1.5f64.to_le_bytes());
+            at += 24 + 8 + 16 + 8;
+        }
+        messages += 1;
+    }
+    assert_eq!(messages, 2);
+    assert!(stream.windows(11).any(|w| w == b"Run/Voltage"));
+
+    assert!(matches!(write_arrow_stream(&mut reader, &[("Run", "Current")], Vec::new()),
+                     Err(TdmsError::ChannelNotFound(_))));
+
+    drop(reader);
+    fs::remove_file(path).unwrap();
+    fs::remove_file(index_path_for(path)).unwrap();
+}
+
+#[test]
+fn test_memory_estimates() {
+    let path = "test_output/memory_estimates.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Run", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Run", "Note", DataType::String).unwrap();
+        writer.create_encoded_channel("Run", "Counts", DataType::I32, ChannelEncoding::DeltaVarint).unwrap();
+        for i in 0..50 {
+            writer.set_channel_property("Run", "Voltage", "block", PropertyValue::I32(i)).unwrap();
+            writer.write_channel_data("Run", "Voltage", &vec![1.0f64; 1000]).unwrap();
+            writer.write_channel_strings("Run", "Note", &["hello"; 10]).unwrap();
+            writer.write_channel_data("Run", "Counts", &(0..1000).collect::<Vec<i32>>()).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(path).unwrap();
+    let voltage = reader.estimate_read_memory("Run", "Voltage").unwrap();
+    assert!((50_000 * 8..=51_000 * 8).contains(&voltage), "{}", voltage);
+    let note = reader.estimate_read_memory("Run", "Note").unwrap();
+    assert!(note >= 500 * (std::mem::size_of::<String>() as u64 + 5), "{}", note);
+    // Counts decode to 4-byte values from far fewer encoded bytes
+    let counts = reader.estimate_read_memory("Run", "Counts").unwrap();
+    assert!((50_000 * 4..50_000 * 8).contains(&counts), "{}", counts);
+    assert!(matches!(reader.estimate_read_memory("Run", "Missing"), Err(TdmsError::ChannelNotFound(_))));
+
+    let open = TdmsReader::estimate_open_memory(path).unwrap();
+    let stats = reader.index_stats().unwrap();
+    assert!(open > stats.metadata_bytes, "{} vs {} metadata bytes", open, stats.metadata_bytes);
+    assert!(open < stats.total_bytes(), "{} vs {} file bytes", open, stats.total_bytes());
+
+    drop(reader);
+    fs::remove_file(path).unwrap();
+    fs::remove_file(index_path_for(path)).unwrap();
+}
+
+#[test]
+fn test_generate_signals() {
+    use std::time::Duration;
+    use tdms_rs::properties::WaveformTiming;
+    use tdms_rs::signal::{generate_file, SignalSpec, Waveform};
+
+    let path = "test_output/signals.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    let sine = SignalSpec::new("Demo", "Sine", Waveform::Sine { frequency: 5.0, amplitude: 1.0 }, 100_000.0, Duration::from_secs(1));
+    let noise = SignalSpec::new("Demo", "Noise", Waveform::Noise { amplitude: 0.5, seed: 1 }, 1_000.0, Duration::from_millis(1500));
+    generate_file(path, &[sine.clone(), noise.clone()]).unwrap();
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    // 100k samples take two blocks
+    assert_eq!(reader.segment_count(), 2);
+    assert_eq!(reader.read_channel_data::<f64>("Demo", "Sine").unwrap(), sine.generate(0, 100_000));
+    assert_eq!(reader.read_channel_data::<f64>("Demo", "Noise").unwrap(), noise.generate(0, 1500));
+    let timing = WaveformTiming::from_properties(reader.get_channel_properties("Demo", "Noise").unwrap()).unwrap();
+    assert_eq!(timing.increment, Some(0.001));
+    assert_eq!(timing.samples, Some(1500));
+    assert!(timing.start_time.is_some());
+    drop(reader);
+
+    let zero_rate = SignalSpec::new("Demo", "Bad", Waveform::Ramp { from: 0.0, to: 1.0 }, 0.0, Duration::from_secs(1));
+    assert!(generate_file(path, &[zero_rate]).is_err());
+
+    fs::remove_file(path).unwrap();
+    fs::remove_file(index_path_for(path)).unwrap();
+}
+
+#[test]
+fn test_find_index_by_timestamp() {
+    let path = "test_output/find_index_by_timestamp.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    let at = |ms: i64| Timestamp { seconds: 3_800_000_000 + ms / 1000, fractions: ((ms % 1000) as u64) << 54 };
+    // One value per millisecond with a run of duplicates, over several segments
+    let mut times: Vec<Timestamp> = (0..20_000).map(|ms| at(2 * ms)).collect();
+    times[9_000..9_010].fill(at(18_000));
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("G", "Time", DataType::TimeStamp).unwrap();
+        writer.create_channel("G", "Value", DataType::I32).unwrap();
+        for block in times.chunks(3_000) {
+            writer.write_channel_data("G", "Time", block).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.write_channel_data("G", "Value", &[1i32]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    let mut find = |time: Timestamp| reader.find_index_by_timestamp("G", "Time", time).unwrap();
+    assert_eq!(find(Timestamp { seconds: 3_799_999_999, fractions: 0 }), 0);
+    assert_eq!(find(at(0)), 0);
+    assert_eq!(find(at(1)), 1);
+    assert_eq!(find(at(12_346)), 6_173);
+    assert_eq!(find(at(18_000)), 9_000);
+    assert_eq!(find(at(39_998)), 19_999);
+    assert_eq!(find(at(40_000)), 20_000);
+    for &probe in &[0, 3_001, 5_999, 17_777, 19_998] {
+        let target = times[probe];
+        assert_eq!(find(target), times.partition_point(|t| *t < target) as u64);
+    }
+    assert!(matches!(reader.find_index_by_timestamp("G", "Value", at(0)), Err(TdmsError::TypeMismatch { .. })));
+    assert!(reader.find_index_by_timestamp("G", "Missing", at(0)).is_err());
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_metadata_crc() {
+    let path = "test_output/metadata_crc.tdms";
+    let crc_path = metadata_crc_path_for(path);
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.enable_metadata_crc().unwrap();
+        writer.create_channel("G", "C", DataType::I32).unwrap();
+        for block in 0..4 {
+            if block == 2 {
+                writer.set_channel_property("G", "C", "stage", PropertyValue::String("second".into())).unwrap();
+            }
+            writer.write_channel_data("G", "C", &[block, block]).unwrap();
+            writer.flush().unwrap();
+        }
+        assert!(writer.enable_metadata_crc().is_err());
+        writer.close().unwrap();
+    }
+    let records = tdms_rs::segment::read_metadata_crc_file(&crc_path).unwrap();
+    assert_eq!(records.len(), 2);
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    assert_eq!(reader.check_metadata_crc(&crc_path).unwrap(), MetadataCrcStatus::Consistent);
+    drop(reader);
+
+    // Damage the property value in the second segment's metadata
+    let mut bytes = fs::read(path).unwrap();
+    let at = bytes.windows(6).position(|w| w == b"second").unwrap();
+    bytes[at] = b'S';
+    fs::write(path, bytes).unwrap();
+    let mut reader = TdmsReader::open(path).unwrap();
+    let segment = reader.segments().iter().position(|s| s.offset == records[1].offset).unwrap();
+    assert_eq!(reader.check_metadata_crc(&crc_path).unwrap(),
+        MetadataCrcStatus::Mismatch { segment, reason: "metadata checksum differs".to_string() });
+
+    fs::remove_file(&crc_path).unwrap();
+    assert_eq!(reader.check_metadata_crc(&crc_path).unwrap(), MetadataCrcStatus::Missing);
+
+    fs::remove_file(path).ok();
+    fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_channel_preview() {
+    let path = "test_output/channel_preview.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("G", "Ramp", DataType::I32).unwrap();
+        writer.create_channel("G", "Notes", DataType::String).unwrap();
+        for block in 0..4 {
+            writer.write_channel_data("G", "Ramp", &(block * 250..(block + 1) * 250).collect::<Vec<i32>>()).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.write_channel_strings("G", "Notes", &["a", "b", "c"]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    let preview = reader.preview::<i32>("G", "Ramp", 3).unwrap();
+    assert_eq!(preview.data_type, DataType::I32);
+    assert_eq!(preview.count, 1000);
+    assert_eq!(preview.first, [0, 1, 2]);
+    assert_eq!(preview.last, [997, 998, 999]);
+    assert_eq!(preview.last_start(), 997);
+
+    // Short channels split between the ends instead of repeating values
+    let notes = reader.preview_strings("G", "Notes", 2).unwrap();
+    assert_eq!((notes.count, notes.first, notes.last), (3, vec!["a".to_string(), "b".to_string()], vec!["c".to_string()]));
+    let whole = reader.preview::<i32>("G", "Ramp", 600).unwrap();
+    assert_eq!((whole.first.len(), whole.last.len(), whole.last_start()), (600, 400, 600));
+
+    assert!(matches!(reader.preview::<f64>("G", "Ramp", 3), Err(TdmsError::TypeMismatch { .. })));
+    assert!(matches!(reader.preview_strings("G", "Ramp", 3), Err(TdmsError::TypeMismatch { .. })));
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(index_path_for(path)).ok();
+}
+
+#[test]
+fn test_read_channel_range() {
+    let path = "test_output/read_channel_range.tdms";
+    fs::create_dir_all("test_output").unwrap();
+    {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("G", "Ramp", DataType::U32).unwrap();
+        writer.create_channel("G", "Labels", DataType::String).unwrap();
+        for block in 0..4 {
+            writer.write_channel_data("G", "Ramp", &(block * 250..(block + 1) * 250).collect::<Vec<u32>>()).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.write_channel_strings("G", "Labels", &["a", "b", "c", "d"]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(path).unwrap();
+    // Straddles the boundary between the second and third segments
+    assert_eq!(reader.read_channel_range::<u32>("G", "Ramp", 490, 20).unwrap(), (490..510).collect::<Vec<u32>>());
+    assert_eq!(reader.read_channel_range::<u32>("G", "Ramp", 995, 10).unwrap(), [995, 996, 997, 998, 999]);
+    assert!(reader.read_channel_range::<u32>("G", "Ramp", 1000, 10).unwrap().is_empty());
+    assert_eq!(reader.channel_conversion_stats("G", "Ramp").bytes_read, 25 * 4);
+    assert!(matches!(reader.read_channel_range::<i32>("G", "Ramp", 1000, 10), Err(TdmsError::TypeMismatch { .. })));
+    assert_eq!(reader.read_channel_range_strings("G", "Labels", 1, 2).unwrap(), ["b", "c"]);
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(index_path_for(path)).ok();
+}
@@ -0,0 +1,99 @@
+// tests/interleaved_tests.rs
+//! Reading segments flagged with the interleaved ToC bit.
+use tdms_rs::*;
+
+const TOC_METADATA: u32 = 1 << 1;
+const TOC_NEW_OBJ_LIST: u32 = 1 << 2;
+const TOC_RAW_DATA: u32 = 1 << 3;
+const TOC_INTERLEAVED: u32 = 1 << 5;
+
+fn put_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn put_index(buf: &mut Vec<u8>, path: &str, data_type: DataType, count: u64) {
+    put_string(buf, path);
+    buf.extend_from_slice(&20u32.to_le_bytes());
+    buf.extend_from_slice(&(data_type as u32).to_le_bytes());
+    buf.extend_from_slice(&1u32.to_le_bytes());
+    buf.extend_from_slice(&count.to_le_bytes());
+    buf.extend_from_slice(&0u32.to_le_bytes());
+}
+
+/// Build one interleaved segment of an i16, f64 and u8 channel with
+/// `per_chunk` values each per chunk; `values` spans all chunks
+fn interleaved_segment(values: &[(i16, f64, u8)], per_chunk: u64) -> Vec<u8> {
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&3u32.to_le_bytes());
+    put_index(&mut meta, "/'Rig'/'Code'", DataType::I16, per_chunk);
+    put_index(&mut meta, "/'Rig'/'Level'", DataType::DoubleFloat, per_chunk);
+    put_index(&mut meta, "/'Rig'/'Flag'", DataType::U8, per_chunk);
+
+    let mut raw = Vec::new();
+    for (code, level, flag) in values {
+        raw.extend_from_slice(&code.to_le_bytes());
+        raw.extend_from_slice(&level.to_le_bytes());
+        raw.push(*flag);
+    }
+
+    let mut segment = Vec::new();
+    segment.extend_from_slice(b"TDSm");
+    segment.extend_from_slice(&(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED).to_le_bytes());
+    segment.extend_from_slice(&4713u32.to_le_bytes());
+    segment.extend_from_slice(&((meta.len() + raw.len()) as u64).to_le_bytes());
+    segment.extend_from_slice(&(meta.len() as u64).to_le_bytes());
+    segment.extend(meta);
+    segment.extend(raw);
+    segment
+}
+
+fn records(range: std::ops::Range<i16>) -> Vec<(i16, f64, u8)> {
+    range.map(|i| (i * 10, i as f64 * 0.5, i as u8)).collect()
+}
+
+fn write_interleaved_file(name: &str) -> String {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = format!("test_output/{}", name);
+    // Two chunks in the first segment, one in the second
+    let mut bytes = interleaved_segment(&records(0..6), 3);
+    bytes.extend(interleaved_segment(&records(6..10), 4));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn test_interleaved_read_all() {
+    let path = write_interleaved_file("interleaved_read_all.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let expected = records(0..10);
+
+    let codes: Vec<i16> = reader.read_channel_data("Rig", "Code").unwrap();
+    assert_eq!(codes, expected.iter().map(|r| r.0).collect::<Vec<_>>());
+    let levels: Vec<f64> = reader.read_channel_data("Rig", "Level").unwrap();
+    assert_eq!(levels, expected.iter().map(|r| r.1).collect::<Vec<_>>());
+    let flags: Vec<u8> = reader.read_channel_data("Rig", "Flag").unwrap();
+    assert_eq!(flags, expected.iter().map(|r| r.2).collect::<Vec<_>>());
+
+    let stats = reader.channel_conversion_stats("Rig", "Level");
+    assert_eq!(stats.bytes_deinterleaved, 80);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_interleaved_chunks() {
+    let path = write_interleaved_file("interleaved_chunks.tdms");
+    let mut reader = TdmsReader::open(&path).unwrap();
+
+    // Chunks start mid-chunk and straddle chunk and segment boundaries
+    let chunks: Vec<Vec<f64>> = reader.iter_channel_data("Rig", "Level", 4).unwrap()
+        .collect::<Result<_>>().unwrap();
+    assert_eq!(chunks, vec![
+        vec![0.0, 0.5, 1.0, 1.5],
+        vec![2.0, 2.5, 3.0, 3.5],
+        vec![4.0, 4.5],
+    ]);
+
+    std::fs::remove_file(&path).ok();
+}
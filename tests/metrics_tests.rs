@@ -0,0 +1,126 @@
+// tests/metrics_tests.rs
+//! Writer health metrics reported through the `metrics` facade.
+#![cfg(feature = "metrics")]
+
+use metrics::{Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tdms_rs::writer::telemetry;
+use tdms_rs::*;
+
+/// Sums every counter and collects every histogram sample, by metric name
+#[derive(Default)]
+struct TestRecorder {
+    counters: Arc<Mutex<HashMap<String, u64>>>,
+    histograms: Arc<Mutex<HashMap<String, Vec<f64>>>>,
+}
+
+struct Handle {
+    name: String,
+    counters: Arc<Mutex<HashMap<String, u64>>>,
+    histograms: Arc<Mutex<HashMap<String, Vec<f64>>>>,
+}
+
+impl CounterFn for Handle {
+    fn increment(&self, value: u64) {
+        *self.counters.lock().unwrap().entry(self.name.clone()).or_default() += value;
+    }
+
+    fn absolute(&self, value: u64) {
+        self.counters.lock().unwrap().insert(self.name.clone(), value);
+    }
+}
+
+impl HistogramFn for Handle {
+    fn record(&self, value: f64) {
+        self.histograms.lock().unwrap().entry(self.name.clone()).or_default().push(value);
+    }
+}
+
+impl TestRecorder {
+    fn handle(&self, key: &Key) -> Arc<Handle> {
+        Arc::new(Handle {
+            name: key.name().to_string(),
+            counters: self.counters.clone(),
+            histograms: self.histograms.clone(),
+        })
+    }
+
+    fn counter(&self, name: &str) -> u64 {
+        self.counters.lock().unwrap().get(name).copied().unwrap_or(0)
+    }
+}
+
+impl Recorder for TestRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.handle(key))
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(self.handle(key))
+    }
+}
+
+#[test]
+fn test_writer_metrics() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/metrics.tdms";
+    let recorder = TestRecorder::default();
+
+    let overhead = metrics::with_local_recorder(&recorder, || {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        for block in 0..3 {
+            writer.write_channel_data("Group", "Values", &[block; 10]).unwrap();
+            writer.flush().unwrap();
+        }
+        let overhead = writer.metadata_overhead();
+        writer.close().unwrap();
+        overhead
+    });
+
+    let file_size = std::fs::metadata(path).unwrap().len();
+    assert_eq!(recorder.counter(telemetry::BYTES_WRITTEN), file_size);
+    assert_eq!(recorder.counter(telemetry::SEGMENTS), overhead.segments);
+    assert!(recorder.histograms.lock().unwrap()[telemetry::FLUSH_SECONDS].len() >= 3);
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(format!("{}_index", path)).ok();
+}
+
+#[test]
+fn test_rotation_metric() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let base = "test_output/metrics_rotating.tdms";
+    let recorder = TestRecorder::default();
+
+    metrics::with_local_recorder(&recorder, || {
+        let mut writer = RotatingTdmsWriter::new(base, 1000).unwrap();
+        writer.create_channel("Group", "Values", DataType::DoubleFloat).unwrap();
+        for _ in 0..5 {
+            writer.write_channel_data("Group", "Values", &[0.5f64; 100]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    });
+
+    let files: Vec<String> = (0..10)
+        .map(|i| if i == 0 { base.to_string() } else { format!("test_output/metrics_rotating.{}.tdms", i) })
+        .filter(|file| std::path::Path::new(file).exists())
+        .collect();
+    assert!(files.len() > 1);
+    assert_eq!(recorder.counter(telemetry::ROTATIONS), files.len() as u64 - 1);
+
+    for file in files {
+        std::fs::remove_file(&file).ok();
+        std::fs::remove_file(format!("{}_index", file)).ok();
+    }
+}
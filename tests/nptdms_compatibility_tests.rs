@@ -2,6 +2,8 @@
 //! Integration tests that write TDMS files with Rust and validate them using Python's nptdms library.
 //! This ensures compatibility with third-party TDMS parsers.
 
+#![allow(clippy::approx_constant)]
+
 use tdms_rs::*;
 use std::fs;
 use std::process::Command;
@@ -495,7 +497,7 @@ fn test_nptdms_multiple_groups() {
     }
     
     // Verify file can be read
-    let mut reader = TdmsReader::open(&path).unwrap();
+    let reader = TdmsReader::open(&path).unwrap();
     let groups = reader.list_groups();
     assert_eq!(groups.len(), 3);
     assert!(groups.contains(&"Group1".to_string()));
@@ -40,3 +40,261 @@ fn test_rotating_writer_creates_new_file_on_size_limit() {
     let read_data: Vec<i32> = reader.read_channel_data("group", "channel").unwrap();
     assert!(!read_data.is_empty());
 }
+
+#[test]
+fn test_rotating_writer_reports_finished_files() {
+    let test_dir = "test_output/rotating_writer_finished";
+    setup_test_dir(test_dir);
+    let base_path = Path::new(test_dir).join("test");
+
+    let mut writer = RotatingTdmsWriter::new(&base_path, 1024).unwrap();
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+    assert!(writer.take_finished_files().is_empty());
+
+    let data: Vec<i32> = (0..1000).collect();
+    for _ in 0..3 {
+        writer.write_channel_data("group", "channel", &data).unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(writer.take_finished_files(), [base_path.with_extension("tdms"), base_path.with_extension("1.tdms")]);
+    assert!(writer.take_finished_files().is_empty());
+    assert_eq!(writer.current_path(), base_path.with_extension("2.tdms"));
+}
+
+#[test]
+fn test_rotating_writer_time_limits() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tdms_rs::{ManualClock, Timestamp};
+
+    let test_dir = "test_output/rotating_writer_time";
+    setup_test_dir(test_dir);
+    let base_path = Path::new(test_dir).join("test");
+
+    // 00:50 on some day
+    let clock = Arc::new(ManualClock::new(Timestamp::from_unix_nanos(1_700_006_400_000_000_000 + 50 * 60_000_000_000)));
+    let rotated = Arc::new(Mutex::new(Vec::new()));
+    let seen = rotated.clone();
+    let mut writer = RotatingTdmsWriter::builder(&base_path)
+        .interval(Duration::from_secs(3600))
+        .max_age(Duration::from_secs(45 * 60))
+        .clock(clock.clone())
+        .on_rotate(move |path| seen.lock().unwrap().push(path.to_path_buf()))
+        .build()
+        .unwrap();
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+
+    let mut write = |minutes: u64| {
+        clock.advance(Duration::from_secs(minutes * 60));
+        writer.write_channel_data("group", "channel", &[minutes as i32]).unwrap();
+        writer.current_path()
+    };
+    assert_eq!(write(0), base_path.with_extension("tdms"));
+    // Crossing the hour rotates after 10 minutes
+    assert_eq!(write(10), base_path.with_extension("1.tdms"));
+    assert_eq!(write(40), base_path.with_extension("1.tdms"));
+    // 01:45, 45 minutes after the file was started
+    assert_eq!(write(5), base_path.with_extension("2.tdms"));
+    writer.close().unwrap();
+
+    assert_eq!(*rotated.lock().unwrap(), [
+        base_path.with_extension("tdms"),
+        base_path.with_extension("1.tdms"),
+        base_path.with_extension("2.tdms"),
+    ]);
+    let mut reader = TdmsReader::open(base_path.with_extension("1.tdms")).unwrap();
+    assert_eq!(reader.read_channel_data::<i32>("group", "channel").unwrap(), [10, 40]);
+}
+
+#[test]
+fn test_rotating_writer_sample_and_segment_limits() {
+    let test_dir = "test_output/rotating_writer_counts";
+    setup_test_dir(test_dir);
+    let base_path = Path::new(test_dir).join("samples");
+
+    let mut writer = RotatingTdmsWriter::builder(&base_path).max_samples(5).build().unwrap();
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+    for block in 0..4 {
+        writer.write_channel_data("group", "channel", &[block; 3]).unwrap();
+    }
+    writer.close().unwrap();
+    for (suffix, values) in [("tdms", 6), ("1.tdms", 6)] {
+        let mut reader = TdmsReader::open(base_path.with_extension(suffix)).unwrap();
+        assert_eq!(reader.read_channel_data::<i32>("group", "channel").unwrap().len(), values);
+    }
+
+    let base_path = Path::new(test_dir).join("segments");
+    let mut writer = RotatingTdmsWriter::builder(&base_path).max_segments(2).build().unwrap();
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+    for block in 0..5 {
+        // A different value count each time keeps writes from being appended to one segment
+        writer.write_channel_data("group", "channel", &vec![block; block as usize + 1]).unwrap();
+        writer.flush().unwrap();
+    }
+    writer.close().unwrap();
+    assert_eq!(TdmsReader::open(base_path.with_extension("tdms")).unwrap().segment_count(), 2);
+    assert_eq!(TdmsReader::open(base_path.with_extension("1.tdms")).unwrap().segment_count(), 2);
+    assert_eq!(TdmsReader::open(base_path.with_extension("2.tdms")).unwrap().segment_count(), 1);
+}
+
+#[test]
+fn test_rotating_writer_naming_template() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tdms_rs::{ManualClock, RotationNaming, Timestamp};
+
+    let test_dir = "test_output/rotating_writer_naming";
+    setup_test_dir(test_dir);
+    let base_path = Path::new(test_dir).join("capture.tdms");
+
+    // 2023-11-15 00:50:00 UTC
+    let clock = Arc::new(ManualClock::new(Timestamp::from_unix_nanos(1_700_006_400_000_000_000 + 50 * 60_000_000_000)));
+    let mut writer = RotatingTdmsWriter::builder(&base_path)
+        .interval(Duration::from_secs(3600))
+        .naming(RotationNaming::template("{stem}_{timestamp}_{index:3}.tdms"))
+        .clock(clock.clone())
+        .build()
+        .unwrap();
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+    writer.write_channel_data("group", "channel", &[1]).unwrap();
+    assert_eq!(writer.current_path(), Path::new(test_dir).join("capture_20231115T005000Z_000.tdms"));
+    clock.advance(Duration::from_secs(15 * 60));
+    writer.write_channel_data("group", "channel", &[2]).unwrap();
+    assert_eq!(writer.current_path(), Path::new(test_dir).join("capture_20231115T010500Z_001.tdms"));
+    writer.close().unwrap();
+    let mut reader = TdmsReader::open(Path::new(test_dir).join("capture_20231115T010500Z_001.tdms")).unwrap();
+    assert_eq!(reader.read_channel_data::<i32>("group", "channel").unwrap(), [2]);
+
+    // Hourly files named by the hour alone
+    let writer = RotatingTdmsWriter::builder(&base_path)
+        .interval(Duration::from_secs(3600))
+        .naming(RotationNaming::template("{{hour}} {timestamp:%Y-%m-%d_%H}.tdms"))
+        .clock(clock.clone())
+        .build()
+        .unwrap();
+    assert_eq!(writer.current_path(), Path::new(test_dir).join("{hour} 2023-11-15_01.tdms"));
+    writer.close().unwrap();
+
+    for template in ["{stem}.tdms", "{stem}_{when}.tdms", "{index:x}.tdms", "{timestamp:%q}.tdms", "{index.tdms"] {
+        let result = RotatingTdmsWriter::builder(&base_path).naming(RotationNaming::template(template)).build();
+        assert!(matches!(result, Err(tdms_rs::TdmsError::InvalidPath(_))), "{}", template);
+    }
+
+    // A rotation that would reuse the current name fails instead of overwriting it
+    let mut writer = RotatingTdmsWriter::builder(&base_path)
+        .max_samples(1)
+        .naming(RotationNaming::template("{stem}_{timestamp:%H}.tdms"))
+        .clock(clock.clone())
+        .build()
+        .unwrap();
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+    writer.write_channel_data("group", "channel", &[1]).unwrap();
+    assert!(matches!(
+        writer.write_channel_data("group", "channel", &[2]),
+        Err(tdms_rs::TdmsError::InvalidPath(_))
+    ));
+
+    // So does one reusing the name of an earlier file, even once it was moved away
+    let first = writer.current_path();
+    clock.advance(Duration::from_secs(3600));
+    writer.write_channel_data("group", "channel", &[3]).unwrap();
+    std::fs::remove_file(&first).unwrap();
+    clock.advance(Duration::from_secs(23 * 3600));
+    assert_eq!(writer.current_path(), Path::new(test_dir).join("capture_02.tdms"));
+    assert!(matches!(
+        writer.write_channel_data("group", "channel", &[4]),
+        Err(tdms_rs::TdmsError::InvalidPath(_))
+    ));
+    assert!(!first.exists());
+}
+
+#[test]
+fn test_rotating_writer_existing_files() {
+    use tdms_rs::RotationNaming;
+
+    let test_dir = "test_output/rotating_writer_existing";
+    setup_test_dir(test_dir);
+    let base_path = Path::new(test_dir).join("test");
+
+    // Numeric suffixes overwrite files left over from an earlier run
+    for _ in 0..2 {
+        let mut writer = RotatingTdmsWriter::builder(&base_path).max_samples(1).build().unwrap();
+        writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+        writer.write_channel_data("group", "channel", &[1]).unwrap();
+        writer.write_channel_data("group", "channel", &[2]).unwrap();
+        assert_eq!(writer.current_path(), Path::new(test_dir).join("test.1.tdms"));
+        writer.close().unwrap();
+    }
+
+    // Templates never do, neither for the first file nor on rotation
+    let naming = RotationNaming::template("{stem}_{index}.tdms");
+    let first = Path::new(test_dir).join("test_0.tdms");
+    let next = Path::new(test_dir).join("test_1.tdms");
+    fs::write(&first, b"earlier run").unwrap();
+    let result = RotatingTdmsWriter::builder(&base_path).naming(naming.clone()).build();
+    assert!(matches!(result, Err(tdms_rs::TdmsError::InvalidPath(_))));
+    assert_eq!(fs::read(&first).unwrap(), b"earlier run");
+
+    fs::remove_file(&first).unwrap();
+    fs::write(&next, b"earlier run").unwrap();
+    let mut writer = RotatingTdmsWriter::builder(&base_path).max_samples(1).naming(naming).build().unwrap();
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).unwrap();
+    writer.write_channel_data("group", "channel", &[1]).unwrap();
+    assert!(matches!(
+        writer.write_channel_data("group", "channel", &[2]),
+        Err(tdms_rs::TdmsError::InvalidPath(_))
+    ));
+    assert_eq!(fs::read(&next).unwrap(), b"earlier run");
+
+    // Once the name is free again the rotation goes through
+    fs::remove_file(&next).unwrap();
+    writer.write_channel_data("group", "channel", &[3]).unwrap();
+    assert_eq!(writer.current_path(), next);
+    writer.close().unwrap();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_async_rotating_writer_on_rotate() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tdms_rs::AsyncRotatingTdmsWriter;
+
+    let test_dir = "test_output/async_rotating_writer_hook";
+    setup_test_dir(test_dir);
+    let base_path = Path::new(test_dir).join("test");
+
+    let uploaded = Arc::new(Mutex::new(Vec::new()));
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let writer = AsyncRotatingTdmsWriter::new(&base_path, 1024).await.unwrap();
+    {
+        let (uploaded, running, peak) = (uploaded.clone(), running.clone(), peak.clone());
+        writer.on_rotate(1, move |path| {
+            let (uploaded, running, peak) = (uploaded.clone(), running.clone(), peak.clone());
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                // The file is complete by the time the hook sees it
+                assert!(TdmsReader::open(&path).unwrap().read_channel_data::<i32>("group", "channel").is_ok());
+                fs::remove_file(&path).unwrap();
+                uploaded.lock().unwrap().push(path);
+                running.fetch_sub(1, Ordering::SeqCst);
+            }
+        }).unwrap();
+    }
+
+    writer.create_channel("group", "channel", tdms_rs::DataType::I32).await.unwrap();
+    for _ in 0..3 {
+        writer.write_channel_data("group", "channel", (0..1000).collect::<Vec<i32>>(), tdms_rs::DataType::I32).await.unwrap();
+        writer.flush().await.unwrap();
+    }
+    writer.close().await.unwrap();
+
+    let mut uploaded = uploaded.lock().unwrap().clone();
+    uploaded.sort();
+    assert_eq!(uploaded, [base_path.with_extension("1.tdms"), base_path.with_extension("2.tdms"), base_path.with_extension("tdms")]);
+    assert_eq!(peak.load(Ordering::SeqCst), 1);
+    assert!(!base_path.with_extension("tdms").exists());
+}
@@ -0,0 +1,186 @@
+// tests/storage_tests.rs
+//! Reading and writing through storage backends defined outside the crate.
+use std::io;
+use std::sync::{Arc, Mutex};
+use tdms_rs::*;
+
+/// A shared in-memory store that scrambles its bytes at rest and counts syncs,
+/// standing in for an encrypted container
+#[derive(Clone, Default)]
+struct ScrambledStorage {
+    bytes: Arc<Mutex<Vec<u8>>>,
+    syncs: Arc<Mutex<usize>>,
+    fail_syncs: Arc<Mutex<bool>>,
+}
+
+const KEY: u8 = 0x5A;
+
+impl TdmsStorage for ScrambledStorage {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.bytes.lock().unwrap();
+        let start = (offset as usize).min(bytes.len());
+        let count = buf.len().min(bytes.len() - start);
+        for (out, byte) in buf.iter_mut().zip(&bytes[start..start + count]) {
+            *out = byte ^ KEY;
+        }
+        Ok(count)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        let mut bytes = self.bytes.lock().unwrap();
+        let end = offset as usize + buf.len();
+        if bytes.len() < end {
+            bytes.resize(end, KEY);
+        }
+        for (stored, byte) in bytes[offset as usize..end].iter_mut().zip(buf) {
+            *stored = byte ^ KEY;
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.bytes.lock().unwrap().len() as u64)
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        if *self.fail_syncs.lock().unwrap() {
+            return Err(io::Error::other("sync failed"));
+        }
+        *self.syncs.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_custom_storage_roundtrip() {
+    let (data, index) = (ScrambledStorage::default(), ScrambledStorage::default());
+    {
+        let mut writer = TdmsWriter::from_storage(data.clone(), index.clone()).unwrap();
+        writer.set_durability_mode(DurabilityMode::Transactional);
+        writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Group", "Label", DataType::String).unwrap();
+        for block in 0..3 {
+            writer.write_channel_data("Group", "Voltage", &[block as f64, block as f64 + 0.5]).unwrap();
+            writer.write_channel_strings("Group", "Label", &[format!("block {}", block)]).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+    assert!(*data.syncs.lock().unwrap() > 0);
+    assert_ne!(&data.bytes.lock().unwrap()[..4], b"TDSm");
+
+    let mut reader = TdmsReader::from_storage(data).unwrap();
+    assert_eq!(reader.segment_count(), 3);
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Voltage").unwrap(), [0.0, 0.5, 1.0, 1.5, 2.0, 2.5]);
+    assert_eq!(reader.read_channel_strings("Group", "Label").unwrap(), ["block 0", "block 1", "block 2"]);
+
+    let mut tag = [0u8; 4];
+    index.read_at(0, &mut tag).unwrap();
+    assert_eq!(&tag, b"TDSh");
+}
+
+#[test]
+fn test_boxed_storage() {
+    let mut data: Box<dyn TdmsStorage> = Box::new(Vec::new());
+    let mut index: Box<dyn TdmsStorage> = Box::new(Vec::new());
+    {
+        let mut writer = TdmsWriter::from_storage(&mut data, &mut index).unwrap();
+        writer.create_channel("Group", "Count", DataType::U32).unwrap();
+        writer.write_channel_data("Group", "Count", &[1u32, 2, 3]).unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::from_storage(data).unwrap();
+    assert_eq!(reader.read_channel_data::<u32>("Group", "Count").unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn test_durability_policy_syncs() {
+    for (policy, expected_syncs) in [
+        (DurabilityPolicy::Never, 0),
+        (DurabilityPolicy::EverySegment, 4),
+        (DurabilityPolicy::EveryBytes(1 << 20), 1),
+    ] {
+        let (data, index) = (ScrambledStorage::default(), ScrambledStorage::default());
+        let mut writer = TdmsWriter::from_storage(data.clone(), index.clone()).unwrap();
+        writer.set_durability_policy(policy);
+        writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+        for block in 0..3 {
+            writer.write_channel_data("Group", "Voltage", &[block as f64]).unwrap();
+            writer.flush().unwrap();
+        }
+        // Every policy but `Never` syncs on close
+        writer.close().unwrap();
+        assert_eq!(*data.syncs.lock().unwrap(), expected_syncs, "{:?}", policy);
+        assert_eq!(*index.syncs.lock().unwrap(), expected_syncs, "{:?}", policy);
+    }
+
+    let data = ScrambledStorage::default();
+    let mut writer = TdmsWriter::from_storage(data.clone(), ScrambledStorage::default()).unwrap();
+    writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+    writer.write_channel_data("Group", "Voltage", &[1.0f64]).unwrap();
+    writer.sync_all().unwrap();
+    assert_eq!(*data.syncs.lock().unwrap(), 1);
+    drop(writer);
+    assert_eq!(TdmsReader::from_storage(data).unwrap().read_channel_data::<f64>("Group", "Voltage").unwrap(), [1.0]);
+}
+
+#[test]
+fn test_drop_finalizes_interrupted_segment() {
+    let (data, index) = (ScrambledStorage::default(), ScrambledStorage::default());
+    {
+        let mut writer = TdmsWriter::from_storage(data.clone(), index.clone()).unwrap();
+        writer.set_durability_mode(DurabilityMode::Transactional);
+        writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Group", "Voltage", &[1.0f64, 2.0]).unwrap();
+        writer.flush().unwrap();
+
+        // The body is written, but the sync before the lead-in patch fails
+        *data.fail_syncs.lock().unwrap() = true;
+        writer.write_channel_data("Group", "Voltage", &[3.0f64]).unwrap();
+        writer.set_channel_property("Group", "Voltage", "unit", PropertyValue::String("V".into())).unwrap();
+        assert!(writer.flush().is_err());
+    }
+
+    let mut reader = TdmsReader::from_storage(data).unwrap();
+    assert_eq!(reader.segment_count(), 2);
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Voltage").unwrap(), [1.0, 2.0, 3.0]);
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_encrypted_file_roundtrip() {
+    let keys = |key_id: &str| match key_id {
+        "lab-2024" => Ok([0x42; 32]),
+        _ => Err(io::Error::new(io::ErrorKind::NotFound, "no such key")),
+    };
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/encrypted.tdms";
+    {
+        let mut writer = TdmsWriter::create_encrypted(path, "lab-2024", &keys).unwrap();
+        writer.set_file_property("operator", PropertyValue::String("ana".into()));
+        writer.create_channel("Group", "Voltage", DataType::DoubleFloat).unwrap();
+        for block in 0..20 {
+            let values: Vec<f64> = (0..5_000).map(|i| (block * 5_000 + i) as f64).collect();
+            writer.write_channel_data("Group", "Voltage", &values).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    // Not readable as plain TDMS, and nothing in the clear
+    assert!(matches!(TdmsReader::open(path), Err(TdmsError::InvalidTag { .. })));
+    let bytes = std::fs::read(path).unwrap();
+    assert!(!bytes.windows(8).any(|window| window == b"operator"));
+
+    let mut reader = TdmsReader::open_encrypted(path, &keys).unwrap();
+    assert_eq!(reader.get_file_properties()["operator"].value, PropertyValue::String("ana".into()));
+    let values: Vec<f64> = reader.read_channel_data("Group", "Voltage").unwrap();
+    assert_eq!(values, (0..100_000).map(|i| i as f64).collect::<Vec<_>>());
+
+    let wrong_key = |_: &str| Ok([0x24; 32]);
+    assert!(TdmsReader::open_encrypted(path, &wrong_key).is_err());
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file(index_path_for(path)).ok();
+}
@@ -27,7 +27,7 @@ fn test_high_level_numeric_iteration() {
         
         // Write in multiple segments to ensure streaming handles segment boundaries correctly
         for i in 0..10 {
-            let data: Vec<i32> = (0..1000).map(|x| (i * 1000 + x) as i32).collect();
+            let data: Vec<i32> = (0..1000).map(|x| i * 1000 + x).collect();
             writer.write_channel_data("Group", "Data", &data).unwrap();
             writer.flush().unwrap();
         }
@@ -250,4 +250,170 @@ fn test_odd_chunk_sizes() {
     }
     
     cleanup_test_file(&path);
-}
\ No newline at end of file
+}
+#[test]
+fn test_owned_iterators() {
+    let path = setup_test_file("streaming_owned.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Group", "Data", DataType::U16).unwrap();
+        writer.create_channel("Group", "Notes", DataType::String).unwrap();
+        for i in 0..5u16 {
+            let data: Vec<u16> = (0..100).map(|x| i * 100 + x).collect();
+            writer.write_channel_data("Group", "Data", &data).unwrap();
+            writer.write_channel_strings("Group", "Notes", &[format!("note {}", i)]).unwrap();
+            writer.flush().unwrap();
+        }
+    }
+
+    // Returned from a function without a reader to borrow from
+    fn open_values(path: &str) -> Result<impl Iterator<Item = Result<Vec<u16>>>> {
+        TdmsReader::open(path)?.into_iter_channel_data("Group", "Data", 64)
+    }
+    let values: Vec<u16> = open_values(&path).unwrap()
+        .flat_map(|chunk| chunk.unwrap())
+        .collect();
+    assert_eq!(values, (0..500).collect::<Vec<u16>>());
+
+    // Runs on another thread, and hands the reader back
+    let reader = TdmsReader::open(&path).unwrap();
+    let mut notes = reader.into_iter_channel_strings("Group", "Notes", 2).unwrap();
+    let first = notes.next().unwrap().unwrap();
+    assert_eq!(first, vec!["note 0", "note 1"]);
+    let handle = std::thread::spawn(move || {
+        let rest: Vec<String> = notes.by_ref().flat_map(|chunk| chunk.unwrap()).collect();
+        (rest, notes.progress(), notes.into_reader())
+    });
+    let (rest, progress, mut reader) = handle.join().unwrap();
+    assert_eq!(rest, vec!["note 2", "note 3", "note 4"]);
+    assert_eq!(progress, 100.0);
+    assert_eq!(reader.read_channel_data::<u16>("Group", "Data").unwrap().len(), 500);
+
+    // Type mismatches surface on the first chunk
+    let mut wrong = TdmsReader::open(&path).unwrap().into_iter_channel_data::<f64>("Group", "Data", 10).unwrap();
+    assert!(matches!(wrong.next(), Some(Err(TdmsError::TypeMismatch { .. }))));
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_follower_dispatches_new_data() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let path = setup_test_file("follower.tdms");
+    let values = Rc::new(RefCell::new(Vec::new()));
+    let events = Rc::new(RefCell::new(Vec::new()));
+
+    let mut follower = TdmsFollower::new(&path);
+    let sink = values.clone();
+    follower.on_new_data::<i32>("Group", "Values", move |start, chunk| {
+        sink.borrow_mut().push((start, chunk.to_vec()));
+    });
+    let sink = events.clone();
+    follower.on_new_strings("Group", "Events", move |_, chunk| {
+        sink.borrow_mut().extend_from_slice(chunk);
+    });
+    assert_eq!(follower.poll().unwrap(), 0);
+
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.create_channel("Group", "Values", DataType::I32).unwrap();
+    writer.write_channel_data("Group", "Values", &[1, 2, 3]).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(follower.poll().unwrap(), 3);
+    assert_eq!(follower.poll().unwrap(), 0);
+
+    writer.create_channel("Group", "Events", DataType::String).unwrap();
+    writer.write_channel_data("Group", "Values", &[4, 5]).unwrap();
+    writer.write_channel_strings("Group", "Events", &["started"]).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(follower.poll().unwrap(), 3);
+    writer.close().unwrap();
+
+    assert_eq!(*values.borrow(), vec![(0, vec![1, 2, 3]), (3, vec![4, 5])]);
+    assert_eq!(*events.borrow(), vec!["started".to_string()]);
+
+    let mut mismatched = TdmsFollower::new(&path);
+    mismatched.on_new_data::<f64>("Group", "Values", |_, _| {});
+    assert!(matches!(mismatched.poll(), Err(TdmsError::TypeMismatch { .. })));
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_channel_windows() {
+    let path = setup_test_file("streaming_windows.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Group", "Data", DataType::I32).unwrap();
+        // Segments of 7 values, so windows straddle segment boundaries
+        for i in 0..5 {
+            let data: Vec<i32> = (i * 7..(i + 1) * 7).collect();
+            writer.write_channel_data("Group", "Data", &data).unwrap();
+            writer.flush().unwrap();
+        }
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let all: Vec<i32> = (0..35).collect();
+    let expected = |window: usize, hop: usize| -> Vec<Vec<i32>> {
+        (0..).map(|i| i * hop).take_while(|start| start + window <= all.len())
+            .map(|start| all[start..start + window].to_vec())
+            .collect()
+    };
+    for (window, hop) in [(8, 4), (10, 1), (5, 5), (4, 9), (35, 1), (36, 1)] {
+        let windows: Vec<Vec<i32>> = reader.iter_channel_windows::<i32>("Group", "Data", window, hop).unwrap()
+            .collect::<Result<_>>().unwrap();
+        assert_eq!(windows, expected(window, hop), "window {} hop {}", window, hop);
+    }
+
+    assert!(reader.iter_channel_windows::<i32>("Group", "Data", 0, 1).is_err());
+    assert!(reader.iter_channel_windows::<i32>("Group", "Data", 4, 0).is_err());
+    let mut mismatched = reader.iter_channel_windows::<f64>("Group", "Data", 4, 2).unwrap();
+    assert!(matches!(mismatched.next(), Some(Err(TdmsError::TypeMismatch { .. }))));
+    drop(mismatched);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_zipped_channels() {
+    let path = setup_test_file("streaming_zipped.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Group", "A", DataType::I32).unwrap();
+        writer.create_channel("Group", "B", DataType::I32).unwrap();
+        writer.create_channel("Group", "Short", DataType::I32).unwrap();
+        // The channels are split into segments differently
+        for i in 0..6 {
+            writer.write_channel_data("Group", "A", &(i * 5..(i + 1) * 5).collect::<Vec<i32>>()).unwrap();
+            if i % 2 == 1 {
+                writer.write_channel_data("Group", "B", &(i * 5 - 5..(i + 1) * 5).map(|v| -v).collect::<Vec<i32>>()).unwrap();
+            }
+            writer.flush().unwrap();
+        }
+        writer.write_channel_data("Group", "Short", &[1]).unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let steps: Vec<Vec<Vec<i32>>> = reader.iter_channels_zipped::<i32>(&[("Group", "B"), ("Group", "A")], 8).unwrap()
+        .collect::<Result<_>>().unwrap();
+    assert_eq!(steps.len(), 4);
+    for (i, step) in steps.iter().enumerate() {
+        let expected: Vec<i32> = (i as i32 * 8..(i as i32 * 8 + 8).min(30)).collect();
+        assert_eq!(step[1], expected);
+        assert_eq!(step[0], expected.iter().map(|v| -v).collect::<Vec<_>>());
+    }
+
+    assert!(matches!(
+        reader.iter_channels_zipped::<i32>(&[("Group", "A"), ("Group", "Short")], 8),
+        Err(TdmsError::Unsupported(_))
+    ));
+    assert!(matches!(
+        reader.iter_channels_zipped::<i32>(&[("Group", "A"), ("Group", "Missing")], 8),
+        Err(TdmsError::ChannelNotFound(_))
+    ));
+    assert_eq!(reader.iter_channels_zipped::<i32>(&[], 8).unwrap().count(), 0);
+
+    cleanup_test_file(&path);
+}
@@ -50,7 +50,7 @@ fn test_writer_stress_test() {
         // Perform many small writes, flushing each to a new segment
         for i in 0..SMALL_WRITE_COUNT {
             let f64_data: Vec<f64> = vec![i as f64; 10];
-            let i32_data: Vec<i32> = vec![i as i32; 10];
+            let i32_data: Vec<i32> = vec![i; 10];
             let string_data: Vec<String> = vec![format!("value_{}", i); 10];
 
             let start_time = 1672531200 + TDMS_EPOCH_OFFSET;
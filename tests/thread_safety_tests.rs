@@ -0,0 +1,69 @@
+// tests/thread_safety_tests.rs
+//! Compile-time and runtime checks that the public types can cross threads.
+use std::fs::File;
+use std::io::BufReader;
+use tdms_rs::*;
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn test_send_sync_markers() {
+    assert_send::<TdmsReader<BufReader<File>>>();
+    assert_sync::<TdmsReader<BufReader<File>>>();
+    assert_send::<ChannelReader>();
+    assert_sync::<ChannelReader>();
+    assert_send::<StreamingReader>();
+    assert_sync::<StreamingReader>();
+    assert_send::<TdmsIter<'static, f64, BufReader<File>>>();
+    assert_send::<TdmsStringIter<'static, BufReader<File>>>();
+    assert_send::<ReaderPool>();
+    assert_sync::<ReaderPool>();
+
+    assert_send::<TdmsWriter>();
+    assert_sync::<TdmsWriter>();
+    assert_send::<RotatingTdmsWriter>();
+    assert_sync::<RotatingTdmsWriter>();
+    #[cfg(feature = "async")]
+    {
+        assert_send::<AsyncTdmsWriter>();
+        assert_sync::<AsyncTdmsWriter>();
+        assert_send::<AsyncRotatingTdmsWriter>();
+        assert_sync::<AsyncRotatingTdmsWriter>();
+    }
+
+    assert_send::<TdmsError>();
+    assert_sync::<TdmsError>();
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_reader_is_send_sync() {
+    assert_send::<TdmsReader<std::io::Cursor<memmap2::Mmap>>>();
+    assert_sync::<TdmsReader<std::io::Cursor<memmap2::Mmap>>>();
+}
+
+#[test]
+fn test_move_reader_and_writer_across_threads() {
+    let path = "test_output/thread_move.tdms";
+    std::fs::create_dir_all("test_output").unwrap();
+
+    let mut writer = TdmsWriter::create(path).unwrap();
+    writer.create_channel("G", "C", DataType::I32).unwrap();
+    let writer = std::thread::spawn(move || {
+        writer.write_channel_data("G", "C", &[1i32, 2, 3]).unwrap();
+        writer.flush().unwrap();
+        writer
+    }).join().unwrap();
+    drop(writer);
+
+    let reader = TdmsReader::open(path).unwrap();
+    let data = std::thread::spawn(move || {
+        let mut reader = reader;
+        reader.read_channel_data::<i32>("G", "C").unwrap()
+    }).join().unwrap();
+    assert_eq!(data, vec![1, 2, 3]);
+
+    std::fs::remove_file(path).ok();
+    std::fs::remove_file("test_output/thread_move.tdms_index").ok();
+}
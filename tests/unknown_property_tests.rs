@@ -0,0 +1,137 @@
+// tests/unknown_property_tests.rs
+//! Files with properties whose data type the reader can't decode.
+use tdms_rs::*;
+
+const TOC_METADATA: u32 = 1 << 1;
+const TOC_NEW_OBJ_LIST: u32 = 1 << 2;
+const TOC_RAW_DATA: u32 = 1 << 3;
+
+fn put_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn segment(toc: u32, meta: Vec<u8>, raw: Vec<u8>) -> Vec<u8> {
+    let mut segment = Vec::new();
+    segment.extend_from_slice(b"TDSm");
+    segment.extend_from_slice(&toc.to_le_bytes());
+    segment.extend_from_slice(&4713u32.to_le_bytes());
+    segment.extend_from_slice(&((meta.len() + raw.len()) as u64).to_le_bytes());
+    segment.extend_from_slice(&(meta.len() as u64).to_le_bytes());
+    segment.extend(meta);
+    segment.extend(raw);
+    segment
+}
+
+/// A segment with one f64 channel whose properties are `unit` (a string),
+/// then a property of type `type_code` with `value_bytes`, then `gain` (an f64)
+fn segment_with_property(values: &[f64], type_code: u32, value_bytes: &[u8]) -> Vec<u8> {
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    put_string(&mut meta, "/'Data'/'Temp'");
+    meta.extend_from_slice(&20u32.to_le_bytes());
+    meta.extend_from_slice(&(DataType::DoubleFloat as u32).to_le_bytes());
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    meta.extend_from_slice(&(values.len() as u64).to_le_bytes());
+    meta.extend_from_slice(&3u32.to_le_bytes());
+    put_string(&mut meta, "unit");
+    meta.extend_from_slice(&(DataType::String as u32).to_le_bytes());
+    put_string(&mut meta, "degC");
+    put_string(&mut meta, "exotic");
+    meta.extend_from_slice(&type_code.to_le_bytes());
+    meta.extend_from_slice(value_bytes);
+    put_string(&mut meta, "gain");
+    meta.extend_from_slice(&(DataType::DoubleFloat as u32).to_le_bytes());
+    meta.extend_from_slice(&2.5f64.to_le_bytes());
+    let raw = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    segment(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA, meta, raw)
+}
+
+fn plain_segment(values: &[f64]) -> Vec<u8> {
+    let mut meta = Vec::new();
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    put_string(&mut meta, "/'Data'/'Temp'");
+    meta.extend_from_slice(&20u32.to_le_bytes());
+    meta.extend_from_slice(&(DataType::DoubleFloat as u32).to_le_bytes());
+    meta.extend_from_slice(&1u32.to_le_bytes());
+    meta.extend_from_slice(&(values.len() as u64).to_le_bytes());
+    meta.extend_from_slice(&0u32.to_le_bytes());
+    let raw = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    segment(TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA, meta, raw)
+}
+
+fn skip_options() -> ReaderOptions {
+    ReaderOptions { unknown_properties: UnknownPropertyPolicy::Skip, ..Default::default() }
+}
+
+#[test]
+fn test_sized_unsupported_property_is_skipped() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/unknown_property_sized.tdms";
+    // A complex double property: known size, no PropertyValue to hold it
+    let complex = [0u8; 16];
+    std::fs::write(path, segment_with_property(&[1.0, 2.0], DataType::ComplexDoubleFloat as u32, &complex)).unwrap();
+
+    assert!(TdmsReader::open(path).is_err());
+
+    let mut reader = TdmsReader::open_with_options(path, skip_options()).unwrap();
+    assert_eq!(reader.warnings().len(), 1);
+    assert!(reader.warnings()[0].message.contains("exotic"));
+    let properties = reader.get_channel_properties("Data", "Temp").unwrap();
+    assert_eq!(properties["unit"].value, PropertyValue::String("degC".into()));
+    assert_eq!(properties["gain"].value, PropertyValue::Double(2.5));
+    assert!(!properties.contains_key("exotic"));
+    let raw = &reader.raw_properties()[0];
+    assert_eq!((raw.name.as_str(), raw.data_type, raw.bytes.as_slice()), ("exotic", DataType::ComplexDoubleFloat, &complex[..]));
+    assert_eq!(reader.read_channel_data::<f64>("Data", "Temp").unwrap(), vec![1.0, 2.0]);
+
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn test_unknown_property_type_skips_rest_of_segment() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/unknown_property_code.tdms";
+    let mut bytes = plain_segment(&[1.0]);
+    bytes.extend(segment_with_property(&[2.0, 3.0], 0x1234, &[0xAB; 12]));
+    bytes.extend(plain_segment(&[4.0]));
+    std::fs::write(path, bytes).unwrap();
+
+    assert!(matches!(TdmsReader::open(path), Err(TdmsError::InvalidDataType(0x1234))));
+
+    let mut reader = TdmsReader::open_with_options(path, skip_options()).unwrap();
+    assert_eq!(reader.warnings().len(), 1);
+    assert_eq!(reader.warnings()[0].segment, 1);
+    assert!(reader.warnings()[0].message.contains("0x1234"));
+    assert_eq!(reader.read_channel_data::<f64>("Data", "Temp").unwrap(), vec![1.0, 4.0]);
+
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn test_undecodable_string_property_keeps_raw_bytes() {
+    std::fs::create_dir_all("test_output").unwrap();
+    let path = "test_output/unknown_property_string.tdms";
+    let text = b"\xB0C \xFF";
+    let mut value = (text.len() as u32).to_le_bytes().to_vec();
+    value.extend_from_slice(text);
+    std::fs::write(path, segment_with_property(&[1.0], DataType::String as u32, &value)).unwrap();
+
+    assert!(matches!(TdmsReader::open(path), Err(TdmsError::InvalidUtf8)));
+
+    let reader = TdmsReader::open_with_options(path, skip_options()).unwrap();
+    assert_eq!(reader.warnings().len(), 1);
+    let properties = reader.get_channel_properties("Data", "Temp").unwrap();
+    assert!(!properties.contains_key("exotic"));
+    assert_eq!(properties["gain"].value, PropertyValue::Double(2.5));
+    assert_eq!(reader.raw_properties(), [RawProperty {
+        path: ObjectPath::Channel { group: "Data".into(), channel: "Temp".into() },
+        name: "exotic".into(),
+        data_type: DataType::String,
+        bytes: text.to_vec(),
+        is_big_endian: false,
+        segment: 0,
+    }]);
+
+    std::fs::remove_file(path).ok();
+}
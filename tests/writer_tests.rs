@@ -156,4 +156,1340 @@ fn test_new_obj_list_on_channel_add() {
     }
     
     cleanup_test_file(&path);
-}
\ No newline at end of file
+}
+#[test]
+fn test_discard_pending() {
+    let path = setup_test_file("discard_pending.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Data", "Values", DataType::F64).unwrap();
+        writer.create_channel("Data", "Labels", DataType::String).unwrap();
+
+        writer.write_channel_data("Data", "Values", &[1.0f64, 2.0, 3.0]).unwrap();
+        writer.write_segment().unwrap();
+        assert_eq!(writer.pending_samples("Data", "Values").unwrap(), 0);
+
+        // Aborted step: buffered but never committed
+        writer.write_channel_data("Data", "Values", &[99.0f64; 50]).unwrap();
+        writer.write_channel_strings("Data", "Labels", &["aborted"]).unwrap();
+        assert_eq!(writer.pending_samples("Data", "Values").unwrap(), 50);
+        assert_eq!(writer.pending_samples("Data", "Labels").unwrap(), 1);
+        assert!(writer.pending_samples("Data", "Missing").is_err());
+
+        writer.discard_pending();
+        assert_eq!(writer.pending_samples("Data", "Values").unwrap(), 0);
+        assert_eq!(writer.pending_samples("Data", "Labels").unwrap(), 0);
+
+        writer.write_channel_data("Data", "Values", &[4.0f64, 5.0]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    {
+        let mut reader = TdmsReader::open(&path).unwrap();
+        let data: Vec<f64> = reader.read_channel_data("Data", "Values").unwrap();
+        assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert!(reader.read_channel_strings("Data", "Labels").is_err());
+    }
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_transactional_durability_matches_buffered() {
+    let buffered = setup_test_file("durability_buffered.tdms");
+    let transactional = setup_test_file("durability_transactional.tdms");
+
+    for (path, mode) in [(&buffered, DurabilityMode::Buffered), (&transactional, DurabilityMode::Transactional)] {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_durability_mode(mode);
+        assert_eq!(writer.durability_mode(), mode);
+        writer.create_channel("Data", "Values", DataType::I32).unwrap();
+
+        // Same-shaped writes exercise the raw-data-append path
+        for i in 0..4 {
+            writer.write_channel_data("Data", "Values", &[i; 10]).unwrap();
+            writer.flush().unwrap();
+        }
+    }
+
+    // The commit ordering must not change the bytes on disk
+    assert_eq!(std::fs::read(&buffered).unwrap(), std::fs::read(&transactional).unwrap());
+
+    let mut reader = TdmsReader::open(&transactional).unwrap();
+    let data: Vec<i32> = reader.read_channel_data("Data", "Values").unwrap();
+    assert_eq!(data.len(), 40);
+    assert_eq!(data[39], 3);
+
+    cleanup_test_file(&buffered);
+    cleanup_test_file(&transactional);
+}
+
+#[test]
+fn test_segment_journal() {
+    let path = setup_test_file("journaled.tdms");
+    let journal_path = "test_output/journaled.tdms_journal";
+    std::fs::remove_file(journal_path).ok();
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.enable_journal(journal_path).unwrap();
+        writer.create_channel("Data", "Values", DataType::F64).unwrap();
+
+        writer.write_channel_data("Data", "Values", &[1.0f64; 8]).unwrap();
+        writer.flush().unwrap();
+        // Same layout: appended to the previous segment
+        writer.write_channel_data("Data", "Values", &[2.0f64; 8]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let journal = std::fs::read_to_string(journal_path).unwrap();
+    let lines: Vec<&str> = journal.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("{\"event\":\"segment\""));
+    assert!(lines[0].contains("\"offset\":0"));
+    assert!(lines[0].contains("\"channels\":[\"/'Data'/'Values'\"]"));
+    assert!(lines[1].starts_with("{\"event\":\"append\""));
+
+    // Final lead-in size from the journal matches the file on disk
+    let file_len = std::fs::metadata(&path).unwrap().len();
+    let expected = format!("\"next_segment_offset\":{}", file_len - 28);
+    assert!(lines[1].contains(&expected), "{}", lines[1]);
+
+    cleanup_test_file(&path);
+    std::fs::remove_file(journal_path).ok();
+}
+
+#[test]
+fn test_deterministic_output_is_byte_identical() {
+    let paths = [setup_test_file("deterministic_a.tdms"), setup_test_file("deterministic_b.tdms")];
+
+    for path in &paths {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_deterministic(true);
+        for i in 0..16 {
+            writer.set_file_property(format!("file_prop_{}", i), PropertyValue::I32(i));
+        }
+        for g in 0..8 {
+            let group = format!("Group{}", g);
+            writer.set_group_property(group.clone(), "index", PropertyValue::I32(g));
+            for c in 0..4 {
+                let channel = format!("Channel{}", c);
+                writer.create_channel(group.clone(), channel.clone(), DataType::F64).unwrap();
+                for p in 0..8 {
+                    writer.set_channel_property(&group, &channel, format!("p{}", p), PropertyValue::Double(p as f64)).unwrap();
+                }
+                writer.write_channel_data(&group, &channel, &[c as f64; 16]).unwrap();
+            }
+        }
+        writer.flush().unwrap();
+
+        // Incremental segment with property changes only
+        for g in 0..8 {
+            writer.set_group_property(format!("Group{}", g), "pass", PropertyValue::I32(2));
+        }
+        writer.flush().unwrap();
+    }
+
+    assert_eq!(std::fs::read(&paths[0]).unwrap(), std::fs::read(&paths[1]).unwrap());
+    assert_eq!(
+        std::fs::read(format!("{}_index", paths[0])).unwrap(),
+        std::fs::read(format!("{}_index", paths[1])).unwrap()
+    );
+
+    for path in &paths {
+        cleanup_test_file(path);
+    }
+}
+
+#[test]
+fn test_deterministic_output_ignores_timing_and_write_sizes() {
+    use std::time::Duration;
+
+    let paths = [setup_test_file("deterministic_chunks_a.tdms"), setup_test_file("deterministic_chunks_b.tdms")];
+    let values: Vec<i32> = (0..64).collect();
+
+    for (path, chunk) in paths.iter().zip([64, 5]) {
+        let mut writer = TdmsWriter::create(path).unwrap();
+        writer.set_deterministic(true);
+        writer.set_heartbeat(Some(Duration::from_millis(1)));
+        writer.set_flush_window(Some(FlushWindow { max_bytes: 128, max_age: Duration::from_millis(1) }));
+        writer.create_channel("Group", "Channel", DataType::I32).unwrap();
+        writer.set_expected_sample_rate("Group", "Channel", 1000.0).unwrap();
+        for block in values.chunks(chunk) {
+            writer.write_channel_data("Group", "Channel", block).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(5));
+        writer.flush().unwrap();
+        writer.record_sample_accounting("Group", "Channel").unwrap();
+        writer.close().unwrap();
+    }
+
+    assert_eq!(std::fs::read(&paths[0]).unwrap(), std::fs::read(&paths[1]).unwrap());
+    for path in &paths {
+        cleanup_test_file(path);
+    }
+}
+
+#[test]
+fn test_injected_clock() {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let path = setup_test_file("manual_clock.tdms");
+    let journal_path = "test_output/manual_clock.tdms_journal";
+    std::fs::remove_file(journal_path).ok();
+
+    // 2020-01-01T00:00:00Z in TDMS epoch seconds
+    let start = Timestamp { seconds: 1_577_836_800 + 2_082_844_800, fractions: 0 };
+    let clock = Arc::new(ManualClock::new(start));
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.set_clock(clock.clone());
+        writer.enable_journal(journal_path).unwrap();
+        assert_eq!(writer.now(), start);
+
+        clock.advance(Duration::from_secs(60));
+        writer.set_file_property("start_time", PropertyValue::Timestamp(writer.now()));
+        writer.create_channel("Data", "Values", DataType::U8).unwrap();
+        writer.write_channel_data("Data", "Values", &[1u8, 2, 3]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let reader = TdmsReader::open(&path).unwrap();
+    match &reader.get_file_properties()["start_time"].value {
+        PropertyValue::Timestamp(ts) => assert_eq!(ts.seconds, start.seconds + 60),
+        other => panic!("unexpected property {:?}", other),
+    }
+
+    let journal = std::fs::read_to_string(journal_path).unwrap();
+    assert!(journal.contains("\"unix_time\":1577836860.000000"), "{}", journal);
+
+    cleanup_test_file(&path);
+    std::fs::remove_file(journal_path).ok();
+}
+
+#[test]
+fn test_close_and_into_inner() {
+    let path = setup_test_file("close_into_inner.tdms");
+
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.create_channel("Data", "Values", DataType::I32).unwrap();
+    writer.write_channel_data("Data", "Values", &[1i32, 2, 3]).unwrap();
+    writer.close().unwrap();
+
+    let reader = TdmsReader::open(&path).unwrap();
+    let file = reader.into_inner().into_inner();
+    let len = file.metadata().unwrap().len();
+    drop(file);
+
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.create_channel("Data", "Values", DataType::I32).unwrap();
+    writer.write_channel_data("Data", "Values", &[1i32, 2, 3]).unwrap();
+    let file = writer.into_inner().unwrap();
+    assert_eq!(file.metadata().unwrap().len(), len);
+    drop(file);
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let values: Vec<i32> = reader.read_channel_data("Data", "Values").unwrap();
+    assert_eq!(values, vec![1, 2, 3]);
+    reader.close();
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_name_policy() {
+    let path = setup_test_file("name_policy.tdms");
+
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    assert_eq!(writer.name_policy(), NamePolicy::Allow);
+    writer.set_name_policy(NamePolicy::Reject);
+    assert!(matches!(
+        writer.create_channel("Data", "bad\0name", DataType::I32),
+        Err(TdmsError::InvalidName { .. })
+    ));
+    assert!(writer.create_channel("", "Values", DataType::I32).is_err());
+
+    // Quotes and slashes are escaped in object paths, so they are fine
+    writer.create_channel("Rig/Bay 1", "Operator's note", DataType::I32).unwrap();
+    writer.write_channel_data("Rig/Bay 1", "Operator's note", &[1i32]).unwrap();
+
+    // Infallible setters report the rejected name on the next flush
+    writer.set_file_property("line\nbreak", PropertyValue::I32(1));
+    assert!(matches!(writer.flush(), Err(TdmsError::InvalidName { .. })));
+    writer.flush().unwrap();
+
+    writer.set_name_policy(NamePolicy::Sanitize);
+    writer.create_channel("Data", "tab\there", DataType::I32).unwrap();
+    writer.write_channel_data("Data", "tab\there", &[2i32, 3]).unwrap();
+    writer.set_channel_property("Data", "tab\there", "", PropertyValue::I32(7)).unwrap();
+    writer.close().unwrap();
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert!(reader.get_file_properties().keys().all(|name| name.starts_with("library")));
+    let values: Vec<i32> = reader.read_channel_data("Data", "tab_here").unwrap();
+    assert_eq!(values, vec![2, 3]);
+    let props = reader.get_channel_properties("Data", "tab_here").unwrap();
+    assert_eq!(props["Untitled"].value, PropertyValue::I32(7));
+    let values: Vec<i32> = reader.read_channel_data("Rig/Bay 1", "Operator's note").unwrap();
+    assert_eq!(values, vec![1]);
+    drop(reader);
+
+    // Dropping the writer leaves a rejected name out but still writes the data
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.set_name_policy(NamePolicy::Reject);
+        writer.create_channel("Data", "Values", DataType::I32).unwrap();
+        writer.write_channel_data("Data", "Values", &[4i32]).unwrap();
+        writer.set_file_property("line\nbreak", PropertyValue::I32(1));
+    }
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.read_channel_data::<i32>("Data", "Values").unwrap(), vec![4]);
+    drop(reader);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_unchanged_properties_are_coalesced() {
+    let path = setup_test_file("property_coalescing.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.set_file_property("title", PropertyValue::String("Run".into()));
+        writer.set_group_property("Data", "rig", PropertyValue::I32(1));
+        writer.create_channel("Data", "Values", DataType::I32).unwrap();
+        writer.flush().unwrap();
+        let size = writer.file_size().unwrap();
+
+        // Same values again: nothing to write
+        writer.set_file_property("title", PropertyValue::String("Run".into()));
+        writer.set_group_property("Data", "rig", PropertyValue::I32(1));
+        writer.set_channel_property("Data", "Values", "unit_string", PropertyValue::String("V".into())).unwrap();
+        writer.flush().unwrap();
+        let size_after_channel_property = writer.file_size().unwrap();
+        assert!(size_after_channel_property > size);
+
+        writer.set_file_property("title", PropertyValue::String("Run".into()));
+        writer.set_group_property("Data", "rig", PropertyValue::I32(1));
+        writer.flush().unwrap();
+        assert_eq!(writer.file_size().unwrap(), size_after_channel_property);
+
+        // A changed value or disabled coalescing writes a new segment
+        writer.set_group_property("Data", "rig", PropertyValue::I32(2));
+        writer.flush().unwrap();
+        let size = writer.file_size().unwrap();
+        assert!(size > size_after_channel_property);
+
+        writer.set_property_coalescing(false);
+        writer.set_file_property("title", PropertyValue::String("Run".into()));
+        writer.flush().unwrap();
+        assert!(writer.file_size().unwrap() > size);
+    }
+
+    let reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.segment_count(), 4);
+    assert_eq!(reader.get_group_properties("Data").unwrap()["rig"].value, PropertyValue::I32(2));
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_explain_next_segment() {
+    let path = setup_test_file("explain_next_segment.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Data", "A", DataType::I32).unwrap();
+        writer.create_channel("Data", "B", DataType::I32).unwrap();
+
+        writer.write_channel_data("Data", "A", &[1i32, 2]).unwrap();
+        let plan = writer.explain_next_segment();
+        assert_eq!(plan.action, SegmentAction::NewSegment);
+        assert!(plan.reasons.contains(&SegmentReason::FirstSegment));
+        writer.flush().unwrap();
+        assert_eq!(writer.explain_next_segment().action, SegmentAction::Nothing);
+
+        writer.write_channel_data("Data", "A", &[3i32, 4]).unwrap();
+        let plan = writer.explain_next_segment();
+        assert_eq!(plan.action, SegmentAction::Append);
+        assert_eq!(plan.to_string(), "append raw data for 1 channel(s)");
+        writer.flush().unwrap();
+
+        writer.write_channel_data("Data", "A", &[5i32]).unwrap();
+        writer.write_channel_data("Data", "B", &[5i32]).unwrap();
+        writer.set_group_property("Data", "rig", PropertyValue::I32(1));
+        let plan = writer.explain_next_segment();
+        let a = ObjectPath::Channel { group: "Data".into(), channel: "A".into() };
+        let b = ObjectPath::Channel { group: "Data".into(), channel: "B".into() };
+        assert_eq!(plan.channels, vec![a.clone(), b.clone()]);
+        assert_eq!(plan.reasons, vec![
+            SegmentReason::GroupPropertiesChanged("Data".into()),
+            SegmentReason::ChannelListChanged,
+            SegmentReason::IndexChanged(a),
+            SegmentReason::IndexChanged(b),
+        ]);
+        writer.flush().unwrap();
+
+        writer.set_channel_property("Data", "A", "unit_string", PropertyValue::String("V".into())).unwrap();
+        writer.flush().unwrap();
+        writer.write_channel_data("Data", "A", &[6i32]).unwrap();
+        writer.write_channel_data("Data", "B", &[6i32]).unwrap();
+        assert_eq!(writer.explain_next_segment().reasons, vec![SegmentReason::PreviousSegmentHasNoData]);
+    }
+
+    let reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.segment_count(), 4);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_flush_window_coalesces_commits() {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let path = setup_test_file("flush_window.tdms");
+    let clock = Arc::new(ManualClock::new(Timestamp::now()));
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.set_clock(clock.clone());
+        writer.set_flush_window(Some(FlushWindow { max_age: Duration::from_secs(1), max_bytes: 64 }));
+        writer.create_channel("Data", "A", DataType::I32).unwrap();
+        writer.create_channel("Data", "B", DataType::I32).unwrap();
+
+        // Chatty flushes with changing value counts would each need a new segment
+        for i in 0..5 {
+            writer.write_channel_data("Data", "A", &[i]).unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(writer.pending_samples("Data", "A").unwrap(), 5);
+
+        // The byte limit closes the window
+        writer.write_channel_data("Data", "B", &[0i32; 16]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.pending_samples("Data", "A").unwrap(), 0);
+
+        // So does its age
+        writer.write_channel_data("Data", "A", &[5]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.pending_samples("Data", "A").unwrap(), 1);
+        clock.advance(Duration::from_secs(2));
+        writer.flush().unwrap();
+        assert_eq!(writer.pending_samples("Data", "A").unwrap(), 0);
+
+        // Dropping commits whatever is still held back
+        writer.write_channel_data("Data", "A", &[6, 7]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.segment_count(), 3);
+    let values: Vec<i32> = reader.read_channel_data("Data", "A").unwrap();
+    assert_eq!(values, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_max_segment_size_splits_writes() {
+    let path = setup_test_file("max_segment_size.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.set_max_segment_size(Some(3000));
+        writer.create_channel("Data", "Wide", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Data", "Narrow", DataType::U8).unwrap();
+
+        // 8000 + 1000 bytes go out as four parts of 250 values (2250 bytes);
+        // three parts would be 3006 bytes each
+        let wide: Vec<f64> = (0..1000).map(|i| i as f64).collect();
+        let narrow: Vec<u8> = (0..1000).map(|i| (i % 256) as u8).collect();
+        writer.write_channel_data("Data", "Wide", &wide).unwrap();
+        writer.write_channel_data("Data", "Narrow", &narrow).unwrap();
+        writer.flush().unwrap();
+
+        // Same index as the last part, but appending would exceed the limit
+        writer.write_channel_data("Data", "Wide", &[1000.0f64; 250]).unwrap();
+        writer.write_channel_data("Data", "Narrow", &[0u8; 250]).unwrap();
+        let plan = writer.explain_next_segment();
+        assert_eq!(plan.reasons, vec![SegmentReason::SegmentSizeLimit]);
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.segment_count(), 5);
+    assert!(reader.segments().iter().all(|s| s.total_raw_data_size <= 3000));
+    let wide: Vec<f64> = reader.read_channel_data("Data", "Wide").unwrap();
+    assert_eq!(wide.len(), 1250);
+    assert_eq!(wide[999], 999.0);
+    let narrow: Vec<u8> = reader.read_channel_data("Data", "Narrow").unwrap();
+    assert_eq!(narrow.len(), 1250);
+    assert_eq!(narrow[300], 44);
+
+    let report = tdms_rs::conformance::check_conformance(&path).unwrap();
+    assert!(report.is_conformant(), "{}", report);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_late_channel_is_padded_on_read() {
+    let path = setup_test_file("late_channel.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[1.0f64, 2.0, 3.0]).unwrap();
+        writer.flush().unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[4.0f64]).unwrap();
+
+        let start = writer.values_written("Rack", "Pressure").unwrap();
+        assert_eq!(start, 4);
+        writer.create_late_channel("Rack", "Temperature", DataType::DoubleFloat, start).unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[5.0f64, 6.0]).unwrap();
+        writer.write_channel_data("Rack", "Temperature", &[20.0f64, 21.0]).unwrap();
+        writer.flush().unwrap();
+
+        // Discarded values don't count
+        writer.write_channel_data("Rack", "Pressure", &[7.0f64]).unwrap();
+        writer.discard_pending();
+        assert_eq!(writer.values_written("Rack", "Pressure").unwrap(), 6);
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.channel_start_index("Rack", "Pressure"), 0);
+    assert_eq!(reader.channel_start_index("Rack", "Temperature"), 4);
+    let pressure: Vec<f64> = reader.read_channel_data("Rack", "Pressure").unwrap();
+    let temperature = reader.read_channel_data_padded("Rack", "Temperature", f64::NAN).unwrap();
+    assert_eq!(pressure.len(), temperature.len());
+    assert!(temperature[..4].iter().all(|v| v.is_nan()));
+    assert_eq!(&temperature[4..], &[20.0, 21.0]);
+
+    let report = tdms_rs::conformance::check_conformance(&path).unwrap();
+    assert!(report.is_conformant(), "{}", report);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_write_checks_value_type() {
+    let path = setup_test_file("write_type_check.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Data", "Counts", DataType::I32).unwrap();
+
+        // Same size, different type
+        assert!(matches!(
+            writer.write_channel_data("Data", "Counts", &[1u32, 2]),
+            Err(TdmsError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            writer.write_channel_data("Data", "Counts", &[1.0f32]),
+            Err(TdmsError::TypeMismatch { .. })
+        ));
+        assert_eq!(writer.pending_samples("Data", "Counts").unwrap(), 0);
+
+        writer.write_channel_data("Data", "Counts", &[-1i32]).unwrap();
+        // The escape hatch only checks the size
+        writer.write_channel_data_unchecked("Data", "Counts", &[u32::MAX]).unwrap();
+        assert!(writer.write_channel_data_unchecked("Data", "Counts", &[0u16]).is_err());
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let counts: Vec<i32> = reader.read_channel_data("Data", "Counts").unwrap();
+    assert_eq!(counts, vec![-1, -1]);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_index_stats() {
+    let path = setup_test_file("index_stats.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Data", "Fast", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Data", "Slow", DataType::I32).unwrap();
+        for i in 0..10 {
+            writer.write_channel_data("Data", "Fast", &vec![i as f64; 100]).unwrap();
+            if i % 5 == 0 {
+                writer.write_channel_data("Data", "Slow", &[i]).unwrap();
+            }
+            writer.flush().unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(&path).unwrap();
+    let stats = reader.index_stats().unwrap();
+    assert_eq!(stats.segment_count, reader.segment_count());
+    assert_eq!(stats.total_bytes(), std::fs::metadata(&path).unwrap().len());
+    assert_eq!(stats.raw_data_bytes, 10 * 800 + 2 * 4);
+    assert!(stats.metadata_overhead_percent() > 0.0 && stats.metadata_overhead_percent() < 50.0);
+
+    let fast = stats.channel("Data", "Fast").unwrap();
+    // Unchanged flushes are appended as further chunks of the previous segment
+    assert_eq!(stats.segment_count, 4);
+    assert_eq!((fast.segment_count, fast.chunk_count, fast.total_values), (4, 10, 1000));
+    assert_eq!((fast.min_chunk_values, fast.max_chunk_values), (100, 100));
+    assert_eq!(fast.chunk_histogram, vec![(64, 10)]);
+    assert_eq!(fast.mean_chunk_values(), 100.0);
+    let slow = stats.channel("Data", "Slow").unwrap();
+    assert_eq!((slow.segment_count, slow.chunk_count, slow.total_values), (2, 2, 2));
+    assert_eq!(slow.chunk_histogram, vec![(1, 2)]);
+    assert_eq!(stats.channels.len(), 2);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_metadata_overhead() {
+    let path = setup_test_file("metadata_overhead.tdms");
+    let defragmented = setup_test_file("metadata_overhead_defragmented.tdms");
+    let overhead;
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        let names: Vec<String> = (0..50).map(|i| format!("a_rather_long_channel_name_{i:03}")).collect();
+        for name in &names {
+            writer.create_channel("Measurements", name, DataType::I32).unwrap();
+        }
+        for i in 0..5 {
+            // A changing channel list makes every flush write a full object list
+            for name in names.iter().skip(i) {
+                writer.write_channel_data("Measurements", name, &[i as i32; 10]).unwrap();
+            }
+            writer.flush().unwrap();
+        }
+        overhead = writer.metadata_overhead();
+        writer.close().unwrap();
+    }
+
+    assert_eq!(overhead.segments, 5);
+    assert_eq!(overhead.lead_in_bytes, 5 * 28);
+    assert_eq!(overhead.total_bytes(), std::fs::metadata(&path).unwrap().len());
+    assert!(overhead.path_bytes > 0 && overhead.path_bytes < overhead.metadata_bytes);
+    assert!(overhead.path_percent() > 50.0);
+
+    let stats = TdmsReader::open(&path).unwrap().index_stats().unwrap();
+    assert_eq!(stats.metadata_bytes, overhead.metadata_bytes);
+    assert_eq!(stats.raw_data_bytes, overhead.raw_data_bytes);
+
+    defragment(&path, &defragmented).unwrap();
+    let compacted = TdmsReader::open(&defragmented).unwrap().index_stats().unwrap();
+    assert!(compacted.metadata_bytes < overhead.metadata_bytes);
+    assert_eq!(compacted.raw_data_bytes, overhead.raw_data_bytes);
+
+    cleanup_test_file(&path);
+    cleanup_test_file(&defragmented);
+}
+
+#[test]
+fn test_stale_index_file_is_detected_and_rewritten() {
+    let path = setup_test_file("stale_index.tdms");
+    let index_path = index_path_for(&path);
+    let stale;
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        writer.write_channel_data("Group", "Values", &[1, 2, 3]).unwrap();
+        writer.flush().unwrap();
+        stale = std::fs::read(&index_path).unwrap();
+        writer.set_channel_property("Group", "Values", "unit", PropertyValue::String("V".into())).unwrap();
+        writer.write_channel_data("Group", "Values", &[4, 5]).unwrap();
+        writer.close().unwrap();
+    }
+    let good = std::fs::read(&index_path).unwrap();
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.check_index_file(&index_path).unwrap(), IndexFileStatus::Consistent);
+
+    // The index missed the last segment
+    std::fs::write(&index_path, &stale).unwrap();
+    assert!(matches!(reader.check_index_file(&index_path).unwrap(), IndexFileStatus::Mismatch { segment: 1, .. }));
+
+    // The index has damaged metadata
+    let mut damaged = good.clone();
+    *damaged.last_mut().unwrap() ^= 0xFF;
+    std::fs::write(&index_path, &damaged).unwrap();
+    assert!(matches!(reader.check_index_file(&index_path).unwrap(), IndexFileStatus::Mismatch { segment: 1, .. }));
+
+    // Reads come from the data file regardless
+    assert_eq!(reader.read_channel_data::<i32>("Group", "Values").unwrap(), vec![1, 2, 3, 4, 5]);
+
+    reader.rewrite_index_file(&index_path).unwrap();
+    assert_eq!(std::fs::read(&index_path).unwrap(), good);
+    assert_eq!(reader.check_index_file(&index_path).unwrap(), IndexFileStatus::Consistent);
+
+    std::fs::remove_file(&index_path).unwrap();
+    assert_eq!(reader.check_index_file(&index_path).unwrap(), IndexFileStatus::Missing);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_open_indexed() {
+    let path = setup_test_file("open_indexed.tdms");
+    let index_path = index_path_for(&path);
+    let stale;
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        writer.create_channel("Group", "Labels", DataType::String).unwrap();
+        writer.write_channel_data("Group", "Values", &[1, 2, 3]).unwrap();
+        writer.write_channel_strings("Group", "Labels", &["a"]).unwrap();
+        writer.flush().unwrap();
+        stale = std::fs::read(&index_path).unwrap();
+        writer.set_channel_property("Group", "Values", "unit", PropertyValue::String("V".into())).unwrap();
+        writer.write_channel_data("Group", "Values", &[4, 5]).unwrap();
+        writer.write_channel_strings("Group", "Labels", &["b", "c"]).unwrap();
+        writer.close().unwrap();
+    }
+    let good = std::fs::read(&index_path).unwrap();
+
+    let mut parsed = TdmsReader::open(&path).unwrap();
+    let mut indexed = TdmsReader::open_indexed(&path).unwrap();
+    assert!(indexed.opened_from_index());
+    assert!(!parsed.opened_from_index());
+    let layout = |reader: &TdmsReader<_>| -> Vec<(u64, u64, u64)> {
+        reader.segments().iter().map(|s| (s.offset, s.metadata_size, s.total_raw_data_size)).collect()
+    };
+    assert_eq!(layout(&indexed), layout(&parsed));
+    assert_eq!(indexed.read_channel_data::<i32>("Group", "Values").unwrap(), [1, 2, 3, 4, 5]);
+    assert_eq!(indexed.read_channel_strings("Group", "Labels").unwrap(), ["a", "b", "c"]);
+    assert_eq!(indexed.get_channel_properties("Group", "Values").unwrap()["unit"].value, PropertyValue::String("V".into()));
+    assert_eq!(indexed.check_index_file(&index_path).unwrap(), IndexFileStatus::Consistent);
+    assert_eq!(parsed.read_channel_data::<i32>("Group", "Values").unwrap(), [1, 2, 3, 4, 5]);
+
+    // An index that missed the last segment, a damaged one and a missing one
+    // all fall back to the data file
+    let mut damaged = good.clone();
+    damaged[0] = b'X';
+    for index in [&stale, &damaged] {
+        std::fs::write(&index_path, index).unwrap();
+        let mut reader = TdmsReader::open_indexed(&path).unwrap();
+        assert!(!reader.opened_from_index());
+        assert_eq!(reader.read_channel_data::<i32>("Group", "Values").unwrap(), [1, 2, 3, 4, 5]);
+    }
+    std::fs::remove_file(&index_path).unwrap();
+    let mut reader = TdmsReader::open_indexed(&path).unwrap();
+    assert!(!reader.opened_from_index());
+    assert_eq!(reader.read_channel_strings("Group", "Labels").unwrap(), ["a", "b", "c"]);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_write_index_file() {
+    let path = setup_test_file("write_index_file.tdms");
+    let index_path = index_path_for(&path);
+    {
+        let mut writer = TdmsWriter::create_with_endianness(&path, Endianness::Big).unwrap();
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        writer.write_channel_data("Group", "Values", &[1, 2, 3]).unwrap();
+        writer.flush().unwrap();
+        writer.set_channel_property("Group", "Values", "unit", PropertyValue::String("V".into())).unwrap();
+        writer.write_channel_data("Group", "Values", &[4, 5]).unwrap();
+        writer.close().unwrap();
+    }
+    let written = std::fs::read(&index_path).unwrap();
+    std::fs::remove_file(&index_path).unwrap();
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(write_index_file(&path, &index_path).unwrap(), reader.segment_count());
+    assert_eq!(std::fs::read(&index_path).unwrap(), written);
+    assert_eq!(reader.check_index_file(&index_path).unwrap(), IndexFileStatus::Consistent);
+
+    let mut indexed = TdmsReader::open_indexed(&path).unwrap();
+    assert!(indexed.opened_from_index());
+    assert_eq!(indexed.read_channel_data::<i32>("Group", "Values").unwrap(), [1, 2, 3, 4, 5]);
+
+    // Not a TDMS file
+    std::fs::write(&path, b"not a tdms file at all, just some text").unwrap();
+    assert!(matches!(write_index_file(&path, &index_path), Err(TdmsError::InvalidTag { .. })));
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_derived_channels() {
+    use tdms_rs::writer::derived::{LinearScale, WindowRms, DERIVED_FROM_PROPERTY};
+
+    let path = setup_test_file("derived_channels.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Rack", "Raw", DataType::I16).unwrap();
+        writer.create_encoded_channel("Rack", "Counter", DataType::U32, ChannelEncoding::DeltaVarint).unwrap();
+        writer.create_channel("Rack", "Label", DataType::String).unwrap();
+        writer.create_derived_channel("Rack", "Volts", "Rack", "Raw", LinearScale::new(0.5, 1.0)).unwrap();
+        writer.create_derived_channel("Rack", "RMS", "Rack", "Raw", WindowRms::new(4)).unwrap();
+        // Derived from a derived channel, and from an encoded one
+        writer.create_derived_channel("Rack", "Volts max", "Rack", "Volts", |input: &[f64], output: &mut Vec<f64>| {
+            output.extend(input.iter().copied().reduce(f64::max));
+        }).unwrap();
+        writer.create_derived_channel("Rack", "Counter x2", "Rack", "Counter", LinearScale::new(2.0, 0.0)).unwrap();
+
+        assert!(matches!(writer.create_derived_channel("Rack", "Bad", "Rack", "Label", LinearScale::new(1.0, 0.0)),
+            Err(TdmsError::Unsupported(_))));
+        assert!(matches!(writer.create_derived_channel("Rack", "Bad", "Rack", "Missing", LinearScale::new(1.0, 0.0)),
+            Err(TdmsError::ChannelNotFound(_))));
+        assert!(matches!(writer.create_derived_channel("Rack", "Raw", "Rack", "Volts", LinearScale::new(1.0, 0.0)),
+            Err(TdmsError::Unsupported(_))));
+
+        writer.write_channel_data("Rack", "Raw", &[2i16, -2, 2, -2, 6, 6]).unwrap();
+        writer.write_channel_data("Rack", "Counter", &[1u32, 2]).unwrap();
+        writer.flush().unwrap();
+        writer.write_channel_data("Rack", "Raw", &[-6i16, -6, 10]).unwrap();
+        writer.flush().unwrap();
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "Volts").unwrap(), [2.0, 0.0, 2.0, 0.0, 4.0, 4.0, -2.0, -2.0, 6.0]);
+    // The second window spans both flushes; the last one is incomplete
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "RMS").unwrap(), [2.0, 6.0]);
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "Volts max").unwrap(), [4.0, 6.0]);
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "Counter x2").unwrap(), [2.0, 4.0]);
+    assert_eq!(reader.get_channel_properties("Rack", "RMS").unwrap()[DERIVED_FROM_PROPERTY].value,
+        PropertyValue::String("/'Rack'/'Raw'".into()));
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_derived_channels_after_failed_commit() {
+    use tdms_rs::writer::derived::LinearScale;
+
+    let path = setup_test_file("derived_failed_commit.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Rack", "Raw", DataType::I32).unwrap();
+        writer.create_derived_channel("Rack", "Double", "Rack", "Raw", LinearScale::new(2.0, 0.0)).unwrap();
+        writer.set_byte_quota(Some(100));
+
+        writer.write_channel_data("Rack", "Raw", &(0..20).collect::<Vec<i32>>()).unwrap();
+        assert!(matches!(writer.flush(), Err(TdmsError::QuotaExceeded { .. })));
+        // The retry derives only what came in since, not the first batch again
+        writer.write_channel_data("Rack", "Raw", &[20i32, 21]).unwrap();
+        assert!(writer.flush().is_err());
+        writer.set_byte_quota(None);
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let expected: Vec<f64> = (0..22).map(|v| f64::from(v) * 2.0).collect();
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "Double").unwrap(), expected);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_open_append() {
+    let path = setup_test_file("open_append.tdms");
+    let index_path = index_path_for(&path);
+    {
+        let mut writer = TdmsWriter::create_with_endianness(&path, Endianness::Big).unwrap();
+        writer.set_file_property("operator", PropertyValue::String("ana".into()));
+        writer.create_channel("Rack", "Voltage", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Rack", "Label", DataType::String).unwrap();
+        writer.create_encoded_channel("Rack", "Counter", DataType::U32, ChannelEncoding::DeltaVarint).unwrap();
+        writer.set_channel_property("Rack", "Voltage", "unit_string", PropertyValue::String("V".into())).unwrap();
+        writer.write_channel_data("Rack", "Voltage", &[1.0f64, 2.0]).unwrap();
+        writer.write_channel_strings("Rack", "Label", &["a"]).unwrap();
+        writer.write_channel_data("Rack", "Counter", &[1u32, 2, 3]).unwrap();
+        writer.close().unwrap();
+    }
+
+    for resume in 0..2 {
+        if resume == 1 {
+            // A missing index is rebuilt from the data file
+            std::fs::remove_file(&index_path).unwrap();
+        }
+        let mut writer = TdmsWriter::open_append(&path).unwrap();
+        assert_eq!(writer.endianness(), Endianness::Big);
+        assert_eq!(writer.values_written("Rack", "Counter").unwrap(), 3 + resume);
+        assert!(matches!(writer.write_channel_data("Rack", "Voltage", &[1i32]), Err(TdmsError::TypeMismatch { .. })));
+        writer.write_channel_data("Rack", "Voltage", &[3.0f64 + resume as f64]).unwrap();
+        writer.write_channel_data("Rack", "Counter", &[4u32]).unwrap();
+        if resume == 1 {
+            writer.write_channel_strings("Rack", "Label", &["b"]).unwrap();
+            writer.set_channel_property("Rack", "Voltage", "unit_string", PropertyValue::String("mV".into())).unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open_indexed(&path).unwrap();
+    assert!(reader.opened_from_index());
+    assert!(reader.segments().iter().all(|segment| segment.is_big_endian));
+    assert_eq!(reader.read_channel_data::<f64>("Rack", "Voltage").unwrap(), [1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(reader.read_channel_strings("Rack", "Label").unwrap(), ["a", "b"]);
+    assert_eq!(reader.read_channel_data::<u32>("Rack", "Counter").unwrap(), [1, 2, 3, 4, 4]);
+    assert_eq!(reader.get_file_properties()["operator"].value, PropertyValue::String("ana".into()));
+    assert_eq!(reader.get_channel_properties("Rack", "Voltage").unwrap()["unit_string"].value,
+        PropertyValue::String("mV".into()));
+    assert_eq!(reader.check_index_file(&index_path).unwrap(), IndexFileStatus::Consistent);
+
+    // A segment left unfinished is not appended to
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes.extend_from_slice(b"TDSm");
+    bytes.extend_from_slice(&[0u8; 4]);
+    bytes.extend_from_slice(&4713u32.to_le_bytes());
+    bytes.extend_from_slice(&[0xFF; 16]);
+    std::fs::write(&path, bytes).unwrap();
+    assert!(matches!(TdmsWriter::open_append(&path), Err(TdmsError::IncompleteSegment(_))));
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_channel_sink() {
+    let path = setup_test_file("channel_sink.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Group", "Values", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Group", "Flags", DataType::Boolean).unwrap();
+
+        assert!(matches!(writer.channel_sink::<f32>("Group", "Values"), Err(TdmsError::TypeMismatch { .. })));
+        assert!(matches!(writer.channel_sink::<f64>("Group", "Missing"), Err(TdmsError::ChannelNotFound(_))));
+
+        for block in 0..3 {
+            let mut sink = writer.channel_sink::<f64>("Group", "Values").unwrap();
+            for i in 0..10 {
+                sink.push((block * 10 + i) as f64).unwrap();
+            }
+            sink.push_slice(&[-1.0, -2.0]).unwrap();
+            assert_eq!(sink.pending(), 12);
+
+            let mut flags = writer.channel_sink::<bool>("Group", "Flags").unwrap();
+            flags.push(block % 2 == 0).unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(writer.values_written("Group", "Values").unwrap(), 36);
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let values: Vec<f64> = reader.read_channel_data("Group", "Values").unwrap();
+    let expected: Vec<f64> = (0..3)
+        .flat_map(|block| (0..10).map(move |i| (block * 10 + i) as f64).chain([-1.0, -2.0]))
+        .collect();
+    assert_eq!(values, expected);
+    assert_eq!(reader.read_channel_data::<bool>("Group", "Flags").unwrap(), vec![true, false, true]);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_write_and_read_by_id() {
+    let path = setup_test_file("by_id.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Group", "Values", DataType::I32).unwrap();
+        writer.create_channel("Group", "Labels", DataType::String).unwrap();
+        writer.create_encoded_channel("Group", "Packed", DataType::I32, ChannelEncoding::DeltaVarint).unwrap();
+        let values = writer.channel_id("Group", "Values").unwrap();
+        let labels = writer.channel_id("Group", "Labels").unwrap();
+        let packed = writer.channel_id("Group", "Packed").unwrap();
+        assert_ne!(values, labels);
+        assert!(matches!(writer.channel_id("Group", "Missing"), Err(TdmsError::ChannelNotFound(_))));
+        assert!(matches!(writer.write_by_id(values, &[1.0f64]), Err(TdmsError::TypeMismatch { .. })));
+
+        for block in 0..3 {
+            writer.write_by_id(values, &[block, block + 1]).unwrap();
+            writer.write_strings_by_id(labels, &[format!("block {}", block)]).unwrap();
+            writer.write_by_id(packed, &[block * 100]).unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(writer.values_written("Group", "Values").unwrap(), 6);
+        assert_eq!(writer.values_written("Group", "Packed").unwrap(), 3);
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let values = reader.channel_id("Group", "Values").unwrap();
+    let labels = reader.channel_id("Group", "Labels").unwrap();
+    assert_eq!(reader.channel_names(labels).unwrap(), ("Group", "Labels"));
+    assert!(reader.channel_id("Group", "Missing").is_err());
+    assert_eq!(reader.read_by_id::<i32>(values).unwrap(), vec![0, 1, 1, 2, 2, 3]);
+    assert_eq!(reader.read_strings_by_id(labels).unwrap(), vec!["block 0", "block 1", "block 2"]);
+    let packed = reader.channel_id("Group", "Packed").unwrap();
+    assert_eq!(reader.read_by_id::<i32>(packed).unwrap(), vec![0, 100, 200]);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_group_only_file() {
+    let path = setup_test_file("group_only.tdms");
+    let defragmented = setup_test_file("group_only_defrag.tdms");
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_group("Empty").unwrap();
+        writer.create_group("Described").unwrap();
+        writer.set_group_property("Described", "operator", PropertyValue::String("ana".into()));
+        writer.create_group("Described").unwrap();
+        writer.close().unwrap();
+    }
+
+    for file in [&path, &defragmented] {
+        if file == &defragmented {
+            defragment(&path, &defragmented).unwrap();
+        }
+        let reader = TdmsReader::open(file).unwrap();
+        let mut groups = reader.list_groups();
+        groups.sort();
+        assert_eq!(groups, vec!["Described", "Empty"]);
+        assert!(reader.get_group_properties("Empty").unwrap().is_empty());
+        assert_eq!(reader.get_group_properties("Described").unwrap()["operator"].value,
+                   PropertyValue::String("ana".into()));
+        assert!(reader.list_channels().is_empty());
+    }
+
+    cleanup_test_file(&path);
+    cleanup_test_file(&defragmented);
+}
+
+#[test]
+fn test_sample_accounting() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tdms_rs::properties::sampling;
+
+    let path = setup_test_file("sample_accounting.tdms");
+    let clock = Arc::new(ManualClock::new(Timestamp::now()));
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.set_clock(clock.clone());
+        writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+        writer.create_channel("Rack", "Unpaced", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[0.0; 50]).unwrap();
+        writer.set_expected_sample_rate("Rack", "Pressure", 100.0).unwrap();
+        assert!(matches!(writer.record_sample_accounting("Rack", "Unpaced"), Err(TdmsError::Unsupported(_))));
+        assert!(writer.set_expected_sample_rate("Rack", "Missing", 1.0).is_err());
+
+        // One second on time, then a second with a quarter of the samples lost
+        for samples in [100, 75] {
+            clock.advance(Duration::from_secs(1));
+            writer.write_channel_data("Rack", "Pressure", &vec![1.0; samples]).unwrap();
+            writer.record_all_sample_accounting().unwrap();
+            writer.flush().unwrap();
+        }
+        let accounting = writer.record_sample_accounting("Rack", "Pressure").unwrap();
+        assert_eq!((accounting.expected, accounting.actual, accounting.dropped()), (200, 175, 25));
+        writer.close().unwrap();
+    }
+
+    let reader = TdmsReader::open(&path).unwrap();
+    let accounting = reader.sample_accounting("Rack", "Pressure").unwrap();
+    assert_eq!(accounting.expected_rate, 100.0);
+    assert_eq!((accounting.expected, accounting.actual), (200, 175));
+    assert_eq!(accounting.drop_ratio(), 0.125);
+    assert!(accounting.at.is_some());
+    assert!(reader.sample_accounting("Rack", "Unpaced").is_none());
+
+    let actual: Vec<i64> = reader.property_history("Rack", "Pressure", sampling::ACTUAL_SAMPLES).iter()
+        .map(|(_, value)| value.as_i64().unwrap())
+        .collect();
+    assert_eq!(actual, vec![100, 175]);
+
+    // Discarding values that were pending when the rate was set
+    let discarded = setup_test_file("sample_accounting_discard.tdms");
+    {
+        let mut writer = TdmsWriter::create(&discarded).unwrap();
+        writer.set_clock(clock.clone());
+        writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[0.0; 10]).unwrap();
+        writer.set_expected_sample_rate("Rack", "Pressure", 100.0).unwrap();
+        writer.discard_pending();
+        let accounting = writer.record_sample_accounting("Rack", "Pressure").unwrap();
+        assert_eq!(accounting.actual, 0);
+        clock.advance(Duration::from_secs(1));
+        writer.write_channel_data("Rack", "Pressure", &[1.0; 100]).unwrap();
+        let accounting = writer.record_sample_accounting("Rack", "Pressure").unwrap();
+        assert_eq!((accounting.expected, accounting.actual), (100, 100));
+    }
+
+    cleanup_test_file(&path);
+    cleanup_test_file(&discarded);
+}
+
+#[test]
+fn test_schema_enforcement() {
+    use tdms_rs::schema::{ChannelSchema, ChannelSpec};
+
+    let path = setup_test_file("schema.tdms");
+    let schema = ChannelSchema::new()
+        .channel("Rack", "Pressure", ChannelSpec::new(DataType::DoubleFloat).unit("bar"))
+        .channel("Rack", "Counts", ChannelSpec::new(DataType::I32))
+        .channel("Rack", "Comment", ChannelSpec::new(DataType::String).optional())
+        .allow_properties(["description"]);
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.set_schema(schema.clone()).unwrap();
+        assert!(matches!(writer.create_channel("Rack", "Pressure", DataType::SingleFloat),
+                         Err(TdmsError::SchemaViolation(_))));
+        assert!(writer.create_channel("Rack", "Unplanned", DataType::I32).is_err());
+        writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[1.0]).unwrap();
+
+        // Counts is missing and Pressure has no unit yet
+        assert!(matches!(writer.flush(), Err(TdmsError::SchemaViolation(message)) if message.contains("Counts")));
+        writer.create_encoded_channel("Rack", "Counts", DataType::I32, ChannelEncoding::DeltaVarint).unwrap();
+        assert!(writer.flush().is_err());
+        writer.set_channel_property("Rack", "Pressure", "unit_string", PropertyValue::String("bar".into())).unwrap();
+        writer.set_channel_property("Rack", "Pressure", "description", PropertyValue::String("inlet".into())).unwrap();
+        writer.flush().unwrap();
+
+        writer.set_channel_property("Rack", "Counts", "operator", PropertyValue::String("ana".into())).unwrap();
+        assert!(matches!(writer.flush(), Err(TdmsError::SchemaViolation(message)) if message.contains("operator")));
+    }
+
+    // A schema contradicting existing channels is rejected
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.create_channel("Rack", "Counts", DataType::U8).unwrap();
+    assert!(writer.set_schema(schema).is_err());
+    assert!(writer.schema().is_none());
+    drop(writer);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_infer_schema() {
+    use tdms_rs::schema::{infer_schema, ChannelSpec};
+
+    let golden = setup_test_file("schema_golden.tdms");
+    {
+        let mut writer = TdmsWriter::create(&golden).unwrap();
+        writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+        writer.set_channel_property("Rack", "Pressure", "unit_string", PropertyValue::String("bar".into())).unwrap();
+        writer.set_channel_property("Rack", "Pressure", "description", PropertyValue::String("inlet".into())).unwrap();
+        writer.write_channel_data("Rack", "Pressure", &[1.0]).unwrap();
+        writer.create_encoded_channel("Rack", "Counts", DataType::I32, ChannelEncoding::DeltaVarint).unwrap();
+        writer.write_channel_data("Rack", "Counts", &[1i32, 2, 3]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let schema = infer_schema(&golden).unwrap();
+    assert_eq!(schema.get("Rack", "Pressure"), Some(&ChannelSpec::new(DataType::DoubleFloat).unit("bar")));
+    assert_eq!(schema.get("Rack", "Counts"), Some(&ChannelSpec::new(DataType::I32)));
+    let allowed: Vec<&str> = schema.allowed_properties().unwrap().iter().map(String::as_str).collect();
+    assert_eq!(allowed, ["description"]);
+
+    // A later run is held to what the golden file looked like
+    let path = setup_test_file("schema_inferred.tdms");
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.set_schema(schema).unwrap();
+    assert!(writer.create_channel("Rack", "Counts", DataType::I64).is_err());
+    writer.create_channel("Rack", "Pressure", DataType::DoubleFloat).unwrap();
+    writer.write_channel_data("Rack", "Pressure", &[2.0]).unwrap();
+    assert!(matches!(writer.flush(), Err(TdmsError::SchemaViolation(_))));
+    drop(writer);
+
+    cleanup_test_file(&golden);
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_byte_quota() {
+    let path = setup_test_file("byte_quota.tdms");
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.set_byte_quota(Some(2000));
+    writer.create_channel("Group", "Data", DataType::DoubleFloat).unwrap();
+
+    let block: Vec<f64> = (0..100).map(f64::from).collect();
+    writer.write_channel_data("Group", "Data", &block).unwrap();
+    writer.flush().unwrap();
+    writer.write_channel_data("Group", "Data", &block).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(writer.bytes_written(), writer.file_size().unwrap());
+
+    // Crossing the cap writes nothing and keeps the data pending
+    let used = writer.bytes_written();
+    writer.write_channel_data("Group", "Data", &block).unwrap();
+    match writer.flush() {
+        Err(TdmsError::QuotaExceeded { limit, used: reported, requested }) => {
+            assert_eq!(limit, 2000);
+            assert_eq!(reported, used);
+            assert_eq!(requested, 800);
+        }
+        other => panic!("expected QuotaExceeded, got {:?}", other),
+    }
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), used);
+
+    writer.set_byte_quota(None);
+    writer.close().unwrap();
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Data").unwrap().len(), 300);
+    drop(reader);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_byte_quota_mid_split() {
+    let path = setup_test_file("byte_quota_split.tdms");
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.set_max_segment_size(Some(80));
+    writer.set_byte_quota(Some(3300));
+    writer.create_channel("Group", "Data", DataType::DoubleFloat).unwrap();
+    writer.create_derived_channel("Group", "Doubled", "Group", "Data", |input: &[f64], out: &mut Vec<f64>| {
+        out.extend(input.iter().map(|v| v * 2.0));
+    }).unwrap();
+
+    // The quota runs out partway through the split parts
+    let values: Vec<f64> = (0..200).map(f64::from).collect();
+    writer.write_channel_data("Group", "Data", &values).unwrap();
+    assert!(matches!(writer.flush(), Err(TdmsError::QuotaExceeded { .. })));
+    let in_file = TdmsReader::open(&path).unwrap().read_channel_data::<f64>("Group", "Data").unwrap();
+    assert!(!in_file.is_empty() && in_file.len() < values.len());
+    assert_eq!(in_file.len() as u64 + writer.pending_samples("Group", "Data").unwrap(), 200);
+    assert_eq!(writer.values_written("Group", "Data").unwrap(), 200);
+
+    // Every part not yet written goes out in order once there is room
+    writer.set_byte_quota(None);
+    writer.close().unwrap();
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Data").unwrap(), values);
+    let doubled: Vec<f64> = values.iter().map(|v| v * 2.0).collect();
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Doubled").unwrap(), doubled);
+    drop(reader);
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_heartbeat_checkpoints() {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let path = setup_test_file("heartbeat.tdms");
+    let start = Timestamp::from_unix_nanos(1_700_000_000_000_000_000);
+    let clock = Arc::new(ManualClock::new(start));
+    let mut writer = TdmsWriter::create(&path).unwrap();
+    writer.set_clock(clock.clone());
+    writer.set_heartbeat(Some(Duration::from_secs(10)));
+    writer.create_channel("Group", "Fast", DataType::DoubleFloat).unwrap();
+    writer.create_encoded_channel("Group", "Counts", DataType::I32, ChannelEncoding::DeltaVarint).unwrap();
+    writer.create_channel("Group", "Idle", DataType::I32).unwrap();
+
+    for _ in 0..3 {
+        writer.write_channel_data("Group", "Fast", &[1.0f64; 100]).unwrap();
+        writer.write_channel_data("Group", "Counts", &[1i32, 2, 3]).unwrap();
+        writer.flush().unwrap();
+        clock.advance(Duration::from_secs(4));
+    }
+    // Not yet due: the checkpoint stays at the first commit
+    let reader = TdmsReader::open(&path).unwrap();
+    let checkpoint = reader.checkpoint().unwrap();
+    assert_eq!(checkpoint.time, start);
+    drop(reader);
+
+    writer.write_channel_data("Group", "Fast", &[2.0f64; 50]).unwrap();
+    writer.flush().unwrap();
+    // Values after the last checkpoint are in the file but not counted
+    writer.write_channel_data("Group", "Fast", &[3.0f64; 7]).unwrap();
+    writer.flush().unwrap();
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let checkpoint = reader.checkpoint().unwrap();
+    assert_eq!(checkpoint.time, start.add_duration(Duration::from_secs(12)));
+    let channel = |name: &str| ObjectPath::Channel { group: "Group".into(), channel: name.into() };
+    assert_eq!(checkpoint.samples, [(channel("Counts"), 9), (channel("Fast"), 350), (channel("Idle"), 0)]);
+    assert_eq!(reader.read_channel_data::<f64>("Group", "Fast").unwrap().len(), 357);
+    drop(reader);
+
+    writer.close().unwrap();
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_complex_roundtrip() {
+    let path = setup_test_file("complex_roundtrip.tdms");
+    let singles = [Complex::new(1.5f32, -2.0), Complex::new(0.0, f32::MAX)];
+    let doubles = [Complex::new(std::f64::consts::PI, 1e-300), Complex::new(-0.0, f64::NEG_INFINITY)];
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_channel("Spectrum", "Single", DataType::ComplexSingleFloat).unwrap();
+        writer.create_channel("Spectrum", "Double", DataType::ComplexDoubleFloat).unwrap();
+        writer.write_channel_data("Spectrum", "Single", &singles).unwrap();
+        writer.write_channel_data("Spectrum", "Double", &doubles).unwrap();
+        writer.flush().unwrap();
+        writer.write_channel_data("Spectrum", "Double", &doubles[..1]).unwrap();
+        // The real part alone isn't a complex value
+        assert!(matches!(writer.write_channel_data("Spectrum", "Double", &[1.0f64]),
+            Err(TdmsError::TypeMismatch { .. })));
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.read_channel_data::<Complex<f32>>("Spectrum", "Single").unwrap(), singles);
+    let read: Vec<Complex<f64>> = reader.read_channel_data("Spectrum", "Double").unwrap();
+    assert_eq!(read, [doubles[0], doubles[1], doubles[0]]);
+    assert!(matches!(reader.read_channel_data::<Complex<f32>>("Spectrum", "Double"),
+        Err(TdmsError::TypeMismatch { .. })));
+
+    cleanup_test_file(&path);
+}
+
+#[test]
+fn test_event_channel() {
+    use std::time::Duration;
+    use tdms_rs::properties::Event;
+
+    let path = setup_test_file("event_channel.tdms");
+    let start = Timestamp::from_unix_nanos(1_700_000_000_000_000_000);
+    let at = |secs: u64| start.add_duration(Duration::from_secs(secs));
+    {
+        let mut writer = TdmsWriter::create(&path).unwrap();
+        writer.create_event_channel("Run", "Events").unwrap();
+        writer.create_channel("Run", "Wrong", DataType::I32).unwrap();
+        writer.write_event("Run", "Events", at(0), "started").unwrap();
+        writer.write_event("Run", "Events", at(5), "valve open").unwrap();
+        writer.flush().unwrap();
+        writer.write_event("Run", "Events", at(9), "stopped").unwrap();
+
+        // Neither half of a rejected event is kept
+        assert!(matches!(writer.write_event("Run", "Missing", at(1), "lost"),
+            Err(TdmsError::ChannelNotFound(_))));
+        writer.create_channel("Run", "Wrong Time", DataType::TimeStamp).unwrap();
+        assert!(writer.write_event("Run", "Wrong", at(1), "lost").is_err());
+        assert_eq!(writer.pending_samples("Run", "Wrong Time").unwrap(), 0);
+        writer.close().unwrap();
+    }
+
+    let mut reader = TdmsReader::open(&path).unwrap();
+    let event = |secs: u64, message: &str| Event { time: at(secs), message: message.to_string() };
+    assert_eq!(reader.read_events("Run", "Events", ..).unwrap(),
+        [event(0, "started"), event(5, "valve open"), event(9, "stopped")]);
+    assert_eq!(reader.read_events("Run", "Events", at(1)..at(9)).unwrap(), [event(5, "valve open")]);
+    assert_eq!(reader.read_events("Run", "Events", at(5)..).unwrap().len(), 2);
+    assert_eq!(reader.get_channel_properties("Run", "Events").unwrap()["tdms_rs_event_time_channel"].value,
+        PropertyValue::String("Events Time".into()));
+
+    cleanup_test_file(&path);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_writer_properties() {
+    let path = setup_test_file("async_properties.tdms");
+    {
+        let writer = AsyncTdmsWriter::create(&path).await.unwrap();
+        writer.set_file_properties([Property::new("operator", PropertyValue::String("ana".into()))]).unwrap();
+        writer.create_group("Setup").await.unwrap();
+        writer.set_group_property("Setup", "rig", PropertyValue::I32(3)).unwrap();
+        writer.create_channel("Setup", "Temp", DataType::F64).await.unwrap();
+        writer.set_channel_property("Setup", "Temp", "unit_string", PropertyValue::String("degC".into())).await.unwrap();
+        writer.set_channel_properties("Setup", "Temp", [Property::new("gain", PropertyValue::Double(0.5))]).await.unwrap();
+        // Channels must exist before their properties can be set
+        assert!(writer.set_channel_property("Setup", "Missing", "gain", PropertyValue::Double(1.0)).await.is_err());
+        writer.write_channel_data("Setup", "Temp", vec![21.5f64, 22.0], DataType::F64).await.unwrap();
+        writer.close().await.unwrap();
+    }
+
+    let reader = TdmsReader::open(&path).unwrap();
+    assert_eq!(reader.get_file_properties()["operator"].value, PropertyValue::String("ana".into()));
+    assert_eq!(reader.get_group_properties("Setup").unwrap()["rig"].value, PropertyValue::I32(3));
+    let channel = reader.get_channel_properties("Setup", "Temp").unwrap();
+    assert_eq!(channel["unit_string"].value, PropertyValue::String("degC".into()));
+    assert_eq!(channel["gain"].value, PropertyValue::Double(0.5));
+
+    cleanup_test_file(&path);
+}